@@ -37,7 +37,7 @@ pub fn derive_envelope(item: TokenStream) -> Result<TokenStream, Error> {
         }
         None => {
             let trait_ident = Ident::new(
-                &format!("{}Envelope", item.ident.to_string()),
+                &format!("{}Envelope", item.ident),
                 item.ident.span(),
             );
             let method_ident =
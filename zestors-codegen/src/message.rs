@@ -55,7 +55,7 @@ pub fn derive_message(item: TokenStream) -> Result<TokenStream, Error> {
     })
 }
 
-pub fn get_msg_type(attrs: &Vec<Attribute>) -> Result<TokenStream, Error> {
+pub fn get_msg_type(attrs: &[Attribute]) -> Result<TokenStream, Error> {
     let mut msg_type = if let Some(attr) = attrs.iter().find(|attr| attr.path.is_ident("msg")) {
         let ty = attr.parse_args::<Type>()?;
         Some(quote! { #ty })
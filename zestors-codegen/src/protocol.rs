@@ -26,7 +26,7 @@ struct ProtocolMsg {
     msg_ty: Type,
 }
 
-fn impl_accepts(item: &ItemEnum, variants: &Vec<ProtocolMsg>) -> Result<TokenStream, Error> {
+fn impl_accepts(item: &ItemEnum, variants: &[ProtocolMsg]) -> Result<TokenStream, Error> {
     let ident = &item.ident;
     let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
 
@@ -59,7 +59,7 @@ fn impl_accepts(item: &ItemEnum, variants: &Vec<ProtocolMsg>) -> Result<TokenStr
     })
 }
 
-fn impl_protocol(item: &ItemEnum, variants: &Vec<ProtocolMsg>) -> Result<TokenStream, Error> {
+fn impl_protocol(item: &ItemEnum, variants: &[ProtocolMsg]) -> Result<TokenStream, Error> {
     let ident = &item.ident;
     let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
 
@@ -104,6 +104,23 @@ fn impl_protocol(item: &ItemEnum, variants: &Vec<ProtocolMsg>) -> Result<TokenSt
         })
         .collect::<Vec<_>>();
 
+    let try_into_msgs = variants
+        .iter()
+        .map(|variant| {
+            let variant_ident = &variant.enum_ident;
+            quote! {
+                Self::#variant_ident(payload) => {
+                    let mut payload = Some(payload);
+                    let any = &mut payload as &mut dyn std::any::Any;
+                    match any.downcast_mut::<Option<M::Payload>>() {
+                        Some(msg) => Ok(msg.take().unwrap()),
+                        None => Err(Self::#variant_ident(payload.unwrap())),
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
     Ok(quote! {
         impl #impl_generics ::zestors::messaging::Protocol for #ident #ty_generics #where_clause {
 
@@ -122,14 +139,22 @@ fn impl_protocol(item: &ItemEnum, variants: &Vec<ProtocolMsg>) -> Result<TokenSt
                     #(#matches)*
                 }
             }
+
+            fn try_into_msg<M: ::zestors::messaging::Message + 'static>(
+                self
+            ) -> Result<M::Payload, Self> {
+                match self {
+                    #(#try_into_msgs)*
+                }
+            }
         }
     })
 }
 
-fn impl_handled_by(item: &ItemEnum, variants: &Vec<ProtocolMsg>) -> Result<TokenStream, Error> {
+fn impl_handled_by(item: &ItemEnum, variants: &[ProtocolMsg]) -> Result<TokenStream, Error> {
     let new_generics = {
         let handle_msg_traits: Punctuated<Type, Token![+]> = variants
-            .into_iter()
+            .iter()
             .map(|variant| {
                 let msg_ty = &variant.msg_ty;
                 let ty: Type = parse_quote! { ::zestors::handler::HandleMessage<#msg_ty> };
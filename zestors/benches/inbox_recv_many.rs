@@ -0,0 +1,54 @@
+//! Compares receiving messages one by one with `Inbox::recv`, against receiving them in batches
+//! with `Inbox::recv_many`.
+//!
+//! The inbox is unbounded, so that the backpressure-delay does not dominate the results.
+//!
+//! Run with `cargo bench --bench inbox_recv_many`.
+
+use std::time::{Duration, Instant};
+use zestors::{prelude::*, protocol};
+
+const MESSAGES: u32 = 1_000_000;
+const BATCH: usize = 64;
+
+#[protocol]
+enum NumberProtocol {
+    Number(u32),
+}
+
+async fn bench(batched: bool) -> Duration {
+    let (child, address) = spawn_with(
+        Link::default(),
+        Capacity::Unbounded,
+        move |mut inbox: Inbox<NumberProtocol>| async move {
+            let mut total = 0u64;
+            if batched {
+                let mut buf = Vec::with_capacity(BATCH);
+                while inbox.recv_many(&mut buf, BATCH).await.is_ok() {
+                    for NumberProtocol::Number(n) in buf.drain(..) {
+                        total += n as u64;
+                    }
+                }
+            } else {
+                while let Ok(NumberProtocol::Number(n)) = inbox.recv().await {
+                    total += n as u64;
+                }
+            }
+            total
+        },
+    );
+    let start = Instant::now();
+    for n in 0..MESSAGES {
+        address.send(n).await.unwrap();
+    }
+    address.close();
+    child.await.unwrap();
+    start.elapsed()
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+async fn main() {
+    println!("Receiving {MESSAGES} messages in a single actor:");
+    println!("- recv:             {:?}", bench(false).await);
+    println!("- recv_many({BATCH}):     {:?}", bench(true).await);
+}
@@ -0,0 +1,57 @@
+//! Compares starting a `OneForOneSpec` by polling all child starts from one task, against
+//! spawning every start onto it's own task.
+//!
+//! Every child blocks it's thread for a while during start, to simulate heavy synchronous work.
+//!
+//! Run with `cargo bench --bench one_for_one_starts`.
+
+use std::time::{Duration, Instant};
+use zestors::{
+    actor_reference::ExitError,
+    prelude::*,
+    supervision::{FatalError, OneForOneSpec, SpawnSpec, Specification, StatefulSpec, Supervisee},
+};
+
+const CHILDREN: usize = 8;
+const START_WORK: Duration = Duration::from_millis(20);
+
+fn tree(spawned_starts: bool) -> OneForOneSpec {
+    let mut tree = OneForOneSpec::new();
+    if spawned_starts {
+        tree = tree.with_spawned_starts(CHILDREN);
+    }
+    for _ in 0..CHILDREN {
+        tree.add_spec(StatefulSpec::new((), |(), _checkpoint| {
+            std::thread::sleep(START_WORK);
+            SpawnSpec::new(
+                |halter: Halter, ()| halter,
+                |exit: Result<(), ExitError>| async move {
+                    exit.map(|()| None).map_err(|e| Box::new(e) as FatalError)
+                },
+                (),
+            )
+        }));
+    }
+    tree
+}
+
+async fn bench(spawned_starts: bool) -> Duration {
+    let spec = tree(spawned_starts);
+    let start = Instant::now();
+    let (supervisee, ()) = spec.start_supervised().await.unwrap();
+    let elapsed = start.elapsed();
+
+    let mut supervisee = Box::pin(supervisee);
+    supervisee.as_mut().halt();
+    futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+        .await
+        .unwrap();
+    elapsed
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+async fn main() {
+    println!("Starting {CHILDREN} children, each blocking for {START_WORK:?}:");
+    println!("- serial-poll:    {:?}", bench(false).await);
+    println!("- spawned starts: {:?}", bench(true).await);
+}
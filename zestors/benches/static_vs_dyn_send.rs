@@ -0,0 +1,66 @@
+//! Compares sending messages to an actor through a static `Address<Inbox<P>>`, against sending
+//! them through a dynamic `Address<DynActor!(u32)>`.
+//!
+//! The static address converts every message directly into the protocol, while the dynamic
+//! address boxes it and downcasts it again before it is sent.
+//!
+//! The inbox is unbounded, so that the backpressure-delay does not dominate the results.
+//!
+//! Run with `cargo bench --bench static_vs_dyn_send`.
+
+use std::time::{Duration, Instant};
+use zestors::{prelude::*, protocol, DynActor};
+
+const MESSAGES: u32 = 1_000_000;
+
+#[protocol]
+enum NumberProtocol {
+    Number(u32),
+}
+
+fn spawn_counter() -> (
+    Child<u64, Inbox<NumberProtocol>>,
+    Address<Inbox<NumberProtocol>>,
+) {
+    spawn_with(
+        Link::default(),
+        Capacity::Unbounded,
+        |mut inbox: Inbox<NumberProtocol>| async move {
+            let mut total = 0;
+            while let Ok(NumberProtocol::Number(n)) = inbox.recv().await {
+                total += n as u64;
+            }
+            total
+        },
+    )
+}
+
+async fn bench_static() -> Duration {
+    let (child, address) = spawn_counter();
+    let start = Instant::now();
+    for n in 0..MESSAGES {
+        address.send(n).await.unwrap();
+    }
+    address.close();
+    child.await.unwrap();
+    start.elapsed()
+}
+
+async fn bench_dyn() -> Duration {
+    let (child, address) = spawn_counter();
+    let address: Address<DynActor!(u32)> = address.transform_into();
+    let start = Instant::now();
+    for n in 0..MESSAGES {
+        address.send(n).await.unwrap();
+    }
+    address.close();
+    child.await.unwrap();
+    start.elapsed()
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+async fn main() {
+    println!("Sending {MESSAGES} messages to a single actor:");
+    println!("- static address:  {:?}", bench_static().await);
+    println!("- dynamic address: {:?}", bench_dyn().await);
+}
@@ -6,7 +6,7 @@ use zestors::{messaging::RecvError, prelude::*};
 async fn inbox_actor(mut inbox: Inbox<()>) {
     loop {
         if let Err(RecvError::Halted) = inbox.recv().await {
-            break ();
+            break;
         }
     }
 }
@@ -50,13 +50,13 @@ async fn main() {
 
     // We can now spawn additional processes onto the actor ..
     child_pool
-        .spawn_onto(|_inbox: Inbox<()>| async move { () })
+        .spawn_onto(|_inbox: Inbox<()>| async move {})
         .unwrap();
 
     // .. and that is also possible with a dynamic child-pool ..
     let mut child_pool = child_pool.into_dyn();
     child_pool
-        .try_spawn_onto(|_inbox: Inbox<()>| async move { () })
+        .try_spawn_onto(|_inbox: Inbox<()>| async move {})
         .unwrap();
 
     // .. but fails if given the wrong inbox-type.
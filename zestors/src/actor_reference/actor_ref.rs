@@ -1,6 +1,6 @@
 use crate::all::*;
 use futures::{future::BoxFuture, Future};
-use std::{any::TypeId, sync::Arc};
+use std::{any::TypeId, sync::Arc, time::Duration};
 
 /// This is the main trait for interacting with an actor, and is implemented for any
 /// [`InboxType`], [`Child`] and [`Address`].
@@ -88,6 +88,40 @@ pub trait ActorRefExt: ActorRef {
         Self::channel_ref(self).actor_id()
     }
 
+    /// The amount of requests that have been sent to this actor, and are awaiting a reply.
+    ///
+    /// Only requests made with [`request`](Self::request), [`try_request`](Self::try_request),
+    /// [`force_request`](Self::force_request) or the same methods of an [`Envelope`] are counted.
+    /// Requires the `request-tracking` feature, see [`crate::messaging`].
+    #[cfg(feature = "request-tracking")]
+    fn pending_requests(&self) -> usize {
+        crate::messaging::pending_requests(self.actor_id())
+    }
+
+    /// The requests that have been sent to this actor and are awaiting a reply, in the order they
+    /// were sent. The same requests are listed as are counted by
+    /// [`pending_requests`](Self::pending_requests).
+    ///
+    /// Requires the `request-tracking` feature, see [`crate::messaging`].
+    #[cfg(feature = "request-tracking")]
+    fn list_pending_requests(&self) -> Vec<PendingRequestInfo> {
+        crate::messaging::list_pending_requests(self.actor_id())
+    }
+
+    /// Cancel a request to this actor that is awaiting a reply, returning whether it was still
+    /// pending.
+    ///
+    /// The caller of the request wakes with a [`RequestError::Cancelled`] (or
+    /// [`TryRequestError::Cancelled`]), and the reply is dropped. The actor can notice this with
+    /// [`Tx::is_closed`] or [`Tx::closed`]. The ids of requests are found with
+    /// [`list_pending_requests`](Self::list_pending_requests).
+    ///
+    /// Requires the `request-tracking` feature, see [`crate::messaging`].
+    #[cfg(feature = "request-tracking")]
+    fn cancel_request(&self, id: RequestId) -> bool {
+        crate::messaging::cancel_request(self.actor_id(), id)
+    }
+
     /// Attempt to send a message to this actor. 
     ///
     /// If the inbox is full or if a [`BackPressure`]-timeout is returned this method will fail
@@ -165,6 +199,35 @@ pub trait ActorRefExt: ActorRef {
         <Self::ActorType as AcceptsExt<M>>::request(Self::channel_ref(self), msg)
     }
 
+    /// Same as [`request`](`Self::request`), but returns the [`RequestTiming`] of the request
+    /// together with the reply.
+    fn request_timed<M, F, E, R>(
+        &self,
+        msg: M,
+    ) -> BoxFuture<'_, Result<(R, RequestTiming), RequestError<M, E>>>
+    where
+        M: Message<Returned = F> + Send + 'static,
+        F: Future<Output = Result<R, E>> + Send,
+        Self::ActorType: Accepts<M>,
+    {
+        <Self::ActorType as AcceptsExt<M>>::request_timed(Self::channel_ref(self), msg)
+    }
+
+    /// Same as [`request`](`Self::request`), but fails with [`RequestError::Timeout`] if no reply
+    /// has been received within the `timeout`. The error contains the type-name of the request.
+    fn request_timeout<M, F, E, R>(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> BoxFuture<'_, Result<R, RequestError<M, E>>>
+    where
+        M: Message<Returned = F> + Send + 'static,
+        F: Future<Output = Result<R, E>> + Send,
+        Self::ActorType: Accepts<M>,
+    {
+        <Self::ActorType as AcceptsExt<M>>::request_timeout(Self::channel_ref(self), msg, timeout)
+    }
+
     /// Same as [`try_send`](`Self::try_send`), but checks at runtime that the message
     /// is actually accepted by the actor.
     fn try_send_checked<M>(&self, msg: M) -> Result<M::Returned, TrySendCheckedError<M>>
@@ -219,6 +282,9 @@ impl<T> ActorRefExt for T where T: ActorRef {}
 
 /// A trait implemented for all [actor-references][`ActorRef`] that can be transformed into and from dynamic
 /// ones.
+///
+/// A transformation only changes the type of the reference, the reference still points to the same
+/// channel. Messages that are already queued are not moved, and keep their order.
 pub trait Transformable: ActorRef {
     /// The output-type of transformations.
     type IntoRef<T>: Transformable<ActorType = T>
@@ -260,4 +326,13 @@ pub trait Transformable: ActorRef {
     fn into_dyn(self) -> Self::IntoRef<DynActor!()> {
         self.transform_unchecked_into()
     }
+
+    /// Seal the accepted messages of this dynamic actor-reference, so that it can only be
+    /// transformed into actor-types accepting fewer messages. (see [`SealedDyn`])
+    fn seal(self) -> SealedDyn<Self>
+    where
+        Self::ActorType: DynActorType,
+    {
+        SealedDyn::new(self)
+    }
 }
@@ -0,0 +1,167 @@
+use crate::all::*;
+use futures::{future::BoxFuture, FutureExt, Sink};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+impl<A: ActorType> Address<A> {
+    /// Convert this address into a [`Sink`] of messages `M`, see [`AddressSink`].
+    pub fn into_sink<M>(self) -> AddressSink<A, M>
+    where
+        A: Accepts<M> + 'static,
+        M: Message + Send + 'static,
+        M::Returned: Send,
+    {
+        AddressSink {
+            address: self,
+            sending: None,
+        }
+    }
+}
+
+/// A [`Sink`] that sends all messages to an actor, created with [`Address::into_sink`].
+///
+/// Every message is sent with [`ActorRefExt::send`], so that the sink applies the backpressure of
+/// the inbox: a full inbox keeps the sink from becoming ready until the message has been sent.
+/// At most one message is sent at a time, and the value returned when sending it is dropped.
+///
+/// Closing the sink only waits for the last message to be sent; it does not close the inbox.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use futures::StreamExt;
+/// use zestors::{prelude::*, protocol};
+///
+/// #[protocol]
+/// enum NumberProtocol {
+///     Number(u32),
+/// }
+///
+/// # async fn main() {
+/// let (child, address) = spawn(|mut inbox: Inbox<NumberProtocol>| async move {
+///     let mut total = 0;
+///     while let Ok(NumberProtocol::Number(n)) = inbox.recv().await {
+///         total += n;
+///     }
+///     total
+/// });
+///
+/// futures::stream::iter(1..=4u32)
+///     .map(Ok)
+///     .forward(address.clone().into_sink())
+///     .await
+///     .unwrap();
+/// address.close();
+/// assert_eq!(child.await.unwrap(), 10);
+/// # }
+/// ```
+pub struct AddressSink<A: ActorType, M: Message> {
+    address: Address<A>,
+    sending: Option<BoxFuture<'static, Result<M::Returned, SendError<M>>>>,
+}
+
+impl<A: ActorType, M: Message> AddressSink<A, M> {
+    /// Get a reference to the address.
+    pub fn address(&self) -> &Address<A> {
+        &self.address
+    }
+
+    /// Convert this back into the address, dropping a message that is still being sent.
+    pub fn into_address(self) -> Address<A> {
+        self.address
+    }
+}
+
+impl<A: ActorType, M: Message> Unpin for AddressSink<A, M> {}
+
+impl<A, M> Sink<M> for AddressSink<A, M>
+where
+    A: Accepts<M> + 'static,
+    M: Message + Send + 'static,
+    M::Returned: Send,
+{
+    type Error = SendError<M>;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some(sending) = &mut self.sending {
+            let res = futures::ready!(sending.poll_unpin(cx));
+            self.sending = None;
+            res?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, msg: M) -> Result<(), Self::Error> {
+        assert!(
+            self.sending.is_none(),
+            "start_send called without poll_ready being ready"
+        );
+        let address = self.address.clone();
+        self.sending = Some(async move { address.send(msg).await }.boxed());
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_ready(cx)
+    }
+}
+
+impl<A: ActorType, M: Message> Debug for AddressSink<A, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AddressSink")
+            .field("actor_id", &self.address.actor_id())
+            .field("sending", &self.sending.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+
+    #[protocol]
+    enum NumberProtocol {
+        Number(u32),
+    }
+
+    #[tokio::test]
+    async fn forward_applies_backpressure() {
+        let (child, address) = spawn_with(
+            Link::default(),
+            Capacity::Bounded(1),
+            |mut inbox: Inbox<NumberProtocol>| async move {
+                let mut received = Vec::new();
+                while let Ok(NumberProtocol::Number(n)) = inbox.recv().await {
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                    received.push(n);
+                }
+                received
+            },
+        );
+
+        futures::stream::iter(0..10u32)
+            .map(Ok)
+            .forward(address.clone().into_sink())
+            .await
+            .unwrap();
+        address.close();
+        assert_eq!(child.await.unwrap(), (0..10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn closed_inbox_returns_message() {
+        let (child, address) = spawn(|inbox: Inbox<NumberProtocol>| async move { drop(inbox) });
+        child.await.unwrap();
+
+        let mut sink = address.into_sink();
+        assert_eq!(sink.send(1u32).await, Err(SendError(1)));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::all::*;
+use futures::future::join_all;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::{timeout_at, Instant};
+
+/// Attempt to send a clone of the message to every address, without waiting.
+///
+/// A full or closed inbox does not prevent delivery to the others: the result for every address
+/// is returned in the same order as the addresses.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, actor_reference::try_broadcast, messaging::TrySendError};
+///
+/// # async fn main() {
+/// let (_child1, address1) = spawn(|mut inbox: Inbox<()>| async move { inbox.recv().await });
+/// let (child2, address2) = spawn(|mut inbox: Inbox<()>| async move { inbox.recv().await });
+/// child2.halt();
+/// child2.await.unwrap();
+///
+/// let results = try_broadcast(&[address1, address2], ());
+/// assert!(results[0].is_ok());
+/// assert_eq!(results[1], Err(TrySendError::Closed(())));
+/// # }
+/// ```
+pub fn try_broadcast<A, M>(
+    addresses: &[Address<A>],
+    msg: M,
+) -> Vec<Result<M::Returned, TrySendError<M>>>
+where
+    A: Accepts<M>,
+    M: Message + Clone,
+{
+    addresses
+        .iter()
+        .map(|address| address.try_send(msg.clone()))
+        .collect()
+}
+
+/// Send a clone of the message to every address, waiting at most `timeout` for all of them.
+///
+/// The message is sent to all addresses concurrently, so a full inbox does not delay delivery to
+/// the others. The result for every address is returned in the same order as the addresses.
+///
+/// Space is reserved in an inbox before the message is cloned into it, so the message is cloned
+/// only once for every address, also if it could not be delivered.
+///
+/// Only an [`Inbox`] can reserve space before the message exists (see [`Address::send_lazy`]), so
+/// unlike [`try_broadcast`] this is not generic over the [`ActorType`]. To broadcast to other
+/// actor-types, [`try_broadcast`] can be used, or a clone can be sent to every address.
+pub async fn broadcast<P, M>(
+    addresses: &[Address<Inbox<P>>],
+    msg: M,
+    timeout: Duration,
+) -> Vec<Result<M::Returned, BroadcastError<M>>>
+where
+    P: Protocol + FromPayload<M>,
+    M: Message + Clone,
+{
+    let deadline = Instant::now() + timeout;
+    let msg = &msg;
+    join_all(addresses.iter().map(|address| async move {
+        match timeout_at(deadline, address.send_lazy(|| msg.clone())).await {
+            Ok(Ok(returned)) => Ok(returned),
+            Ok(Err(SendLazyError::ClosedAfterBuild(msg))) => Err(BroadcastError::Closed(msg)),
+            Ok(Err(SendLazyError::Closed(_))) => Err(BroadcastError::Closed(msg.clone())),
+            Err(_) => Err(BroadcastError::Timeout(msg.clone())),
+        }
+    }))
+    .await
+}
+
+/// Error returned for an address when [`broadcast`]ing a message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum BroadcastError<M> {
+    /// The channel has been closed, and no longer accepts new messages.
+    #[error("Couldn't send message because Channel is closed")]
+    Closed(M),
+    /// The channel stayed full until the timeout expired.
+    #[error("Couldn't send message because the timeout expired")]
+    Timeout(M),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::basic_actor;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Message, Debug)]
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Self(self.0.clone())
+        }
+    }
+
+    #[protocol]
+    enum CountedProtocol {
+        Counted(Counted),
+    }
+
+    fn full_actor() -> (Child<(), Inbox<()>>, Address<Inbox<()>>) {
+        let (child, address) = spawn_with(
+            Link::default(),
+            Capacity::Bounded(1),
+            |inbox: Inbox<()>| async move {
+                let _inbox = inbox;
+                futures::future::pending().await
+            },
+        );
+        address.try_send(()).unwrap();
+        (child, address)
+    }
+
+    #[tokio::test]
+    async fn try_broadcast_skips_full_inboxes() {
+        let (_child1, address1) = spawn(basic_actor!());
+        let (_child2, address2) = full_actor();
+        let (_child3, address3) = spawn(basic_actor!());
+
+        let results = try_broadcast(&[address1, address2, address3], ());
+        assert_eq!(results, vec![Ok(()), Err(TrySendError::Full(())), Ok(())]);
+    }
+
+    #[tokio::test]
+    async fn broadcast_times_out_on_full_inboxes() {
+        let (_child1, address1) = spawn(basic_actor!());
+        let (_child2, address2) = full_actor();
+
+        let results = broadcast(&[address1, address2], (), Duration::from_millis(10)).await;
+        assert_eq!(results, vec![Ok(()), Err(BroadcastError::Timeout(()))]);
+    }
+
+    #[tokio::test]
+    async fn broadcast_clones_once_per_address() {
+        let (_child1, address1) = spawn(|mut inbox: Inbox<CountedProtocol>| async move {
+            while inbox.recv().await.is_ok() {}
+        });
+        let (_child2, address2) = spawn_with(
+            Link::default(),
+            Capacity::Bounded(1),
+            |inbox: Inbox<CountedProtocol>| async move {
+                let _inbox = inbox;
+                futures::future::pending::<()>().await
+            },
+        );
+        let clones = Arc::new(AtomicUsize::new(0));
+        address2.try_send(Counted(clones.clone())).unwrap();
+
+        let results = broadcast(
+            &[address1, address2],
+            Counted(clones.clone()),
+            Duration::from_millis(10),
+        )
+        .await;
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(BroadcastError::Timeout(_))));
+        assert_eq!(clones.load(Ordering::Relaxed), 2);
+    }
+}
@@ -1,11 +1,12 @@
 use crate::all::*;
-use futures::{Future, FutureExt, Stream};
+use futures::{task::AtomicWaker, Future, FutureExt, Stream};
 use std::{
     mem,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex, MutexGuard},
     task::{Context, Poll}, time::Duration,
 };
+use tokio::task::JoinHandle;
 
 /// A child is a unique [reference](ActorRef) to an actor similar to a [`tokio::task::JoinHandle`]. The
 /// child can be used to halt/abort the actor and to monitor it's exit by awaiting it. When a child is dropped,
@@ -18,12 +19,23 @@ use std::{
 /// by awaiting the [`Child`], and returns an [`Result<E, ExitError>`](ExitError). A [`MultiProcess`]-child
 /// can be [`streamed`](Stream) instead.
 ///
+/// # Dropping
+/// An [`Address`] never keeps an actor alive, only the [`Link`] of the child decides what happens
+/// when it is dropped. If the link is attached, dropping the child (or [`ChildPool`]) halts all
+/// processes and aborts them if they have not exited after the shutdown-time, regardless of any
+/// addresses that are still around. Once all processes have exited the inbox is closed, so that
+/// sends through the remaining addresses (including ones that are waiting for space) fail with
+/// `Closed`. If the link is detached, the actor keeps running until it exits on it's own.
+///
+/// Dropping all addresses first has no effect on the actor: it can still be halted and awaited
+/// through it's child.
+///
 /// # Generics
 /// The [`Child<E, A, C>`] is specified by:
 /// - `E`: Stands for `Exit` and is the value that the spawned child exits with.
 /// - `A`: Stands for [`ActorType`] and specifies the type of the actor.
 /// - `C`: Stands for [`ChildType`] and specifies whether this child is pooled or not.
-/// This can either be [`SingleProcess`] or [`MultiProcess`].
+///   This can either be [`SingleProcess`] or [`MultiProcess`].
 ///
 /// The following shorthand notation can be used:
 /// ```no_compile
@@ -44,6 +56,40 @@ where
     join_handles: Option<C::JoinHandles<E>>,
     link: Link,
     is_aborted: bool,
+    ready: Option<Arc<ReadyBarrier>>,
+    spawned: Option<Arc<SpawnedHandles<E>>>,
+}
+
+/// The join-handles of the processes spawned through a [`WeakChildPool`], which are adopted by
+/// it's pool.
+///
+/// A pool that is being streamed registers it's waker here, so that it's woken up to adopt (and
+/// poll) a process as soon as it has been spawned.
+#[derive(Debug)]
+pub(crate) struct SpawnedHandles<E> {
+    handles: Mutex<Vec<JoinHandle<E>>>,
+    waker: AtomicWaker,
+}
+
+impl<E> SpawnedHandles<E> {
+    /// Add the handle of a newly spawned process and wake the pool.
+    pub(crate) fn push(&self, handle: JoinHandle<E>) {
+        self.lock().push(handle);
+        self.waker.wake();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<JoinHandle<E>>> {
+        self.handles.lock().unwrap()
+    }
+}
+
+impl<E> Default for SpawnedHandles<E> {
+    fn default() -> Self {
+        Self {
+            handles: Mutex::new(Vec::new()),
+            waker: AtomicWaker::new(),
+        }
+    }
 }
 
 /// Type-alias for child-pools, see [`Child`] for usage.
@@ -66,17 +112,44 @@ where
             link,
             channel,
             is_aborted: false,
+            ready: None,
+            spawned: None,
         }
     }
 
-    fn into_parts(self) -> (Arc<A::Channel>, Option<C::JoinHandles<E>>, Link, bool) {
+    pub(crate) fn with_ready_barrier(mut self, barrier: Arc<ReadyBarrier>) -> Self {
+        self.ready = Some(barrier);
+        self
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn into_parts(
+        self,
+    ) -> (
+        Arc<A::Channel>,
+        Option<C::JoinHandles<E>>,
+        Link,
+        bool,
+        Option<Arc<ReadyBarrier>>,
+        Option<Arc<SpawnedHandles<E>>>,
+    ) {
         let no_drop = mem::ManuallyDrop::new(self);
         unsafe {
             let handle = std::ptr::read(&no_drop.join_handles);
             let channel = std::ptr::read(&no_drop.channel);
             let link = std::ptr::read(&no_drop.link);
             let is_aborted = std::ptr::read(&no_drop.is_aborted);
-            (channel, handle, link, is_aborted)
+            let ready = std::ptr::read(&no_drop.ready);
+            let spawned = std::ptr::read(&no_drop.spawned);
+            (channel, handle, link, is_aborted, ready, spawned)
+        }
+    }
+
+    /// Move the processes that have been spawned through a [`WeakChildPool`] into this child.
+    fn adopt_spawned(&mut self) {
+        if let Some(spawned) = &self.spawned {
+            let spawned = mem::take(&mut *spawned.lock());
+            C::adopt(self.join_handles.as_mut().unwrap(), spawned);
         }
     }
 
@@ -84,7 +157,8 @@ where
     ///
     /// # Warning
     /// This will not run the drop implementation and therefore the actor will not be halted/aborted.
-    pub fn into_join_handles(self) -> C::JoinHandles<E> {
+    pub fn into_join_handles(mut self) -> C::JoinHandles<E> {
+        self.adopt_spawned();
         self.into_parts().1.take().unwrap()
     }
 
@@ -126,6 +200,7 @@ where
         self.channel.close();
         let was_aborted = self.is_aborted;
         self.is_aborted = true;
+        self.adopt_spawned();
         C::abort(self.join_handles.as_ref().unwrap());
         !was_aborted
     }
@@ -133,6 +208,10 @@ where
     /// Whether the tokio-tasks are finished. (see [`tokio::task::JoinHandle::is_finished`])
     pub fn is_finished(&self) -> bool {
         C::is_finished(self.join_handles.as_ref().unwrap())
+            && self
+                .spawned
+                .as_ref()
+                .is_none_or(|spawned| spawned.lock().iter().all(JoinHandle::is_finished))
     }
 }
 
@@ -147,12 +226,14 @@ where
     where
         A: MultiProcessInbox,
     {
-        let (channel, mut join_handles, link, is_aborted) = self.into_parts();
+        let (channel, mut join_handles, link, is_aborted, ready, spawned) = self.into_parts();
         ChildPool {
             channel,
             join_handles: Some(vec![join_handles.take().unwrap()]),
             link,
             is_aborted,
+            ready,
+            spawned,
         }
     }
 
@@ -182,18 +263,42 @@ where
 {
     /// The amount of tokio-tasks that have not finished.
     pub fn task_count(&self) -> usize {
+        let spawned = self.spawned.as_ref().map(|spawned| spawned.lock());
         self.join_handles
             .as_ref()
             .unwrap()
             .iter()
+            .chain(spawned.iter().flat_map(|spawned| spawned.iter()))
             .filter(|handle| !handle.is_finished())
-            .collect::<Vec<_>>()
-            .len()
+            .count()
     }
 
     /// The amount of tokio-tasks, including those that have finished.
     pub fn handle_count(&self) -> usize {
-        self.join_handles.as_ref().unwrap().len()
+        let spawned = self.spawned.as_ref().map(|spawned| spawned.lock());
+        self.join_handles.as_ref().unwrap().len() + spawned.map_or(0, |spawned| spawned.len())
+    }
+
+    /// Get a [`WeakChildPool`] to this pool, which can spawn processes onto it without owning it.
+    ///
+    /// The processes spawned through the [`WeakChildPool`] are part of this pool, just like the
+    /// ones spawned with [`ChildPool::spawn_onto`].
+    pub fn downgrade(&mut self) -> WeakChildPool<E, A> {
+        let address = self.clone_address();
+        let spawned = self.spawned.get_or_insert_with(Default::default);
+        WeakChildPool::new(address, Arc::downgrade(spawned))
+    }
+
+    /// Wait until every process spawned with [`ready_spawn_many`] has sent it's [`ReadySignal`].
+    ///
+    /// This returns an error as soon as one of these processes exits without having sent it's
+    /// signal. Processes spawned onto the pool later on are not waited for, and pools that were
+    /// not spawned with [`ready_spawn_many`] are ready immediately.
+    pub async fn ready(&self) -> Result<(), ReadyError> {
+        match &self.ready {
+            Some(barrier) => barrier.wait().await,
+            None => Ok(()),
+        }
     }
 
     /// Same as [`Self::shutdown`] but with a custom shutdown-time.
@@ -219,6 +324,21 @@ where
     ///
     /// This method fails if the actor has already exited.
     pub fn spawn_onto<Fun, Fut>(&mut self, fun: Fun) -> Result<(), SpawnError<Fun>>
+    where
+        Fun: FnOnce(A) -> Fut + Send + 'static,
+        Fut: Future<Output = E> + Send + 'static,
+        A: MultiProcessInbox,
+    {
+        self.spawn_onto_with(&TokioSpawner, fun)
+    }
+
+    /// Same as [`Child::spawn_onto`], but the process is spawned with the given [`Spawner`]
+    /// instead of onto the ambient runtime.
+    pub fn spawn_onto_with<Fun, Fut>(
+        &mut self,
+        spawner: &impl Spawner,
+        fun: Fun,
+    ) -> Result<(), SpawnError<Fun>>
     where
         Fun: FnOnce(A) -> Fut + Send + 'static,
         Fut: Future<Output = E> + Send + 'static,
@@ -227,7 +347,10 @@ where
         match self.channel.try_increment_process_count() {
             Ok(_) => {
                 let inbox = A::from_channel(self.channel.clone());
-                let handle = tokio::task::spawn(async move { fun(inbox).await });
+                let handle = spawner.spawn(CapturePanics::new(ActorContext::unnamed(
+                    self.channel.actor_id(),
+                    async move { fun(inbox).await },
+                )));
                 self.join_handles.as_mut().unwrap().push(handle);
                 Ok(())
             }
@@ -242,6 +365,76 @@ where
         }
     }
 
+    /// Same as [`Child::spawn_onto`], but the process is spawned onto the [`LocalSet`] instead of
+    /// the shared runtime. The future of the process therefore does not have to be [`Send`], and is
+    /// always polled on the thread of the [`LocalSet`].
+    ///
+    /// The process only makes progress while the [`LocalSet`] is being run, and is aborted when
+    /// the [`LocalSet`] is dropped. Since a [`LocalSet`] can't cross threads, this must be called
+    /// from the thread that owns it; the pool itself can still be sent to other threads.
+    ///
+    /// [`LocalSet`]: tokio::task::LocalSet
+    pub fn spawn_on<Fun, Fut>(
+        &mut self,
+        local: &tokio::task::LocalSet,
+        fun: Fun,
+    ) -> Result<(), SpawnError<Fun>>
+    where
+        Fun: FnOnce(A) -> Fut + 'static,
+        Fut: Future<Output = E> + 'static,
+        A: MultiProcessInbox,
+    {
+        self.spawn_process(fun, |fun, inbox, actor_id| {
+            local.spawn_local(CapturePanics::new(ActorContext::unnamed(actor_id, fun(inbox))))
+        })
+    }
+
+    /// Same as [`Child::spawn_on`], but the process is spawned onto the main thread with the
+    /// [`MainThreadSpawner`], which can be done from any thread.
+    ///
+    /// The function is called on the main thread, so the future of the process does not have to
+    /// be [`Send`].
+    pub fn spawn_on_main_thread<Fun, Fut>(
+        &mut self,
+        spawner: &MainThreadSpawner,
+        fun: Fun,
+    ) -> Result<(), SpawnError<Fun>>
+    where
+        Fun: FnOnce(A) -> Fut + Send + 'static,
+        Fut: Future<Output = E> + 'static,
+        A: MultiProcessInbox,
+    {
+        let capture = is_capturing();
+        self.spawn_process(fun, |fun, inbox, actor_id| {
+            spawner.spawn_local(move || {
+                CapturePanics::with_capture(ActorContext::unnamed(actor_id, fun(inbox)), capture)
+            })
+        })
+    }
+
+    /// Add a process to the channel, and spawn it with the given function.
+    fn spawn_process<Fun>(
+        &mut self,
+        fun: Fun,
+        spawn: impl FnOnce(Fun, A, ActorId) -> JoinHandle<E>,
+    ) -> Result<(), SpawnError<Fun>>
+    where
+        A: MultiProcessInbox,
+    {
+        match self.channel.try_increment_process_count() {
+            Ok(_) => {
+                let inbox = A::from_channel(self.channel.clone());
+                let handle = spawn(fun, inbox, self.channel.actor_id());
+                self.join_handles.as_mut().unwrap().push(handle);
+                Ok(())
+            }
+            Err(AddProcessError::ActorHasExited) => Err(SpawnError(fun)),
+            Err(AddProcessError::SingleProcessOnly) => {
+                panic!("Error with implementation of the Inbox. This is a Bug, please report it.")
+            }
+        }
+    }
+
     /// Attempt to spawn an additional process onto the channel.
     ///
     /// This method can fail if
@@ -262,8 +455,12 @@ where
 
         match channel.try_increment_process_count() {
             Ok(_) => {
+                let actor_id = channel.actor_id();
                 let inbox = T::from_channel(channel);
-                let handle = tokio::task::spawn(async move { fun(inbox).await });
+                let handle = tokio::task::spawn(CapturePanics::new(ActorContext::unnamed(
+                    actor_id,
+                    async move { fun(inbox).await },
+                )));
                 self.join_handles.as_mut().unwrap().push(handle);
                 Ok(())
             }
@@ -303,12 +500,14 @@ where
     where
         T: DynActorType,
     {
-        let (channel, join_handles, link, is_aborted) = self.into_parts();
+        let (channel, join_handles, link, is_aborted, ready, spawned) = self.into_parts();
         Child {
             join_handles,
             channel: <A::Channel as Channel>::into_dyn(channel),
             link,
             is_aborted,
+            ready,
+            spawned,
         }
     }
 
@@ -317,12 +516,14 @@ where
         Self::ActorType: TransformInto<T>,
         T: ActorType,
     {
-        let (channel, join_handles, link, is_aborted) = self.into_parts();
+        let (channel, join_handles, link, is_aborted, ready, spawned) = self.into_parts();
         Child {
             join_handles,
             channel: A::transform_into(channel),
             link,
             is_aborted,
+            ready,
+            spawned,
         }
     }
 
@@ -332,19 +533,23 @@ where
         T: ActorType,
         T::Channel: Sized + 'static,
     {
-        let (channel, join_handles, link, is_aborted) = self.into_parts();
+        let (channel, join_handles, link, is_aborted, ready, spawned) = self.into_parts();
         match channel.clone().into_any().downcast() {
             Ok(channel) => Ok(Child {
                 join_handles,
                 channel,
                 link,
                 is_aborted,
+                ready,
+                spawned,
             }),
             Err(_) => Err(Child {
                 join_handles,
                 channel,
                 link,
                 is_aborted,
+                ready,
+                spawned,
             }),
         }
     }
@@ -384,7 +589,12 @@ where
     type Item = Result<E, ExitError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if self.join_handles.as_ref().unwrap().len() == 0 {
+        // Registered before adopting, so that a process spawned in between still wakes us.
+        if let Some(spawned) = &self.spawned {
+            spawned.waker.register(cx.waker());
+        }
+        self.adopt_spawned();
+        if self.join_handles.as_ref().unwrap().is_empty() {
             return Poll::Ready(None);
         }
 
@@ -407,12 +617,13 @@ where
 {
     fn drop(&mut self) {
         if let Link::Attached(shutdown_time) = &mut self.link {
-            let shutdown_time = shutdown_time.clone();
+            let shutdown_time = *shutdown_time;
             if !self.is_aborted && !self.is_finished() {
                 self.halt();
+                self.adopt_spawned();
                 let handles = self.join_handles.take().unwrap();
                 tokio::task::spawn(async move {
-                    tokio::time::sleep(shutdown_time.clone()).await;
+                    tokio::time::sleep(shutdown_time).await;
                     C::abort(&handles)
                 });
             }
@@ -496,7 +707,7 @@ mod test {
     async fn dropping_aborts() {
         let (tx, rx) = oneshot::channel();
         let (child, _addr) = spawn_with(
-            Link::Attached(Duration::from_millis(1).into()),
+            Link::Attached(Duration::from_millis(1)),
             Default::default(),
             |mut inbox: Inbox<()>| async move {
                 if let Err(RecvError::Halted) = inbox.recv().await {
@@ -534,10 +745,7 @@ mod test {
     #[tokio::test]
     async fn downcast() {
         let (child, _addr) = spawn(basic_actor!());
-        assert!(matches!(
-            child.transform_into::<DynActor!()>().downcast::<Inbox<()>>(),
-            Ok(_)
-        ));
+        assert!(child.transform_into::<DynActor!()>().downcast::<Inbox<()>>().is_ok());
     }
 
     #[tokio::test]
@@ -563,12 +771,12 @@ mod test {
         let pool = child.into_pool();
         assert_eq!(pool.task_count(), 1);
         assert_eq!(pool.process_count(), 1);
-        assert_eq!(pool.is_aborted(), false);
+        assert!(!pool.is_aborted());
 
         let (mut child, _addr) = spawn(basic_actor!());
         child.abort();
         let pool = child.into_pool();
-        assert_eq!(pool.is_aborted(), true);
+        assert!(pool.is_aborted());
     }
 }
 
@@ -609,6 +817,41 @@ mod test_pooled {
         assert_eq!(HALT_COUNT.load(Ordering::Acquire), 3);
     }
 
+    #[tokio::test]
+    async fn dropping_pool_closes_outstanding_addresses() {
+        let (child, addr) = spawn_many_with(
+            Link::Attached(Duration::from_millis(10)),
+            Capacity::Bounded(1),
+            0..3,
+            |_, _inbox: Inbox<()>| pending::<()>(),
+        );
+        addr.try_send(()).unwrap();
+        let waiting_addr = addr.clone();
+        let waiting_send = tokio::spawn(async move { waiting_addr.send(()).await });
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        drop(child);
+        addr.clone().await;
+        assert!(matches!(addr.try_send(()), Err(TrySendError::Closed(()))));
+        assert!(matches!(waiting_send.await.unwrap(), Err(SendError(()))));
+    }
+
+    #[tokio::test]
+    async fn dropping_addresses_keeps_pool_alive() {
+        let (mut child, addr) = spawn_many(0..3, pooled_basic_actor!());
+        drop(addr);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert!(!child.has_exited());
+
+        child.halt();
+        let mut exits = 0;
+        while let Some(exit) = futures::StreamExt::next(&mut child).await {
+            exit.unwrap();
+            exits += 1;
+        }
+        assert_eq!(exits, 3);
+    }
+
     #[tokio::test]
     async fn dropping_detached() {
         static HALT_COUNT: AtomicU8 = AtomicU8::new(0);
@@ -637,7 +880,29 @@ mod test_pooled {
     async fn downcast() {
         let (pool, _addr) = spawn_many(0..5, pooled_basic_actor!());
         let pool: ChildPool<_> = pool.transform_into();
-        assert!(matches!(pool.downcast::<Inbox<()>>(), Ok(_)));
+        assert!(pool.downcast::<Inbox<()>>().is_ok());
+    }
+
+    #[tokio::test]
+    async fn transform_keeps_queued_messages_in_order() {
+        let (pool, address) = spawn_many(0..1, |_, mut inbox: Inbox<U32Protocol>| async move {
+            let mut received = Vec::new();
+            while let Ok(U32Protocol::U32(n)) = inbox.recv().await {
+                received.push(n);
+            }
+            received
+        });
+        for n in 0..5u32 {
+            address.send(n).await.unwrap();
+        }
+
+        let mut pool = pool.transform_into::<DynActor!(u32)>();
+        for n in 5..10u32 {
+            pool.send(n).await.unwrap();
+        }
+        pool.close();
+        let received = futures::StreamExt::next(&mut pool).await.unwrap().unwrap();
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
     }
 
     #[tokio::test]
@@ -650,6 +915,44 @@ mod test_pooled {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn spawn_on_local_set() {
+        let (mut child, addr) = spawn_many(0..1, pooled_basic_actor!());
+        let basic_processes = child.process_ids();
+        let local = tokio::task::LocalSet::new();
+        let counter = std::rc::Rc::new(std::cell::Cell::new(0));
+        let local_counter = counter.clone();
+        child
+            .spawn_on(&local, move |mut inbox: Inbox<()>| async move {
+                loop {
+                    if let Err(e) = inbox.recv().await {
+                        break e;
+                    }
+                    local_counter.set(local_counter.get() + 1);
+                }
+            })
+            .unwrap();
+        assert_eq!(child.process_count(), 2);
+
+        // Only the process on the local set is left to receive the message.
+        assert_eq!(child.halt_where(|id| basic_processes.contains(&id)), 1);
+
+        local
+            .run_until(async {
+                futures::StreamExt::next(&mut child)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                addr.send(()).await.unwrap();
+                addr.close();
+                while let Some(exit) = futures::StreamExt::next(&mut child).await {
+                    exit.unwrap();
+                }
+            })
+            .await;
+        assert_eq!(counter.get(), 1);
+    }
+
     #[tokio::test]
     async fn spawn_err_exit() {
         let (mut child, addr) = spawn_many(0..1, pooled_basic_actor!());
@@ -9,6 +9,7 @@ pub trait ChildType {
     type JoinHandles<E: Send + 'static>: Send + 'static;
     fn abort<E: Send + 'static>(handles: &Self::JoinHandles<E>);
     fn is_finished<E: Send + 'static>(handles: &Self::JoinHandles<E>) -> bool;
+    fn adopt<E: Send + 'static>(handles: &mut Self::JoinHandles<E>, spawned: Vec<JoinHandle<E>>);
 }
 
 /// The default [`ChildType`].
@@ -25,6 +26,13 @@ impl ChildType for SingleProcess {
     fn is_finished<E: Send + 'static>(handles: &Self::JoinHandles<E>) -> bool {
         handles.is_finished()
     }
+
+    fn adopt<E: Send + 'static>(_handles: &mut Self::JoinHandles<E>, spawned: Vec<JoinHandle<E>>) {
+        assert!(
+            spawned.is_empty(),
+            "A single-process child can't adopt processes"
+        );
+    }
 }
 
 /// The pooled [`ChildType`].
@@ -43,4 +51,8 @@ impl ChildType for MultiProcess {
     fn is_finished<E: Send + 'static>(handles: &Self::JoinHandles<E>) -> bool {
         handles.iter().all(|handle| handle.is_finished())
     }
+
+    fn adopt<E: Send + 'static>(handles: &mut Self::JoinHandles<E>, spawned: Vec<JoinHandle<E>>) {
+        handles.extend(spawned)
+    }
 }
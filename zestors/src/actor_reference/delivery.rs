@@ -0,0 +1,143 @@
+use crate::all::*;
+use std::fmt::Debug;
+use thiserror::Error;
+use tokio::sync::oneshot;
+
+/// The guarantee with which a message is delivered by [`Address::deliver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DeliveryMode {
+    /// Fire-and-forget: fail with [`DeliveryError::Full`] instead of waiting for space, the same
+    /// as [`try_send`](ActorRefExt::try_send).
+    BestEffort,
+    /// Wait until there is space, the same as [`send`](ActorRefExt::send).
+    #[default]
+    Blocking,
+    /// Wait until there is space, and then until the actor has received the message from it's
+    /// inbox. If the message is dropped before it is received, this fails with
+    /// [`DeliveryError::Dropped`].
+    Confirmed,
+}
+
+impl<P: Protocol> Address<Inbox<P>> {
+    /// Send a message with the given [`DeliveryMode`].
+    ///
+    /// # Usage
+    /// ```
+    /// # tokio_test::block_on(main());
+    /// use zestors::{prelude::*, protocol, actor_reference::DeliveryMode};
+    ///
+    /// #[protocol]
+    /// enum NumberProtocol {
+    ///     Number(u32),
+    /// }
+    ///
+    /// # async fn main() {
+    /// let (child, address) = spawn(|mut inbox: Inbox<NumberProtocol>| async move {
+    ///     let NumberProtocol::Number(n) = inbox.recv().await.unwrap();
+    ///     n
+    /// });
+    /// // This resolves once the actor has received the message.
+    /// address.deliver(10u32, DeliveryMode::Confirmed).await.unwrap();
+    /// assert_eq!(address.msg_count(), 0);
+    /// assert_eq!(child.await.unwrap(), 10);
+    /// # }
+    /// ```
+    pub async fn deliver<M>(
+        &self,
+        msg: M,
+        mode: DeliveryMode,
+    ) -> Result<M::Returned, DeliveryError<M>>
+    where
+        P: FromPayload<M>,
+        M: Message,
+        M::Returned: Send,
+    {
+        match mode {
+            DeliveryMode::BestEffort => self.try_send(msg).map_err(|e| match e {
+                TrySendError::Full(msg) => DeliveryError::Full(msg),
+                TrySendError::Closed(msg) => DeliveryError::Closed(msg),
+            }),
+            DeliveryMode::Blocking => self
+                .send(msg)
+                .await
+                .map_err(|SendError(msg)| DeliveryError::Closed(msg)),
+            DeliveryMode::Confirmed => {
+                let permit = match <Self as ActorRef>::channel_ref(self).reserve().await {
+                    Ok(permit) => permit,
+                    Err(SendError(())) => return Err(DeliveryError::Closed(msg)),
+                };
+                let (sends, returns, trace) = create_traced(msg);
+                let (ack_tx, ack_rx) = oneshot::channel();
+                let sent = traced(trace, || permit.send_acked(P::from_payload(sends), ack_tx));
+                if let Err(SendError(prot)) = sent {
+                    return Err(DeliveryError::Closed(unwrap_then_cancel(prot, returns)));
+                }
+                match ack_rx.await {
+                    Ok(()) => Ok(returns),
+                    Err(_) => Err(DeliveryError::Dropped),
+                }
+            }
+        }
+    }
+}
+
+/// Error returned by [`Address::deliver`].
+#[derive(Clone, PartialEq, Eq, Hash, Error)]
+pub enum DeliveryError<M> {
+    /// The inbox is full, only returned for [`DeliveryMode::BestEffort`].
+    #[error("Couldn't deliver message because Channel is full")]
+    Full(M),
+    /// The inbox is closed.
+    #[error("Couldn't deliver message because Channel is closed")]
+    Closed(M),
+    /// The message was sent, but dropped before the actor received it. This is only returned for
+    /// [`DeliveryMode::Confirmed`].
+    #[error("Couldn't deliver message because it was dropped before being received")]
+    Dropped,
+}
+
+impl<M> Debug for DeliveryError<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("Full(..)"),
+            Self::Closed(_) => f.write_str("Closed(..)"),
+            Self::Dropped => f.write_str("Dropped"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn confirmed_waits_until_received() {
+        let (child, address) = spawn_with(
+            Link::default(),
+            Capacity::Bounded(2),
+            |mut inbox: Inbox<U32Protocol>| async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                let U32Protocol::U32(n) = inbox.recv().await.unwrap();
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                n
+            },
+        );
+        address
+            .deliver(1u32, DeliveryMode::Confirmed)
+            .await
+            .unwrap();
+        assert_eq!(address.msg_count(), 0);
+        address.deliver(2u32, DeliveryMode::Blocking).await.unwrap();
+
+        // The actor exits without receiving the third message.
+        let (confirmed, best_effort) = tokio::join!(
+            address.deliver(3u32, DeliveryMode::Confirmed),
+            address.deliver(4u32, DeliveryMode::BestEffort)
+        );
+        assert!(matches!(confirmed, Err(DeliveryError::Dropped)));
+        assert!(matches!(best_effort, Err(DeliveryError::Full(4))));
+        assert_eq!(child.await.unwrap(), 1);
+    }
+}
@@ -10,6 +10,14 @@
 //! The address is a cloneable reference to the actor that can be shared with other processes to allow 
 //! them to communicate.
 //! 
+//! An address can be rate-limited with [`Address::with_rate_limit`], which returns a [`RateLimitedAddress`]
+//! that delays or drops messages sent in excess of the limit. With [`Address::send_lazy`], a message is
+//! only built once the inbox has space for it, and with [`Address::deliver`] the [`DeliveryMode`] can be
+//! chosen per message, for example to wait until the actor has received it.
+//! 
+//! Messages can be distributed over a group of workers in proportion to their weights with a
+//! [`WeightedRouter`], for example when some of them run on faster cores.
+//! 
 //! # Monitoring
 //! An actor can be monitored using it's [`Child`] or [`Address`] by awaiting them. When the actor exits,
 //! it will notify the child and address and they return a value; a `Child<E, _>` returns a
@@ -45,6 +53,10 @@
 //! - `Child<_, _>` = `Child<_, _, SingleProcess>`
 //! - `ChildPool<_, _>` = `Child<_, _, MultiProcess>`
 //! 
+//! # Registry
+//! Addresses can be registered under a name with a [`Registry`], so that other actors can look them
+//! up. Names are deregistered automatically when the actor exits.
+//! 
 //! | __<--__ [`messaging`] | [`actor_type`] __-->__ |
 //! |---|---|
 //! 
@@ -58,11 +70,31 @@ use crate::{all::*, *};
 
 mod actor_ref;
 mod address;
+mod address_sink;
+mod broadcast;
 mod child;
 mod child_type;
+mod delivery;
+mod rate_limit;
+mod registry;
+mod resize;
+mod sealed;
+mod send_lazy;
 mod shutdown;
+mod weak_child_pool;
+mod weighted;
 pub use actor_ref::*;
 pub use address::*;
+pub use address_sink::*;
+pub use broadcast::*;
 pub use child::*;
 pub use child_type::*;
+pub use delivery::*;
+pub use rate_limit::*;
+pub use registry::*;
+pub use resize::*;
+pub use sealed::*;
+pub use send_lazy::*;
 pub use shutdown::*;
+pub use weak_child_pool::*;
+pub use weighted::*;
@@ -0,0 +1,336 @@
+use crate::all::*;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::time::Instant;
+
+impl<A: ActorType> Address<A> {
+    /// Limit the rate at which messages can be sent with this address to `per_second`, see
+    /// [`RateLimitedAddress`].
+    ///
+    /// Panics if `per_second` is `0`.
+    pub fn with_rate_limit(self, per_second: u32) -> RateLimitedAddress<A> {
+        RateLimitedAddress::new(self, per_second)
+    }
+}
+
+/// What a [`RateLimitedAddress`] does with messages sent in excess of the rate-limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RateLimitMode {
+    /// Sending waits until the message is within the rate-limit.
+    #[default]
+    Delay,
+    /// Sending fails with [`RateLimitedError::RateLimited`].
+    Drop,
+}
+
+/// An [`Address`] that limits the rate at which messages are delivered to the actor, independent of
+/// the capacity of it's inbox. This is implemented as a token-bucket, which is shared by all
+/// clones of the address.
+///
+/// Excess messages are either delayed or dropped depending on the [`RateLimitMode`]. Only sending
+/// through this address is limited; other addresses of the actor are unaffected. A message that is
+/// not delivered, because the inbox is full or closed or because sending was cancelled, does not
+/// count towards the rate-limit.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::actor_reference::RateLimitMode;
+///
+/// # async fn main() {
+/// let (child, address) = spawn(|mut inbox: Inbox<()>| async move {
+///     while let Ok(_msg) = inbox.recv().await {}
+/// });
+/// let address = address.with_rate_limit(10).with_mode(RateLimitMode::Drop);
+///
+/// for _ in 0..10 {
+///     address.send(()).await.unwrap();
+/// }
+/// assert!(address.send(()).await.is_err());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RateLimitedAddress<A: ActorType> {
+    address: Address<A>,
+    bucket: Arc<Mutex<TokenBucket>>,
+    mode: RateLimitMode,
+}
+
+impl<A: ActorType> RateLimitedAddress<A> {
+    fn new(address: Address<A>, per_second: u32) -> Self {
+        assert!(per_second > 0);
+        Self {
+            address,
+            bucket: Arc::new(Mutex::new(TokenBucket::new(per_second, per_second))),
+            mode: RateLimitMode::default(),
+        }
+    }
+
+    /// Set the amount of messages that can be sent at once after a period of inactivity. By
+    /// default this is equal to the per-second rate.
+    ///
+    /// Panics if `burst` is `0`.
+    pub fn with_burst(self, burst: u32) -> Self {
+        assert!(burst > 0);
+        {
+            let mut bucket = self.bucket.lock().unwrap();
+            *bucket = TokenBucket::new(bucket.per_second, burst);
+        }
+        self
+    }
+
+    /// Set what happens with messages in excess of the rate-limit.
+    pub fn with_mode(mut self, mode: RateLimitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Whether excess messages are delayed or dropped.
+    pub fn mode(&self) -> RateLimitMode {
+        self.mode
+    }
+
+    /// The underlying address, which is not rate-limited.
+    pub fn address(&self) -> &Address<A> {
+        &self.address
+    }
+
+    /// Convert this back into an address, which is not rate-limited.
+    pub fn into_address(self) -> Address<A> {
+        self.address
+    }
+
+    /// Attempt to send a message to the actor without waiting.
+    ///
+    /// This fails with [`RateLimitedError::RateLimited`] if the rate-limit is exceeded, regardless
+    /// of the [`RateLimitMode`].
+    pub fn try_send<M>(&self, msg: M) -> Result<M::Returned, RateLimitedError<M>>
+    where
+        M: Message,
+        A: Accepts<M>,
+    {
+        if !self.bucket.lock().unwrap().try_take(Instant::now()) {
+            return Err(RateLimitedError::RateLimited(msg));
+        }
+        let token = Token(Some(&self.bucket));
+        let returned = self.address.try_send(msg).map_err(|e| match e {
+            TrySendError::Closed(msg) => RateLimitedError::Closed(msg),
+            TrySendError::Full(msg) => RateLimitedError::Full(msg),
+        })?;
+        token.spend();
+        Ok(returned)
+    }
+
+    /// Send a message to the actor, see [`ActorRefExt::send`].
+    ///
+    /// If the rate-limit is exceeded, this either waits until the message is within the limit or
+    /// fails with [`RateLimitedError::RateLimited`], depending on the [`RateLimitMode`].
+    pub async fn send<M>(&self, msg: M) -> Result<M::Returned, RateLimitedError<M>>
+    where
+        M: Message,
+        A: Accepts<M>,
+    {
+        let token = match self.mode {
+            RateLimitMode::Delay => {
+                let wait = self.bucket.lock().unwrap().reserve(Instant::now());
+                let token = Token(Some(&self.bucket));
+                if !wait.is_zero() {
+                    tokio::time::sleep(wait).await;
+                }
+                token
+            }
+            RateLimitMode::Drop => {
+                if !self.bucket.lock().unwrap().try_take(Instant::now()) {
+                    return Err(RateLimitedError::RateLimited(msg));
+                }
+                Token(Some(&self.bucket))
+            }
+        };
+        let returned = self
+            .address
+            .send(msg)
+            .await
+            .map_err(|SendError(msg)| RateLimitedError::Closed(msg))?;
+        token.spend();
+        Ok(returned)
+    }
+}
+
+impl<A: ActorType> Clone for RateLimitedAddress<A> {
+    fn clone(&self) -> Self {
+        Self {
+            address: self.address.clone(),
+            bucket: self.bucket.clone(),
+            mode: self.mode,
+        }
+    }
+}
+
+/// Error returned when sending a message with a [`RateLimitedAddress`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum RateLimitedError<M> {
+    /// The message would exceed the rate-limit.
+    #[error("Couldn't send message because the rate-limit was exceeded")]
+    RateLimited(M),
+    /// The channel is full.
+    #[error("Couldn't send message because Channel is full")]
+    Full(M),
+    /// The channel has been closed, and no longer accepts new messages.
+    #[error("Couldn't send message because Channel is closed")]
+    Closed(M),
+}
+
+/// A token taken from the [`TokenBucket`], which is put back when dropped unless it has been
+/// spent on delivering a message.
+struct Token<'a>(Option<&'a Mutex<TokenBucket>>);
+
+impl Token<'_> {
+    fn spend(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for Token<'_> {
+    fn drop(&mut self) {
+        if let Some(Ok(mut bucket)) = self.0.map(Mutex::lock) {
+            bucket.put_back();
+        }
+    }
+}
+
+/// A token-bucket refilling at `per_second` tokens, up to `burst` tokens.
+#[derive(Debug)]
+struct TokenBucket {
+    per_second: u32,
+    burst: u32,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_second: u32, burst: u32) -> Self {
+        Self {
+            per_second,
+            burst,
+            tokens: burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.per_second as f64).min(self.burst as f64);
+        self.last_refill = now;
+    }
+
+    /// Take a token if one is available.
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Take a token, returning how long to wait until it is available.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.per_second as f64)
+        }
+    }
+
+    /// Put back a token that was taken or reserved, but not used.
+    fn put_back(&mut self) {
+        self.tokens = (self.tokens + 1.0).min(self.burst as f64);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::basic_actor;
+
+    #[test]
+    fn token_bucket_refills() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10, 2);
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        assert!(!bucket.try_take(start));
+        assert!(bucket.try_take(start + Duration::from_millis(100)));
+        assert!(!bucket.try_take(start + Duration::from_millis(100)));
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn delayed_sends() {
+        let (_child, address) = spawn(basic_actor!());
+        let address = address.with_rate_limit(100).with_burst(1);
+        assert_eq!(address.mode(), RateLimitMode::Delay);
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            address.send(()).await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn dropped_sends() {
+        let (_child, address) = spawn(basic_actor!());
+        let address = address
+            .with_rate_limit(1)
+            .with_burst(2)
+            .with_mode(RateLimitMode::Drop);
+        let clone = address.clone();
+
+        address.send(()).await.unwrap();
+        clone.try_send(()).unwrap();
+        assert_eq!(
+            address.send(()).await,
+            Err(RateLimitedError::RateLimited(()))
+        );
+        assert_eq!(clone.try_send(()), Err(RateLimitedError::RateLimited(())));
+    }
+
+    #[tokio::test]
+    async fn undelivered_sends_put_back_their_token() {
+        let (_child, address) = spawn(basic_actor!());
+        let address = address
+            .with_rate_limit(1)
+            .with_burst(1)
+            .with_mode(RateLimitMode::Drop);
+        address.address().halt();
+        address.address().clone().await;
+
+        assert_eq!(address.try_send(()), Err(RateLimitedError::Closed(())));
+        assert_eq!(address.send(()).await, Err(RateLimitedError::Closed(())));
+        assert_eq!(address.try_send(()), Err(RateLimitedError::Closed(())));
+    }
+
+    #[tokio::test]
+    async fn cancelled_delay_puts_back_token() {
+        let (_child, address) = spawn(basic_actor!());
+        let address = address.with_rate_limit(10).with_burst(1);
+        address.send(()).await.unwrap();
+
+        let send = tokio::time::timeout(Duration::from_millis(10), address.send(())).await;
+        assert!(send.is_err());
+        assert!(address.bucket.lock().unwrap().tokens >= 0.0);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{all::*, DynActor};
+use futures::{stream::FuturesUnordered, StreamExt};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Debug,
+    marker::PhantomData,
+};
+use thiserror::Error;
+
+/// A handle to a registry-actor, where addresses can be registered under a name so that other
+/// actors can look them up.
+///
+/// The registry is an actor itself, spawned with [`Registry::spawn`], and every method sends a
+/// request to it. The handle can be cloned freely. When a registered actor exits, it's name is
+/// deregistered automatically.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, actor_reference::Registry};
+///
+/// # async fn main() {
+/// let (_registry_child, registry) = Registry::<Inbox<()>>::spawn();
+/// let (child, address) = spawn(|mut inbox: Inbox<()>| async move { inbox.recv().await });
+///
+/// registry.register("worker", address).await.unwrap();
+/// assert!(registry.lookup("worker").await.unwrap().is_some());
+///
+/// child.halt();
+/// child.await.unwrap().unwrap_err();
+/// # tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+/// assert!(registry.lookup("worker").await.unwrap().is_none());
+/// # }
+/// ```
+pub struct Registry<A: ActorType + 'static> {
+    address: Address<Inbox<RegistryProtocol<A>>>,
+}
+
+impl<A: ActorType + 'static> Registry<A> {
+    /// Spawn a new registry-actor.
+    pub fn spawn() -> (Child<()>, Self) {
+        let (child, address) = spawn(run_registry::<A>);
+        (child.transform_into(), Self { address })
+    }
+
+    /// Register the address under the name.
+    ///
+    /// This fails if another actor is already registered under the same name.
+    pub async fn register(
+        &self,
+        name: impl Into<String>,
+        address: Address<A>,
+    ) -> Result<(), RegistryError> {
+        let msg = Register {
+            name: name.into(),
+            address,
+        };
+        match self.address.request(msg).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(RegistryError::NameTaken),
+            Err(_) => Err(RegistryError::Exited),
+        }
+    }
+
+    /// Look up the address registered under the name.
+    pub async fn lookup(
+        &self,
+        name: impl Into<String>,
+    ) -> Result<Option<Address<A>>, RegistryError> {
+        let msg = Lookup(name.into(), PhantomData);
+        self.address
+            .request(msg)
+            .await
+            .map_err(|_| RegistryError::Exited)
+    }
+
+    /// Deregister the name, returning the address that was registered under it.
+    pub async fn deregister(
+        &self,
+        name: impl Into<String>,
+    ) -> Result<Option<Address<A>>, RegistryError> {
+        let msg = Deregister(name.into(), PhantomData);
+        self.address
+            .request(msg)
+            .await
+            .map_err(|_| RegistryError::Exited)
+    }
+
+    /// Get the address of the registry-actor.
+    pub fn address(&self) -> Address<DynActor!()> {
+        self.address.clone().transform_into()
+    }
+}
+
+impl<A: ActorType + 'static> Clone for Registry<A> {
+    fn clone(&self) -> Self {
+        Self {
+            address: self.address.clone(),
+        }
+    }
+}
+
+impl<A: ActorType + 'static> Debug for Registry<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registry")
+            .field("actor_id", &self.address.actor_id())
+            .finish()
+    }
+}
+
+/// Error returned when using a [`Registry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum RegistryError {
+    /// Another actor is already registered under the name.
+    #[error("Couldn't register because the name is already taken")]
+    NameTaken,
+    /// The registry-actor has exited.
+    #[error("Couldn't reach the registry because it has exited")]
+    Exited,
+}
+
+//------------------------------------------------------------------------------------------------
+//  Registry actor
+//------------------------------------------------------------------------------------------------
+
+#[derive(Message)]
+#[request(bool)]
+struct Register<A: ActorType + 'static> {
+    name: String,
+    address: Address<A>,
+}
+
+#[derive(Message)]
+#[request(Option<Address<A>>)]
+struct Lookup<A: ActorType + 'static>(String, PhantomData<fn() -> A>);
+
+#[derive(Message)]
+#[request(Option<Address<A>>)]
+struct Deregister<A: ActorType + 'static>(String, PhantomData<fn() -> A>);
+
+#[protocol]
+enum RegistryProtocol<A: ActorType + 'static> {
+    Register(Register<A>),
+    Lookup(Lookup<A>),
+    Deregister(Deregister<A>),
+}
+
+async fn run_registry<A: ActorType + 'static>(mut inbox: Inbox<RegistryProtocol<A>>) {
+    let mut names: HashMap<String, Address<A>> = HashMap::new();
+    let mut exits = FuturesUnordered::new();
+
+    loop {
+        tokio::select! {
+            msg = inbox.recv() => match msg {
+                Ok(RegistryProtocol::Register((Register { name, address }, tx))) => {
+                    match names.entry(name) {
+                        Entry::Occupied(_) => {
+                            let _ = tx.send(false);
+                        }
+                        Entry::Vacant(entry) => {
+                            let exit = address.clone();
+                            let id = address.actor_id();
+                            let name = entry.key().clone();
+                            exits.push(async move {
+                                exit.await;
+                                (name, id)
+                            });
+                            entry.insert(address);
+                            let _ = tx.send(true);
+                        }
+                    }
+                }
+                Ok(RegistryProtocol::Lookup((Lookup(name, _), tx))) => {
+                    let _ = tx.send(names.get(&name).cloned());
+                }
+                Ok(RegistryProtocol::Deregister((Deregister(name, _), tx))) => {
+                    let _ = tx.send(names.remove(&name));
+                }
+                Err(_) => break,
+            },
+            Some((name, id)) = exits.next(), if !exits.is_empty() => {
+                // The name may have been registered again by another actor since.
+                if names.get(&name).map(|address| address.actor_id()) == Some(id) {
+                    names.remove(&name);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn names_are_unique() {
+        let (_child, registry) = Registry::<Inbox<()>>::spawn();
+        let (_child1, address1) = spawn(crate::_test::basic_actor!());
+        let (_child2, address2) = spawn(crate::_test::basic_actor!());
+
+        registry.register("a", address1.clone()).await.unwrap();
+        assert_eq!(
+            registry.register("a", address2).await,
+            Err(RegistryError::NameTaken)
+        );
+        let found = registry.lookup("a").await.unwrap().unwrap();
+        assert_eq!(found.actor_id(), address1.actor_id());
+
+        assert!(registry.deregister("a").await.unwrap().is_some());
+        assert!(registry.lookup("a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn exited_actors_are_deregistered() {
+        let (_child, registry) = Registry::<Inbox<()>>::spawn();
+        let (child1, address1) = spawn(crate::_test::basic_actor!());
+        let (_child2, address2) = spawn(crate::_test::basic_actor!());
+
+        registry.register("a", address1).await.unwrap();
+        child1.halt();
+        child1.await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(registry.lookup("a").await.unwrap().is_none());
+
+        registry.register("a", address2).await.unwrap();
+        assert!(registry.lookup("a").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn exited_registry_is_an_error() {
+        let (mut child, registry) = Registry::<Inbox<()>>::spawn();
+        child.halt();
+        (&mut child).await.unwrap();
+        assert!(matches!(
+            registry.lookup("a").await,
+            Err(RegistryError::Exited)
+        ));
+    }
+}
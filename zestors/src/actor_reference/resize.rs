@@ -0,0 +1,243 @@
+use crate::all::*;
+use futures::Future;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+/// Decides which processes are halted when a pool is shrunk with [`ChildPool::resize`].
+///
+/// A process of an [`Inbox`] only receives a halt when it tries to receive it's next message, or
+/// while it awaits [`Inbox::halted_or_closed`]. A process that watches for halts while it is
+/// handling a message could be halted halfway through, dropping the reply of that message. This
+/// policy makes that unlikely by only sending as many halts as there are idle processes, which
+/// are waiting to receive the halt. (A busy process that watches for halts can still win the race
+/// for a halt that was sent for an idle process.)
+///
+/// If processes are still busy after the `timeout`, the remaining processes are halted without
+/// taking the policy into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResizePolicy {
+    /// Only halt as many processes as are idle, and wait for busy processes to become idle
+    /// before halting the rest. (default = `true`)
+    pub prefer_idle: bool,
+    /// Wait until all queued messages have been received before halting any processes, so
+    /// that the full pool drains the queue. (default = `false`)
+    pub drain_before_halt: bool,
+    /// The maximum time to wait for the policy. (default = `1 sec`)
+    pub timeout: Duration,
+}
+
+impl Default for ResizePolicy {
+    fn default() -> Self {
+        Self {
+            prefer_idle: true,
+            drain_before_halt: false,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// # Methods for resizing a pool with an [`Inbox`].
+impl<E, P> ChildPool<E, Inbox<P>>
+where
+    E: Send + 'static,
+    P: Protocol + Send,
+{
+    /// The amount of processes that are busy processing a message, see
+    /// [`InboxChannel::busy_count`].
+    pub fn busy_count(&self) -> usize {
+        <Self as ActorRef>::channel_ref(self).busy_count()
+    }
+
+    /// The ids of the processes of which the inbox still exists, see [`Inbox::process_id`].
+    pub fn process_ids(&self) -> Vec<ProcessId> {
+        <Self as ActorRef>::channel_ref(self).process_ids()
+    }
+
+    /// Halt only the processes of which the id matches the predicate, leaving the others running,
+    /// and return how many were halted.
+    ///
+    /// Halts that are sent to the pool can normally be received by any of it's processes, since
+    /// they share a single inbox. These halts are instead only received by the [`Inbox`] of the
+    /// matching process, see [`Inbox::process_id`].
+    pub fn halt_where(&mut self, pred: impl Fn(ProcessId) -> bool) -> usize {
+        let channel = <Self as ActorRef>::channel_ref(self);
+        let process_ids = channel
+            .process_ids()
+            .into_iter()
+            .filter(|process_id| pred(*process_id))
+            .collect::<Vec<_>>();
+        let count = process_ids.len();
+        channel.halt_processes(process_ids);
+        count
+    }
+
+    /// Resize the pool to `size` processes.
+    ///
+    /// When growing, new processes are spawned with `fun`. When shrinking, processes are halted
+    /// using the [`ResizePolicy`]; this completes once all halts have been sent, which can take
+    /// up to the [`ResizePolicy::timeout`]. The processes exit on their own after receiving the
+    /// halt, and their exits can be received from the pool as usual.
+    ///
+    /// This fails if the actor has exited while growing.
+    pub async fn resize<Fun, Fut>(
+        &mut self,
+        size: usize,
+        policy: ResizePolicy,
+        fun: Fun,
+    ) -> Result<(), SpawnError<Fun>>
+    where
+        Fun: FnOnce(Inbox<P>) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = E> + Send + 'static,
+    {
+        let channel = <Self as ActorRef>::channel_ref(self).clone();
+        let spawn_fun = fun.clone();
+        let spawn_onto = move || self.spawn_onto(spawn_fun.clone()).is_ok();
+        match resize_channel(&channel, size, policy, spawn_onto).await {
+            true => Ok(()),
+            false => Err(SpawnError(fun)),
+        }
+    }
+}
+
+/// # Methods for resizing a weak pool with an [`Inbox`].
+impl<E, P> WeakChildPool<E, Inbox<P>>
+where
+    E: Send + 'static,
+    P: Protocol + Send,
+{
+    /// Resize the pool to `size` processes, see [`ChildPool::resize`].
+    ///
+    /// This fails if the pool has been dropped, or if the actor has exited while growing.
+    pub async fn resize<Fun, Fut>(
+        &self,
+        size: usize,
+        policy: ResizePolicy,
+        fun: Fun,
+    ) -> Result<(), SpawnError<Fun>>
+    where
+        Fun: FnOnce(Inbox<P>) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = E> + Send + 'static,
+    {
+        let channel = <Self as ActorRef>::channel_ref(self).clone();
+        let spawn_fun = fun.clone();
+        let spawn_onto = move || self.spawn_onto(spawn_fun.clone()).is_ok();
+        match resize_channel(&channel, size, policy, spawn_onto).await {
+            true => Ok(()),
+            false => Err(SpawnError(fun)),
+        }
+    }
+}
+
+/// Resize the actor of the channel to `size` processes, growing it with `spawn_onto`.
+///
+/// Returns `false` if a process could not be spawned.
+async fn resize_channel<P: Protocol>(
+    channel: &InboxChannel<P>,
+    size: usize,
+    policy: ResizePolicy,
+    mut spawn_onto: impl FnMut() -> bool,
+) -> bool {
+    // The processes that have not been halted yet.
+    let live = || {
+        channel
+            .process_count()
+            .saturating_sub(channel.pending_halt_count())
+    };
+
+    for _ in live()..size {
+        if !spawn_onto() {
+            return false;
+        }
+    }
+
+    let deadline = Instant::now() + policy.timeout;
+    while live() > size {
+        let listener = channel.get_idle_listener();
+        let remaining = live() - size;
+        let halts = if policy.drain_before_halt && channel.msg_count() > 0 {
+            0
+        } else if policy.prefer_idle {
+            live().saturating_sub(channel.busy_count()).min(remaining)
+        } else {
+            remaining
+        };
+
+        if halts > 0 {
+            channel.halt_some(halts as u32);
+            continue;
+        }
+        tokio::select! {
+            () = listener => (),
+            () = sleep_until(deadline) => {
+                channel.halt_some(remaining as u32);
+                break;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn busy_process_is_halted_after_it_replies() {
+        let worker = |mut inbox: Inbox<U32Protocol>| async move {
+            let mut handled = 0;
+            while let Ok(U32Protocol::U32(n)) = inbox.recv().await {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                handled += n;
+            }
+            handled
+        };
+        let (mut pool, address) = spawn_many(0..2, move |_, inbox| worker(inbox));
+        address.send(7u32).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(pool.busy_count(), 1);
+
+        pool.resize(1, ResizePolicy::default(), worker)
+            .await
+            .unwrap();
+        assert_eq!(pool.next().await.unwrap().unwrap(), 0);
+
+        pool.resize(0, ResizePolicy::default(), worker)
+            .await
+            .unwrap();
+        assert_eq!(pool.busy_count(), 0);
+        assert_eq!(pool.next().await.unwrap().unwrap(), 7);
+
+        pool.resize(1, ResizePolicy::default(), worker)
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn only_matching_processes_are_halted() {
+        let (mut pool, _address) =
+            spawn_many(0..4, |_, mut inbox: Inbox<U32Protocol>| async move {
+                while inbox.recv().await.is_ok() {}
+                inbox.process_id()
+            });
+        let process_ids = pool.process_ids();
+        assert_eq!(process_ids.len(), 4);
+
+        let halted = &process_ids[1..3];
+        assert_eq!(
+            pool.halt_where(|process_id| halted.contains(&process_id)),
+            2
+        );
+        let mut exited = vec![
+            pool.next().await.unwrap().unwrap(),
+            pool.next().await.unwrap().unwrap(),
+        ];
+        exited.sort();
+        assert_eq!(exited, halted);
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(pool.process_count(), 2);
+        assert_eq!(pool.process_ids(), [process_ids[0], process_ids[3]]);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::all::*;
+use futures::{future::BoxFuture, Future};
+use std::any::TypeId;
+
+/// A dynamic actor-reference of which the accepted messages are frozen, created with
+/// [`Transformable::seal`].
+///
+/// A sealed reference can only be transformed into actor-types that accept fewer messages, never
+/// into ones that accept more; this is checked against the sealed actor-type instead of against
+/// the actor itself. This makes it possible to hand out a reference to less-trusted code, without
+/// it being able to send messages outside of the sealed set.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, protocol, actor_type::DynActor};
+///
+/// #[protocol]
+/// enum MyProtocol {
+///     A(u32),
+///     B(u64),
+/// }
+///
+/// # async fn main() {
+/// let (child, address) = spawn(|_inbox: Inbox<MyProtocol>| async move { () });
+/// let sealed = address.transform_into::<DynActor!(u32)>().seal();
+///
+/// // Transforming into an actor-type that accepts more messages fails.
+/// let sealed = sealed.try_transform_into::<DynActor!(u32, u64)>().unwrap_err();
+/// assert!(sealed.try_transform_into::<DynActor!()>().is_ok());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SealedDyn<R>(R);
+
+impl<R> SealedDyn<R>
+where
+    R: Transformable,
+    R::ActorType: DynActorType,
+{
+    pub(crate) fn new(actor_ref: R) -> Self {
+        Self(actor_ref)
+    }
+
+    fn channel(&self) -> &<R::ActorType as ActorType>::Channel {
+        &**R::channel_ref(&self.0)
+    }
+
+    /// The type-ids of all messages this sealed reference accepts.
+    pub fn accepted_types(&self) -> Box<[TypeId]> {
+        <R::ActorType as DynActorType>::msg_ids()
+    }
+
+    /// Whether this sealed reference accepts a [`Message`] of this type-id.
+    pub fn accepts(&self, id: &TypeId) -> bool {
+        self.accepted_types().contains(id)
+    }
+
+    /// Transform into an actor-type that accepts fewer messages, checking at compile-time.
+    pub fn transform_into<T>(self) -> SealedDyn<R::IntoRef<T>>
+    where
+        R::ActorType: TransformInto<T>,
+        T: DynActorType,
+    {
+        SealedDyn(self.0.transform_into())
+    }
+
+    /// Try to transform into another actor-type, checking at runtime whether all messages of `T`
+    /// are part of the sealed set.
+    ///
+    /// This fails if `T` accepts any message not accepted by this sealed reference.
+    pub fn try_transform_into<T>(self) -> Result<SealedDyn<R::IntoRef<T>>, Self>
+    where
+        T: DynActorType,
+    {
+        let accepted = self.accepted_types();
+        if T::msg_ids().iter().all(|id| accepted.contains(id)) {
+            Ok(SealedDyn(self.0.transform_unchecked_into()))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// See [`ActorRefExt::try_send`].
+    pub fn try_send<M>(&self, msg: M) -> Result<M::Returned, TrySendError<M>>
+    where
+        M: Message,
+        R::ActorType: Accepts<M>,
+    {
+        <R::ActorType as Accepts<M>>::try_send(self.channel(), msg)
+    }
+
+    /// See [`ActorRefExt::force_send`].
+    pub fn force_send<M>(&self, msg: M) -> Result<M::Returned, TrySendError<M>>
+    where
+        M: Message,
+        R::ActorType: Accepts<M>,
+    {
+        <R::ActorType as Accepts<M>>::force_send(self.channel(), msg)
+    }
+
+    /// See [`ActorRefExt::send_blocking`].
+    pub fn send_blocking<M>(&self, msg: M) -> Result<M::Returned, SendError<M>>
+    where
+        M: Message,
+        R::ActorType: Accepts<M>,
+    {
+        <R::ActorType as Accepts<M>>::send_blocking(self.channel(), msg)
+    }
+
+    /// See [`ActorRefExt::send`].
+    pub fn send<M>(&self, msg: M) -> <R::ActorType as Accepts<M>>::SendFut<'_>
+    where
+        M: Message,
+        R::ActorType: Accepts<M>,
+    {
+        <R::ActorType as Accepts<M>>::send(self.channel(), msg)
+    }
+
+    /// See [`ActorRefExt::request`].
+    pub fn request<M, F, E, Rep>(&self, msg: M) -> BoxFuture<'_, Result<Rep, RequestError<M, E>>>
+    where
+        M: Message<Returned = F> + Send + 'static,
+        F: Future<Output = Result<Rep, E>> + Send,
+        R::ActorType: Accepts<M>,
+    {
+        <R::ActorType as AcceptsExt<M>>::request(self.channel(), msg)
+    }
+
+    /// The [`ActorId`] of this actor.
+    pub fn actor_id(&self) -> ActorId {
+        self.channel().actor_id()
+    }
+
+    /// Whether the channel of this actor is closed.
+    pub fn is_closed(&self) -> bool {
+        self.channel().is_closed()
+    }
+
+    /// Whether all processes of this actor have exited.
+    pub fn has_exited(&self) -> bool {
+        self.channel().has_exited()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::basic_actor;
+
+    #[protocol]
+    enum TwoProtocol {
+        A(u32),
+        B(u64),
+    }
+
+    #[tokio::test]
+    async fn sealed_can_only_narrow() {
+        let (_child, address) = spawn(basic_actor!(TwoProtocol));
+
+        let unsealed = address.clone().into_dyn();
+        assert!(unsealed.try_transform_into::<DynActor!(u32, u64)>().is_ok());
+
+        let sealed = address.transform_into::<DynActor!(u32)>().seal();
+        assert!(sealed.accepts(&TypeId::of::<u32>()));
+        assert!(!sealed.accepts(&TypeId::of::<u64>()));
+        sealed.try_send(10u32).unwrap();
+
+        let sealed = sealed.try_transform_into::<DynActor!(u64)>().unwrap_err();
+        let sealed = sealed
+            .try_transform_into::<DynActor!(u32, u64)>()
+            .unwrap_err();
+        let sealed = sealed.try_transform_into::<DynActor!(u32)>().unwrap();
+        let sealed: SealedDyn<Address<DynActor!()>> = sealed.transform_into();
+        assert_eq!(sealed.accepted_types().len(), 0);
+        assert!(sealed.try_transform_into::<DynActor!(u32)>().is_err());
+    }
+}
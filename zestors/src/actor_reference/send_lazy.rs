@@ -0,0 +1,170 @@
+use crate::all::*;
+use std::fmt::Debug;
+use thiserror::Error;
+
+impl<P: Protocol> Address<Inbox<P>> {
+    /// Send a message that is only built once the inbox has space for it.
+    ///
+    /// This first reserves space in the inbox, waiting until there is some, and only then calls
+    /// `f` to build the message. While waiting, no message exists yet, so this can be used for
+    /// messages that are expensive to build, or that should be built as late as possible.
+    ///
+    /// If the inbox is closed while waiting, `f` is returned without having been called.
+    ///
+    /// # Usage
+    /// ```
+    /// # tokio_test::block_on(main());
+    /// use zestors::{prelude::*, protocol};
+    ///
+    /// #[protocol]
+    /// enum NumberProtocol {
+    ///     Number(u32),
+    /// }
+    ///
+    /// # async fn main() {
+    /// let (child, address) = spawn(|mut inbox: Inbox<NumberProtocol>| async move {
+    ///     let NumberProtocol::Number(n) = inbox.recv().await.unwrap();
+    ///     n
+    /// });
+    /// address.send_lazy(|| 10u32).await.unwrap();
+    /// assert_eq!(child.await.unwrap(), 10);
+    /// # }
+    /// ```
+    pub async fn send_lazy<M, F>(&self, f: F) -> Result<M::Returned, SendLazyError<F, M>>
+    where
+        F: FnOnce() -> M,
+        P: FromPayload<M>,
+        M: Message,
+    {
+        match <Self as ActorRef>::channel_ref(self).reserve().await {
+            Ok(permit) => send_with_permit(permit, f).map_err(SendLazyError::ClosedAfterBuild),
+            Err(SendError(())) => Err(SendLazyError::Closed(f)),
+        }
+    }
+
+    /// Same as [`Address::send_lazy`], but fails with [`TrySendLazyError::Full`] instead of
+    /// waiting when there is no space.
+    pub fn try_send_lazy<M, F>(&self, f: F) -> Result<M::Returned, TrySendLazyError<F, M>>
+    where
+        F: FnOnce() -> M,
+        P: FromPayload<M>,
+        M: Message,
+    {
+        match <Self as ActorRef>::channel_ref(self).try_reserve() {
+            Ok(permit) => send_with_permit(permit, f).map_err(TrySendLazyError::ClosedAfterBuild),
+            Err(TrySendError::Full(())) => Err(TrySendLazyError::Full(f)),
+            Err(TrySendError::Closed(())) => Err(TrySendLazyError::Closed(f)),
+        }
+    }
+}
+
+/// Build the message and send it with the permit, returning the message if the inbox was closed
+/// in the meantime.
+fn send_with_permit<P, M, F>(permit: SendPermit<'_, P>, f: F) -> Result<M::Returned, M>
+where
+    F: FnOnce() -> M,
+    P: Protocol + FromPayload<M>,
+    M: Message,
+{
+    let (sends, returns, trace) = create_traced(f());
+    match traced(trace, || permit.send(P::from_payload(sends))) {
+        Ok(()) => Ok(returns),
+        Err(SendError(prot)) => Err(unwrap_then_cancel(prot, returns)),
+    }
+}
+
+/// Error returned by [`Address::send_lazy`].
+#[derive(Clone, PartialEq, Eq, Hash, Error)]
+pub enum SendLazyError<F, M> {
+    /// The inbox is closed, and the message has not been built.
+    #[error("Couldn't send message because Channel is closed")]
+    Closed(F),
+    /// The inbox was closed after space had been reserved, and the message has been built.
+    #[error("Couldn't send message because Channel closed after the message was built")]
+    ClosedAfterBuild(M),
+}
+
+impl<F, M> Debug for SendLazyError<F, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed(_) => f.write_str("Closed(..)"),
+            Self::ClosedAfterBuild(_) => f.write_str("ClosedAfterBuild(..)"),
+        }
+    }
+}
+
+/// Error returned by [`Address::try_send_lazy`].
+#[derive(Clone, PartialEq, Eq, Hash, Error)]
+pub enum TrySendLazyError<F, M> {
+    /// The inbox is full, and the message has not been built.
+    #[error("Couldn't send message because Channel is full")]
+    Full(F),
+    /// The inbox is closed, and the message has not been built.
+    #[error("Couldn't send message because Channel is closed")]
+    Closed(F),
+    /// The inbox was closed after space had been reserved, and the message has been built.
+    #[error("Couldn't send message because Channel closed after the message was built")]
+    ClosedAfterBuild(M),
+}
+
+impl<F, M> Debug for TrySendLazyError<F, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("Full(..)"),
+            Self::Closed(_) => f.write_str("Closed(..)"),
+            Self::ClosedAfterBuild(_) => f.write_str("ClosedAfterBuild(..)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn message_is_built_once_there_is_space() {
+        let (child, address) = spawn_with(
+            Link::default(),
+            Capacity::Bounded(1),
+            |mut inbox: Inbox<U32Protocol>| async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                let mut total = 0;
+                while let Ok(U32Protocol::U32(n)) = inbox.recv().await {
+                    total += n;
+                }
+                total
+            },
+        );
+        address.try_send_lazy(|| 1u32).unwrap();
+        assert!(matches!(
+            address.try_send_lazy(|| -> u32 { panic!("inbox is full") }),
+            Err(TrySendLazyError::Full(_))
+        ));
+
+        // Other senders can not take space that has been reserved.
+        let channel = <Address<Inbox<U32Protocol>> as ActorRef>::channel_ref(&address);
+        let permit = channel.reserve().await.unwrap();
+        assert_eq!(address.try_send(2u32), Err(TrySendError::Full(2)));
+        assert!(permit.send(U32Protocol::U32(2)).is_ok());
+
+        address.send_lazy(|| 3u32).await.unwrap();
+        address.close();
+        assert_eq!(child.await.unwrap(), 6);
+    }
+
+    #[tokio::test]
+    async fn closed_inbox_returns_closure() {
+        let (_child, address) = spawn(|mut inbox: Inbox<U32Protocol>| async move {
+            let _ = inbox.recv().await;
+        });
+        address.close();
+        assert!(matches!(
+            address
+                .send_lazy(|| -> u32 { panic!("inbox is closed") })
+                .await,
+            Err(SendLazyError::Closed(_))
+        ));
+    }
+}
@@ -7,7 +7,7 @@ use std::{
     time::Duration,
 };
 use thiserror::Error;
-use tokio::time::Sleep;
+use tokio::{sync::watch, time::Sleep};
 
 //------------------------------------------------------------------------------------------------
 //  ShutdownFut
@@ -17,6 +17,7 @@ use tokio::time::Sleep;
 pub struct ShutdownFut<'a, E: Send + 'static, T: ActorType> {
     child: &'a mut Child<E, T>,
     sleep: Option<Pin<Box<Sleep>>>,
+    warning: Option<(AbortWarningTx, Duration)>,
 }
 
 impl<'a, E: Send + 'static, T: ActorType> ShutdownFut<'a, E, T> {
@@ -26,8 +27,19 @@ impl<'a, E: Send + 'static, T: ActorType> ShutdownFut<'a, E, T> {
         ShutdownFut {
             child,
             sleep: Some(Box::pin(tokio::time::sleep(duration))),
+            warning: None,
         }
     }
+
+    /// Before aborting the actor, first send a warning to the [`AbortWarning`]s and give the
+    /// actor the `grace` time to exit.
+    ///
+    /// This allows tasks to send a best-effort reply for in-flight requests before they are
+    /// aborted.
+    pub fn with_abort_warning(mut self, warning: AbortWarningTx, grace: Duration) -> Self {
+        self.warning = Some((warning, grace));
+        self
+    }
 }
 
 impl<'a, E: Send + 'static, T: ActorType> Unpin for ShutdownFut<'a, E, T> {}
@@ -40,11 +52,19 @@ impl<'a, E: Send + 'static, T: ActorType> Future for ShutdownFut<'a, E, T> {
             return Poll::Ready(res);
         }
 
-        if let Some(sleep) = &mut self.sleep {
+        while let Some(sleep) = &mut self.sleep {
             ready!(sleep.poll_unpin(cx));
-            self.child.abort();
-            self.sleep = None;
-        };
+            self.sleep = match self.warning.take() {
+                Some((warning, grace)) => {
+                    warning.warn();
+                    Some(Box::pin(tokio::time::sleep(grace)))
+                }
+                None => {
+                    self.child.abort();
+                    None
+                }
+            };
+        }
 
         Poll::Pending
     }
@@ -60,6 +80,7 @@ impl<'a, E: Send + 'static, T: ActorType> Future for ShutdownFut<'a, E, T> {
 pub struct ShutdownStream<'a, E: Send + 'static, T: ActorType> {
     pool: &'a mut ChildPool<E, T>,
     sleep: Option<Pin<Box<Sleep>>>,
+    warning: Option<(AbortWarningTx, Duration)>,
 }
 
 impl<'a, E: Send + 'static, T: ActorType> ShutdownStream<'a, E, T> {
@@ -69,8 +90,15 @@ impl<'a, E: Send + 'static, T: ActorType> ShutdownStream<'a, E, T> {
         ShutdownStream {
             pool,
             sleep: Some(Box::pin(tokio::time::sleep(duration))),
+            warning: None,
         }
     }
+
+    /// Same as [`ShutdownFut::with_abort_warning`], but for all processes of the pool.
+    pub fn with_abort_warning(mut self, warning: AbortWarningTx, grace: Duration) -> Self {
+        self.warning = Some((warning, grace));
+        self
+    }
 }
 
 impl<'a, E: Send + 'static, T: ActorType> Stream for ShutdownStream<'a, E, T> {
@@ -81,16 +109,72 @@ impl<'a, E: Send + 'static, T: ActorType> Stream for ShutdownStream<'a, E, T> {
             return Poll::Ready(item);
         }
 
-        if let Some(sleep) = &mut self.sleep {
+        while let Some(sleep) = &mut self.sleep {
             ready!(sleep.poll_unpin(cx));
-            self.pool.abort();
-            self.sleep = None;
-        };
+            self.sleep = match self.warning.take() {
+                Some((warning, grace)) => {
+                    warning.warn();
+                    Some(Box::pin(tokio::time::sleep(grace)))
+                }
+                None => {
+                    self.pool.abort();
+                    None
+                }
+            };
+        }
 
         Poll::Pending
     }
 }
 
+//------------------------------------------------------------------------------------------------
+//  AbortWarning
+//------------------------------------------------------------------------------------------------
+
+/// Create a new abort-warning channel, consisting of an [`AbortWarningTx`] and an
+/// [`AbortWarning`].
+///
+/// The `AbortWarningTx` can be passed to [`ShutdownFut::with_abort_warning`] or
+/// [`ShutdownStream::with_abort_warning`], while the `AbortWarning` is given to the processes.
+pub fn new_abort_warning() -> (AbortWarningTx, AbortWarning) {
+    let (tx, rx) = watch::channel(false);
+    (AbortWarningTx(tx), AbortWarning(rx))
+}
+
+/// The sending part of an abort-warning channel, created with [`new_abort_warning`].
+#[derive(Debug)]
+pub struct AbortWarningTx(watch::Sender<bool>);
+
+impl AbortWarningTx {
+    /// Warn all [`AbortWarning`]s that they are about to be aborted.
+    pub fn warn(&self) {
+        self.0.send_replace(true);
+    }
+}
+
+/// The receiving part of an abort-warning channel, created with [`new_abort_warning`].
+///
+/// This can be cloned and given to every process of an actor.
+#[derive(Debug, Clone)]
+pub struct AbortWarning(watch::Receiver<bool>);
+
+impl AbortWarning {
+    /// Wait until the warning has been sent. If the [`AbortWarningTx`] is dropped without
+    /// sending a warning, this never resolves.
+    pub async fn warned(&mut self) {
+        while !self.is_warned() {
+            if self.0.changed().await.is_err() {
+                futures::future::pending::<()>().await;
+            }
+        }
+    }
+
+    /// Whether the warning has been sent.
+    pub fn is_warned(&self) -> bool {
+        *self.0.borrow()
+    }
+}
+
 //------------------------------------------------------------------------------------------------
 //  ExitError
 //------------------------------------------------------------------------------------------------
@@ -122,6 +206,15 @@ impl ExitError {
             ExitError::Abort => true,
         }
     }
+
+    /// The [`PanicReport`] of the panic, if it was captured with
+    /// [`SupervisorBuilder::capture_panics`].
+    pub fn panic_report(&self) -> Option<&PanicReport> {
+        match self {
+            ExitError::Panic(payload) => payload.downcast_ref(),
+            ExitError::Abort => None,
+        }
+    }
 }
 
 impl From<tokio::task::JoinError> for ExitError {
@@ -148,7 +241,7 @@ mod test {
     async fn shutdown_success() {
         let (mut child, _addr) = spawn(basic_actor!());
         assert!(child
-            .shutdown_with(Duration::from_millis(5).into())
+            .shutdown_with(Duration::from_millis(5))
             .await
             .is_ok());
     }
@@ -159,9 +252,28 @@ mod test {
             pending::<()>().await;
         });
         assert!(matches!(
-            child.shutdown_with(Duration::from_millis(5).into()).await,
+            child.shutdown_with(Duration::from_millis(5)).await,
+            Err(ExitError::Abort)
+        ));
+    }
+
+    #[tokio::test]
+    async fn shutdown_abort_warning() {
+        let (warning_tx, mut warning) = new_abort_warning();
+        let (tx, rx) = new_request::<&str>();
+        let (mut child, _addr) = spawn(|_inbox: Inbox<()>| async move {
+            warning.warned().await;
+            tx.send("aborting").unwrap();
+            pending::<()>().await;
+        });
+        assert!(matches!(
+            child
+                .shutdown_with(Duration::from_millis(5))
+                .with_abort_warning(warning_tx, Duration::from_millis(5))
+                .await,
             Err(ExitError::Abort)
         ));
+        assert_eq!(rx.await, Ok("aborting"));
     }
 
     #[tokio::test]
@@ -169,7 +281,7 @@ mod test {
         let (mut child, _addr) = spawn_many(0..3, pooled_basic_actor!());
 
         let results = child
-            .shutdown_with(Duration::from_millis(5).into())
+            .shutdown_with(Duration::from_millis(5))
             .collect::<Vec<_>>()
             .await;
         assert_eq!(results.len(), 3);
@@ -186,7 +298,7 @@ mod test {
         });
 
         let results = child
-            .shutdown_with(Duration::from_millis(5).into())
+            .shutdown_with(Duration::from_millis(5))
             .collect::<Vec<_>>()
             .await;
         assert_eq!(results.len(), 3);
@@ -196,6 +308,26 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn shutdown_pool_abort_warning() {
+        let (warning_tx, warning) = new_abort_warning();
+        let (mut child, _addr) = spawn_many(0..3, move |_, _inbox: Inbox<()>| {
+            let mut warning = warning.clone();
+            async move { warning.warned().await }
+        });
+
+        let results = child
+            .shutdown_with(Duration::from_millis(5))
+            .with_abort_warning(warning_tx, Duration::from_millis(50))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 3);
+
+        for result in results {
+            assert!(result.is_ok());
+        }
+    }
+
     #[tokio::test]
     async fn shutdown_pool_mixed() {
         let (child, _addr) = spawn(|_inbox: Inbox<()>| async move {
@@ -213,7 +345,7 @@ mod test {
         child.spawn_onto(basic_actor!()).unwrap();
 
         let results = child
-            .shutdown_with(Duration::from_millis(5).into())
+            .shutdown_with(Duration::from_millis(5))
             .collect::<Vec<_>>()
             .await;
 
@@ -0,0 +1,149 @@
+use super::child::SpawnedHandles;
+use crate::{all::*, DynActor};
+use futures::Future;
+use std::sync::{Arc, Weak};
+
+/// A [reference](ActorRef) to a [`ChildPool`] that can spawn processes onto it without owning it,
+/// created with [`ChildPool::downgrade`].
+///
+/// The processes spawned through a weak pool are adopted by the [`ChildPool`]: their exits are
+/// received from it, and they are halted and aborted together with it's other processes. Once the
+/// [`ChildPool`] has been dropped, no processes can be spawned anymore.
+///
+/// Just like an [`Address`], a weak pool never keeps the actor alive.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use futures::StreamExt;
+/// use zestors::prelude::*;
+///
+/// # async fn main() {
+/// let (mut pool, _address) = spawn_many(0..1, |i, _inbox: Inbox<()>| async move { i });
+/// let weak_pool = pool.downgrade();
+/// weak_pool.spawn_onto(|_inbox: Inbox<()>| async move { 10 }).unwrap();
+///
+/// let mut exits = vec![pool.next().await.unwrap().unwrap(), pool.next().await.unwrap().unwrap()];
+/// exits.sort();
+/// assert_eq!(exits, vec![0, 10]);
+///
+/// drop(pool);
+/// assert!(weak_pool.spawn_onto(|_inbox: Inbox<()>| async move { 20 }).is_err());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WeakChildPool<E: Send + 'static, A: ActorType = DynActor!()> {
+    address: Address<A>,
+    spawned: Weak<SpawnedHandles<E>>,
+}
+
+impl<E: Send + 'static, A: ActorType> WeakChildPool<E, A> {
+    pub(crate) fn new(address: Address<A>, spawned: Weak<SpawnedHandles<E>>) -> Self {
+        Self { address, spawned }
+    }
+
+    /// Whether the [`ChildPool`] has been dropped.
+    pub fn is_dropped(&self) -> bool {
+        self.spawned.strong_count() == 0
+    }
+
+    /// Attempt to spawn an additional process onto the pool.
+    ///
+    /// This method fails if the pool has been dropped, or if the actor has already exited.
+    pub fn spawn_onto<Fun, Fut>(&self, fun: Fun) -> Result<(), SpawnError<Fun>>
+    where
+        Fun: FnOnce(A) -> Fut + Send + 'static,
+        Fut: Future<Output = E> + Send + 'static,
+        A: MultiProcessInbox,
+    {
+        let Some(spawned) = self.spawned.upgrade() else {
+            return Err(SpawnError(fun));
+        };
+        let channel = <Self as ActorRef>::channel_ref(self);
+        match channel.try_increment_process_count() {
+            Ok(_) => {
+                let inbox = A::from_channel(channel.clone());
+                let handle = tokio::task::spawn(CapturePanics::new(ActorContext::unnamed(
+                    channel.actor_id(),
+                    async move { fun(inbox).await },
+                )));
+                spawned.push(handle);
+                Ok(())
+            }
+            Err(AddProcessError::ActorHasExited) => Err(SpawnError(fun)),
+            Err(AddProcessError::SingleProcessOnly) => {
+                panic!("Error with implementation of the Inbox. This is a Bug, please report it.")
+            }
+        }
+    }
+}
+
+impl<E: Send + 'static, A: ActorType> Clone for WeakChildPool<E, A> {
+    fn clone(&self) -> Self {
+        Self {
+            address: self.address.clone(),
+            spawned: self.spawned.clone(),
+        }
+    }
+}
+
+impl<E: Send + 'static, A: ActorType> ActorRef for WeakChildPool<E, A> {
+    type ActorType = A;
+    fn channel_ref(this: &Self) -> &Arc<<Self::ActorType as ActorType>::Channel> {
+        <Address<A> as ActorRef>::channel_ref(&this.address)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn spawned_processes_are_adopted_by_pool() {
+        let (mut pool, _address) = spawn_many(0..1, |_, halter: MultiHalter| halter);
+        let weak_pool = pool.downgrade();
+        weak_pool.spawn_onto(|halter: MultiHalter| halter).unwrap();
+        assert_eq!(pool.handle_count(), 2);
+        assert_eq!(pool.task_count(), 2);
+
+        assert!(pool.abort());
+        for _ in 0..2 {
+            assert!(pool.next().await.unwrap().is_err());
+        }
+        assert!(pool.next().await.is_none());
+        assert!(pool.is_finished());
+    }
+
+    #[tokio::test]
+    async fn resize_through_weak_pool() {
+        let worker =
+            |mut inbox: Inbox<U32Protocol>| async move { while inbox.recv().await.is_ok() {} };
+        let (mut pool, address) = spawn_many(0..1, move |_, inbox| worker(inbox));
+        let weak_pool = pool.downgrade();
+
+        weak_pool
+            .resize(3, ResizePolicy::default(), worker)
+            .await
+            .unwrap();
+        assert_eq!(address.process_count(), 3);
+        weak_pool
+            .resize(1, ResizePolicy::default(), worker)
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            pool.next().await.unwrap().unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(address.process_count(), 1);
+
+        drop(pool);
+        assert!(weak_pool.is_dropped());
+        weak_pool
+            .resize(2, ResizePolicy::default(), worker)
+            .await
+            .unwrap_err();
+    }
+}
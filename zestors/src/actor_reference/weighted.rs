@@ -0,0 +1,225 @@
+use crate::all::*;
+use futures::Future;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// A group of [`Address`]es to which messages are distributed by weighted round-robin, so that a
+/// worker with weight `3` receives three times as many messages as a worker with weight `1`.
+///
+/// The processes of a [`ChildPool`] share a single inbox and can not be sent to separately, so
+/// every worker of the router is a separate actor, for example running on a faster core. The
+/// messages are interleaved as evenly as possible (smooth weighted round-robin), instead of
+/// sending all messages of one worker in a row. Workers with weight `0` don't receive any messages.
+/// The position of the router is shared by all clones.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, actor_reference::WeightedRouter};
+///
+/// # async fn main() {
+/// let (_fast, fast) = spawn(|mut inbox: Inbox<()>| async move {
+///     while let Ok(_msg) = inbox.recv().await {}
+/// });
+/// let (_slow, slow) = spawn(|mut inbox: Inbox<()>| async move {
+///     while let Ok(_msg) = inbox.recv().await {}
+/// });
+/// let router = WeightedRouter::new([(fast, 3), (slow, 1)]);
+///
+/// let mut chosen = Vec::new();
+/// for _ in 0..4 {
+///     chosen.push(router.send(()).await.unwrap().0);
+/// }
+/// assert_eq!(chosen, vec![0, 0, 1, 0]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WeightedRouter<A: ActorType> {
+    addresses: Vec<Address<A>>,
+    weights: Vec<u32>,
+    current: Arc<Mutex<Vec<i64>>>,
+}
+
+impl<A: ActorType> WeightedRouter<A> {
+    /// Create a new router from the addresses of the workers and their weights.
+    ///
+    /// Panics if the weights of all workers are `0`.
+    pub fn new(workers: impl IntoIterator<Item = (Address<A>, u32)>) -> Self {
+        let (addresses, weights): (Vec<_>, Vec<_>) = workers.into_iter().unzip();
+        assert!(weights.iter().any(|weight| *weight > 0));
+        Self {
+            current: Arc::new(Mutex::new(vec![0; weights.len()])),
+            addresses,
+            weights,
+        }
+    }
+
+    /// The addresses of the workers, in the order they were given.
+    pub fn addresses(&self) -> &[Address<A>] {
+        &self.addresses
+    }
+
+    /// The weights of the workers, in the order they were given.
+    pub fn weights(&self) -> &[u32] {
+        &self.weights
+    }
+
+    /// Choose the index of the worker that receives the next message, advancing the router.
+    pub fn next_index(&self) -> usize {
+        let mut current = self.current.lock().unwrap();
+        let total: i64 = self.weights.iter().map(|weight| *weight as i64).sum();
+        for (current, weight) in current.iter_mut().zip(&self.weights) {
+            *current += *weight as i64;
+        }
+        let (index, _) = current
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, current)| **current)
+            .unwrap();
+        current[index] -= total;
+        index
+    }
+
+    /// Attempt to send the message to the next worker without waiting, returning the index of the
+    /// chosen worker.
+    ///
+    /// A worker that is full or closed is not skipped, the message is returned in the error instead.
+    pub fn try_send<M>(&self, msg: M) -> Result<(usize, M::Returned), TrySendError<M>>
+    where
+        M: Message,
+        A: Accepts<M>,
+    {
+        let index = self.next_index();
+        let returned = self.addresses[index].try_send(msg)?;
+        Ok((index, returned))
+    }
+
+    /// Send the message to the next worker, waiting for space in it's inbox, and return the index
+    /// of the chosen worker.
+    pub async fn send<M>(&self, msg: M) -> Result<(usize, M::Returned), SendError<M>>
+    where
+        M: Message,
+        A: Accepts<M>,
+    {
+        let index = self.next_index();
+        let returned = self.addresses[index].send(msg).await?;
+        Ok((index, returned))
+    }
+
+    /// Send the request to the next worker and wait for it's reply, returning the index of the
+    /// worker that replied.
+    ///
+    /// If a worker does not reply within the `timeout`, or the request fails otherwise, the request
+    /// is retried against a different worker for up to `max_attempts` attempts in total. Only once
+    /// every worker has been tried, a worker is tried again. The error of the last attempt is
+    /// returned if all attempts fail.
+    ///
+    /// Since the processes of a [`ChildPool`] share a single inbox, a wedged process can only be
+    /// failed over from if every worker is a separate actor.
+    pub async fn request_failover<M, F, E, R>(
+        &self,
+        msg: M,
+        timeout: Duration,
+        max_attempts: usize,
+    ) -> Result<(usize, R), RequestError<M, E>>
+    where
+        M: Message<Returned = F> + Clone + Send + 'static,
+        F: Future<Output = Result<R, E>> + Send,
+        A: Accepts<M>,
+    {
+        let mut tried = vec![false; self.addresses.len()];
+        let mut attempt = 1;
+        loop {
+            if tried
+                .iter()
+                .zip(&self.weights)
+                .all(|(tried, weight)| *tried || *weight == 0)
+            {
+                tried.fill(false);
+            }
+            let index = loop {
+                let index = self.next_index();
+                if !tried[index] {
+                    break index;
+                }
+            };
+            tried[index] = true;
+
+            match self.addresses[index]
+                .request_timeout(msg.clone(), timeout)
+                .await
+            {
+                Ok(reply) => break Ok((index, reply)),
+                Err(e) if attempt >= max_attempts => break Err(e),
+                Err(_) => attempt += 1,
+            }
+        }
+    }
+}
+
+impl<A: ActorType> Clone for WeightedRouter<A> {
+    fn clone(&self) -> Self {
+        Self {
+            addresses: self.addresses.clone(),
+            weights: self.weights.clone(),
+            current: self.current.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::basic_actor;
+
+    #[tokio::test]
+    async fn workers_receive_proportional_load() {
+        let (children, addresses): (Vec<_>, Vec<_>) = (0..4).map(|_| spawn(basic_actor!())).unzip();
+        let router = WeightedRouter::new(addresses.into_iter().zip([3, 1, 0, 2]));
+
+        let mut counts = [0; 4];
+        for _ in 0..60 {
+            let (index, ()) = router.send(()).await.unwrap();
+            counts[index] += 1;
+        }
+        assert_eq!(counts, [30, 10, 0, 20]);
+        drop(children);
+    }
+
+    #[derive(Message, Debug, Clone)]
+    #[request(u32)]
+    struct Ping;
+
+    #[protocol]
+    enum PingProtocol {
+        Ping(Ping),
+    }
+
+    #[tokio::test]
+    async fn request_fails_over_to_another_worker() {
+        let (wedged_child, wedged) = spawn(|mut inbox: Inbox<PingProtocol>| async move {
+            let _msg = inbox.recv().await;
+            std::future::pending::<()>().await
+        });
+        let (healthy_child, healthy) = spawn(|mut inbox: Inbox<PingProtocol>| async move {
+            while let Ok(PingProtocol::Ping((Ping, tx))) = inbox.recv().await {
+                let _ = tx.send(1);
+            }
+        });
+        let router = WeightedRouter::new([(wedged, 1), (healthy, 1)]);
+
+        let timeout = Duration::from_millis(10);
+        assert_eq!(
+            router.request_failover(Ping, timeout, 2).await.unwrap(),
+            (1, 1)
+        );
+        assert!(matches!(
+            router.request_failover(Ping, timeout, 1).await,
+            Err(RequestError::Timeout { .. })
+        ));
+        drop((wedged_child, healthy_child));
+    }
+}
@@ -1,3 +1,5 @@
+#[allow(unused)]
+use crate::all::*;
 use std::{
     fmt::{Debug, Display},
     sync::atomic::{AtomicU64, Ordering},
@@ -24,7 +26,33 @@ impl ActorId {
 
 impl Display for ActorId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        <Self as Debug>::fmt(&self, f)
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// A process-id is an incrementally-generated id, unique per [`Inbox`] of a process.
+///
+/// The processes of a [`ChildPool`] share the [`ActorId`] of the pool, but each have their own
+/// process-id, see [`Inbox::process_id`].
+#[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct ProcessId(u64);
+
+impl ProcessId {
+    /// Generate a new unique process-id.
+    pub(crate) fn generate() -> Self {
+        static NEXT_PROCESS_ID: AtomicU64 = AtomicU64::new(0);
+        ProcessId(NEXT_PROCESS_ID.fetch_add(1, Ordering::AcqRel))
+    }
+
+    /// Convert the process-id to a u64.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for ProcessId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
     }
 }
 
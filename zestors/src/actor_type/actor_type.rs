@@ -6,7 +6,7 @@ use std::{any::TypeId, marker::PhantomData, sync::Arc};
 /// either be statically or dynamically typed:
 /// - __Static__: A static actor-type is defined as the [`InboxType`].
 /// - __Dynamic__: A dynamic actor-type is defined as a [`struct@DynActor<dyn _>`], usually written
-/// as [`DynActor!(Msg1, Msg2, ..]`)(DynActor!).
+///   as [`DynActor!(Msg1, Msg2, ..]`)(DynActor!).
 ///
 /// The actor-type is used as the generic parameter `A` in for example a [`Child<_, A, _>`] and
 /// an [`Address<A>`]. These can then be `transformed` into and between dynamic actor-types.
@@ -0,0 +1,165 @@
+use crate::all::*;
+use futures::{stream::FusedStream, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// An [`Inbox<P>`] with a [`Capacity::Bounded(N)`](Capacity::Bounded) that is part of the type.
+/// Since the capacity is fixed, the [`InboxType::Config`] is `()`, and the actor can be spawned
+/// with [`spawn_bounded`]. A capacity of `0` is rejected at compile-time.
+///
+/// This uses the same [`InboxChannel<P>`] as an [`Inbox<P>`], and accepts the same messages.
+/// A `BoundedInbox` can therefore be transformed into a [`DynActor!`] in the same way.
+/// Because the capacity is not part of the channel, a dynamic reference to this actor can be
+/// downcast into both an [`Inbox<P>`] and a `BoundedInbox<P, N>` of any `N`.
+#[derive(Debug)]
+pub struct BoundedInbox<P: Protocol, const N: usize>(Inbox<P>);
+
+impl<P: Protocol, const N: usize> BoundedInbox<P, N> {
+    const NON_ZERO: () = assert!(
+        N > 0,
+        "The capacity of a BoundedInbox must be larger than 0"
+    );
+
+    /// The capacity of this inbox.
+    pub const CAPACITY: usize = N;
+
+    fn from_channel(channel: Arc<InboxChannel<P>>) -> Self {
+        Self(Inbox::from_channel(channel))
+    }
+
+    /// Whether this inbox has been halted
+    pub fn halted(&self) -> bool {
+        self.0.halted()
+    }
+
+    /// Attempt to receive a message from the channel.
+    pub fn try_recv(&mut self) -> Result<P, TryRecvError> {
+        self.0.try_recv()
+    }
+
+    /// Receive a message from the channel, waiting for one to appear.
+    pub fn recv(&mut self) -> RecvFut<'_, P> {
+        self.0.recv()
+    }
+
+    /// Convert this into a regular [`Inbox<P>`].
+    pub fn into_inbox(self) -> Inbox<P> {
+        self.0
+    }
+}
+
+impl<P: Protocol + Send, const N: usize> InboxType for BoundedInbox<P, N> {
+    type Config = ();
+
+    fn init_single_inbox(
+        config: (),
+        address_count: usize,
+        actor_id: ActorId,
+    ) -> (Arc<Self::Channel>, Self) {
+        let channel = Self::init_multi_inbox(config, 1, address_count, actor_id);
+        (channel.clone(), Self::from_channel(channel))
+    }
+}
+
+impl<P: Protocol + Send, const N: usize> MultiProcessInbox for BoundedInbox<P, N> {
+    fn init_multi_inbox(
+        _config: (),
+        process_count: usize,
+        address_count: usize,
+        actor_id: ActorId,
+    ) -> Arc<Self::Channel> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::NON_ZERO;
+        Arc::new(InboxChannel::new(
+            address_count,
+            process_count,
+            Capacity::Bounded(N),
+            actor_id,
+        ))
+    }
+
+    fn from_channel(channel: Arc<Self::Channel>) -> Self {
+        Self::from_channel(channel)
+    }
+}
+
+impl<P: Protocol, const N: usize> ActorType for BoundedInbox<P, N> {
+    type Channel = InboxChannel<P>;
+}
+
+impl<P: Protocol, const N: usize> ActorRef for BoundedInbox<P, N> {
+    type ActorType = Self;
+
+    fn channel_ref(this: &Self) -> &Arc<<Self::ActorType as ActorType>::Channel> {
+        <Inbox<P> as ActorRef>::channel_ref(&this.0)
+    }
+}
+
+impl<P: Protocol, const N: usize> Stream for BoundedInbox<P, N> {
+    type Item = Result<P, Halted>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+impl<P: Protocol, const N: usize> FusedStream for BoundedInbox<P, N> {
+    fn is_terminated(&self) -> bool {
+        self.0.is_terminated()
+    }
+}
+
+impl<M, P, const N: usize> Accepts<M> for BoundedInbox<P, N>
+where
+    P: Protocol + FromPayload<M>,
+    M: Message,
+    M::Returned: Send,
+{
+    type SendFut<'a> = InboxSendFut<'a, P, M>;
+
+    fn try_send(channel: &Self::Channel, msg: M) -> Result<M::Returned, TrySendError<M>> {
+        <Inbox<P> as Accepts<M>>::try_send(channel, msg)
+    }
+
+    fn force_send(channel: &Self::Channel, msg: M) -> Result<M::Returned, TrySendError<M>> {
+        <Inbox<P> as Accepts<M>>::force_send(channel, msg)
+    }
+
+    fn send_blocking(channel: &Self::Channel, msg: M) -> Result<M::Returned, SendError<M>> {
+        <Inbox<P> as Accepts<M>>::send_blocking(channel, msg)
+    }
+
+    fn send(channel: &Self::Channel, msg: M) -> InboxSendFut<'_, P, M> {
+        <Inbox<P> as Accepts<M>>::send(channel, msg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn capacity_is_bounded() {
+        let (child, address) = spawn_bounded::<(), 2, _, _, _>(|_inbox| async move {
+            futures::future::pending::<()>().await
+        });
+        assert_eq!(address.capacity(), Capacity::Bounded(2));
+        address.try_send(()).unwrap();
+        address.try_send(()).unwrap();
+        assert!(matches!(address.try_send(()), Err(TrySendError::Full(()))));
+        child.halt();
+    }
+
+    #[tokio::test]
+    async fn transforms_into_dyn() {
+        let (child, address) =
+            spawn_bounded::<(), 4, _, _, _>(|mut inbox| async move { inbox.recv().await.unwrap() });
+        let address = address.transform_into::<DynActor!(())>();
+        address.send(()).await.unwrap();
+        assert!(child.await.is_ok());
+        assert!(address.downcast::<BoundedInbox<(), 4>>().is_ok());
+    }
+}
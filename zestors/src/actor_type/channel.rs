@@ -88,8 +88,8 @@ impl dyn Channel {
         &self,
         msg: M,
     ) -> Result<M::Returned, TrySendCheckedError<M>> {
-        let (sends, returns) = M::create(msg);
-        match self.try_send_box(BoxPayload::new::<M>(sends)) {
+        let (sends, returns, trace) = create_traced(msg);
+        match traced(trace, || self.try_send_box(BoxPayload::new::<M>(sends))) {
             Ok(()) => Ok(returns),
             Err(e) => match e {
                 TrySendCheckedError::Full(boxed) => Err(TrySendCheckedError::Full(
@@ -109,8 +109,8 @@ impl dyn Channel {
         &self,
         msg: M,
     ) -> Result<M::Returned, TrySendCheckedError<M>> {
-        let (sends, returns) = M::create(msg);
-        let res = self.force_send_box(BoxPayload::new::<M>(sends));
+        let (sends, returns, trace) = create_traced(msg);
+        let res = traced(trace, || self.force_send_box(BoxPayload::new::<M>(sends)));
 
         match res {
             Ok(()) => Ok(returns),
@@ -132,8 +132,10 @@ impl dyn Channel {
         &self,
         msg: M,
     ) -> Result<M::Returned, SendCheckedError<M>> {
-        let (sends, returns) = M::create(msg);
-        let res = self.send_box_blocking(BoxPayload::new::<M>(sends));
+        let (sends, returns, trace) = create_traced(msg);
+        let res = traced(trace, || {
+            self.send_box_blocking(BoxPayload::new::<M>(sends))
+        });
 
         match res {
             Ok(()) => Ok(returns),
@@ -154,8 +156,8 @@ impl dyn Channel {
         M: Message + Send + 'static,
     {
         Box::pin(async move {
-            let (sends, returns) = M::create(msg);
-            let res = self.send_box(BoxPayload::new::<M>(sends)).await;
+            let (sends, returns, trace) = create_traced(msg);
+            let res = trace_scope(trace, self.send_box(BoxPayload::new::<M>(sends))).await;
 
             match res {
                 Ok(()) => Ok(returns),
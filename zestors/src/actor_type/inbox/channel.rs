@@ -8,16 +8,19 @@ use std::{
     pin::Pin,
     sync::{
         atomic::{AtomicI32, AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     task::{ready, Context, Poll},
 };
-use tokio::time::Sleep;
+#[cfg(feature = "message-replay")]
+use std::sync::OnceLock;
+use tokio::{sync::oneshot, time::Sleep};
 
 /// A [Channel] with an inbox used to receive messages.
+#[allow(clippy::type_complexity)]
 pub struct InboxChannel<P> {
     /// The underlying queue
-    queue: ConcurrentQueue<P>,
+    queue: ConcurrentQueue<Queued<P>>,
     /// The capacity of the channel
     capacity: Capacity,
     /// The amount of addresses associated to this channel.
@@ -33,11 +36,27 @@ pub struct InboxChannel<P> {
     send_event: Event,
     /// Subscribe when waiting for Actor to exit.
     exit_event: Event,
+    /// Subscribe when waiting for a busy inbox to become idle.
+    idle_event: Event,
+    /// The amount of slots of a bounded queue that have been reserved by a [`SendPermit`].
+    reserved: AtomicUsize,
     /// The amount of processes that should still be halted.
     /// Can be negative bigger than amount of processes in total.
     halt_count: AtomicI32,
+    /// The processes of the inboxes associated to this channel.
+    processes: Mutex<Vec<ProcessId>>,
+    /// The processes that should be halted, set with [`InboxChannel::halt_processes`].
+    halted_processes: Mutex<Vec<ProcessId>>,
+    /// The amount of processes in `halted_processes`, so that it is only locked if there are any.
+    halted_process_count: AtomicUsize,
+    /// The amount of inboxes that have received a message, and have not tried to receive the
+    /// next one yet.
+    busy_count: AtomicUsize,
     /// The actor_id, generated once and cannot be changed afterwards.
     actor_id: ActorId,
+    /// Called with every message that is taken out of the channel, once it has been set.
+    #[cfg(feature = "message-replay")]
+    recorder: OnceLock<Box<dyn Fn(&P) + Send + Sync>>,
 }
 
 impl<P: Protocol> InboxChannel<P> {
@@ -61,8 +80,16 @@ impl<P: Protocol> InboxChannel<P> {
             recv_event: Event::new(),
             send_event: Event::new(),
             exit_event: Event::new(),
+            idle_event: Event::new(),
+            reserved: AtomicUsize::new(0),
             halt_count: AtomicI32::new(0),
+            processes: Mutex::new(Vec::new()),
+            halted_processes: Mutex::new(Vec::new()),
+            halted_process_count: AtomicUsize::new(0),
+            busy_count: AtomicUsize::new(0),
             actor_id,
+            #[cfg(feature = "message-replay")]
+            recorder: OnceLock::new(),
         }
     }
 
@@ -90,28 +117,101 @@ impl<P: Protocol> InboxChannel<P> {
             self.close();
             // Also notify the exit-listeners, since the process exited.
             self.exit_event.notify(usize::MAX);
-            // drop all messages, since no more inboxes exist.
-            while self.pop_msg().is_ok() {}
+            // drop all messages, since no more inboxes exist. Their acks are dropped, since
+            // they have not been received.
+            while self.queue.pop().is_ok() {}
         }
 
         prev_count
     }
 
+    /// Register the process of an inbox that has been created on this channel.
+    pub(crate) fn add_process(&self, process_id: ProcessId) {
+        self.processes.lock().unwrap().push(process_id);
+    }
+
+    /// Deregister the process of an inbox that has been dropped, including any of it's halts
+    /// that have not been received.
+    pub(crate) fn remove_process(&self, process_id: ProcessId) {
+        self.processes
+            .lock()
+            .unwrap()
+            .retain(|process| *process != process_id);
+        self.take_process_halt(process_id);
+    }
+
+    /// The processes of the inboxes that currently exist.
+    pub(crate) fn process_ids(&self) -> Vec<ProcessId> {
+        self.processes.lock().unwrap().clone()
+    }
+
+    /// Halt only the given processes, which receive the halt the next time they try to receive.
+    ///
+    /// ## Notifies
+    /// * all recv_listeners
+    pub(crate) fn halt_processes(&self, process_ids: impl IntoIterator<Item = ProcessId>) {
+        let mut halted_processes = self.halted_processes.lock().unwrap();
+        let prev_len = halted_processes.len();
+        halted_processes.extend(process_ids);
+        self.halted_process_count
+            .fetch_add(halted_processes.len() - prev_len, Ordering::AcqRel);
+        drop(halted_processes);
+        self.recv_event.notify(usize::MAX);
+    }
+
+    /// Remove the halt of the process, returning whether it had one.
+    fn take_process_halt(&self, process_id: ProcessId) -> bool {
+        if self.halted_process_count.load(Ordering::Acquire) == 0 {
+            return false;
+        }
+        let mut halted_processes = self.halted_processes.lock().unwrap();
+        match halted_processes
+            .iter()
+            .position(|halted| *halted == process_id)
+        {
+            Some(index) => {
+                halted_processes.swap_remove(index);
+                self.halted_process_count.fetch_sub(1, Ordering::AcqRel);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Takes the next message out of the channel.
     ///
     /// Returns an error if the queue is closed, returns none if there is no message
     /// in the queue.
     ///
+    /// If the message was sent with an ack, the ack is sent. The trace of the message becomes the
+    /// current trace of the receiver.
+    ///
     /// ## Notifies
     /// on success -> 1 send_listener & 1 recv_listener
     pub(crate) fn pop_msg(&self) -> Result<P, PopError> {
-        self.queue.pop().map(|msg| {
+        self.queue.pop().map(|Queued { msg, ack, trace }| {
+            set_current_trace(trace);
+            if let Some(ack) = ack {
+                let _ = ack.send(());
+            }
             self.send_event.notify(usize::MAX);
             self.recv_event.notify(usize::MAX);
+            #[cfg(feature = "message-replay")]
+            if let Some(recorder) = self.recorder.get() {
+                recorder(&msg);
+            }
             msg
         })
     }
 
+    /// Set the recorder that is called with every message taken out of the channel from now on.
+    ///
+    /// A recorder can only be set once, later recorders are ignored.
+    #[cfg(feature = "message-replay")]
+    pub(crate) fn set_recorder(&self, recorder: Box<dyn Fn(&P) + Send + Sync>) {
+        let _ = self.recorder.set(recorder);
+    }
+
     /// Push a message into the channel.
     ///
     /// Can fail either because the queue is full, or because it is closed.
@@ -119,13 +219,23 @@ impl<P: Protocol> InboxChannel<P> {
     /// ## Notifies
     /// on success -> 1 recv_listener
     pub(crate) fn push_msg(&self, msg: P) -> Result<(), PushError<P>> {
-        match self.queue.push(msg) {
-            Ok(()) => {
-                self.recv_event.notify(usize::MAX);
-                Ok(())
-            }
-            Err(e) => Err(e),
-        }
+        let queued = Queued {
+            msg,
+            ack: None,
+            trace: current_trace(),
+        };
+        self.push_queued(queued).map_err(|e| match e {
+            PushError::Full(queued) => PushError::Full(queued.msg),
+            PushError::Closed(queued) => PushError::Closed(queued.msg),
+        })
+    }
+
+    /// Same as [`InboxChannel::push_msg`], but the message is pushed together with it's `ack`
+    /// and trace.
+    fn push_queued(&self, queued: Queued<P>) -> Result<(), PushError<Queued<P>>> {
+        self.queue.push(queued)?;
+        self.recv_event.notify(usize::MAX);
+        Ok(())
     }
 
     /// Can be called by an inbox to know whether it should halt.
@@ -133,7 +243,10 @@ impl<P: Protocol> InboxChannel<P> {
     /// This decrements the halt-counter by one when it is called, therefore every
     /// inbox should only receive true from this method once! The inbox keeps it's own
     /// local state about whether it has received true from this method.
-    pub(crate) fn inbox_should_halt(&self) -> bool {
+    ///
+    /// The inbox of the process is also halted if it was halted with
+    /// [`InboxChannel::halt_processes`].
+    pub(crate) fn inbox_should_halt(&self, process_id: ProcessId) -> bool {
         // If the count is bigger than 0, we might have to halt.
         if self.halt_count.load(Ordering::Acquire) > 0 {
             // Now subtract 1 from the count
@@ -145,8 +258,40 @@ impl<P: Protocol> InboxChannel<P> {
             }
         }
 
-        // Otherwise, just continue
-        false
+        // Otherwise, only halt if this process was halted separately.
+        self.take_process_halt(process_id)
+    }
+
+    /// Mark an inbox as busy or idle, given it's local state.
+    ///
+    /// ## Notifies
+    /// * `busy -> idle` -> all idle-listeners
+    pub(crate) fn set_busy(&self, busy: &mut bool, to: bool) {
+        if *busy != to {
+            *busy = to;
+            if to {
+                self.busy_count.fetch_add(1, Ordering::AcqRel);
+            } else {
+                self.busy_count.fetch_sub(1, Ordering::AcqRel);
+                self.idle_event.notify(usize::MAX);
+            }
+        }
+    }
+
+    /// The amount of inboxes that are busy processing a message: they have received one, and
+    /// have not tried to receive the next one yet.
+    pub fn busy_count(&self) -> usize {
+        self.busy_count.load(Ordering::Acquire)
+    }
+
+    /// The amount of halts that have not been received by an inbox yet.
+    pub(crate) fn pending_halt_count(&self) -> usize {
+        self.halt_count.load(Ordering::Acquire).max(0) as usize
+    }
+
+    /// Get a new idle-event listener
+    pub(crate) fn get_idle_listener(&self) -> EventListener {
+        self.idle_event.listen()
     }
 
     /// Get a new recv-event listener
@@ -161,8 +306,12 @@ impl<P: Protocol> InboxChannel<P> {
 
     /// This will attempt to receive a message from the [Inbox]. If there is no message, this
     /// will return `None`.
-    pub(crate) fn try_recv(&self, signaled_halt: &mut bool) -> Result<P, TryRecvError> {
-        if !(*signaled_halt) && self.inbox_should_halt() {
+    pub(crate) fn try_recv(
+        &self,
+        signaled_halt: &mut bool,
+        process_id: ProcessId,
+    ) -> Result<P, TryRecvError> {
+        if !(*signaled_halt) && self.inbox_should_halt(process_id) {
             *signaled_halt = true;
             Err(TryRecvError::Halted)
         } else {
@@ -177,12 +326,17 @@ impl<P: Protocol> InboxChannel<P> {
     pub(crate) fn recv<'a>(
         &'a self,
         signaled_halt: &'a mut bool,
+        process_id: ProcessId,
         listener: &'a mut Option<EventListener>,
+        busy: &'a mut bool,
     ) -> RecvFut<'a, P> {
+        self.set_busy(busy, false);
         RecvFut {
             channel: self,
             signaled_halt,
+            process_id,
             recv_listener: listener,
+            busy,
         }
     }
 
@@ -191,6 +345,9 @@ impl<P: Protocol> InboxChannel<P> {
     }
 
     pub(crate) fn send_protocol_now(&self, msg: P) -> Result<(), TrySendError<P>> {
+        if let Capacity::Bounded(_) = &self.capacity {
+            return self.send_reserved(msg);
+        }
         self.push_msg(msg).map_err(|e| match e {
             PushError::Full(msg) => TrySendError::Full(msg),
             PushError::Closed(msg) => TrySendError::Closed(msg),
@@ -199,7 +356,8 @@ impl<P: Protocol> InboxChannel<P> {
 
     pub(crate) fn try_send_protocol(&self, msg: P) -> Result<(), TrySendError<P>> {
         match self.capacity() {
-            Capacity::Bounded(_) | Capacity::Unbounded => self.push_msg(msg),
+            Capacity::Bounded(_) => return self.send_reserved(msg),
+            Capacity::Unbounded => self.push_msg(msg),
             Capacity::BackPressure(backoff) => match backoff.get_timeout(self.msg_count()) {
                 Some(_) => return Err(TrySendError::Full(msg)),
                 None => self.push_msg(msg),
@@ -214,6 +372,77 @@ impl<P: Protocol> InboxChannel<P> {
     pub(crate) fn send_protocol_blocking(&self, msg: P) -> Result<(), SendError<P>> {
         futures::executor::block_on(self.send_protocol(msg))
     }
+
+    /// Send a message into a bounded queue, by reserving a slot first.
+    ///
+    /// All messages of a bounded queue are sent this way, so that a slot of a [`SendPermit`]
+    /// can not be taken by another sender.
+    fn send_reserved(&self, msg: P) -> Result<(), TrySendError<P>> {
+        match self.try_reserve() {
+            Ok(permit) => permit
+                .send(msg)
+                .map_err(|SendError(msg)| TrySendError::Closed(msg)),
+            Err(TrySendError::Full(())) => Err(TrySendError::Full(msg)),
+            Err(TrySendError::Closed(())) => Err(TrySendError::Closed(msg)),
+        }
+    }
+
+    /// Attempt to reserve space for a single message, without waiting.
+    ///
+    /// This fails if the queue is full, or if sending would have to wait because of the
+    /// [`BackPressure`].
+    pub(crate) fn try_reserve(&self) -> Result<SendPermit<'_, P>, TrySendError<()>> {
+        if self.is_closed() {
+            return Err(TrySendError::Closed(()));
+        }
+        match &self.capacity {
+            Capacity::Bounded(size) => {
+                let size = *size;
+                self.reserved
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |reserved| {
+                        (self.queue.len() + reserved < size).then_some(reserved + 1)
+                    })
+                    .map_err(|_| TrySendError::Full(()))?;
+                Ok(SendPermit::new(self, true))
+            }
+            Capacity::BackPressure(back_pressure) => {
+                match back_pressure.get_timeout(self.msg_count()) {
+                    Some(_) => Err(TrySendError::Full(())),
+                    None => Ok(SendPermit::new(self, false)),
+                }
+            }
+            Capacity::Unbounded => Ok(SendPermit::new(self, false)),
+        }
+    }
+
+    /// Reserve space for a single message, waiting until there is space.
+    ///
+    /// For a [`BackPressure`] this waits for the timeout, in the same way as sending does.
+    pub(crate) async fn reserve(&self) -> Result<SendPermit<'_, P>, SendError<()>> {
+        if let Capacity::BackPressure(back_pressure) = &self.capacity {
+            if let Some(timeout) = back_pressure.get_timeout(self.msg_count()) {
+                tokio::time::sleep(timeout).await;
+            }
+            return match self.is_closed() {
+                true => Err(SendError(())),
+                false => Ok(SendPermit::new(self, false)),
+            };
+        }
+
+        loop {
+            match self.try_reserve() {
+                Ok(permit) => return Ok(permit),
+                Err(TrySendError::Closed(())) => return Err(SendError(())),
+                Err(TrySendError::Full(())) => (),
+            }
+            let listener = self.get_send_listener();
+            match self.try_reserve() {
+                Ok(permit) => return Ok(permit),
+                Err(TrySendError::Closed(())) => return Err(SendError(())),
+                Err(TrySendError::Full(())) => listener.await,
+            }
+        }
+    }
 }
 
 impl<P: Protocol> Channel for InboxChannel<P> {
@@ -377,10 +606,89 @@ impl<M> Debug for InboxChannel<M> {
             .field("address_count", &self.address_count)
             .field("inbox_count", &self.inbox_count)
             .field("halt_count", &self.halt_count)
+            .field("busy_count", &self.busy_count)
             .finish()
     }
 }
 
+/// A message in the queue, together with an optional ack for when it is received and the trace
+/// it was sent with.
+struct Queued<P> {
+    msg: P,
+    ack: Option<oneshot::Sender<()>>,
+    trace: MessageTrace,
+}
+
+//------------------------------------------------------------------------------------------------
+//  SendPermit
+//------------------------------------------------------------------------------------------------
+
+/// Space for a single message in the channel, reserved with [`InboxChannel::reserve`].
+///
+/// Dropping the permit without sending releases the space again.
+#[derive(Debug)]
+pub(crate) struct SendPermit<'a, P: Protocol> {
+    channel: &'a InboxChannel<P>,
+    reserved: bool,
+}
+
+impl<'a, P: Protocol> SendPermit<'a, P> {
+    fn new(channel: &'a InboxChannel<P>, reserved: bool) -> Self {
+        Self { channel, reserved }
+    }
+
+    /// Send the message into the reserved space. This only fails if the channel is closed.
+    pub(crate) fn send(self, msg: P) -> Result<(), SendError<P>> {
+        self.send_queued(msg, None)
+    }
+
+    /// Same as [`SendPermit::send`], but the `ack` is sent once the message has been received.
+    pub(crate) fn send_acked(self, msg: P, ack: oneshot::Sender<()>) -> Result<(), SendError<P>> {
+        self.send_queued(msg, Some(ack))
+    }
+
+    fn send_queued(
+        mut self,
+        msg: P,
+        ack: Option<oneshot::Sender<()>>,
+    ) -> Result<(), SendError<P>> {
+        let mut queued = Queued {
+            msg,
+            ack,
+            trace: current_trace(),
+        };
+        let result = loop {
+            match self.channel.push_queued(queued) {
+                Ok(()) => break Ok(()),
+                Err(PushError::Closed(queued)) => break Err(SendError(queued.msg)),
+                // A bounded queue can report that it is full while another message is being
+                // popped, even though our slot is reserved. The pop finishes shortly after.
+                Err(PushError::Full(unsent)) => {
+                    queued = unsent;
+                    std::hint::spin_loop();
+                }
+            }
+        };
+        if self.reserved {
+            // The slot is now taken by the message, or the channel is closed. Senders that
+            // waited while the slot was reserved may have missed the notification of a pop.
+            self.channel.reserved.fetch_sub(1, Ordering::AcqRel);
+            self.channel.send_event.notify(usize::MAX);
+            self.reserved = false;
+        }
+        result
+    }
+}
+
+impl<'a, P: Protocol> Drop for SendPermit<'a, P> {
+    fn drop(&mut self) {
+        if self.reserved {
+            self.channel.reserved.fetch_sub(1, Ordering::AcqRel);
+            self.channel.send_event.notify(usize::MAX);
+        }
+    }
+}
+
 //------------------------------------------------------------------------------------------------
 //  RecvFut
 //------------------------------------------------------------------------------------------------
@@ -389,7 +697,9 @@ impl<M> Debug for InboxChannel<M> {
 pub struct RecvFut<'a, P> {
     channel: &'a InboxChannel<P>,
     signaled_halt: &'a mut bool,
+    process_id: ProcessId,
     recv_listener: &'a mut Option<EventListener>,
+    busy: &'a mut bool,
 }
 
 impl<'a, P: Protocol> Unpin for RecvFut<'a, P> {}
@@ -405,8 +715,11 @@ impl<'a, P: Protocol> Future for RecvFut<'a, P> {
                 .recv_listener
                 .get_or_insert(this.channel.get_recv_listener());
 
-            match this.channel.try_recv(&mut this.signaled_halt) {
-                Ok(msg) => break Poll::Ready(Ok(msg)),
+            match this.channel.try_recv(this.signaled_halt, this.process_id) {
+                Ok(msg) => {
+                    this.channel.set_busy(this.busy, true);
+                    break Poll::Ready(Ok(msg));
+                }
                 Err(error) => match error {
                     TryRecvError::Halted => break Poll::Ready(Err(RecvError::Halted)),
                     TryRecvError::ClosedAndEmpty => {
@@ -433,6 +746,81 @@ impl<'a, M> Drop for RecvFut<'a, M> {
     }
 }
 
+//------------------------------------------------------------------------------------------------
+//  ClosedFut
+//------------------------------------------------------------------------------------------------
+
+/// Future returned by [`Inbox::closed`] and [`Inbox::halted_or_closed`].
+#[derive(Debug)]
+pub struct ClosedFut<'a, P> {
+    channel: &'a InboxChannel<P>,
+    signaled_halt: Option<(&'a mut bool, ProcessId)>,
+    until_closed: bool,
+    listener: Option<EventListener>,
+}
+
+impl<'a, P: Protocol> ClosedFut<'a, P> {
+    pub(crate) fn new(
+        channel: &'a InboxChannel<P>,
+        signaled_halt: Option<(&'a mut bool, ProcessId)>,
+    ) -> Self {
+        Self {
+            channel,
+            signaled_halt,
+            until_closed: true,
+            listener: None,
+        }
+    }
+
+    /// A future that only resolves once the inbox has been halted, ignoring closing.
+    pub(crate) fn halted(
+        channel: &'a InboxChannel<P>,
+        signaled_halt: &'a mut bool,
+        process_id: ProcessId,
+    ) -> Self {
+        Self {
+            until_closed: false,
+            ..Self::new(channel, Some((signaled_halt, process_id)))
+        }
+    }
+
+    fn is_done(&mut self) -> bool {
+        if let Some((signaled_halt, process_id)) = &mut self.signaled_halt {
+            if **signaled_halt {
+                return true;
+            }
+            if self.channel.inbox_should_halt(*process_id) {
+                **signaled_halt = true;
+                return true;
+            }
+        }
+        self.until_closed && self.channel.is_closed()
+    }
+}
+
+impl<'a, P: Protocol> Unpin for ClosedFut<'a, P> {}
+
+impl<'a, P: Protocol> Future for ClosedFut<'a, P> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        loop {
+            if this.listener.is_none() {
+                this.listener = Some(this.channel.get_recv_listener());
+            }
+
+            if this.is_done() {
+                this.listener = None;
+                return Poll::Ready(());
+            }
+            ready!(this.listener.as_mut().unwrap().poll_unpin(cx));
+            this.listener = None;
+        }
+    }
+}
+
 //------------------------------------------------------------------------------------------------
 //  SendProtocolFut
 //------------------------------------------------------------------------------------------------
@@ -575,6 +963,39 @@ mod test {
         Msg(Arc<()>),
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn concurrent_bounded_sends_do_not_panic() {
+        let (child, address) = spawn_with(
+            Link::default(),
+            Capacity::Bounded(1),
+            |mut inbox: Inbox<()>| async move {
+                let mut received = 0;
+                while inbox.recv().await.is_ok() {
+                    received += 1;
+                }
+                received
+            },
+        );
+        let senders = (0..8)
+            .map(|_| {
+                let address = address.clone();
+                tokio::spawn(async move {
+                    for _ in 0..200 {
+                        address.send(()).await.unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for sender in senders {
+            tokio::time::timeout(Duration::from_secs(10), sender)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        address.close();
+        assert_eq!(child.await.unwrap(), 1600);
+    }
+
     #[test]
     fn try_send_with_space() {
         let channel = InboxChannel::<()>::new(1, 1, Capacity::Bounded(10), ActorId::generate());
@@ -655,11 +1076,58 @@ mod test {
             assert!(time.elapsed().as_millis() > 2);
         });
 
-        channel.recv(&mut false, &mut None).await.unwrap();
+        channel
+            .recv(&mut false, ProcessId::generate(), &mut None, &mut false)
+            .await
+            .unwrap();
         tokio::time::sleep(Duration::from_millis(2)).await;
-        channel.recv(&mut false, &mut None).await.unwrap();
+        channel
+            .recv(&mut false, ProcessId::generate(), &mut None, &mut false)
+            .await
+            .unwrap();
         tokio::time::sleep(Duration::from_millis(2)).await;
-        channel.recv(&mut false, &mut None).await.unwrap();
+        channel
+            .recv(&mut false, ProcessId::generate(), &mut None, &mut false)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn closed_resolves_after_close() {
+        let channel = Arc::new(InboxChannel::<()>::new(
+            1,
+            1,
+            Capacity::default(),
+            ActorId::generate(),
+        ));
+        let channel_clone = channel.clone();
+
+        tokio::task::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(2)).await;
+            channel_clone.push_msg(()).unwrap();
+            channel_clone.halt_some(1);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+            channel_clone.close();
+        });
+
+        ClosedFut::new(&channel, None).await;
+        assert!(channel.is_closed());
+        assert_eq!(channel.msg_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn halted_or_closed_resolves_after_halt() {
+        let channel = InboxChannel::<()>::new(1, 1, Capacity::default(), ActorId::generate());
+        channel.halt_some(1);
+
+        let mut signaled_halt = false;
+        ClosedFut::new(&channel, Some((&mut signaled_halt, ProcessId::generate()))).await;
+        assert!(signaled_halt);
+        assert!(!channel.is_closed());
+        assert_eq!(
+            channel.try_recv(&mut signaled_halt, ProcessId::generate()),
+            Err(TryRecvError::Empty)
+        );
     }
 
     #[test]
@@ -668,10 +1136,16 @@ mod test {
         channel.push_msg(()).unwrap();
         channel.push_msg(()).unwrap();
 
-        assert!(channel.try_recv(&mut true).is_ok());
-        assert!(channel.try_recv(&mut false).is_ok());
-        assert_eq!(channel.try_recv(&mut true), Err(TryRecvError::Empty));
-        assert_eq!(channel.try_recv(&mut false), Err(TryRecvError::Empty));
+        assert!(channel.try_recv(&mut true, ProcessId::generate()).is_ok());
+        assert!(channel.try_recv(&mut false, ProcessId::generate()).is_ok());
+        assert_eq!(
+            channel.try_recv(&mut true, ProcessId::generate()),
+            Err(TryRecvError::Empty)
+        );
+        assert_eq!(
+            channel.try_recv(&mut false, ProcessId::generate()),
+            Err(TryRecvError::Empty)
+        );
     }
 
     #[test]
@@ -681,14 +1155,14 @@ mod test {
         channel.push_msg(()).unwrap();
         channel.close();
 
-        assert!(channel.try_recv(&mut true).is_ok());
-        assert!(channel.try_recv(&mut false).is_ok());
+        assert!(channel.try_recv(&mut true, ProcessId::generate()).is_ok());
+        assert!(channel.try_recv(&mut false, ProcessId::generate()).is_ok());
         assert_eq!(
-            channel.try_recv(&mut true),
+            channel.try_recv(&mut true, ProcessId::generate()),
             Err(TryRecvError::ClosedAndEmpty)
         );
         assert_eq!(
-            channel.try_recv(&mut false),
+            channel.try_recv(&mut false, ProcessId::generate()),
             Err(TryRecvError::ClosedAndEmpty)
         );
     }
@@ -700,11 +1174,20 @@ mod test {
         channel.push_msg(()).unwrap();
         channel.halt_some(1);
 
-        assert_eq!(channel.try_recv(&mut false), Err(TryRecvError::Halted));
-        assert!(channel.try_recv(&mut true).is_ok());
-        assert!(channel.try_recv(&mut false).is_ok());
-        assert_eq!(channel.try_recv(&mut true), Err(TryRecvError::Empty));
-        assert_eq!(channel.try_recv(&mut false), Err(TryRecvError::Empty));
+        assert_eq!(
+            channel.try_recv(&mut false, ProcessId::generate()),
+            Err(TryRecvError::Halted)
+        );
+        assert!(channel.try_recv(&mut true, ProcessId::generate()).is_ok());
+        assert!(channel.try_recv(&mut false, ProcessId::generate()).is_ok());
+        assert_eq!(
+            channel.try_recv(&mut true, ProcessId::generate()),
+            Err(TryRecvError::Empty)
+        );
+        assert_eq!(
+            channel.try_recv(&mut false, ProcessId::generate()),
+            Err(TryRecvError::Empty)
+        );
     }
 
     #[tokio::test]
@@ -714,9 +1197,16 @@ mod test {
         channel.push_msg(()).unwrap();
         channel.close();
 
-        assert_eq!(channel.recv(&mut false, &mut listener).await, Ok(()));
         assert_eq!(
-            channel.recv(&mut false, &mut listener).await,
+            channel
+                .recv(&mut false, ProcessId::generate(), &mut listener, &mut false)
+                .await,
+            Ok(())
+        );
+        assert_eq!(
+            channel
+                .recv(&mut false, ProcessId::generate(), &mut listener, &mut false)
+                .await,
             Err(RecvError::ClosedAndEmpty)
         );
     }
@@ -733,9 +1223,16 @@ mod test {
 
         let handle = tokio::task::spawn(async move {
             let mut listener = None;
-            assert_eq!(channel_clone.recv(&mut false, &mut listener).await, Ok(()));
             assert_eq!(
-                channel_clone.recv(&mut false, &mut listener).await,
+                channel_clone
+                    .recv(&mut false, ProcessId::generate(), &mut listener, &mut false)
+                    .await,
+                Ok(())
+            );
+            assert_eq!(
+                channel_clone
+                    .recv(&mut false, ProcessId::generate(), &mut listener, &mut false)
+                    .await,
                 Err(RecvError::ClosedAndEmpty)
             );
         });
@@ -759,19 +1256,21 @@ mod test {
         let handle = tokio::task::spawn(async move {
             let mut listener = None;
             let mut halt = false;
-            let mut recv1 = channel_clone.recv(&mut halt, &mut listener);
+            let mut busy = false;
+            let mut recv1 =
+                channel_clone.recv(&mut halt, ProcessId::generate(), &mut listener, &mut busy);
             tokio::select! {
                 biased;
                 _ = &mut recv1 => {
                     panic!()
                 }
-                _ = ready(||()) => {
-                    ()
-                }
+                _ = ready(||()) => {}
             }
             let mut listener = None;
             let mut halt = false;
-            let recv2 = channel_clone.recv(&mut halt, &mut listener);
+            let mut busy = false;
+            let recv2 =
+                channel_clone.recv(&mut halt, ProcessId::generate(), &mut listener, &mut busy);
             drop(recv1);
             recv2.await.unwrap();
         });
@@ -940,7 +1439,7 @@ mod test {
     fn add_inbox_with_0_addresses_is_ok() {
         let channel = InboxChannel::<()>::new(1, 1, Capacity::default(), ActorId::generate());
         channel.remove_inbox();
-        assert!(matches!(channel.try_increment_process_count(), Err(_)));
+        assert!(channel.try_increment_process_count().is_err());
         assert_eq!(channel.process_count(), 0);
     }
 
@@ -1016,9 +1515,9 @@ mod test {
         let channel = InboxChannel::<()>::new(1, 3, Capacity::default(), ActorId::generate());
         channel.halt_some(2);
 
-        assert!(channel.inbox_should_halt());
-        assert!(channel.inbox_should_halt());
-        assert!(!channel.inbox_should_halt());
+        assert!(channel.inbox_should_halt(ProcessId::generate()));
+        assert!(channel.inbox_should_halt(ProcessId::generate()));
+        assert!(!channel.inbox_should_halt(ProcessId::generate()));
     }
 
     struct Listeners {
@@ -1037,15 +1536,12 @@ mod test {
         fn size_10<T: Protocol>(channel: &InboxChannel<T>) -> Self {
             Self {
                 recv: (0..10)
-                    .into_iter()
                     .map(|_| channel.get_recv_listener())
                     .collect(),
                 exit: (0..10)
-                    .into_iter()
                     .map(|_| channel.get_exit_listener())
                     .collect(),
                 send: (0..10)
-                    .into_iter()
                     .map(|_| channel.get_send_listener())
                     .collect(),
             }
@@ -15,22 +15,29 @@ pub use channel::*;
 /// `P` accepts can be sent to this actor. This inbox also allows for multiple processes to be spawned
 /// onto a single actor.
 ///
-/// Messages may be received with [`Inbox::recv`], [`Inbox::try_recv`] or by using [`Stream`].
+/// Messages may be received with [`Inbox::recv`], [`Inbox::try_recv`], [`Inbox::recv_many`] or by
+/// using [`Stream`].
 ///
 /// This also implements [`HandlerState`], which allows this to be used as a [`Handler::State`].
 #[derive(Debug)]
 pub struct Inbox<P: Protocol> {
     channel: Arc<InboxChannel<P>>,
+    process_id: ProcessId,
     halted: bool,
     recv_listener: Option<EventListener>,
+    busy: bool,
 }
 
 impl<P: Protocol> Inbox<P> {
     pub(crate) fn from_channel(channel: Arc<InboxChannel<P>>) -> Self {
+        let process_id = ProcessId::generate();
+        channel.add_process(process_id);
         Inbox {
             channel,
+            process_id,
             halted: false,
             recv_listener: None,
+            busy: false,
         }
     }
 
@@ -39,14 +46,71 @@ impl<P: Protocol> Inbox<P> {
         self.halted
     }
 
+    /// The id of the process this inbox belongs to, which is unique for every process of a
+    /// [`ChildPool`].
+    pub fn process_id(&self) -> ProcessId {
+        self.process_id
+    }
+
     /// Attempt to receive a message from the channel.
     pub fn try_recv(&mut self) -> Result<P, TryRecvError> {
-        self.channel.try_recv(&mut self.halted)
+        self.channel.set_busy(&mut self.busy, false);
+        let msg = self.channel.try_recv(&mut self.halted, self.process_id)?;
+        self.channel.set_busy(&mut self.busy, true);
+        Ok(msg)
     }
 
     /// Receive a message from the channel, waiting for one to appear.
+    ///
+    /// From the moment a message is received until the next call to receive, this inbox is
+    /// counted as busy, see [`InboxChannel::busy_count`].
     pub fn recv(&mut self) -> RecvFut<'_, P> {
-        self.channel.recv(&mut self.halted, &mut self.recv_listener)
+        self.channel.recv(
+            &mut self.halted,
+            self.process_id,
+            &mut self.recv_listener,
+            &mut self.busy,
+        )
+    }
+
+    /// Receive up to `max` messages at once, appending them to `buf` and returning how many
+    /// were received.
+    ///
+    /// This waits for the first message the same as [`Inbox::recv`], after which all messages
+    /// that are currently in the inbox are taken without waiting. A halt that arrives after the
+    /// first message has been received is returned by the next call to receive. If `max` is
+    /// `0`, this returns `Ok(0)` immediately.
+    pub async fn recv_many(&mut self, buf: &mut Vec<P>, max: usize) -> Result<usize, RecvError> {
+        if max == 0 {
+            return Ok(0);
+        }
+        buf.push(self.recv().await?);
+
+        let mut count = 1;
+        while count < max {
+            match self.channel.pop_msg() {
+                Ok(msg) => buf.push(msg),
+                Err(_) => break,
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Wait until the channel has been closed, without receiving any messages.
+    ///
+    /// Messages that were sent before closing can still be received afterwards. This allows an
+    /// actor that is awaiting something other than it's inbox to `select!` on being closed.
+    pub fn closed(&self) -> ClosedFut<'_, P> {
+        ClosedFut::new(&self.channel, None)
+    }
+
+    /// Same as [`Inbox::closed`], but also resolves once this inbox has been halted.
+    ///
+    /// When this resolves because of a halt, [`Inbox::halted`] returns `true` and the halt is not
+    /// received again with [`Inbox::recv`].
+    pub fn halted_or_closed(&mut self) -> ClosedFut<'_, P> {
+        ClosedFut::new(&self.channel, Some((&mut self.halted, self.process_id)))
     }
 }
 
@@ -126,14 +190,18 @@ impl<P: Protocol> Stream for Inbox<P> {
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
         let this = &mut *self;
+        this.channel.set_busy(&mut this.busy, false);
 
         let result = loop {
             let recv_listener = this
                 .recv_listener
                 .get_or_insert(this.channel.get_recv_listener());
 
-            match this.channel.try_recv(&mut this.halted) {
-                Ok(msg) => break Poll::Ready(Some(Ok(msg))),
+            match this.channel.try_recv(&mut this.halted, this.process_id) {
+                Ok(msg) => {
+                    this.channel.set_busy(&mut this.busy, true);
+                    break Poll::Ready(Some(Ok(msg)));
+                }
                 Err(error) => match error {
                     TryRecvError::Halted => break Poll::Ready(Some(Err(Halted))),
                     TryRecvError::ClosedAndEmpty => break Poll::Ready(None),
@@ -160,6 +228,8 @@ impl<P: Protocol> FusedStream for Inbox<P> {
 
 impl<P: Protocol> Drop for Inbox<P> {
     fn drop(&mut self) {
+        self.channel.set_busy(&mut self.busy, false);
+        self.channel.remove_process(self.process_id);
         self.channel.remove_inbox();
     }
 }
@@ -173,9 +243,9 @@ where
     type SendFut<'a> = InboxSendFut<'a, P, M>;
 
     fn try_send(channel: &Self::Channel, msg: M) -> Result<M::Returned, TrySendError<M>> {
-        let (sends, returns) = M::create(msg);
+        let (sends, returns, trace) = create_traced(msg);
 
-        match channel.try_send_protocol(P::from_payload(sends)) {
+        match traced(trace, || channel.try_send_protocol(P::from_payload(sends))) {
             Ok(()) => Ok(returns),
             Err(e) => match e {
                 TrySendError::Closed(prot) => {
@@ -189,9 +259,9 @@ where
     }
 
     fn force_send(channel: &Self::Channel, msg: M) -> Result<M::Returned, TrySendError<M>> {
-        let (sends, returns) = M::create(msg);
+        let (sends, returns, trace) = create_traced(msg);
 
-        match channel.send_protocol_now(P::from_payload(sends)) {
+        match traced(trace, || channel.send_protocol_now(P::from_payload(sends))) {
             Ok(()) => Ok(returns),
             Err(e) => match e {
                 TrySendError::Closed(prot) => {
@@ -205,19 +275,22 @@ where
     }
 
     fn send_blocking(channel: &Self::Channel, msg: M) -> Result<M::Returned, SendError<M>> {
-        let (sends, returns) = M::create(msg);
+        let (sends, returns, trace) = create_traced(msg);
 
-        match channel.send_protocol_blocking(P::from_payload(sends)) {
+        match traced(trace, || {
+            channel.send_protocol_blocking(P::from_payload(sends))
+        }) {
             Ok(()) => Ok(returns),
             Err(SendError(prot)) => Err(SendError(unwrap_then_cancel(prot, returns))),
         }
     }
 
     fn send(channel: &Self::Channel, msg: M) -> InboxSendFut<'_, P, M> {
-        let (payload, returned) = M::create(msg);
+        let (payload, returned, trace) = create_traced(msg);
         InboxSendFut {
             returned: Some(returned),
             prot_fut: channel.send_protocol(P::from_payload(payload)),
+            trace,
         }
     }
 }
@@ -230,6 +303,7 @@ where
 pub struct InboxSendFut<'a, P: Protocol, M: Message> {
     returned: Option<M::Returned>,
     prot_fut: SendProtocolFut<'a, P>,
+    trace: MessageTrace,
 }
 
 impl<'a, P, M> Future for InboxSendFut<'a, P, M>
@@ -240,7 +314,9 @@ where
     type Output = Result<M::Returned, SendError<M>>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.prot_fut.poll_unpin(cx).map(|res| match res {
+        let trace = self.trace;
+        let poll = traced(trace, || self.prot_fut.poll_unpin(cx));
+        poll.map(|res| match res {
             Ok(()) => Ok(self.returned.take().unwrap()),
             Err(SendError(protocol)) => Err(SendError(unwrap_then_cancel(
                 protocol,
@@ -252,9 +328,40 @@ where
 
 impl<'a, P: Protocol, M: Message> Unpin for InboxSendFut<'a, P, M> {}
 
-fn unwrap_then_cancel<P: FromPayload<M>, M: Message>(prot: P, returned: M::Returned) -> M {
+pub(crate) fn unwrap_then_cancel<P: FromPayload<M>, M: Message>(prot: P, returned: M::Returned) -> M {
     let Ok(sent) = prot.try_into_payload() else {
         panic!("")
     };
     M::cancel(sent, returned)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+
+    #[tokio::test]
+    async fn recv_many_takes_available_messages() {
+        let (child, address) = spawn(|mut inbox: Inbox<U32Protocol>| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            let mut buf = Vec::new();
+            let first = inbox.recv_many(&mut buf, 3).await.unwrap();
+            let second = inbox.recv_many(&mut buf, 3).await.unwrap();
+            let closed = inbox.recv_many(&mut buf, 3).await;
+            (first, second, closed, buf)
+        });
+        for n in 0..5u32 {
+            address.send(n).await.unwrap();
+        }
+        address.close();
+
+        let (first, second, closed, buf) = child.await.unwrap();
+        assert_eq!((first, second), (3, 2));
+        assert_eq!(closed, Err(RecvError::ClosedAndEmpty));
+        let buf = buf
+            .into_iter()
+            .map(|U32Protocol::U32(n)| n)
+            .collect::<Vec<_>>();
+        assert_eq!(buf, vec![0, 1, 2, 3, 4]);
+    }
+}
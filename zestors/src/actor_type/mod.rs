@@ -37,8 +37,18 @@
 //! 
 //! A [`DynActor`](struct@DynActor) can be downcast into the original [`InboxType`] with [`Transformable::downcast`].
 //! 
+//! A dynamic actor-reference can be sealed with [`Transformable::seal`], after which it can only be transformed
+//! into actor-types that accept fewer messages. (see [`SealedDyn`])
+//! 
 //! All addresses that can be transformed implement [`IntoAddress`] and all children [`IntoChild`].
 //! 
+//! # Performance
+//! Sending a message to a static actor-type, such as an [`Address<Inbox<P>>`](Address), is
+//! monomorphized: the message is converted directly into the protocol `P`, without any boxing or
+//! dynamic dispatch. A [`DynActor`](struct@DynActor) instead boxes every message and downcasts it
+//! again before it is sent, which makes sending roughly 25% slower for small messages. The
+//! `static_vs_dyn_send` benchmark compares the two with `cargo bench --bench static_vs_dyn_send`.
+//! 
 //! | __<--__ [`actor_reference`](crate::actor_reference) | [`spawning`](crate::spawning) __-->__ |
 //! |---|---|
 //! 
@@ -51,7 +61,9 @@
 use crate::all::*;
 
 mod actor_id;
+#[allow(clippy::module_inception)]
 mod actor_type;
+mod bounded_inbox;
 mod channel;
 mod dyn_actor;
 mod errors;
@@ -59,7 +71,7 @@ mod halter;
 mod inbox;
 mod multi_halter;
 pub use {
-    actor_id::*, actor_type::*, channel::*, dyn_actor::*, errors::*, halter::*,
+    actor_id::*, actor_type::*, bounded_inbox::*, channel::*, dyn_actor::*, errors::*, halter::*,
     inbox::*, multi_halter::*,
 };
 
@@ -10,6 +10,7 @@ use std::{any::TypeId, fmt::Debug};
 ///
 /// (See [`handler`](crate::handler) for an overview)
 #[derive(Message)]
+#[allow(clippy::type_complexity)]
 pub struct Action<H: Handler> {
     function: Box<
         dyn for<'a> FnOnce(
@@ -0,0 +1,203 @@
+use crate::all::*;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// A [`Server`] that handles the messages of it's [`Protocol`] in batches instead of one by one.
+/// Batch-servers are run with [`run_batch_server`].
+///
+/// Messages are collected into a batch until the [`BatchPolicy`] of the server is reached, after
+/// which the whole batch is given to [`BatchServer::handle_batch`]. Requests keep their [`Tx`]
+/// within the batch, so that the handler can reply to every request separately.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::{export::async_trait, handler::*, prelude::*, protocol};
+///
+/// #[derive(Message, Debug)]
+/// #[request(usize)]
+/// struct Insert(u32);
+///
+/// #[protocol]
+/// enum InsertProtocol {
+///     Insert(Insert),
+/// }
+///
+/// struct Database(Vec<u32>);
+///
+/// #[async_trait]
+/// impl BatchServer for Database {
+///     type Protocol = InsertProtocol;
+///
+///     async fn handle_batch(
+///         &mut self,
+///         batch: Vec<InsertProtocol>,
+///         ctx: &mut ServerCtx<InsertProtocol>,
+///     ) {
+///         let size = batch.len();
+///         for InsertProtocol::Insert((Insert(n), tx)) in batch {
+///             self.0.push(n);
+///             ctx.reply(tx, size);
+///         }
+///     }
+///
+///     fn batch_policy(&self) -> BatchPolicy {
+///         BatchPolicy::new(2, Duration::from_millis(10))
+///     }
+/// }
+///
+/// # async fn main() {
+/// let (child, address) = spawn(|inbox| run_batch_server(inbox, Database(Vec::new())));
+/// let first = address.send(Insert(1)).await.unwrap();
+/// let second = address.send(Insert(2)).await.unwrap();
+/// assert_eq!((first.await.unwrap(), second.await.unwrap()), (2, 2));
+/// assert_eq!(address.request(Insert(3)).await.unwrap(), 1);
+/// child.halt();
+/// assert_eq!(child.await.unwrap().0, vec![1, 2, 3]);
+/// # }
+/// ```
+#[async_trait]
+pub trait BatchServer: Send + Sized + 'static {
+    /// The protocol of the inbox this server receives it's messages from.
+    type Protocol: Protocol;
+
+    /// Handle a batch of messages from the inbox, in the order they were received.
+    async fn handle_batch(
+        &mut self,
+        batch: Vec<Self::Protocol>,
+        ctx: &mut ServerCtx<Self::Protocol>,
+    );
+
+    /// The [`BatchPolicy`] that decides when a batch is handled. This is called once, when the
+    /// server is started. The default is [`BatchPolicy::default`].
+    fn batch_policy(&self) -> BatchPolicy {
+        BatchPolicy::default()
+    }
+}
+
+/// Decides when the batch of a [`BatchServer`] is handled: as soon as it contains `max_count`
+/// messages, or `max_delay` after it's first message was received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BatchPolicy {
+    /// The maximum amount of messages in a batch. A `max_count` of `0` is the same as `1`.
+    pub max_count: usize,
+    /// The maximum time the first message of a batch waits before the batch is handled.
+    pub max_delay: Duration,
+}
+
+impl BatchPolicy {
+    /// Create a new batch-policy.
+    pub fn new(max_count: usize, max_delay: Duration) -> Self {
+        Self {
+            max_count,
+            max_delay,
+        }
+    }
+}
+
+impl Default for BatchPolicy {
+    /// Handles a batch every 100 messages or after 50 milliseconds.
+    fn default() -> Self {
+        Self::new(100, Duration::from_millis(50))
+    }
+}
+
+/// Run the batch-server by receiving messages from the inbox, and handling them in batches.
+///
+/// The server is returned once it has been halted, the inbox is closed and empty, or
+/// [`ServerCtx::stop`] has been called. Messages that have already been collected into a batch
+/// when the server is halted or the inbox is closed are handled first.
+///
+/// The [`ServerCtx::cancel_scope`] is cancelled as soon as the server is halted, even while it is
+/// handling a batch, and otherwise when the server returns.
+pub async fn run_batch_server<S: BatchServer>(inbox: Inbox<S::Protocol>, mut server: S) -> S {
+    let channel = ActorRef::channel_ref(&inbox).clone();
+    let scope = CancelScope::new();
+    let policy = server.batch_policy();
+    let max_count = policy.max_count.max(1);
+    let mut ctx = ServerCtx {
+        inbox,
+        stopped: false,
+        scope: scope.clone(),
+    };
+    while !ctx.stopped {
+        let mut batch = match ctx.inbox.recv().await {
+            Ok(msg) => vec![msg],
+            Err(_) => break,
+        };
+        let mut exhausted = false;
+        let deadline = tokio::time::sleep(policy.max_delay);
+        futures::pin_mut!(deadline);
+        while batch.len() < max_count {
+            tokio::select! {
+                msg = ctx.inbox.recv() => match msg {
+                    Ok(msg) => batch.push(msg),
+                    Err(_) => {
+                        exhausted = true;
+                        break;
+                    }
+                },
+                () = &mut deadline => break,
+            }
+        }
+
+        let mut halted = false;
+        let process_id = ctx.inbox.process_id();
+        let handled = server.handle_batch(batch, &mut ctx);
+        futures::pin_mut!(handled);
+        tokio::select! {
+            () = &mut handled => (),
+            () = ClosedFut::halted(&channel, &mut halted, process_id) => {
+                scope.cancel();
+                handled.await;
+            }
+        }
+        if halted || exhausted {
+            break;
+        }
+    }
+    scope.cancel();
+    server
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+
+    struct Collector(Vec<Vec<u32>>);
+
+    #[async_trait]
+    impl BatchServer for Collector {
+        type Protocol = U32Protocol;
+
+        async fn handle_batch(
+            &mut self,
+            batch: Vec<U32Protocol>,
+            _ctx: &mut ServerCtx<U32Protocol>,
+        ) {
+            self.0
+                .push(batch.into_iter().map(|U32Protocol::U32(n)| n).collect());
+        }
+
+        fn batch_policy(&self) -> BatchPolicy {
+            BatchPolicy::new(3, Duration::from_millis(20))
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_by_count_and_delay() {
+        let (child, address) = spawn(|inbox| run_batch_server(inbox, Collector(Vec::new())));
+        for n in 0..4 {
+            address.send(n).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        address.send(4u32).await.unwrap();
+        address.close();
+        assert_eq!(
+            child.await.unwrap().0,
+            vec![vec![0, 1, 2], vec![3], vec![4]]
+        );
+    }
+}
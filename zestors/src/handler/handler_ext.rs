@@ -10,6 +10,7 @@ pub trait HandlerExt: Handler {
         event_loop(self, state).await
     }
 
+    #[allow(clippy::type_complexity)]
     fn spawn_with(
         self,
         link: Link,
@@ -24,6 +25,7 @@ pub trait HandlerExt: Handler {
         })
     }
 
+    #[allow(clippy::type_complexity)]
     fn spawn(
         self,
     ) -> (
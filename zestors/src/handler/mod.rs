@@ -24,6 +24,15 @@ accepts the message [`Action<Self>`], then actions (closures) can be sent and ha
 automatically on the actor. An upside of this is that it is now possible to send messages with
 generics, but a downside is that the actor's protocol is now tightly coupled to the state. 
 
+# Server
+For simple actors that don't need a [`HandlerState`], the [`Server`] trait can be used instead. A
+server handles every message of it's [`Protocol`] from a single method, and is run on an [`Inbox`]
+with [`run_server`]. The [`ServerCtx`] gives access to the actor's own address, and can be used to
+reply to requests and to stop the server. A server that accepts the standard [`Stop`] message can be
+asked to stop politely, after which [`Server::on_stop`] is called. The logic of a server can be swapped at runtime
+by sending it a [`SetHandler`] message, which keeps the state of the server. A [`BatchServer`] is
+run with [`run_batch_server`] and handles it's messages in batches, as decided by it's [`BatchPolicy`].
+
 | __<--__ [`spawning`](crate::spawning) | [`runtime`](crate::runtime) __-->__ |
 |---|---|
 
@@ -162,7 +171,10 @@ use crate::all::*;
 mod action;
 mod handler_ext;
 mod state;
+#[allow(clippy::module_inception)]
 mod handler;
 mod event_loop;
 mod scheduler;
-pub use {action::*, handler_ext::*, state::*, handler::*, scheduler::*};
+mod server;
+mod batch_server;
+pub use {action::*, handler_ext::*, state::*, handler::*, scheduler::*, server::*, batch_server::*};
@@ -14,6 +14,7 @@ use std::{
 /// 1 From the state `T`.
 /// 2. From any scheduled futures.
 /// 3. From the scheduled stream.
+#[allow(clippy::type_complexity)]
 pub struct Scheduler<P: Protocol, H: Handler> {
     inner_state: Inbox<P>,
     futures: FuturesUnordered<Pin<Box<dyn Future<Output = HandlerItem<P, H>> + Send>>>,
@@ -80,6 +81,7 @@ impl<P: Protocol, H: Handler> Scheduler<P, H> {
     }
 
     /// Remove the [`Stream`] if it was scheduled.
+    #[allow(clippy::type_complexity)]
     pub fn remove_stream(
         &mut self,
     ) -> Option<Pin<Box<dyn Stream<Item = HandlerItem<P, H>> + Send>>> {
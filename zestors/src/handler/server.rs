@@ -0,0 +1,447 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::FutureExt;
+use std::panic::AssertUnwindSafe;
+
+/// A lightweight alternative to the [`Handler`] trait, for actors that handle their whole
+/// [`Protocol`] from a single method. Servers are run with [`run_server`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{export::async_trait, handler::*, prelude::*, protocol};
+///
+/// #[derive(Message, Debug)]
+/// #[request(u32)]
+/// struct Double(u32);
+///
+/// #[protocol]
+/// enum CounterProtocol {
+///     Add(u32),
+///     Double(Double),
+/// }
+///
+/// struct Counter(u32);
+///
+/// #[async_trait]
+/// impl Server for Counter {
+///     type Protocol = CounterProtocol;
+///
+///     async fn handle(&mut self, msg: CounterProtocol, ctx: &mut ServerCtx<CounterProtocol>) {
+///         match msg {
+///             CounterProtocol::Add(n) => self.0 += n,
+///             CounterProtocol::Double((Double(n), tx)) => ctx.reply(tx, n * 2),
+///         }
+///     }
+/// }
+///
+/// # async fn main() {
+/// let (child, address) = spawn(|inbox| run_server(inbox, Counter(0)));
+/// address.send(10u32).await.unwrap();
+/// assert_eq!(address.request(Double(4)).await.unwrap(), 8);
+/// child.halt();
+/// assert_eq!(child.await.unwrap().0, 10);
+/// # }
+/// ```
+#[async_trait]
+pub trait Server: Send + Sized + 'static {
+    /// The protocol of the inbox this server receives it's messages from.
+    type Protocol: Protocol;
+
+    /// Handle a single message from the inbox.
+    async fn handle(&mut self, msg: Self::Protocol, ctx: &mut ServerCtx<Self::Protocol>);
+
+    /// Called when the server receives a [`Stop`] message, right before [`run_server`] returns.
+    async fn on_stop(&mut self, ctx: &mut ServerCtx<Self::Protocol>) {
+        let _ = ctx;
+    }
+
+    /// Whether [`run_server`] should catch panics of [`Server::handle`], and continue with the
+    /// next message instead of exiting.
+    ///
+    /// By default panics are not caught. A caught panic is still reported by the panic-hook, and
+    /// a request that was being handled is dropped so that the caller receives an error. Any
+    /// changes the handler made to the server before it panicked are kept.
+    fn catch_handler_panics(&self) -> bool {
+        false
+    }
+}
+
+/// A standard message that asks a [`Server`] to stop.
+///
+/// This is different from halting the inbox: [`run_server`] handles it as any other message, in
+/// order, after which [`Server::on_stop`] is called and the server is returned.
+///
+/// A server recognizes it as soon as it's protocol accepts [`Stop`], it is then never given to
+/// [`Server::handle`].
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Stop;
+
+/// Handles the messages of a [`Server`] instead of [`Server::handle`], once it has been set with
+/// a [`SetHandler`] message.
+#[async_trait]
+pub trait ServerHandler<S: Server>: Send + 'static {
+    /// Handle a single message from the inbox, with access to the state of the server.
+    async fn handle(&mut self, server: &mut S, msg: S::Protocol, ctx: &mut ServerCtx<S::Protocol>);
+}
+
+/// A standard message that swaps the handler of a [`Server`] for all subsequent messages, without
+/// restarting it and losing it's state.
+///
+/// [`run_server`] swaps the handler between messages, never while a message is being handled. The
+/// new handler is used instead of [`Server::handle`], and replaces a handler that was set before.
+/// [`Server::catch_handler_panics`] applies to it as well. It can be sent with
+/// [`Address::set_handler`] to any server with a protocol that accepts it.
+pub struct SetHandler<S: Server>(pub Box<dyn ServerHandler<S>>);
+
+impl<S: Server> SetHandler<S> {
+    /// Create a new message that swaps in the given handler.
+    pub fn new(handler: impl ServerHandler<S>) -> Self {
+        Self(Box::new(handler))
+    }
+}
+
+impl<S: Server> std::fmt::Debug for SetHandler<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SetHandler").finish_non_exhaustive()
+    }
+}
+
+impl<S: Server> Message for SetHandler<S> {
+    type Payload = Self;
+    type Returned = ();
+
+    fn create(self) -> (Self, ()) {
+        (self, ())
+    }
+
+    fn cancel(sent: Self, _returned: ()) -> Self {
+        sent
+    }
+}
+
+impl<P: Protocol> Address<Inbox<P>> {
+    /// Swap the handler of the [`Server`] of this actor, see [`SetHandler`].
+    pub async fn set_handler<S>(
+        &self,
+        handler: impl ServerHandler<S>,
+    ) -> Result<(), SendError<SetHandler<S>>>
+    where
+        S: Server<Protocol = P>,
+        Inbox<P>: Accepts<SetHandler<S>>,
+    {
+        self.send(SetHandler::new(handler)).await
+    }
+}
+
+/// Run the server by receiving messages from the inbox, and handling them one by one.
+///
+/// The server is returned once it has been halted, the inbox is closed and empty,
+/// [`ServerCtx::stop`] has been called, or it has received a [`Stop`] message.
+///
+/// The [`ServerCtx::cancel_scope`] is cancelled as soon as the server is halted, even while it is
+/// handling a message, and otherwise when the server returns. If
+/// [`Server::catch_handler_panics`] is enabled, a panicking handler does not stop the server.
+/// The handler can be swapped between messages with a [`SetHandler`] message.
+pub async fn run_server<S: Server>(inbox: Inbox<S::Protocol>, mut server: S) -> S {
+    let channel = ActorRef::channel_ref(&inbox).clone();
+    let scope = CancelScope::new();
+    let mut ctx = ServerCtx {
+        inbox,
+        stopped: false,
+        scope: scope.clone(),
+    };
+    let mut handler: Option<Box<dyn ServerHandler<S>>> = None;
+    while !ctx.stopped {
+        let msg = match ctx.inbox.recv().await {
+            Ok(msg) => msg,
+            Err(_) => break,
+        };
+        let msg = match msg.try_into_msg::<Stop>() {
+            Ok(Stop) => {
+                server.on_stop(&mut ctx).await;
+                break;
+            }
+            Err(msg) => msg,
+        };
+        let msg = match msg.try_into_msg::<SetHandler<S>>() {
+            Ok(SetHandler(new_handler)) => {
+                handler = Some(new_handler);
+                continue;
+            }
+            Err(msg) => msg,
+        };
+
+        let mut halted = false;
+        let process_id = ctx.inbox.process_id();
+        let catch_panics = server.catch_handler_panics();
+        let handled = async {
+            let handle = async {
+                match &mut handler {
+                    Some(handler) => handler.handle(&mut server, msg, &mut ctx).await,
+                    None => server.handle(msg, &mut ctx).await,
+                }
+            };
+            if catch_panics {
+                let _ = AssertUnwindSafe(handle).catch_unwind().await;
+            } else {
+                handle.await;
+            }
+        };
+        futures::pin_mut!(handled);
+        tokio::select! {
+            () = &mut handled => (),
+            () = ClosedFut::halted(&channel, &mut halted, process_id) => {
+                scope.cancel();
+                handled.await;
+            }
+        }
+        if halted {
+            break;
+        }
+    }
+    scope.cancel();
+    server
+}
+
+/// The context given to a [`Server`] while it handles a message.
+#[derive(Debug)]
+pub struct ServerCtx<P: Protocol> {
+    pub(super) inbox: Inbox<P>,
+    pub(super) stopped: bool,
+    pub(super) scope: CancelScope,
+}
+
+impl<P: Protocol> ServerCtx<P> {
+    /// Get a new address of this actor.
+    pub fn address(&self) -> Address<Inbox<P>> {
+        self.inbox.clone_address()
+    }
+
+    /// Whether the actor has been halted.
+    pub fn is_halted(&self) -> bool {
+        self.inbox.halted()
+    }
+
+    /// Stop the server after the current message has been handled.
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    /// Reply to a request, ignoring the reply if the caller is no longer waiting for it.
+    pub fn reply<R>(&self, tx: Tx<R>, reply: R) {
+        let _ = tx.send(reply);
+    }
+
+    /// The [`CancelScope`] of this server, which is cancelled once the server is halted.
+    ///
+    /// Requests that the server sends to other actors can be linked to this scope with
+    /// [`Rx::with_scope`], so that they are cancelled instead of awaited when the server is halted.
+    pub fn cancel_scope(&self) -> &CancelScope {
+        &self.scope
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Message, Debug)]
+    #[request(bool)]
+    struct IsHalted;
+
+    #[protocol]
+    enum TestProtocol {
+        Stop(()),
+        Forward(u32),
+        IsHalted(IsHalted),
+    }
+
+    #[derive(Default)]
+    struct TestServer(Vec<u32>);
+
+    #[async_trait]
+    impl Server for TestServer {
+        type Protocol = TestProtocol;
+
+        async fn handle(&mut self, msg: TestProtocol, ctx: &mut ServerCtx<TestProtocol>) {
+            match msg {
+                TestProtocol::Stop(()) => ctx.stop(),
+                TestProtocol::Forward(0) => self.0.push(0),
+                TestProtocol::Forward(n) => ctx.address().try_send(n - 1).unwrap(),
+                TestProtocol::IsHalted((IsHalted, tx)) => ctx.reply(tx, ctx.is_halted()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn server_stops() {
+        let (child, address) = spawn(|inbox| run_server(inbox, TestServer::default()));
+        assert!(!address.request(IsHalted).await.unwrap());
+        address.send(3u32).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        address.send(()).await.unwrap();
+        assert_eq!(child.await.unwrap().0, vec![0]);
+    }
+
+    #[protocol]
+    enum StoppableProtocol {
+        Stop(Stop),
+        Add(u32),
+    }
+
+    #[derive(Default, Debug, PartialEq)]
+    struct StoppableServer {
+        total: u32,
+        stopped: bool,
+    }
+
+    #[async_trait]
+    impl Server for StoppableServer {
+        type Protocol = StoppableProtocol;
+
+        async fn handle(
+            &mut self,
+            msg: StoppableProtocol,
+            _ctx: &mut ServerCtx<StoppableProtocol>,
+        ) {
+            if let StoppableProtocol::Add(n) = msg {
+                self.total += n
+            }
+        }
+
+        async fn on_stop(&mut self, _ctx: &mut ServerCtx<StoppableProtocol>) {
+            self.stopped = true;
+        }
+    }
+
+    #[tokio::test]
+    async fn stop_message_exits_cleanly() {
+        let (child, address) = spawn(|inbox| run_server(inbox, StoppableServer::default()));
+        address.send(3u32).await.unwrap();
+        address.send(Stop).await.unwrap();
+        let _ = address.send(4u32).await;
+        assert_eq!(
+            child.await.unwrap(),
+            StoppableServer {
+                total: 3,
+                stopped: true
+            }
+        );
+    }
+
+    #[derive(Message, Debug)]
+    #[request(u32)]
+    struct Panic;
+
+    #[protocol]
+    enum PanicProtocol {
+        Add(u32),
+        Panic(Panic),
+    }
+
+    #[derive(Default)]
+    struct PanicServer(u32);
+
+    #[async_trait]
+    impl Server for PanicServer {
+        type Protocol = PanicProtocol;
+
+        async fn handle(&mut self, msg: PanicProtocol, _ctx: &mut ServerCtx<PanicProtocol>) {
+            match msg {
+                PanicProtocol::Add(n) => self.0 += n,
+                PanicProtocol::Panic(_) => panic!("handler panicked"),
+            }
+        }
+
+        fn catch_handler_panics(&self) -> bool {
+            true
+        }
+    }
+
+    #[protocol]
+    enum SwappableProtocol {
+        SetHandler(SetHandler<SwappableServer>),
+        Add(u32),
+    }
+
+    #[derive(Default)]
+    struct SwappableServer(u32);
+
+    #[async_trait]
+    impl Server for SwappableServer {
+        type Protocol = SwappableProtocol;
+
+        async fn handle(
+            &mut self,
+            msg: SwappableProtocol,
+            _ctx: &mut ServerCtx<SwappableProtocol>,
+        ) {
+            if let SwappableProtocol::Add(n) = msg {
+                self.0 += n
+            }
+        }
+    }
+
+    struct Multiplying(u32);
+
+    #[async_trait]
+    impl ServerHandler<SwappableServer> for Multiplying {
+        async fn handle(
+            &mut self,
+            server: &mut SwappableServer,
+            msg: SwappableProtocol,
+            _ctx: &mut ServerCtx<SwappableProtocol>,
+        ) {
+            if let SwappableProtocol::Add(n) = msg {
+                server.0 += n * self.0
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn swapped_handler_keeps_state() {
+        let (child, address) = spawn(|inbox| run_server(inbox, SwappableServer::default()));
+        address.send(1u32).await.unwrap();
+        address.set_handler(Multiplying(10)).await.unwrap();
+        address.send(1u32).await.unwrap();
+        address.set_handler(Multiplying(100)).await.unwrap();
+        address.send(1u32).await.unwrap();
+        address.close();
+        assert_eq!(child.await.unwrap().0, 111);
+    }
+
+    #[tokio::test]
+    async fn caught_panic_continues() {
+        let (child, address) = spawn(|inbox| run_server(inbox, PanicServer::default()));
+        address.send(1u32).await.unwrap();
+        assert!(matches!(
+            address.request(Panic).await,
+            Err(RequestError::NoReply(_))
+        ));
+        address.send(2u32).await.unwrap();
+        address.close();
+        assert_eq!(child.await.unwrap().0, 3);
+    }
+
+    struct WaitingServer(Option<Result<(), ScopedRxError>>);
+
+    #[async_trait]
+    impl Server for WaitingServer {
+        type Protocol = TestProtocol;
+
+        async fn handle(&mut self, _msg: TestProtocol, ctx: &mut ServerCtx<TestProtocol>) {
+            let (_tx, rx) = new_request::<()>();
+            self.0 = Some(rx.with_scope(ctx.cancel_scope()).await);
+        }
+    }
+
+    #[tokio::test]
+    async fn halt_cancels_scope() {
+        let (child, address) = spawn(|inbox| run_server(inbox, WaitingServer(None)));
+        address.send(()).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        child.halt();
+        assert_eq!(child.await.unwrap().0, Some(Err(ScopedRxError::Cancelled)));
+    }
+}
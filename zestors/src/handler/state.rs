@@ -37,11 +37,7 @@ pub enum HandlerItem<P, S: Handler> {
 
 impl<P, H: Handler> HandlerItem<P, H> {
     pub fn is_dead(&self) -> bool {
-        if let Self::Event(Event::Dead) = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Self::Event(Event::Dead))
     }
 }
 
@@ -10,7 +10,7 @@
 //! - [`spawning`] - Spawning of actors.
 //! - [`handler`] - A simpler way to write your actors.
 //! - [`runtime`] - Runtime configuration.
-//! - [`supervision`] - (Not yet implemented)
+//! - [`supervision`] - Supervising actors and restarting them when they exit.
 //! - [`distribution`] - (Not yet implemented)
 //!
 //! # Minimal example
@@ -26,6 +26,8 @@ pub mod messaging;
 pub mod runtime;
 pub mod spawning;
 pub mod supervision;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 extern crate self as zestors;
 
@@ -46,6 +48,7 @@ pub mod prelude {
 mod all {
     pub use crate::actor_reference::*;
     pub use crate::actor_type::*;
+    #[allow(unused_imports)] // Distribution does not export anything yet.
     pub use crate::distribution::*;
     pub use crate::handler::*;
     pub use crate::messaging::*;
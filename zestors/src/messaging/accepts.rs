@@ -1,5 +1,8 @@
+use super::{deadlock::Waiting, pending::PendingRequest};
 use crate::all::*;
 use futures::{future::BoxFuture, Future};
+use std::{any::type_name, time::Duration};
+use tokio::time::Instant;
 
 /// [`Accepts`] is implemented for any [`ActorType`] that accepts the [`Message`] `M`.
 pub trait Accepts<M: Message>: ActorType {
@@ -22,10 +25,13 @@ pub trait AcceptsExt<M: Message>: Accepts<M> {
     {
         Box::pin(async move {
             match Self::try_send(channel, msg) {
-                Ok(rx) => match rx.await {
-                    Ok(msg) => Ok(msg),
-                    Err(e) => Err(TryRequestError::NoReply(e)),
-                },
+                Ok(rx) => {
+                    match PendingRequest::counted(channel.actor_id(), type_name::<M>(), rx).await {
+                        Some(Ok(msg)) => Ok(msg),
+                        Some(Err(e)) => Err(TryRequestError::NoReply(e)),
+                        None => Err(TryRequestError::Cancelled),
+                    }
+                }
                 Err(TrySendError::Closed(msg)) => Err(TryRequestError::Closed(msg)),
                 Err(TrySendError::Full(msg)) => Err(TryRequestError::Full(msg)),
             }
@@ -42,10 +48,13 @@ pub trait AcceptsExt<M: Message>: Accepts<M> {
     {
         Box::pin(async move {
             match Self::force_send(channel, msg) {
-                Ok(rx) => match rx.await {
-                    Ok(msg) => Ok(msg),
-                    Err(e) => Err(TryRequestError::NoReply(e)),
-                },
+                Ok(rx) => {
+                    match PendingRequest::counted(channel.actor_id(), type_name::<M>(), rx).await {
+                        Some(Ok(msg)) => Ok(msg),
+                        Some(Err(e)) => Err(TryRequestError::NoReply(e)),
+                        None => Err(TryRequestError::Cancelled),
+                    }
+                }
                 Err(TrySendError::Closed(msg)) => Err(TryRequestError::Closed(msg)),
                 Err(TrySendError::Full(msg)) => Err(TryRequestError::Full(msg)),
             }
@@ -61,14 +70,65 @@ pub trait AcceptsExt<M: Message>: Accepts<M> {
         F: Future<Output = Result<R, E>> + Send,
     {
         Box::pin(async move {
+            let Ok(_waiting) = Waiting::start(channel.actor_id()) else {
+                return Err(RequestError::Deadlock(msg));
+            };
             match Self::send(channel, msg).await {
-                Ok(rx) => match rx.await {
-                    Ok(msg) => Ok(msg),
-                    Err(e) => Err(RequestError::NoReply(e)),
-                },
+                Ok(rx) => {
+                    match PendingRequest::counted(channel.actor_id(), type_name::<M>(), rx).await {
+                        Some(Ok(msg)) => Ok(msg),
+                        Some(Err(e)) => Err(RequestError::NoReply(e)),
+                        None => Err(RequestError::Cancelled),
+                    }
+                }
                 Err(SendError(msg)) => Err(RequestError::Closed(msg)),
             }
         })
     }
+
+    fn request_timed<F, E, R>(
+        channel: &Self::Channel,
+        msg: M,
+    ) -> BoxFuture<'_, Result<(R, RequestTiming), RequestError<M, E>>>
+    where
+        M: Message<Returned = F> + Send + 'static,
+        F: Future<Output = Result<R, E>> + Send,
+    {
+        Box::pin(async move {
+            let Ok(_waiting) = Waiting::start(channel.actor_id()) else {
+                return Err(RequestError::Deadlock(msg));
+            };
+            match Self::send(channel, msg).await {
+                Ok(rx) => {
+                    let queued_at = Instant::now();
+                    match PendingRequest::counted(channel.actor_id(), type_name::<M>(), rx).await {
+                        Some(Ok(msg)) => Ok((msg, RequestTiming::replied_now(queued_at))),
+                        Some(Err(e)) => Err(RequestError::NoReply(e)),
+                        None => Err(RequestError::Cancelled),
+                    }
+                }
+                Err(SendError(msg)) => Err(RequestError::Closed(msg)),
+            }
+        })
+    }
+
+    fn request_timeout<F, E, R>(
+        channel: &Self::Channel,
+        msg: M,
+        timeout: Duration,
+    ) -> BoxFuture<'_, Result<R, RequestError<M, E>>>
+    where
+        M: Message<Returned = F> + Send + 'static,
+        F: Future<Output = Result<R, E>> + Send,
+    {
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, Self::request(channel, msg)).await {
+                Ok(result) => result,
+                Err(_) => Err(RequestError::Timeout {
+                    message_type: type_name::<M>(),
+                }),
+            }
+        })
+    }
 }
 impl<M: Message, T> AcceptsExt<M> for T where T: Accepts<M> {}
@@ -0,0 +1,131 @@
+#[allow(unused)]
+use crate::all::*;
+use std::marker::PhantomData;
+
+/// Create a new acknowledged request, consisting of an [`AckTx<R>`], an [`AckRx`] and an
+/// [`Rx<R>`].
+///
+/// The `AckTx` can first acknowledge the request to the `AckRx`, and later send the reply `R` to
+/// the `Rx`.
+pub fn new_ack_request<R>() -> (AckTx<R>, AckRx, Rx<R>) {
+    let (ack_tx, ack_rx) = new_request();
+    let (tx, rx) = new_request();
+    (
+        AckTx {
+            ack: Some(ack_tx),
+            reply: tx,
+        },
+        ack_rx,
+        rx,
+    )
+}
+
+/// The receiver of the acknowledgement of an [`AckRequest`].
+///
+/// This resolves with `Ok(())` once the request has been acknowledged, or fails if the [`AckTx`]
+/// was dropped before acknowledging.
+pub type AckRx = Rx<()>;
+
+/// A request which is acknowledged separately from being replied to, used as the `#[msg(..)]`
+/// attribute of the [`derive@Message`] derive macro.
+///
+/// When sent, this returns an `(AckRx, Rx<R>)`, and the actor receives the message together with
+/// an [`AckTx<R>`]. The actor can [`ack`](AckTx::ack) the message as soon as it has been
+/// received, and [`reply`](AckTx::reply) once it has been processed. This allows the sender to
+/// stop retrying the message once it's receipt is acknowledged, while still awaiting the reply.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, messaging::AckRequest};
+///
+/// #[derive(Message)]
+/// #[msg(AckRequest<u32>)]
+/// struct Job(u32);
+///
+/// # async fn main() {
+/// let ((job, mut tx), (ack_rx, rx)) = Job(10).create();
+/// tx.ack();
+/// assert_eq!(ack_rx.await, Ok(()));
+/// tx.reply(job.0 * 2).unwrap();
+/// assert_eq!(rx.await, Ok(20));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AckRequest<R>(PhantomData<fn() -> R>);
+
+impl<M, R> MessageDerive<M> for AckRequest<R> {
+    type Payload = (M, AckTx<R>);
+    type Returned = (AckRx, Rx<R>);
+
+    fn create(msg: M) -> ((M, AckTx<R>), (AckRx, Rx<R>)) {
+        let (tx, ack_rx, rx) = new_ack_request();
+        ((msg, tx), (ack_rx, rx))
+    }
+
+    fn cancel(sent: (M, AckTx<R>), _returned: (AckRx, Rx<R>)) -> M {
+        sent.0
+    }
+}
+
+/// The transmitter part of an [`AckRequest`], created with [`new_ack_request`].
+#[derive(Debug)]
+pub struct AckTx<R> {
+    ack: Option<Tx<()>>,
+    reply: Tx<R>,
+}
+
+impl<R> AckTx<R> {
+    /// Acknowledge the request, this does nothing if it has already been acknowledged.
+    pub fn ack(&mut self) {
+        if let Some(ack) = self.ack.take() {
+            let _ = ack.send(());
+        }
+    }
+
+    /// Whether the request has been acknowledged.
+    pub fn is_acked(&self) -> bool {
+        self.ack.is_none()
+    }
+
+    /// Send the reply, acknowledging the request first if that has not been done yet.
+    pub fn reply(mut self, reply: R) -> Result<(), TxError<R>> {
+        self.ack();
+        self.reply.send(reply)
+    }
+
+    /// Whether the [`Rx`] for the reply has been closed/dropped.
+    pub fn is_closed(&self) -> bool {
+        self.reply.is_closed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn reply_acknowledges() {
+        let (tx, ack_rx, rx) = new_ack_request();
+        assert!(!tx.is_acked());
+        tx.reply(1).unwrap();
+        assert_eq!(ack_rx.await, Ok(()));
+        assert_eq!(rx.await, Ok(1));
+    }
+
+    #[tokio::test]
+    async fn dropped_without_ack() {
+        let (tx, ack_rx, rx) = new_ack_request::<()>();
+        drop(rx);
+        assert!(tx.is_closed());
+        drop(tx);
+        assert_eq!(ack_rx.await, Err(RxError));
+
+        let (mut tx, ack_rx, rx) = new_ack_request::<()>();
+        tx.ack();
+        assert!(tx.is_acked());
+        drop(tx);
+        assert_eq!(ack_rx.await, Ok(()));
+        assert_eq!(rx.await, Err(RxError));
+    }
+}
@@ -1,5 +1,5 @@
 use super::*;
-use std::any::Any;
+use std::any::{Any, TypeId};
 
 /// A wrapper-type around a `Box<dyn Any + Send>` for a [`Message::Payload`].
 #[derive(Debug)]
@@ -27,6 +27,25 @@ impl BoxPayload {
         }
     }
 
+    /// Same as [`BoxPayload::downcast`], but returns a [`DowncastError`] describing the expected
+    /// and actual type of the payload. The payload is dropped if the downcast fails.
+    pub fn downcast_or_err<M>(self) -> Result<M::Payload, DowncastError>
+    where
+        M: Message,
+        M::Payload: 'static,
+    {
+        let actual = self.payload_type_id();
+        self.downcast::<M>().map_err(|_| DowncastError {
+            expected: std::any::type_name::<M::Payload>(),
+            actual,
+        })
+    }
+
+    /// The [`TypeId`] of the payload inside.
+    pub fn payload_type_id(&self) -> TypeId {
+        (*self.0).type_id()
+    }
+
     pub(crate) fn downcast_and_cancel<M>(self, returned: M::Returned) -> Result<M, Self>
     where
         M: Message,
@@ -75,5 +94,13 @@ mod test {
 
         let boxed = BoxPayload::new::<Msg1>(Msg1);
         assert!(boxed.downcast::<Msg2>().is_err());
+
+        let boxed = BoxPayload::new::<Msg1>(Msg1);
+        assert_eq!(boxed.payload_type_id(), TypeId::of::<Msg1>());
+        let Err(error) = boxed.downcast_or_err::<Msg2>() else {
+            panic!("downcast should fail");
+        };
+        assert!(error.expected.ends_with("Msg2"));
+        assert_eq!(error.actual, TypeId::of::<Msg1>());
     }
 }
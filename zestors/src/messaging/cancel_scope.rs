@@ -0,0 +1,249 @@
+#[allow(unused)]
+use crate::all::*;
+use event_listener::{Event, EventListener};
+use futures::{Future, FutureExt};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
+};
+
+/// A scope that cancels all requests linked to it, and those of it's child-scopes, at once.
+///
+/// Requests are linked to a scope with [`Rx::with_scope`]: once the scope is cancelled, the
+/// [`ScopedRx`] resolves with [`ScopedRxError::Cancelled`] and closes the request, so that the
+/// actor handling it can see with [`Tx::is_closed`] that the work is no longer needed.
+///
+/// Scopes form a tree, where cancelling a scope also cancels all of it's children. A [`Server`]
+/// has it's own scope, which is cancelled when the server is halted or stops; see
+/// [`ServerCtx::cancel_scope`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::messaging::{new_request, CancelScope, ScopedRxError};
+///
+/// # async fn main() {
+/// let parent = CancelScope::new();
+/// let child = parent.child();
+/// let (_tx, rx) = new_request::<u32>();
+/// let rx = rx.with_scope(&child);
+///
+/// parent.cancel();
+/// assert!(child.is_cancelled());
+/// assert_eq!(rx.await, Err(ScopedRxError::Cancelled));
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct CancelScope(Arc<ScopeInner>);
+
+#[derive(Default)]
+struct ScopeInner {
+    cancelled: AtomicBool,
+    event: Event,
+    children: Mutex<Vec<Weak<ScopeInner>>>,
+    /// Keeps the parent alive, so that it can still cancel this scope.
+    _parent: Option<Arc<ScopeInner>>,
+}
+
+impl CancelScope {
+    /// Create a new scope that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a child-scope, which is cancelled when this scope is cancelled.
+    ///
+    /// If this scope has already been cancelled, the child is cancelled as well.
+    pub fn child(&self) -> Self {
+        let child = Self(Arc::new(ScopeInner {
+            _parent: Some(self.0.clone()),
+            ..Default::default()
+        }));
+        let mut children = self.0.children.lock().unwrap();
+        if self.is_cancelled() {
+            child.cancel();
+        } else {
+            children.retain(|child| child.strong_count() > 0);
+            children.push(Arc::downgrade(&child.0));
+        }
+        child
+    }
+
+    /// Cancel this scope and all of it's children.
+    pub fn cancel(&self) {
+        let children = {
+            let mut children = self.0.children.lock().unwrap();
+            if self.0.cancelled.swap(true, Ordering::AcqRel) {
+                return;
+            }
+            std::mem::take(&mut *children)
+        };
+        self.0.event.notify(usize::MAX);
+        for child in children.iter().filter_map(Weak::upgrade) {
+            CancelScope(child).cancel();
+        }
+    }
+
+    /// Whether this scope has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Wait until this scope is cancelled.
+    pub fn cancelled(&self) -> CancelledFut<'_> {
+        CancelledFut {
+            scope: self,
+            listener: None,
+        }
+    }
+}
+
+impl Debug for CancelScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CancelScope")
+            .field(&self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Future returned by [`CancelScope::cancelled`].
+#[derive(Debug)]
+pub struct CancelledFut<'a> {
+    scope: &'a CancelScope,
+    listener: Option<EventListener>,
+}
+
+impl<'a> Future for CancelledFut<'a> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            if this.scope.is_cancelled() {
+                this.listener = None;
+                return Poll::Ready(());
+            }
+            match &mut this.listener {
+                Some(listener) => {
+                    futures::ready!(listener.poll_unpin(cx));
+                    this.listener = None;
+                }
+                None => this.listener = Some(this.scope.0.event.listen()),
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  ScopedRx
+//------------------------------------------------------------------------------------------------
+
+impl<M> Rx<M> {
+    /// Link this request to the [`CancelScope`], so that it is cancelled together with the scope.
+    pub fn with_scope(self, scope: &CancelScope) -> ScopedRx<M> {
+        ScopedRx {
+            rx: Some(self),
+            scope: scope.clone(),
+            listener: None,
+        }
+    }
+}
+
+/// An [`Rx`] that is linked to a [`CancelScope`], created with [`Rx::with_scope`].
+///
+/// When the scope is cancelled, the `Rx` is dropped so that the [`Tx`] is closed, and this
+/// resolves with [`ScopedRxError::Cancelled`].
+#[derive(Debug)]
+pub struct ScopedRx<M> {
+    rx: Option<Rx<M>>,
+    scope: CancelScope,
+    listener: Option<EventListener>,
+}
+
+impl<M> ScopedRx<M> {
+    /// The scope this request is linked to.
+    pub fn scope(&self) -> &CancelScope {
+        &self.scope
+    }
+}
+
+impl<M> Unpin for ScopedRx<M> {}
+
+impl<M> Future for ScopedRx<M> {
+    type Output = Result<M, ScopedRxError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let Some(rx) = &mut this.rx else {
+            return Poll::Ready(Err(ScopedRxError::Cancelled));
+        };
+
+        if let Poll::Ready(res) = rx.poll_unpin(cx) {
+            this.rx = None;
+            return Poll::Ready(res.map_err(|_| ScopedRxError::Closed));
+        }
+
+        loop {
+            if this.scope.is_cancelled() {
+                this.rx = None;
+                this.listener = None;
+                return Poll::Ready(Err(ScopedRxError::Cancelled));
+            }
+            match &mut this.listener {
+                Some(listener) => {
+                    futures::ready!(listener.poll_unpin(cx));
+                    this.listener = None;
+                }
+                None => this.listener = Some(this.scope.0.event.listen()),
+            }
+        }
+    }
+}
+
+/// Error returned when receiving a message using a [`ScopedRx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum ScopedRxError {
+    /// The [`Tx`] was dropped without sending a reply.
+    #[error("Failed to receive from Rx because it is closed.")]
+    Closed,
+    /// The [`CancelScope`] was cancelled.
+    #[error("Failed to receive from Rx because it's scope was cancelled.")]
+    Cancelled,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn reply_before_cancel() {
+        let scope = CancelScope::new();
+        let (tx, rx) = new_request();
+        tx.send(10).unwrap();
+        assert_eq!(rx.with_scope(&scope).await, Ok(10));
+    }
+
+    #[tokio::test]
+    async fn cancel_closes_tx() {
+        let scope = CancelScope::new();
+        let (mut tx, rx) = new_request::<()>();
+        let rx = tokio::spawn(rx.with_scope(&scope.child().child()));
+
+        tokio::task::yield_now().await;
+        scope.cancel();
+        assert_eq!(rx.await.unwrap(), Err(ScopedRxError::Cancelled));
+        tx.closed().await;
+    }
+
+    #[test]
+    fn child_of_cancelled_scope_is_cancelled() {
+        let scope = CancelScope::new();
+        scope.cancel();
+        assert!(scope.child().is_cancelled());
+    }
+}
@@ -0,0 +1,253 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::{
+    future::{BoxFuture, Shared, WeakShared},
+    Future, FutureExt,
+};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::sync::oneshot;
+
+type SharedReply<R> = Shared<BoxFuture<'static, Result<R, RxError>>>;
+type InFlight<K, R> = Arc<Mutex<HashMap<K, WeakShared<BoxFuture<'static, Result<R, RxError>>>>>>;
+
+/// Keeps track of the requests that are in flight by their key, so that identical requests sent
+/// with [`Address::request_coalesced`] reach the actor only once and share the reply.
+///
+/// Two requests are considered identical if their keys are equal, regardless of the messages
+/// themselves. The key should therefore identify everything the reply depends on, for example the
+/// key that is looked up in a cache.
+///
+/// A key is only remembered while it's request is in flight: it is forgotten as soon as the reply
+/// has been received, or once all [`SharedRx`]s of the request have been dropped. The amount of
+/// memory used is therefore bounded by the amount of concurrent requests with distinct keys.
+/// Since the reply is shared, it must be [`Clone`].
+///
+/// All clones share the same requests.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{messaging::RequestCoalescer, prelude::*, protocol};
+///
+/// #[derive(Message, Debug)]
+/// #[request(String)]
+/// struct Get(u32);
+///
+/// #[protocol]
+/// enum CacheProtocol {
+///     Get(Get),
+/// }
+///
+/// # async fn main() {
+/// let (_child, address) = spawn(|mut inbox: Inbox<CacheProtocol>| async move {
+///     while let Ok(CacheProtocol::Get((Get(key), tx))) = inbox.recv().await {
+///         let _ = tx.send(format!("value of {key}"));
+///     }
+/// });
+///
+/// let coalescer = RequestCoalescer::new();
+/// let first = address.request_coalesced(&coalescer, 1, Get(1)).await.unwrap();
+/// let duplicate = address.request_coalesced(&coalescer, 1, Get(1)).await.unwrap();
+/// assert_eq!(coalescer.in_flight(), 1);
+/// assert_eq!(first.await, Ok("value of 1".to_string()));
+/// assert_eq!(duplicate.await, Ok("value of 1".to_string()));
+/// # }
+/// ```
+pub struct RequestCoalescer<K, R> {
+    in_flight: InFlight<K, R>,
+}
+
+impl<K: Eq + Hash + Clone + Send + 'static, R: Clone + Send + Sync + 'static>
+    RequestCoalescer<K, R>
+{
+    /// Create a new coalescer without any requests in flight.
+    pub fn new() -> Self {
+        Self {
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The amount of distinct keys that are in flight.
+    pub fn in_flight(&self) -> usize {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        in_flight.retain(|_, reply| reply.upgrade().is_some());
+        in_flight.len()
+    }
+
+    /// Get the reply of the request in flight with the given key, or register a new one that is
+    /// sent by the returned sender if there is none.
+    #[allow(clippy::type_complexity)]
+    fn join_or_register(
+        &self,
+        key: &K,
+    ) -> Result<SharedRx<R>, (SharedRx<R>, oneshot::Sender<Rx<R>>)> {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        in_flight.retain(|_, reply| reply.upgrade().is_some());
+        if let Some(reply) = in_flight.get(key).and_then(WeakShared::upgrade) {
+            return Ok(SharedRx(reply));
+        }
+
+        let (tx, rx) = oneshot::channel::<Rx<R>>();
+        let (key_clone, in_flight_clone) = (key.clone(), self.in_flight.clone());
+        let reply = async move {
+            let reply = match rx.await {
+                Ok(rx) => rx.await,
+                Err(_) => Err(RxError),
+            };
+            in_flight_clone.lock().unwrap().remove(&key_clone);
+            reply
+        }
+        .boxed()
+        .shared();
+        in_flight.insert(key.clone(), reply.downgrade().unwrap());
+        Err((SharedRx(reply), tx))
+    }
+
+    /// Forget the request in flight with the given key.
+    fn remove(&self, key: &K) {
+        self.in_flight.lock().unwrap().remove(key);
+    }
+}
+
+impl<K: Eq + Hash + Clone + Send + 'static, R: Clone + Send + Sync + 'static> Default
+    for RequestCoalescer<K, R>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, R> Clone for RequestCoalescer<K, R> {
+    fn clone(&self) -> Self {
+        Self {
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+impl<K, R> Debug for RequestCoalescer<K, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestCoalescer").finish_non_exhaustive()
+    }
+}
+
+/// The reply of a request sent with [`Address::request_coalesced`], which is shared by all
+/// identical requests. This can be cloned, and every clone receives the same reply.
+///
+/// This fails with an [`RxError`] if the request was dropped without a reply, for example when
+/// the caller that sent it was cancelled before the request was sent.
+pub struct SharedRx<R>(SharedReply<R>);
+
+impl<R> Clone for SharedRx<R> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<R> Debug for SharedRx<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedRx").finish_non_exhaustive()
+    }
+}
+
+impl<R: Clone> Future for SharedRx<R> {
+    type Output = Result<R, RxError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll_unpin(cx)
+    }
+}
+
+impl<A: ActorType> Address<A> {
+    /// Send the request unless an identical request with the same key is already in flight, and
+    /// return the [`SharedRx`] of the reply.
+    ///
+    /// Only the first request with a key reaches the actor, while duplicates that are sent before
+    /// it has been replied to share it's reply; the messages of the duplicates are dropped. See
+    /// [`RequestCoalescer`] for when keys are forgotten.
+    ///
+    /// If the first request can't be sent, the message is returned in the error and duplicates
+    /// that were sent in the meantime fail with an [`RxError`].
+    pub async fn request_coalesced<K, M, R>(
+        &self,
+        coalescer: &RequestCoalescer<K, R>,
+        key: K,
+        msg: M,
+    ) -> Result<SharedRx<R>, SendError<M>>
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        M: Message<Returned = Rx<R>> + Send + 'static,
+        R: Clone + Send + Sync + 'static,
+        A: Accepts<M>,
+    {
+        let (reply, tx) = match coalescer.join_or_register(&key) {
+            Ok(reply) => return Ok(reply),
+            Err(registered) => registered,
+        };
+        match self.send(msg).await {
+            Ok(rx) => {
+                let _ = tx.send(rx);
+                Ok(reply)
+            }
+            Err(e) => {
+                coalescer.remove(&key);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[derive(Message, Debug)]
+    #[request(u32)]
+    struct Lookup;
+
+    #[protocol]
+    enum LookupProtocol {
+        Lookup(Lookup),
+    }
+
+    #[tokio::test]
+    async fn duplicates_share_a_single_request() {
+        let (_child, address) = spawn(|mut inbox: Inbox<LookupProtocol>| async move {
+            let mut received = 0;
+            while let Ok(LookupProtocol::Lookup((Lookup, tx))) = inbox.recv().await {
+                received += 1;
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                let _ = tx.send(received);
+            }
+        });
+        let coalescer = RequestCoalescer::new();
+
+        let mut replies = Vec::new();
+        for key in ["a", "a", "b", "a"] {
+            replies.push(
+                address
+                    .request_coalesced(&coalescer, key, Lookup)
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert_eq!(coalescer.in_flight(), 2);
+        let replies = futures::future::join_all(replies).await;
+        assert_eq!(replies, vec![Ok(1), Ok(1), Ok(2), Ok(1)]);
+        assert_eq!(coalescer.in_flight(), 0);
+
+        let reply = address
+            .request_coalesced(&coalescer, "a", Lookup)
+            .await
+            .unwrap();
+        assert_eq!(reply.await, Ok(3));
+    }
+}
@@ -0,0 +1,166 @@
+#[allow(unused)]
+use crate::all::*;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A request that carries the deadline of the caller, so that the actor can skip work that can't
+/// finish in time.
+///
+/// This wraps any request `M` with a [`Message::Payload`] of `(M, Tx<R>)`, such as messages with a
+/// `#[request(R)]` attribute. When sent, this returns an [`Rx<R>`] and the actor receives the
+/// message together with a [`DeadlineTx<R>`], from which the deadline can be read.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::{prelude::*, messaging::DeadlineRequest};
+///
+/// #[derive(Message)]
+/// #[request(Result<u32, &'static str>)]
+/// struct Job(u32);
+///
+/// # async fn main() {
+/// let ((job, tx), rx) = DeadlineRequest::after(Job(10), Duration::from_secs(1)).create();
+/// if tx.is_expired() {
+///     tx.send(Err("too late")).unwrap();
+/// } else {
+///     tx.send(Ok(job.0 * 2)).unwrap();
+/// }
+/// assert_eq!(rx.await, Ok(Ok(20)));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DeadlineRequest<M> {
+    msg: M,
+    deadline: Instant,
+}
+
+impl<M> DeadlineRequest<M> {
+    /// Create a new request that must be handled before the `deadline`.
+    pub fn new(msg: M, deadline: Instant) -> Self {
+        Self { msg, deadline }
+    }
+
+    /// Create a new request that must be handled within the `timeout`, starting now.
+    pub fn after(msg: M, timeout: Duration) -> Self {
+        Self::new(msg, Instant::now() + timeout)
+    }
+
+    /// The deadline of the request.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Take the message out, discarding the deadline.
+    pub fn into_inner(self) -> M {
+        self.msg
+    }
+}
+
+impl<M, R> Message for DeadlineRequest<M>
+where
+    M: Message<Payload = (M, Tx<R>), Returned = Rx<R>> + Send + 'static,
+    R: Send + 'static,
+{
+    type Payload = (M, DeadlineTx<R>);
+    type Returned = Rx<R>;
+
+    fn create(self) -> ((M, DeadlineTx<R>), Rx<R>) {
+        let ((msg, tx), rx) = self.msg.create();
+        let tx = DeadlineTx {
+            tx,
+            deadline: self.deadline,
+        };
+        ((msg, tx), rx)
+    }
+
+    fn cancel((msg, tx): (M, DeadlineTx<R>), rx: Rx<R>) -> Self {
+        let deadline = tx.deadline;
+        Self {
+            msg: M::cancel((msg, tx.tx), rx),
+            deadline,
+        }
+    }
+}
+
+/// The transmitter part of a [`DeadlineRequest`], which knows the deadline of the caller.
+#[derive(Debug)]
+pub struct DeadlineTx<R> {
+    tx: Tx<R>,
+    deadline: Instant,
+}
+
+impl<R> DeadlineTx<R> {
+    /// The deadline of the request.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// The time remaining until the deadline, or zero if it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Send the reply.
+    pub fn send(self, reply: R) -> Result<(), TxError<R>> {
+        self.tx.send(reply)
+    }
+
+    /// Whether the [`Rx`] has closed/dropped the oneshot-channel.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Convert this into a regular [`Tx`], discarding the deadline.
+    pub fn into_tx(self) -> Tx<R> {
+        self.tx
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Message, Debug)]
+    #[request(Result<u32, ()>)]
+    struct Job(u32);
+
+    #[protocol]
+    enum JobProtocol {
+        Job(DeadlineRequest<Job>),
+    }
+
+    #[tokio::test]
+    async fn actor_skips_expired_jobs() {
+        let (child, address) = spawn(|mut inbox: Inbox<JobProtocol>| async move {
+            while let Ok(JobProtocol::Job((job, tx))) = inbox.recv().await {
+                let reply = if tx.is_expired() { Err(()) } else { Ok(job.0) };
+                tx.send(reply).unwrap();
+            }
+        });
+
+        let job = DeadlineRequest::after(Job(1), Duration::from_secs(10));
+        assert_eq!(address.request(job).await.unwrap(), Ok(1));
+        let job = DeadlineRequest::new(Job(2), Instant::now());
+        assert_eq!(address.request(job).await.unwrap(), Err(()));
+        child.halt();
+    }
+
+    #[tokio::test]
+    async fn remaining_time() {
+        let ((job, tx), rx) = DeadlineRequest::after(Job(1), Duration::from_secs(10)).create();
+        assert!(tx.remaining() > Duration::from_secs(9));
+        assert!(!tx.is_expired());
+        tx.send(Ok(job.0)).unwrap();
+        assert_eq!(rx.await, Ok(Ok(1)));
+
+        let ((_job, tx), _rx) = DeadlineRequest::new(Job(1), Instant::now()).create();
+        assert_eq!(tx.remaining(), Duration::ZERO);
+    }
+}
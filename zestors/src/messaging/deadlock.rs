@@ -0,0 +1,132 @@
+#[allow(unused)]
+use crate::all::*;
+
+#[cfg(feature = "deadlock-detection")]
+use std::sync::Mutex;
+
+/// The wait-for graph: every edge is an actor waiting for the reply of another actor.
+#[cfg(feature = "deadlock-detection")]
+static WAITING: Mutex<Vec<(ActorId, ActorId)>> = Mutex::new(Vec::new());
+
+/// Returned by [`Waiting::start`] when waiting would complete a cycle.
+#[cfg_attr(not(feature = "deadlock-detection"), allow(dead_code))]
+#[derive(Debug)]
+pub(crate) struct Deadlock;
+
+/// Marks the current actor as waiting for the reply of another actor, until it is dropped.
+///
+/// This only does something with the `deadlock-detection` feature enabled, otherwise the
+/// current actor is never marked as waiting.
+#[derive(Debug)]
+pub(crate) struct Waiting {
+    #[cfg(feature = "deadlock-detection")]
+    edge: Option<(ActorId, ActorId)>,
+}
+
+impl Waiting {
+    /// Start waiting for the actor, failing if that actor is (transitively) waiting for the
+    /// current actor already.
+    ///
+    /// Outside of an actor, this never fails.
+    #[cfg(feature = "deadlock-detection")]
+    pub(crate) fn start(actor_id: ActorId) -> Result<Self, Deadlock> {
+        let Some(context) = ActorContext::current() else {
+            return Ok(Self { edge: None });
+        };
+        let mut waiting = WAITING.lock().unwrap();
+
+        let mut reachable = vec![actor_id];
+        let mut index = 0;
+        while let Some(&from) = reachable.get(index) {
+            if from == context.id {
+                return Err(Deadlock);
+            }
+            for &(_, to) in waiting.iter().filter(|(waiter, _)| *waiter == from) {
+                if !reachable.contains(&to) {
+                    reachable.push(to);
+                }
+            }
+            index += 1;
+        }
+
+        let edge = (context.id, actor_id);
+        waiting.push(edge);
+        Ok(Self { edge: Some(edge) })
+    }
+
+    #[cfg(not(feature = "deadlock-detection"))]
+    pub(crate) fn start(_actor_id: ActorId) -> Result<Self, Deadlock> {
+        Ok(Self {})
+    }
+}
+
+#[cfg(feature = "deadlock-detection")]
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        if let Some(edge) = self.edge {
+            let mut waiting = WAITING.lock().unwrap();
+            if let Some(index) = waiting.iter().position(|other| *other == edge) {
+                waiting.swap_remove(index);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "deadlock-detection"))]
+mod test {
+    use super::*;
+
+    #[derive(Message, Debug)]
+    #[request(bool)]
+    struct Start(Address<Inbox<AProtocol>>, Address<Inbox<BProtocol>>);
+
+    #[derive(Message, Debug)]
+    #[request(bool)]
+    struct CallBack(Address<Inbox<AProtocol>>);
+
+    #[derive(Message, Debug)]
+    #[request(bool)]
+    struct Hello;
+
+    #[protocol]
+    #[derive(Debug)]
+    enum AProtocol {
+        Start(Start),
+        Hello(Hello),
+    }
+
+    #[protocol]
+    #[derive(Debug)]
+    enum BProtocol {
+        CallBack(CallBack),
+    }
+
+    #[tokio::test]
+    async fn request_cycle_is_detected() {
+        let (_a, a) = spawn(|mut inbox: Inbox<AProtocol>| async move {
+            while let Ok(msg) = inbox.recv().await {
+                match msg {
+                    AProtocol::Start((Start(a, b), tx)) => {
+                        let _ = tx.send(b.request(CallBack(a)).await.unwrap());
+                    }
+                    AProtocol::Hello((Hello, tx)) => {
+                        let _ = tx.send(false);
+                    }
+                }
+            }
+        });
+        let (_b, b) = spawn(|mut inbox: Inbox<BProtocol>| async move {
+            while let Ok(BProtocol::CallBack((CallBack(a), tx))) = inbox.recv().await {
+                let deadlock = matches!(a.request(Hello).await, Err(RequestError::Deadlock(_)));
+                let _ = tx.send(deadlock);
+            }
+        });
+
+        assert!(a.request(Start(a.clone(), b.clone())).await.unwrap());
+        assert!(!a.request(Hello).await.unwrap());
+        let waiting = WAITING.lock().unwrap();
+        assert!(!waiting
+            .iter()
+            .any(|(from, _)| *from == a.actor_id() || *from == b.actor_id()));
+    }
+}
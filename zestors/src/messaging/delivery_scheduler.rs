@@ -0,0 +1,224 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use std::{fmt::Debug, time::Duration};
+use thiserror::Error;
+use tokio::time::{sleep_until, Instant};
+
+/// A handle to an actor that delivers messages to other actors after a delay.
+///
+/// Deliveries are scheduled with [`DeliveryScheduler::schedule`] and
+/// [`DeliveryScheduler::schedule_at`], which return a [`ScheduledDelivery`] that can be used to
+/// cancel it. A delivery is dropped without sending the message if the target actor exits before
+/// it is due. All deliveries wait on the timers of the tokio runtime, which are kept in a timer
+/// wheel, so that many deliveries can be pending at the same time.
+///
+/// The scheduler is spawned with [`DeliveryScheduler::spawn`], or supervised with
+/// [`DeliveryScheduler::spec`]. All pending deliveries are dropped when the scheduler exits.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::{messaging::DeliveryScheduler, prelude::*, protocol};
+///
+/// #[protocol]
+/// enum TickProtocol {
+///     Tick(u32),
+/// }
+///
+/// # async fn main() {
+/// let (_scheduler_child, scheduler) = DeliveryScheduler::spawn();
+/// let (child, address) = spawn(|mut inbox: Inbox<TickProtocol>| async move {
+///     let TickProtocol::Tick(n) = inbox.recv().await.unwrap();
+///     n
+/// });
+///
+/// let delay = Duration::from_millis(10);
+/// let delivery = scheduler.schedule(delay, address.clone(), 1u32).await.unwrap();
+/// scheduler.schedule(delay, address, 2u32).await.unwrap();
+/// delivery.cancel();
+/// assert_eq!(child.await.unwrap(), 2);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeliveryScheduler {
+    address: Address<Inbox<DeliveryProtocol>>,
+}
+
+impl DeliveryScheduler {
+    /// Spawn a new scheduler.
+    pub fn spawn() -> (Child<()>, Self) {
+        let (child, address) = spawn(run_delivery_scheduler);
+        (child.into_dyn(), Self { address })
+    }
+
+    /// A [`Specification`] of a scheduler, which is restarted if it panics and completes once it
+    /// has been halted.
+    ///
+    /// Every start returns a new handle, and the handles of previous starts can no longer be used
+    /// to schedule deliveries.
+    pub fn spec() -> impl Specification<Ref = DeliveryScheduler> {
+        SpawnSpec::new(
+            |inbox: Inbox<DeliveryProtocol>, ()| run_delivery_scheduler(inbox),
+            |exit: Result<(), ExitError>| async move { Ok(exit.err().map(|_| ())) },
+            (),
+        )
+        .on_start(|address| DeliveryScheduler { address })
+    }
+
+    /// Deliver the message to the target once the delay has passed.
+    pub async fn schedule<T, M>(
+        &self,
+        delay: Duration,
+        target: Address<T>,
+        msg: M,
+    ) -> Result<ScheduledDelivery, SchedulerExited>
+    where
+        T: ActorType + Accepts<M> + 'static,
+        M: Message + Send + 'static,
+    {
+        self.schedule_at(Instant::now() + delay, target, msg).await
+    }
+
+    /// Deliver the message to the target at the given instant.
+    ///
+    /// If the target is full at that time, the delivery waits for space in it's inbox.
+    pub async fn schedule_at<T, M>(
+        &self,
+        instant: Instant,
+        target: Address<T>,
+        msg: M,
+    ) -> Result<ScheduledDelivery, SchedulerExited>
+    where
+        T: ActorType + Accepts<M> + 'static,
+        M: Message + Send + 'static,
+    {
+        let scope = CancelScope::new();
+        let cancelled = scope.clone();
+        let exited = target.clone();
+        let delivery = async move {
+            tokio::select! {
+                () = sleep_until(instant) => {
+                    let _ = target.send(msg).await;
+                }
+                () = exited => (),
+                () = cancelled.cancelled() => (),
+            }
+        }
+        .boxed();
+
+        self.address
+            .request(Schedule(delivery))
+            .await
+            .map_err(|_| SchedulerExited)?;
+        Ok(ScheduledDelivery { scope })
+    }
+
+    /// Halt the scheduler, dropping all pending deliveries.
+    pub fn halt(&self) {
+        self.address.halt()
+    }
+}
+
+/// A delivery that has been scheduled with a [`DeliveryScheduler`], which can be used to cancel
+/// it. Dropping this does not cancel the delivery.
+#[derive(Debug, Clone)]
+pub struct ScheduledDelivery {
+    scope: CancelScope,
+}
+
+impl ScheduledDelivery {
+    /// Cancel the delivery, if the message has not been delivered yet.
+    pub fn cancel(&self) {
+        self.scope.cancel()
+    }
+
+    /// Whether the delivery has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.scope.is_cancelled()
+    }
+}
+
+/// Error returned by the [`DeliveryScheduler`] when the scheduler has exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't schedule the delivery because the scheduler has exited")]
+pub struct SchedulerExited;
+
+//------------------------------------------------------------------------------------------------
+//  Process
+//------------------------------------------------------------------------------------------------
+
+#[protocol]
+#[derive(Debug)]
+enum DeliveryProtocol {
+    Schedule(Schedule),
+}
+
+#[derive(Message)]
+#[request(())]
+struct Schedule(BoxFuture<'static, ()>);
+
+impl Debug for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Schedule").finish_non_exhaustive()
+    }
+}
+
+/// Run all scheduled deliveries until the scheduler is halted.
+async fn run_delivery_scheduler(mut inbox: Inbox<DeliveryProtocol>) {
+    let mut deliveries = FuturesUnordered::new();
+    loop {
+        tokio::select! {
+            msg = inbox.recv() => match msg {
+                Ok(DeliveryProtocol::Schedule((Schedule(delivery), tx))) => {
+                    deliveries.push(delivery);
+                    let _ = tx.send(());
+                }
+                Err(_) => break,
+            },
+            Some(()) = deliveries.next(), if !deliveries.is_empty() => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+
+    #[tokio::test]
+    async fn deliveries_are_dropped_when_cancelled_or_target_exited() {
+        let (_scheduler_child, scheduler) = DeliveryScheduler::spawn();
+        let (child, address) = spawn(|mut inbox: Inbox<U32Protocol>| async move {
+            let mut received = Vec::new();
+            while let Ok(U32Protocol::U32(n)) = inbox.recv().await {
+                received.push(n);
+            }
+            received
+        });
+        let (exited_child, exited) = spawn(|_inbox: Inbox<U32Protocol>| async move {});
+        exited_child.await.unwrap();
+
+        let delay = |millis| Duration::from_millis(millis);
+        scheduler
+            .schedule(delay(20), address.clone(), 2u32)
+            .await
+            .unwrap();
+        scheduler
+            .schedule(delay(10), address.clone(), 1u32)
+            .await
+            .unwrap();
+        let cancelled = scheduler
+            .schedule(delay(10), address.clone(), 3u32)
+            .await
+            .unwrap();
+        cancelled.cancel();
+        scheduler.schedule(delay(10), exited, 4u32).await.unwrap();
+
+        tokio::time::sleep(delay(40)).await;
+        address.halt();
+        assert_eq!(child.await.unwrap(), vec![1, 2]);
+        assert!(cancelled.is_cancelled());
+    }
+}
@@ -27,28 +27,28 @@ where
     ///
     /// See [`crate::messaging`] for more information.
     pub fn try_send(self) -> Result<M::Returned, TrySendError<M>> {
-        <A as Accepts<M>>::try_send(&self.channel, self.msg)
+        <A as Accepts<M>>::try_send(self.channel, self.msg)
     }
 
     /// Attempt to send the message.
     ///
     /// See [`crate::messaging`] for more information.
     pub fn force_send(self) -> Result<M::Returned, TrySendError<M>> {
-        <A as Accepts<M>>::force_send(&self.channel, self.msg)
+        <A as Accepts<M>>::force_send(self.channel, self.msg)
     }
 
     /// Attempt to send the message.
     ///
     /// See [`crate::messaging`] for more information.
     pub fn send_blocking(self) -> Result<M::Returned, SendError<M>> {
-        <A as Accepts<M>>::send_blocking(&self.channel, self.msg)
+        <A as Accepts<M>>::send_blocking(self.channel, self.msg)
     }
 
     /// Attempt to send the message.
     ///
     /// See [`crate::messaging`] for more information.
     pub fn send(self) -> <A as Accepts<M>>::SendFut<'a> {
-        <A as Accepts<M>>::send(&self.channel, self.msg)
+        <A as Accepts<M>>::send(self.channel, self.msg)
     }
 
     /// Attempt to send the message.
@@ -59,7 +59,7 @@ where
         M: Message<Returned = F> + Send + 'static,
         F: Future<Output = Result<R, E>> + Send,
     {
-        <A as AcceptsExt<M>>::try_request(&self.channel, self.msg)
+        <A as AcceptsExt<M>>::try_request(self.channel, self.msg)
     }
 
     /// Attempt to send the message.
@@ -70,7 +70,7 @@ where
         M: Message<Returned = F> + Send + 'static,
         F: Future<Output = Result<R, E>> + Send,
     {
-        <A as AcceptsExt<M>>::force_request(&self.channel, self.msg)
+        <A as AcceptsExt<M>>::force_request(self.channel, self.msg)
     }
 
     /// Attempt to send the message.
@@ -81,6 +81,6 @@ where
         M: Message<Returned = F> + Send + 'static,
         F: Future<Output = Result<R, E>> + Send,
     {
-        <A as AcceptsExt<M>>::request(&self.channel, self.msg)
+        <A as AcceptsExt<M>>::request(self.channel, self.msg)
     }
 }
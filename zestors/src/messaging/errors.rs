@@ -1,3 +1,5 @@
+#[allow(unused)]
+use crate::all::*;
 use thiserror::Error;
 
 /// Error returned when trying to send a message, checking at compile-time that
@@ -32,23 +34,50 @@ pub enum TrySendError<M> {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
 pub struct SendError<M>(pub M);
 
+/// Error returned by [`BoxPayload::downcast_or_err`] when the payload is of a different type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't downcast payload into {expected}, because it has type-id {actual:?}")]
+pub struct DowncastError {
+    /// The type-name of the payload that was expected.
+    pub expected: &'static str,
+    /// The type-id of the actual payload.
+    pub actual: std::any::TypeId,
+}
+
 /// Error returned when sending a request.
 ///
-/// This error combines failures in sending and receiving.
+/// This error combines failures in sending and receiving. New ways for a request to fail can be
+/// added without a breaking change, which is why this is `non_exhaustive`.
 #[derive(Debug, thiserror::Error, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum RequestError<M, E> {
     NoReply(E),
     Closed(M),
+    /// No reply was received within the timeout of [`request_timeout`](ActorRefExt::request_timeout).
+    /// The `message_type` is the type-name of the request.
+    Timeout { message_type: &'static str },
+    /// The request was not sent, because waiting for it's reply would complete a cycle of actors
+    /// that wait for each other's replies. This is only detected with the `deadlock-detection`
+    /// feature.
+    Deadlock(M),
+    /// The request was cancelled by it's id before a reply was received. This is only possible
+    /// with the `request-tracking` feature.
+    Cancelled,
 }
 
 /// Error returned when trying to send a request.
 ///
-/// This error combines failures in sending and receiving.
+/// This error combines failures in sending and receiving. Like [`RequestError`], this is
+/// `non_exhaustive`.
 #[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TryRequestError<M, E> {
     NoReply(E),
     Closed(M),
     Full(M),
+    /// The request was cancelled by it's id before a reply was received. This is only possible
+    /// with the `request-tracking` feature.
+    Cancelled,
 }
 
 /// Error returned when receiving a message.
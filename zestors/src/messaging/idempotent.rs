@@ -0,0 +1,276 @@
+#[allow(unused)]
+use crate::all::*;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// A key that identifies a message across retries, see [`Idempotent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IdempotencyKey(pub u64);
+
+impl From<u64> for IdempotencyKey {
+    fn from(key: u64) -> Self {
+        Self(key)
+    }
+}
+
+/// A message that carries an [`IdempotencyKey`], so that the actor can drop duplicates with a
+/// [`Deduplicator`].
+///
+/// This wraps any message `M`, and the actor receives the key together with the
+/// [`Message::Payload`] of `M`. A client that retries a message should send it with the same key
+/// every time.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::{prelude::*, messaging::{Deduplicator, Idempotent}};
+///
+/// #[derive(Message)]
+/// #[request(u32)]
+/// struct Charge(u32);
+///
+/// # async fn main() {
+/// let mut dedup = Deduplicator::new(100, Duration::from_secs(60));
+/// let mut charged = 0;
+/// let mut rxs = Vec::new();
+///
+/// for _ in 0..2 {
+///     let ((key, (charge, tx)), rx) = Idempotent::new(Charge(10), 1).create();
+///     rxs.push(rx);
+///     if let Some(tx) = dedup.check_request(key, tx) {
+///         charged += charge.0;
+///         dedup.reply(key, tx, charged);
+///     }
+/// }
+///
+/// assert_eq!(charged, 10);
+/// for rx in rxs {
+///     assert_eq!(rx.await, Ok(10));
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Idempotent<M> {
+    msg: M,
+    key: IdempotencyKey,
+}
+
+impl<M> Idempotent<M> {
+    /// Create a new message with the given key.
+    pub fn new(msg: M, key: impl Into<IdempotencyKey>) -> Self {
+        Self {
+            msg,
+            key: key.into(),
+        }
+    }
+
+    /// The key of the message.
+    pub fn key(&self) -> IdempotencyKey {
+        self.key
+    }
+
+    /// Take the message out, discarding the key.
+    pub fn into_inner(self) -> M {
+        self.msg
+    }
+}
+
+impl<M: Message> Message for Idempotent<M> {
+    type Payload = (IdempotencyKey, M::Payload);
+    type Returned = M::Returned;
+
+    fn create(self) -> (Self::Payload, Self::Returned) {
+        let (payload, returned) = self.msg.create();
+        ((self.key, payload), returned)
+    }
+
+    fn cancel((key, payload): Self::Payload, returned: Self::Returned) -> Self {
+        Self {
+            msg: M::cancel(payload, returned),
+            key,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Deduplicator
+//------------------------------------------------------------------------------------------------
+
+/// Keeps track of the [`IdempotencyKey`]s an actor has received, so that duplicates can be
+/// dropped.
+///
+/// A key is remembered for the `window` after it was first received, and at most `capacity` keys
+/// are remembered at the same time: when full, the oldest key is forgotten. A duplicate that is
+/// received after it's key has been forgotten is treated as a new message.
+///
+/// For requests, the reply of the first message is replayed to every duplicate with
+/// [`Deduplicator::reply`]. Duplicates received before the reply is sent wait for it, and are
+/// dropped if the key is forgotten before then.
+#[derive(Debug)]
+pub struct Deduplicator<R = ()> {
+    capacity: usize,
+    window: Duration,
+    keys: HashMap<IdempotencyKey, Entry<R>>,
+    order: VecDeque<(IdempotencyKey, Instant)>,
+}
+
+#[derive(Debug)]
+struct Entry<R> {
+    reply: Option<R>,
+    waiting: Vec<Tx<R>>,
+}
+
+impl<R> Deduplicator<R> {
+    /// Create a new deduplicator, remembering at most `capacity` keys for the `window`.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is `0`.
+    pub fn new(capacity: usize, window: Duration) -> Self {
+        assert!(capacity > 0, "capacity must be greater than 0");
+        Self {
+            capacity,
+            window,
+            keys: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// The maximum amount of keys remembered at the same time.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// How long a key is remembered after it was first received.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// The amount of keys currently remembered, including expired ones that have not been
+    /// forgotten yet.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether no keys are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Check whether the message with this key is new, remembering the key if it is.
+    ///
+    /// Returns `false` if the message is a duplicate, and should be dropped.
+    pub fn check(&mut self, key: IdempotencyKey) -> bool {
+        self.forget_expired();
+        if self.keys.contains_key(&key) {
+            return false;
+        }
+
+        if self.keys.len() >= self.capacity {
+            if let Some((oldest, _)) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+        self.keys.insert(
+            key,
+            Entry {
+                reply: None,
+                waiting: Vec::new(),
+            },
+        );
+        self.order.push_back((key, Instant::now()));
+        true
+    }
+
+    fn forget_expired(&mut self) {
+        while let Some((key, received)) = self.order.front() {
+            if received.elapsed() < self.window {
+                break;
+            }
+            self.keys.remove(key);
+            self.order.pop_front();
+        }
+    }
+}
+
+impl<R: Clone> Deduplicator<R> {
+    /// Check whether the request with this key is new, returning the [`Tx`] if it is.
+    ///
+    /// If the request is a duplicate, this returns `None` and the `tx` receives the reply of the
+    /// original request, as soon as it has been sent with [`Deduplicator::reply`].
+    pub fn check_request(&mut self, key: IdempotencyKey, tx: Tx<R>) -> Option<Tx<R>> {
+        if self.check(key) {
+            return Some(tx);
+        }
+
+        let entry = self.keys.get_mut(&key).unwrap();
+        match &entry.reply {
+            Some(reply) => {
+                let _ = tx.send(reply.clone());
+            }
+            None => entry.waiting.push(tx),
+        }
+        None
+    }
+
+    /// Reply to a request, and to all of it's duplicates.
+    ///
+    /// The reply is remembered, and replayed to duplicates received later on.
+    pub fn reply(&mut self, key: IdempotencyKey, tx: Tx<R>, reply: R) {
+        if let Some(entry) = self.keys.get_mut(&key) {
+            for waiting in entry.waiting.drain(..) {
+                let _ = waiting.send(reply.clone());
+            }
+            entry.reply = Some(reply.clone());
+        }
+        let _ = tx.send(reply);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn duplicates_are_dropped() {
+        let mut dedup = Deduplicator::<()>::new(2, Duration::from_secs(60));
+        assert!(dedup.check(IdempotencyKey(1)));
+        assert!(!dedup.check(IdempotencyKey(1)));
+        assert!(dedup.check(IdempotencyKey(2)));
+
+        // The oldest key is forgotten once the capacity is reached.
+        assert!(dedup.check(IdempotencyKey(3)));
+        assert_eq!(dedup.len(), 2);
+        assert!(dedup.check(IdempotencyKey(1)));
+    }
+
+    #[tokio::test]
+    async fn keys_expire_after_window() {
+        let mut dedup = Deduplicator::<()>::new(10, Duration::from_millis(10));
+        assert!(dedup.check(IdempotencyKey(1)));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(dedup.check(IdempotencyKey(1)));
+        assert_eq!(dedup.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn duplicates_wait_for_reply() {
+        let mut dedup = Deduplicator::new(10, Duration::from_secs(60));
+        let key = IdempotencyKey(1);
+        let (tx1, rx1) = new_request();
+        let (tx2, rx2) = new_request();
+        let (tx3, rx3) = new_request();
+
+        let tx1 = dedup.check_request(key, tx1).unwrap();
+        assert!(dedup.check_request(key, tx2).is_none());
+        dedup.reply(key, tx1, 10u32);
+        assert!(dedup.check_request(key, tx3).is_none());
+
+        assert_eq!(rx1.await, Ok(10));
+        assert_eq!(rx2.await, Ok(10));
+        assert_eq!(rx3.await, Ok(10));
+    }
+}
@@ -12,7 +12,7 @@
 //!
 //! # The `Message` macro
 //! When using the derive [`macro@Message`] macro it is possible to set a `#[msg(T)]` or `#[request(T)]`
-//! attribute which specifies how the actor should handle the message. There are three types for which this
+//! attribute which specifies how the actor should handle the message. There are four types for which this
 //! is implemented automatically:
 //!
 //! | Attribute | Result |
@@ -20,6 +20,7 @@
 //! | `none` / `#[msg(())]` | A simple message that does not receive a reply, the [`Message::Payload`] is `M` and [`Message::Returned`] is `()`. |
 //! | `#[request(T)]` / `#[msg(Rx<T>)]` | A request of `T` where the [`Message::Payload`] is [`(M, Tx<T>)`](Tx)  and the [`Message::Returned`] is [`Rx<T>`]. |
 //! | `#[msg(Tx<T>)]` | Same as `Rx` but swapped. |
+//! | `#[msg(AckRequest<T>)]` | A request of `T` that is acknowledged separately, where the [`Message::Payload`] is [`(M, AckTx<T>)`](AckTx) and the [`Message::Returned`] is [`(AckRx, Rx<T>)`](AckRx). |
 //!
 //! It is possible to create custom types usable in the `#[msg(..)]` attribute by implementing [`MessageDerive<M>`]
 //! for this type.
@@ -58,6 +59,63 @@
 //! These  methods will send the request and subsequently await a response from the actor with a single method
 //! and `.await` point.
 //!
+//! With [`request_timeout`](ActorRefExt::request_timeout) the request fails with a [`RequestError::Timeout`]
+//! if no reply arrives in time; the error names the type of the request, so that logs show which request hung.
+//!
+//! With [`request_timed`](ActorRefExt::request_timed) the reply is returned together with a [`RequestTiming`],
+//! which contains when the request was queued and replied to, for building latency histograms.
+//!
+//! With the `deadlock-detection` feature, every actor that awaits a reply is marked as waiting for the actor it
+//! sent the request to. A request that would complete a cycle of actors waiting for each other fails with a
+//! [`RequestError::Deadlock`] instead of hanging forever. Since this is detected per actor, a pool of which
+//! only some processes are waiting can be reported as well.
+//!
+//! With the `request-tracking` feature, every request is counted as pending for the actor it was sent to,
+//! from the moment it has been sent until the reply has been received or the request is dropped. The
+//! amount of pending requests of an actor is returned by [`pending_requests`](ActorRefExt::pending_requests),
+//! which can show that an actor is backed up before it's inbox fills. This updates a global registry twice for
+//! every request, so it is disabled by default.
+//!
+//! Every pending request gets a [`RequestId`] when it is sent, and can be listed together with it's type-name and
+//! the time it was sent with [`list_pending_requests`](ActorRefExt::list_pending_requests). A request that is stuck
+//! can then be cancelled with [`cancel_request`](ActorRefExt::cancel_request): the caller wakes with a
+//! [`RequestError::Cancelled`] and the reply is dropped, so that the actor sees [`Tx::is_closed`].
+//!
+//! A request can carry the deadline of the caller by wrapping it in a [`DeadlineRequest`]. The actor then
+//! receives a [`DeadlineTx`] instead of a [`Tx`], and can reply with an error instead of doing work that can't
+//! finish in time.
+//!
+//! Messages that may be sent more than once by a retrying client can carry an [`IdempotencyKey`] by wrapping
+//! them in an [`Idempotent`] message. The actor then drops duplicates with a [`Deduplicator`], which replays the
+//! original reply to duplicate requests.
+//!
+//! On the side of the caller, identical requests that are in flight at the same time can be coalesced with
+//! [`Address::request_coalesced`], so that only one reaches the actor and all callers share it's [`SharedRx`]. The
+//! requests in flight are tracked by key in a [`RequestCoalescer`].
+//!
+//! Requests can be linked to a [`CancelScope`] with [`Rx::with_scope`], so that they are all cancelled at once when
+//! the scope is cancelled, for example when the actor that sent them is halted.
+//!
+//! Many pending requests can be awaited in order of priority with a [`PriorityRequestSet`], or all at once
+//! with [`join_all_timeout`], which gives every [`Rx`] it's own timeout.
+//!
+//! Messages can be delivered to an actor after a delay with a [`DeliveryScheduler`], which is an actor itself.
+//! Every [`ScheduledDelivery`] can be cancelled, and is dropped when it's target exits before it is due.
+//!
+//! A request that expects many replies can use a [`StreamRx`] ([`new_stream_request`]) instead. If the replies
+//! are small and numerous, a [`BatchStreamRequest`] can be used to send them in chunks.
+//!
+//! # Tracing
+//! With the `message-tracing` feature, the first request that is sent outside of a trace starts a new [`TraceId`], which is carried with the message
+//! in the inbox of the actor. While handling it, the actor can get the trace with [`current_trace_id`], and all
+//! messages and requests it sends carry the same trace. A request that is forwarded through many actors can
+//! therefore be followed in their logs.
+//!
+//! # Sub-protocols
+//! A [`SubProtocol`] routes the messages of a large protocol to a smaller one, which only contains the
+//! messages a handler cares about. Messages that are not matched are forwarded, dropped or returned
+//! according to it's [`Fallback`].
+//!
 //! # Envelope
 //! An [`Envelope`](struct@Envelope) is a [`Message`] containing an [`Address`] of where it should be sent. An envelope
 //! can be created with the [`ActorRefExt::envelope`] function.
@@ -79,16 +137,47 @@ use crate::all::*;
 
 pub use zestors_codegen::{protocol, Envelope, Message};
 mod accepts;
+mod ack_request;
 mod box_payload;
+mod cancel_scope;
+mod coalesce;
+mod deadline_request;
+mod deadlock;
+mod delivery_scheduler;
 mod envelope;
 mod errors;
+mod idempotent;
 mod message;
+mod pending;
+mod priority_request_set;
 mod protocol;
 mod request;
+mod stream_request;
+mod sub_protocol;
+mod trace;
 pub use accepts::*;
+pub use ack_request::*;
 pub use box_payload::*;
+pub use cancel_scope::*;
+pub use coalesce::*;
+pub use deadline_request::*;
+pub use delivery_scheduler::*;
 pub use envelope::*;
 pub use errors::*;
+pub use idempotent::*;
 pub use message::*;
+pub use priority_request_set::*;
 pub use protocol::*;
 pub use request::*;
+pub use stream_request::*;
+pub use sub_protocol::*;
+#[cfg(feature = "message-tracing")]
+pub use trace::{current_trace_id, TraceId};
+#[cfg(feature = "request-tracking")]
+pub(crate) use pending::{cancel_request, list_pending_requests, pending_requests};
+#[cfg(feature = "request-tracking")]
+pub use pending::{PendingRequestInfo, RequestId};
+pub(crate) use trace::{
+    create_traced, current_trace, set_current_trace, start_trace, trace_scope, traced, MessageTrace,
+    TraceScope,
+};
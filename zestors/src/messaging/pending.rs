@@ -0,0 +1,252 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+
+#[cfg(feature = "request-tracking")]
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        LazyLock, Mutex,
+    },
+};
+#[cfg(feature = "request-tracking")]
+use tokio::{sync::oneshot, time::Instant};
+
+/// The requests every actor has not replied to yet.
+#[cfg(feature = "request-tracking")]
+static PENDING: LazyLock<Mutex<HashMap<ActorId, HashMap<RequestId, Pending>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A request-id is an incrementally-generated id, unique per pending request.
+#[cfg(feature = "request-tracking")]
+#[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct RequestId(u64);
+
+#[cfg(feature = "request-tracking")]
+impl RequestId {
+    fn generate() -> Self {
+        static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+        RequestId(NEXT_REQUEST_ID.fetch_add(1, Ordering::AcqRel))
+    }
+
+    /// Convert the request-id to a u64.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(feature = "request-tracking")]
+impl Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// Information about a request that is awaiting a reply, returned by
+/// [`list_pending_requests`](ActorRefExt::list_pending_requests).
+#[cfg(feature = "request-tracking")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingRequestInfo {
+    /// The id, with which the request can be cancelled.
+    pub id: RequestId,
+    /// The type-name of the request.
+    pub message_type: &'static str,
+    /// When the request was sent.
+    pub sent_at: Instant,
+}
+
+/// A request in the registry, together with the sender that cancels it.
+#[cfg(feature = "request-tracking")]
+#[derive(Debug)]
+struct Pending {
+    message_type: &'static str,
+    sent_at: Instant,
+    cancel: oneshot::Sender<()>,
+}
+
+/// Registers a request as pending for the actor it was sent to, until it is dropped.
+///
+/// This only does something with the `request-tracking` feature enabled.
+#[derive(Debug)]
+pub(crate) struct PendingRequest {
+    #[cfg(feature = "request-tracking")]
+    actor_id: ActorId,
+    #[cfg(feature = "request-tracking")]
+    id: RequestId,
+}
+
+impl PendingRequest {
+    /// Start tracking a request that has been sent to the actor, returning the receiver that
+    /// completes once the request is cancelled.
+    #[cfg(feature = "request-tracking")]
+    fn start(actor_id: ActorId, message_type: &'static str) -> (Self, oneshot::Receiver<()>) {
+        let (cancel, cancelled) = oneshot::channel();
+        let id = RequestId::generate();
+        let pending = Pending {
+            message_type,
+            sent_at: Instant::now(),
+            cancel,
+        };
+        PENDING
+            .lock()
+            .unwrap()
+            .entry(actor_id)
+            .or_default()
+            .insert(id, pending);
+        (Self { actor_id, id }, cancelled)
+    }
+
+    /// Await the reply of a request that has been sent to the actor, tracking it as pending
+    /// until the reply is received or this future is dropped.
+    ///
+    /// Returns `None` if the request was cancelled with [`cancel_request`], in which case the
+    /// reply is dropped.
+    #[cfg(feature = "request-tracking")]
+    pub(crate) async fn counted<F: Future>(
+        actor_id: ActorId,
+        message_type: &'static str,
+        reply: F,
+    ) -> Option<F::Output> {
+        let (_pending, cancelled) = Self::start(actor_id, message_type);
+        tokio::select! {
+            reply = reply => Some(reply),
+            Ok(()) = cancelled => None,
+        }
+    }
+
+    #[cfg(not(feature = "request-tracking"))]
+    pub(crate) async fn counted<F: Future>(
+        _actor_id: ActorId,
+        _message_type: &'static str,
+        reply: F,
+    ) -> Option<F::Output> {
+        Some(reply.await)
+    }
+}
+
+#[cfg(feature = "request-tracking")]
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        let mut pending = PENDING.lock().unwrap();
+        if let Some(requests) = pending.get_mut(&self.actor_id) {
+            requests.remove(&self.id);
+            if requests.is_empty() {
+                pending.remove(&self.actor_id);
+            }
+        }
+    }
+}
+
+/// The amount of requests that have been sent to the actor, and are awaiting a reply.
+#[cfg(feature = "request-tracking")]
+pub(crate) fn pending_requests(actor_id: ActorId) -> usize {
+    PENDING
+        .lock()
+        .unwrap()
+        .get(&actor_id)
+        .map(HashMap::len)
+        .unwrap_or_default()
+}
+
+/// The requests that have been sent to the actor and are awaiting a reply, in the order they
+/// were sent.
+#[cfg(feature = "request-tracking")]
+pub(crate) fn list_pending_requests(actor_id: ActorId) -> Vec<PendingRequestInfo> {
+    let mut requests = PENDING
+        .lock()
+        .unwrap()
+        .get(&actor_id)
+        .map(|requests| {
+            requests
+                .iter()
+                .map(|(id, pending)| PendingRequestInfo {
+                    id: *id,
+                    message_type: pending.message_type,
+                    sent_at: pending.sent_at,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    requests.sort_by_key(|info| info.id);
+    requests
+}
+
+/// Cancel the pending request of the actor, returning whether it was still pending.
+#[cfg(feature = "request-tracking")]
+pub(crate) fn cancel_request(actor_id: ActorId, id: RequestId) -> bool {
+    let mut pending = PENDING.lock().unwrap();
+    let Some(requests) = pending.get_mut(&actor_id) else {
+        return false;
+    };
+    let cancelled = requests
+        .remove(&id)
+        .map(|request| request.cancel.send(()).is_ok());
+    if requests.is_empty() {
+        pending.remove(&actor_id);
+    }
+    cancelled.unwrap_or(false)
+}
+
+#[cfg(all(test, feature = "request-tracking"))]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+    use std::time::Duration;
+
+    #[derive(Message, Debug)]
+    #[request(u32)]
+    struct Echo(u32);
+
+    #[protocol]
+    #[derive(Debug)]
+    enum EchoProtocol {
+        Echo(Echo),
+    }
+
+    #[tokio::test]
+    async fn requests_are_pending_until_replied() {
+        let (_child, address) = spawn(|mut inbox: Inbox<EchoProtocol>| async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            while let Ok(EchoProtocol::Echo((Echo(n), tx))) = inbox.recv().await {
+                let _ = tx.send(n);
+            }
+        });
+        let (first, second, pending) =
+            tokio::join!(address.request(Echo(1)), address.request(Echo(2)), async {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                address.pending_requests()
+            });
+        assert_eq!((first.unwrap(), second.unwrap()), (1, 2));
+        assert_eq!(pending, 2);
+        assert_eq!(address.pending_requests(), 0);
+
+        // Sending without awaiting the reply is not counted.
+        let (_child, address) = spawn(|mut inbox: Inbox<U32Protocol>| async move {
+            let _ = inbox.recv().await;
+        });
+        address.send(1u32).await.unwrap();
+        assert_eq!(address.pending_requests(), 0);
+    }
+
+    #[tokio::test]
+    async fn cancelled_requests_close_their_tx() {
+        let (child, address) = spawn(|mut inbox: Inbox<EchoProtocol>| async move {
+            let EchoProtocol::Echo((Echo(_), mut tx)) = inbox.recv().await.unwrap();
+            tx.closed().await;
+            tx.is_closed()
+        });
+        let (reply, ()) = tokio::join!(address.request(Echo(1)), async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let pending = address.list_pending_requests();
+            assert_eq!(pending.len(), 1);
+            assert!(pending[0].message_type.ends_with("Echo"));
+            assert!(address.cancel_request(pending[0].id));
+            assert!(!address.cancel_request(pending[0].id));
+        });
+        assert!(matches!(reply, Err(RequestError::Cancelled)));
+        assert!(child.await.unwrap());
+        assert_eq!(address.pending_requests(), 0);
+    }
+}
@@ -0,0 +1,178 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::{FutureExt, Stream};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A set of pending requests, where every [`Rx`] has a priority `K`.
+///
+/// As a [`Stream`], this yields the replies together with their priority. Among all replies that
+/// are ready, the one with the highest priority is yielded first, regardless of the order in which
+/// they arrived. Replies of equal priority are yielded in the order they were pushed.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::messaging::{new_request, PriorityRequestSet};
+/// use futures::StreamExt;
+///
+/// # async fn main() {
+/// let (low_tx, low_rx) = new_request();
+/// let (high_tx, high_rx) = new_request();
+///
+/// let mut set = PriorityRequestSet::new();
+/// set.push(1, low_rx);
+/// set.push(10, high_rx);
+///
+/// low_tx.send("low").unwrap();
+/// high_tx.send("high").unwrap();
+/// assert_eq!(set.next().await, Some((10, Ok("high"))));
+/// assert_eq!(set.next().await, Some((1, Ok("low"))));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PriorityRequestSet<K, R> {
+    pending: Vec<(K, u64, Rx<R>)>,
+    ready: BinaryHeap<ReadyReply<K, R>>,
+    next_seq: u64,
+}
+
+impl<K: Ord, R> PriorityRequestSet<K, R> {
+    /// Create a new, empty set.
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            ready: BinaryHeap::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Add a pending request with the given priority.
+    pub fn push(&mut self, priority: K, rx: Rx<R>) {
+        self.pending.push((priority, self.next_seq, rx));
+        self.next_seq += 1;
+    }
+
+    /// The amount of requests that have not been yielded yet.
+    pub fn len(&self) -> usize {
+        self.pending.len() + self.ready.len()
+    }
+
+    /// Whether all requests have been yielded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Ord, R> Default for PriorityRequestSet<K, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, R> Unpin for PriorityRequestSet<K, R> {}
+
+impl<K: Ord, R> Stream for PriorityRequestSet<K, R> {
+    type Item = (K, Result<R, RxError>);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        let mut i = 0;
+        while i < this.pending.len() {
+            if let Poll::Ready(reply) = this.pending[i].2.poll_unpin(cx) {
+                let (priority, seq, _rx) = this.pending.swap_remove(i);
+                this.ready.push(ReadyReply {
+                    priority,
+                    seq: Reverse(seq),
+                    reply,
+                });
+            } else {
+                i += 1;
+            }
+        }
+
+        match this.ready.pop() {
+            Some(ready) => Poll::Ready(Some((ready.priority, ready.reply))),
+            None if this.pending.is_empty() => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// A reply that is ready, ordered by priority and then by insertion-order.
+#[derive(Debug)]
+struct ReadyReply<K, R> {
+    priority: K,
+    seq: Reverse<u64>,
+    reply: Result<R, RxError>,
+}
+
+impl<K: Ord, R> PartialEq for ReadyReply<K, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, R> Eq for ReadyReply<K, R> {}
+
+impl<K: Ord, R> PartialOrd for ReadyReply<K, R> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, R> Ord for ReadyReply<K, R> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.priority, self.seq).cmp(&(&other.priority, other.seq))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn ready_replies_by_priority() {
+        let mut set = PriorityRequestSet::new();
+        let txs = [3, 1, 2, 1]
+            .into_iter()
+            .map(|priority| {
+                let (tx, rx) = new_request();
+                set.push(priority, rx);
+                tx
+            })
+            .collect::<Vec<_>>();
+
+        for (i, tx) in txs.into_iter().enumerate() {
+            tx.send(i).unwrap();
+        }
+
+        let replies = set.collect::<Vec<_>>().await;
+        assert_eq!(
+            replies,
+            vec![(3, Ok(0)), (2, Ok(2)), (1, Ok(1)), (1, Ok(3))]
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_replies_are_awaited() {
+        let mut set = PriorityRequestSet::new();
+        let (low_tx, low_rx) = new_request();
+        let (high_tx, high_rx) = new_request::<()>();
+        set.push(1, low_rx);
+        set.push(2, high_rx);
+
+        low_tx.send(()).unwrap();
+        assert_eq!(set.next().await, Some((1, Ok(()))));
+        drop(high_tx);
+        assert_eq!(set.next().await, Some((2, Err(RxError))));
+        assert_eq!(set.next().await, None);
+        assert!(set.is_empty());
+    }
+}
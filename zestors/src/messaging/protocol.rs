@@ -26,6 +26,28 @@ pub trait Protocol: Send + 'static {
     fn accepts_msg(msg_id: &TypeId) -> bool
     where
         Self: Sized;
+
+    /// Attempt to take the [`Message::Payload`] of `M` out of the protocol, without knowing
+    /// whether the protocol accepts `M`.
+    ///
+    /// # Implementation
+    /// By default this goes through a [`BoxPayload`], the [`macro@protocol`] macro implements it
+    /// without allocating.
+    fn try_into_msg<M: Message + 'static>(self) -> Result<M::Payload, Self>
+    where
+        Self: Sized,
+    {
+        if !Self::accepts_msg(&TypeId::of::<M>()) {
+            return Err(self);
+        }
+        match self.into_boxed_payload().downcast::<M>() {
+            Ok(payload) => Ok(payload),
+            Err(boxed) => match Self::try_from_boxed_payload(boxed) {
+                Ok(protocol) => Err(protocol),
+                Err(_) => unreachable!("A protocol can be created from it's own payload"),
+            },
+        }
+    }
 }
 
 /// Specifies that a [`Protocol`] can be created from the [`Message::Payload`] of `M`.
@@ -88,3 +110,20 @@ impl FromPayload<()> for () {
         Ok(self)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+
+    #[test]
+    fn try_into_msg_takes_out_accepted_payload() {
+        assert!(matches!(U32Protocol::U32(1).try_into_msg::<u32>(), Ok(1)));
+        assert!(matches!(
+            U32Protocol::U32(1).try_into_msg::<()>(),
+            Err(U32Protocol::U32(1))
+        ));
+        assert!(matches!(().try_into_msg::<()>(), Ok(())));
+        assert!(matches!(().try_into_msg::<u32>(), Err(())));
+    }
+}
@@ -1,9 +1,10 @@
-use futures::{Future, FutureExt};
+use futures::{stream::FuturesUnordered, Future, FutureExt, StreamExt};
 use std::{
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
-use tokio::sync::oneshot;
+use tokio::{sync::oneshot, time::Instant};
 use crate::all::*;
 
 /// Create a new request, consisting of a [`Tx<T>`] and an [`Rx<T>`].
@@ -11,10 +12,48 @@ use crate::all::*;
 ///
 /// This is just a wrapper around a [`tokio::sync::oneshot`] channel.
 pub fn new_request<T>() -> (Tx<T>, Rx<T>) {
+    start_trace();
     let (tx, rx) = oneshot::channel();
     (Tx(tx), Rx(rx))
 }
 
+/// Wait for the messages of all [`Rx`]es, where every `Rx` fails with a
+/// [`RecvTimeoutError::Timeout`] if it's message is not received within `per_item`.
+///
+/// All timeouts start at the same time, so that this completes within `per_item`. A slow `Rx`
+/// only fails it's own result, which is at the same position as the `Rx` in `rxs`.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::messaging::*;
+///
+/// # async fn main() {
+/// let (tx1, rx1) = new_request();
+/// let (_tx2, rx2) = new_request::<u32>();
+/// tx1.send(1).unwrap();
+///
+/// let results = join_all_timeout(vec![rx1, rx2], Duration::from_millis(10)).await;
+/// assert_eq!(results, vec![Ok(1), Err(RecvTimeoutError::Timeout)]);
+/// # }
+/// ```
+pub async fn join_all_timeout<M>(
+    rxs: Vec<Rx<M>>,
+    per_item: Duration,
+) -> Vec<Result<M, RecvTimeoutError>> {
+    let mut results = rxs.iter().map(|_| None).collect::<Vec<_>>();
+    let mut pending = rxs
+        .into_iter()
+        .enumerate()
+        .map(|(index, rx)| rx.recv_timeout(per_item).map(move |result| (index, result)))
+        .collect::<FuturesUnordered<_>>();
+    while let Some((index, result)) = pending.next().await {
+        results[index] = Some(result);
+    }
+    results.into_iter().map(Option::unwrap).collect()
+}
+
 //------------------------------------------------------------------------------------------------
 //  Tx
 //------------------------------------------------------------------------------------------------
@@ -31,6 +70,18 @@ impl<M> Tx<M> {
         self.0.send(msg).map_err(|msg| TxError(msg))
     }
 
+    /// Send a message that is only built if the [`Rx`] is still waiting for it.
+    ///
+    /// This checks whether the `Rx` has been closed before calling `f`, so that expensive replies
+    /// are not built when nobody will receive them.
+    pub fn send_with(self, f: impl FnOnce() -> M) -> Result<(), TxWithError<M>> {
+        if self.is_closed() {
+            return Err(TxWithError::Closed);
+        }
+        self.send(f())
+            .map_err(|TxError(msg)| TxWithError::ClosedAfterBuild(msg))
+    }
+
     /// Whether the [`Rx`] has closed/dropped the oneshot-channel.
     pub fn is_closed(&self) -> bool {
         self.0.is_closed()
@@ -72,15 +123,68 @@ impl<M> Rx<M> {
         self.0.try_recv().map_err(|e| e.into())
     }
 
+    /// Take the message out if it exists, consuming the `Rx`.
+    ///
+    /// Returns `Ok(None)` if the message has not been sent yet, in which case the `Rx` is dropped and
+    /// the message can no longer be received.
+    pub fn now_or_never(mut self) -> Result<Option<M>, RxError> {
+        match self.0.try_recv() {
+            Ok(msg) => Ok(Some(msg)),
+            Err(oneshot::error::TryRecvError::Empty) => Ok(None),
+            Err(oneshot::error::TryRecvError::Closed) => Err(RxError),
+        }
+    }
+
     /// Block the thread while waiting for the message.
     pub fn recv_blocking(self) -> Result<M, RxError> {
         self.0.blocking_recv().map_err(|e| e.into())
     }
 
+    /// Wait for the message, failing if it has not been received within the `timeout`.
+    pub async fn recv_timeout(self, timeout: Duration) -> Result<M, RecvTimeoutError> {
+        match tokio::time::timeout(timeout, self).await {
+            Ok(Ok(msg)) => Ok(msg),
+            Ok(Err(RxError)) => Err(RecvTimeoutError::Closed),
+            Err(_) => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
     /// Close the oneshot-channel, preventing the [`Tx`] from sending a message.
     pub fn close(&mut self) {
         self.0.close()
     }
+
+    /// Wait for the message, and send it through the `tx` of another request.
+    ///
+    /// This relays a reply from downstream back to the original caller. If the [`Tx`] of this
+    /// `Rx` is dropped without sending, `tx` is dropped as well: the caller then receives an
+    /// [`RxError`], the same as if it had been waiting on this `Rx`. If the caller drops it's
+    /// `Rx` first, this stops waiting and closes this `Rx`.
+    ///
+    /// # Usage
+    /// ```
+    /// # tokio_test::block_on(main());
+    /// use zestors::messaging::*;
+    ///
+    /// # async fn main() {
+    /// let (downstream_tx, downstream_rx) = new_request();
+    /// let (caller_tx, caller_rx) = new_request();
+    /// let relay = tokio::spawn(downstream_rx.forward_to(caller_tx));
+    ///
+    /// downstream_tx.send(10).unwrap();
+    /// assert_eq!(caller_rx.await, Ok(10));
+    /// relay.await.unwrap();
+    /// # }
+    /// ```
+    pub async fn forward_to(self, mut tx: Tx<M>) {
+        let msg = tokio::select! {
+            msg = self => msg,
+            () = tx.closed() => return,
+        };
+        if let Ok(msg) = msg {
+            let _ = tx.send(msg);
+        }
+    }
 }
 
 impl<M, R> MessageDerive<M> for Rx<R> {
@@ -107,6 +211,30 @@ impl<M> Future for Rx<M> {
     }
 }
 
+/// The timing of a request, returned together with it's reply by
+/// [`request_timed`](ActorRefExt::request_timed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestTiming {
+    /// When the request was put into the inbox of the actor.
+    pub queued_at: Instant,
+    /// When the reply was received from the [`Rx`].
+    pub replied_at: Instant,
+    /// The time between queueing the request and receiving the reply.
+    pub round_trip: Duration,
+}
+
+impl RequestTiming {
+    /// The timing of a request that was queued at `queued_at` and has just been replied to.
+    pub(crate) fn replied_now(queued_at: Instant) -> Self {
+        let replied_at = Instant::now();
+        Self {
+            queued_at,
+            replied_at,
+            round_trip: replied_at - queued_at,
+        }
+    }
+}
+
 //------------------------------------------------------------------------------------------------
 //  Errors
 //------------------------------------------------------------------------------------------------
@@ -140,7 +268,148 @@ impl From<oneshot::error::TryRecvError> for TryRxError {
     }
 }
 
+/// Error returned when receiving a message using [`Rx::recv_timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, thiserror::Error)]
+pub enum RecvTimeoutError {
+    /// The [`Tx`] was dropped without sending a message.
+    #[error("Failed to receive from Rx because it is closed.")]
+    Closed,
+    /// No message was received within the timeout.
+    #[error("Failed to receive from Rx within the timeout.")]
+    Timeout,
+}
+
 /// Error returned when sending a message using a [`Tx`].
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, thiserror::Error)]
 #[error("Failed to send to Tx because it is closed.")]
 pub struct TxError<M>(pub M);
+
+/// Error returned when sending a message using [`Tx::send_with`].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, thiserror::Error)]
+pub enum TxWithError<M> {
+    /// The [`Rx`] was closed before the message was built.
+    #[error("Failed to send to Tx because it is closed.")]
+    Closed,
+    /// The [`Rx`] was closed after the message was built.
+    #[error("Failed to send to Tx because it closed after the message was built.")]
+    ClosedAfterBuild(M),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn now_or_never() {
+        let (tx, rx) = new_request();
+        tx.send(1).unwrap();
+        assert_eq!(rx.now_or_never(), Ok(Some(1)));
+
+        let (_tx, rx) = new_request::<u32>();
+        assert_eq!(rx.now_or_never(), Ok(None));
+
+        let (tx, rx) = new_request::<u32>();
+        drop(tx);
+        assert_eq!(rx.now_or_never(), Err(RxError));
+    }
+
+    #[test]
+    fn send_with_only_builds_when_waiting() {
+        let (tx, rx) = new_request();
+        tx.send_with(|| 1).unwrap();
+        assert_eq!(rx.now_or_never(), Ok(Some(1)));
+
+        let (tx, rx) = new_request::<u32>();
+        drop(rx);
+        assert_eq!(
+            tx.send_with(|| panic!("the reply should not be built")),
+            Err(TxWithError::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn forward_to_relays_errors_and_stops_when_caller_drops() {
+        let (downstream_tx, downstream_rx) = new_request::<u32>();
+        let (caller_tx, caller_rx) = new_request();
+        drop(downstream_tx);
+        downstream_rx.forward_to(caller_tx).await;
+        assert_eq!(caller_rx.await, Err(RxError));
+
+        let (downstream_tx, downstream_rx) = new_request::<u32>();
+        let (caller_tx, caller_rx) = new_request();
+        drop(caller_rx);
+        downstream_rx.forward_to(caller_tx).await;
+        assert!(downstream_tx.is_closed());
+    }
+
+    #[tokio::test]
+    async fn join_all_timeout_is_positional() {
+        let (tx1, rx1) = new_request();
+        let (_tx2, rx2) = new_request();
+        let (tx3, rx3) = new_request::<u32>();
+        let (tx4, rx4) = new_request();
+        drop(tx3);
+        tx4.send(4).unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            tx1.send(1).unwrap();
+        });
+
+        let results = join_all_timeout(vec![rx1, rx2, rx3, rx4], Duration::from_millis(50)).await;
+        assert_eq!(
+            results,
+            vec![
+                Ok(1),
+                Err(RecvTimeoutError::Timeout),
+                Err(RecvTimeoutError::Closed),
+                Ok(4)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn request_timeout_names_message_type() {
+        #[derive(Message)]
+        #[request(())]
+        struct Hang;
+
+        #[protocol]
+        enum HangProtocol {
+            Hang(Hang),
+        }
+
+        let (_child, address) = spawn(|mut inbox: Inbox<HangProtocol>| async move {
+            let _request = inbox.recv().await;
+            futures::future::pending::<()>().await;
+        });
+        let timeout = std::time::Duration::from_millis(10);
+        let Err(RequestError::Timeout { message_type }) =
+            address.request_timeout(Hang, timeout).await
+        else {
+            panic!("request should time out");
+        };
+        assert!(message_type.ends_with("Hang"));
+    }
+
+    #[tokio::test]
+    async fn request_timed_measures_round_trip() {
+        #[derive(Message, Debug)]
+        #[request(u32)]
+        struct Delayed(u64);
+
+        #[protocol]
+        enum DelayedProtocol {
+            Delayed(Delayed),
+        }
+
+        let (_child, address) = spawn(|mut inbox: Inbox<DelayedProtocol>| async move {
+            let DelayedProtocol::Delayed((Delayed(millis), tx)) = inbox.recv().await.unwrap();
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+            tx.send(10).unwrap();
+        });
+        let (reply, timing) = address.request_timed(Delayed(10)).await.unwrap();
+        assert_eq!(reply, 10);
+        assert!(timing.round_trip >= Duration::from_millis(10));
+        assert_eq!(timing.replied_at - timing.queued_at, timing.round_trip);
+    }
+}
@@ -0,0 +1,437 @@
+use futures::{ready, Stream};
+use std::{
+    mem,
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{sync::mpsc, time::Instant};
+#[allow(unused)]
+use crate::all::*;
+
+/// Create a new stream-request, consisting of a [`StreamTx<T>`] and a [`StreamRx<T>`].
+/// The `StreamTx` (_transmitter_) can be used to send many messages `T` to the `StreamRx` (_receiver_).
+///
+/// This is just a wrapper around an unbounded [`tokio::sync::mpsc`] channel.
+pub fn new_stream_request<T>() -> (StreamTx<T>, StreamRx<T>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (
+        StreamTx(tx),
+        StreamRx {
+            rx,
+            complete: false,
+        },
+    )
+}
+
+/// A message sent over the channel of a stream-request.
+#[derive(Debug)]
+enum StreamItem<M> {
+    Item(M),
+    /// Sent by the [`StreamTx`] to indicate no more messages will follow.
+    End,
+}
+
+//------------------------------------------------------------------------------------------------
+//  StreamTx
+//------------------------------------------------------------------------------------------------
+
+/// The transmitter part of a stream-request, created with [`new_stream_request`].
+///
+/// The stream can be ended explicitly with [`StreamTx::close`] or [`StreamTx::send_and_close`],
+/// after which the [`StreamRx`] is [complete](StreamRx::is_complete). If the `StreamTx` is
+/// dropped instead, the stream still ends but is not complete.
+#[derive(Debug)]
+pub struct StreamTx<M>(mpsc::UnboundedSender<StreamItem<M>>);
+
+impl<M> StreamTx<M> {
+    /// Send a message.
+    pub fn send(&self, msg: M) -> Result<(), TxError<M>> {
+        self.0.send(StreamItem::Item(msg)).map_err(|e| match e.0 {
+            StreamItem::Item(msg) => TxError(msg),
+            StreamItem::End => unreachable!(),
+        })
+    }
+
+    /// Send a final message, and then end the stream.
+    pub fn send_and_close(self, msg: M) -> Result<(), TxError<M>> {
+        self.send(msg)?;
+        self.close();
+        Ok(())
+    }
+
+    /// End the stream, signalling to the [`StreamRx`] that no more messages will follow.
+    pub fn close(self) {
+        let _ = self.0.send(StreamItem::End);
+    }
+
+    /// Whether the [`StreamRx`] has closed/dropped the channel.
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    /// Wait for the [`StreamRx`] to close/drop the channel.
+    pub async fn closed(&self) {
+        self.0.closed().await
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  StreamRx
+//------------------------------------------------------------------------------------------------
+
+/// The receiver part of a stream-request, created with [`new_stream_request`].
+///
+/// This implements [`MessageDerive<M>`] to be used with the [`derive@Message`] derive macro.
+#[derive(Debug)]
+pub struct StreamRx<M> {
+    rx: mpsc::UnboundedReceiver<StreamItem<M>>,
+    complete: bool,
+}
+
+impl<M> StreamRx<M> {
+    /// Attempt to take the next message out, if it exists.
+    pub fn try_recv(&mut self) -> Result<M, TryRxError> {
+        if self.complete {
+            return Err(TryRxError::Closed);
+        }
+        match self.rx.try_recv() {
+            Ok(item) => self.unwrap_item(Some(item)).ok_or(TryRxError::Closed),
+            Err(mpsc::error::TryRecvError::Empty) => Err(TryRxError::Empty),
+            Err(mpsc::error::TryRecvError::Disconnected) => Err(TryRxError::Closed),
+        }
+    }
+
+    /// Block the thread while waiting for the next message.
+    pub fn recv_blocking(&mut self) -> Result<M, RxError> {
+        if self.complete {
+            return Err(RxError);
+        }
+        let item = self.rx.blocking_recv();
+        self.unwrap_item(item).ok_or(RxError)
+    }
+
+    /// Close the channel, preventing the [`StreamTx`] from sending more messages.
+    ///
+    /// Messages that were already sent can still be received.
+    pub fn close(&mut self) {
+        self.rx.close()
+    }
+
+    /// Whether the [`StreamTx`] has explicitly ended the stream, and all messages have been
+    /// received.
+    ///
+    /// This stays `false` if the stream ended because the `StreamTx` was dropped.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn unwrap_item(&mut self, item: Option<StreamItem<M>>) -> Option<M> {
+        match item {
+            Some(StreamItem::Item(msg)) => Some(msg),
+            Some(StreamItem::End) => {
+                self.complete = true;
+                self.rx.close();
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+impl<M, R> MessageDerive<M> for StreamRx<R> {
+    type Payload = (M, StreamTx<R>);
+    type Returned = StreamRx<R>;
+
+    fn create(msg: M) -> ((M, StreamTx<R>), StreamRx<R>) {
+        let (tx, rx) = new_stream_request();
+        ((msg, tx), rx)
+    }
+
+    fn cancel(sent: (M, StreamTx<R>), _returned: StreamRx<R>) -> M {
+        sent.0
+    }
+}
+
+impl<M> Unpin for StreamRx<M> {}
+
+impl<M> Stream for StreamRx<M> {
+    type Item = M;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.complete {
+            return Poll::Ready(None);
+        }
+        let item = ready!(self.rx.poll_recv(cx));
+        Poll::Ready(self.unwrap_item(item))
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  BatchStreamRequest
+//------------------------------------------------------------------------------------------------
+
+/// A stream-request that sends it's replies in chunks of `Vec<R>` instead of one by one.
+///
+/// The [`BatchStreamTx`] buffers replies and sends them as a single chunk once `max_items` have
+/// been buffered, or when the flush-interval has passed since the last chunk was sent. The
+/// [`StreamRx`] then yields `Vec<R>` chunks.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::messaging::BatchStreamRequest;
+/// use futures::StreamExt;
+///
+/// # async fn main() {
+/// let (mut tx, mut rx) = BatchStreamRequest::new(2).create();
+/// for i in 0..5 {
+///     tx.send(i).unwrap();
+/// }
+/// drop(tx);
+/// assert_eq!(rx.collect::<Vec<_>>().await, vec![vec![0, 1], vec![2, 3], vec![4]]);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BatchStreamRequest<R> {
+    max_items: usize,
+    flush_interval: Option<Duration>,
+    phantom: std::marker::PhantomData<fn() -> R>,
+}
+
+impl<R> BatchStreamRequest<R> {
+    /// Create a new batched stream-request, sending a chunk once `max_items` are buffered.
+    ///
+    /// # Panics
+    /// Panics if `max_items` is `0`.
+    pub fn new(max_items: usize) -> Self {
+        assert!(max_items > 0, "max_items must be greater than 0");
+        Self {
+            max_items,
+            flush_interval: None,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Also send a chunk if the given interval has passed since the previous one was sent.
+    ///
+    /// The buffered replies are flushed by a background task, so that they are sent even if no
+    /// more replies follow.
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = Some(flush_interval);
+        self
+    }
+
+    /// Create the [`BatchStreamTx`] and [`StreamRx`].
+    ///
+    /// # Panics
+    /// Panics if a flush-interval is set and this is called outside of a tokio runtime.
+    pub fn create(self) -> (BatchStreamTx<R>, StreamRx<Vec<R>>)
+    where
+        R: Send + 'static,
+    {
+        let (tx, rx) = new_stream_request();
+        let batch = Arc::new(Mutex::new(Batch {
+            tx,
+            buffer: Vec::with_capacity(self.max_items),
+            max_items: self.max_items,
+            last_flush: Instant::now(),
+        }));
+        if let Some(interval) = self.flush_interval {
+            tokio::spawn(flush_periodically(Arc::downgrade(&batch), interval));
+        }
+        (
+            BatchStreamTx {
+                batch,
+                flush_interval: self.flush_interval,
+            },
+            rx,
+        )
+    }
+}
+
+/// Flush the batch whenever the interval has passed since the previous flush, until the
+/// [`BatchStreamTx`] is dropped or the [`StreamRx`] is closed.
+async fn flush_periodically<R>(batch: Weak<Mutex<Batch<R>>>, interval: Duration) {
+    let mut deadline = Instant::now() + interval;
+    loop {
+        tokio::time::sleep_until(deadline).await;
+        let Some(batch) = batch.upgrade() else { break };
+        let mut batch = batch.lock().unwrap();
+        if batch.tx.is_closed() {
+            break;
+        }
+        if batch.last_flush.elapsed() >= interval {
+            let _ = batch.flush();
+        }
+        deadline = batch.last_flush + interval;
+    }
+}
+
+/// The transmitter part of a [`BatchStreamRequest`].
+///
+/// Any replies that are still buffered are flushed when this is dropped.
+#[derive(Debug)]
+pub struct BatchStreamTx<R> {
+    batch: Arc<Mutex<Batch<R>>>,
+    flush_interval: Option<Duration>,
+}
+
+/// The state of a [`BatchStreamTx`], shared with the task that flushes it periodically.
+#[derive(Debug)]
+struct Batch<R> {
+    tx: StreamTx<Vec<R>>,
+    buffer: Vec<R>,
+    max_items: usize,
+    last_flush: Instant,
+}
+
+impl<R> Batch<R> {
+    fn flush(&mut self) -> Result<(), TxError<Vec<R>>> {
+        self.last_flush = Instant::now();
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = mem::replace(&mut self.buffer, Vec::with_capacity(self.max_items));
+        self.tx.send(chunk)
+    }
+}
+
+impl<R> BatchStreamTx<R> {
+    /// Buffer a reply, sending the chunk if it is full or if the flush-interval has passed.
+    ///
+    /// If the [`StreamRx`] is closed, the unsent chunk is returned.
+    pub fn send(&mut self, msg: R) -> Result<(), TxError<Vec<R>>> {
+        let mut batch = self.batch.lock().unwrap();
+        batch.buffer.push(msg);
+        let interval_passed = self
+            .flush_interval
+            .map(|interval| batch.last_flush.elapsed() >= interval)
+            .unwrap_or(false);
+
+        if batch.buffer.len() >= batch.max_items || interval_passed {
+            batch.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Immediately send all buffered replies as a chunk.
+    ///
+    /// This does nothing if no replies are buffered. If the [`StreamRx`] is closed, the unsent
+    /// chunk is returned.
+    pub fn flush(&mut self) -> Result<(), TxError<Vec<R>>> {
+        self.batch.lock().unwrap().flush()
+    }
+
+    /// The amount of replies that are buffered, but not yet sent.
+    pub fn buffered(&self) -> usize {
+        self.batch.lock().unwrap().buffer.len()
+    }
+
+    /// Whether the [`StreamRx`] has closed/dropped the channel.
+    pub fn is_closed(&self) -> bool {
+        self.batch.lock().unwrap().tx.is_closed()
+    }
+}
+
+impl<R> Drop for BatchStreamTx<R> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn stream_request() {
+        let (tx, rx) = new_stream_request();
+        for i in 0..3 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+        assert_eq!(rx.collect::<Vec<_>>().await, vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn closed_stream_is_complete() {
+        let (tx, mut rx) = new_stream_request();
+        tx.send(0).unwrap();
+        tx.send_and_close(1).unwrap();
+        assert!(!rx.is_complete());
+        assert_eq!(rx.try_recv(), Ok(0));
+        assert_eq!(rx.try_recv(), Ok(1));
+        assert_eq!(rx.try_recv(), Err(TryRxError::Closed));
+        assert!(rx.is_complete());
+
+        let (tx, rx) = new_stream_request::<()>();
+        tx.close();
+        assert_eq!(rx.collect::<Vec<_>>().await, vec![]);
+    }
+
+    #[tokio::test]
+    async fn dropped_stream_is_not_complete() {
+        let (tx, mut rx) = new_stream_request::<()>();
+        drop(tx);
+        assert_eq!(rx.next().await, None);
+        assert!(!rx.is_complete());
+    }
+
+    #[tokio::test]
+    async fn batches_are_split_at_max_items() {
+        let (mut tx, mut rx) = BatchStreamRequest::new(3).create();
+        for i in 0..7 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(rx.try_recv(), Ok(vec![0, 1, 2]));
+        assert_eq!(rx.try_recv(), Ok(vec![3, 4, 5]));
+        assert_eq!(rx.try_recv(), Err(TryRxError::Empty));
+        assert_eq!(tx.buffered(), 1);
+
+        drop(tx);
+        assert_eq!(rx.try_recv(), Ok(vec![6]));
+        assert_eq!(rx.try_recv(), Err(TryRxError::Closed));
+    }
+
+    #[tokio::test]
+    async fn explicit_flush() {
+        let (mut tx, mut rx) = BatchStreamRequest::new(10).create();
+        tx.flush().unwrap();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        assert_eq!(rx.try_recv(), Err(TryRxError::Empty));
+        tx.flush().unwrap();
+        assert_eq!(rx.try_recv(), Ok(vec![1, 2]));
+        assert_eq!(rx.try_recv(), Err(TryRxError::Empty));
+    }
+
+    #[tokio::test]
+    async fn flush_interval() {
+        let (mut tx, mut rx) = BatchStreamRequest::new(10)
+            .with_flush_interval(Duration::from_millis(10))
+            .create();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        assert_eq!(rx.try_recv(), Err(TryRxError::Empty));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(rx.try_recv(), Ok(vec![1, 2]));
+        assert_eq!(tx.buffered(), 0);
+
+        tx.send(3).unwrap();
+        assert_eq!(rx.next().await, Some(vec![3]));
+        drop(tx);
+        assert_eq!(rx.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn closed_rx_returns_chunk() {
+        let (mut tx, rx) = BatchStreamRequest::new(2).create();
+        drop(rx);
+        tx.send(1).unwrap();
+        assert_eq!(tx.send(2), Err(TxError(vec![1, 2])));
+    }
+}
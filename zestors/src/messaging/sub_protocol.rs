@@ -0,0 +1,178 @@
+#[allow(unused)]
+use crate::all::*;
+use std::{any::TypeId, fmt::Debug, marker::PhantomData};
+use thiserror::Error;
+
+/// Routes the messages of a large [`Protocol`] to a smaller protocol `S`, which only contains the
+/// messages a handler cares about.
+///
+/// A message is matched if `S` accepts it's type-id (see [`Protocol::accepts_msg`]), in which case
+/// it is converted into `S`. All other messages are handled by the [`Fallback`]. This makes it
+/// possible to write middleware- or proxy-actors that handle a few messages themselves, and pass
+/// the rest on.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, protocol, messaging::{Fallback, SubProtocol}};
+///
+/// #[protocol]
+/// enum FullProtocol {
+///     Number(u32),
+///     Text(String),
+/// }
+///
+/// #[protocol]
+/// enum NumberProtocol {
+///     Number(u32),
+/// }
+///
+/// # async fn main() {
+/// let router = SubProtocol::<NumberProtocol>::new(Fallback::Drop);
+/// assert!(matches!(
+///     router.route(FullProtocol::Number(10)).await,
+///     Ok(Some(NumberProtocol::Number(10)))
+/// ));
+/// assert!(matches!(
+///     router.route(FullProtocol::Text("dropped".into())).await,
+///     Ok(None)
+/// ));
+/// # }
+/// ```
+pub struct SubProtocol<S> {
+    fallback: Fallback,
+    phantom: PhantomData<fn() -> S>,
+}
+
+/// What a [`SubProtocol`] does with messages that it does not match.
+#[derive(Debug)]
+pub enum Fallback {
+    /// Forward the message to the actor, waiting until it has been sent.
+    Forward(Address),
+    /// Drop the message. If the message was a request, the caller receives no reply.
+    Drop,
+    /// Return the message as a [`RouteError::Unmatched`].
+    Error,
+}
+
+impl<S: Protocol> SubProtocol<S> {
+    /// Create a new sub-protocol, handling unmatched messages with the fallback.
+    pub fn new(fallback: Fallback) -> Self {
+        Self {
+            fallback,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The fallback used for unmatched messages.
+    pub fn fallback(&self) -> &Fallback {
+        &self.fallback
+    }
+
+    /// Whether the message with this type-id is matched.
+    pub fn matches(msg_id: &TypeId) -> bool {
+        S::accepts_msg(msg_id)
+    }
+
+    /// Route the message, returning it as `S` if it is matched.
+    ///
+    /// Unmatched messages are handled by the [`Fallback`], after which this returns `Ok(None)`;
+    /// with [`Fallback::Error`], or if forwarding fails, the message is returned as an error.
+    pub async fn route<P: Protocol>(&self, msg: P) -> Result<Option<S>, RouteError> {
+        let payload = match S::try_from_boxed_payload(msg.into_boxed_payload()) {
+            Ok(msg) => return Ok(Some(msg)),
+            Err(payload) => payload,
+        };
+        match &self.fallback {
+            Fallback::Forward(address) => {
+                let channel = <Address as ActorRef>::channel_ref(address);
+                match channel.send_box(payload).await {
+                    Ok(()) => Ok(None),
+                    Err(e) => Err(RouteError::ForwardFailed(e)),
+                }
+            }
+            Fallback::Drop => Ok(None),
+            Fallback::Error => Err(RouteError::Unmatched(payload)),
+        }
+    }
+}
+
+impl<S> Debug for SubProtocol<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubProtocol")
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+/// Error returned by [`SubProtocol::route`] when an unmatched message could not be handled.
+#[derive(Debug, Error)]
+pub enum RouteError {
+    /// The message is not matched, and the fallback is [`Fallback::Error`].
+    #[error("Couldn't route message because it is not part of the sub-protocol")]
+    Unmatched(BoxPayload),
+    /// The message could not be forwarded.
+    #[error("Couldn't route message because forwarding it failed")]
+    ForwardFailed(SendCheckedError<BoxPayload>),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[protocol]
+    enum FullProtocol {
+        Number(u32),
+        Text(String),
+        Empty(()),
+    }
+
+    #[protocol]
+    enum NumberProtocol {
+        Number(u32),
+    }
+
+    #[protocol]
+    enum TextProtocol {
+        Text(String),
+    }
+
+    #[tokio::test]
+    async fn unmatched_messages_are_forwarded() {
+        let (child, address) = spawn(|mut inbox: Inbox<TextProtocol>| async move {
+            let TextProtocol::Text(text) = inbox.recv().await.unwrap();
+            text
+        });
+        let router = SubProtocol::<NumberProtocol>::new(Fallback::Forward(address.into_dyn()));
+
+        assert!(matches!(
+            router.route(FullProtocol::Number(1)).await,
+            Ok(Some(NumberProtocol::Number(1)))
+        ));
+        assert!(matches!(
+            router.route(FullProtocol::Text("hi".into())).await,
+            Ok(None)
+        ));
+        assert_eq!(child.await.unwrap(), "hi");
+
+        assert!(matches!(
+            router.route(FullProtocol::Empty(())).await,
+            Err(RouteError::ForwardFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unmatched_messages_are_returned() {
+        let router = SubProtocol::<NumberProtocol>::new(Fallback::Error);
+        match router.route(FullProtocol::Text("hi".into())).await {
+            Err(RouteError::Unmatched(payload)) => {
+                assert_eq!(payload.downcast::<String>().unwrap(), "hi")
+            }
+            _ => panic!("expected the message to be returned"),
+        }
+        assert!(SubProtocol::<NumberProtocol>::matches(&TypeId::of::<u32>()));
+        assert!(!SubProtocol::<NumberProtocol>::matches(
+            &TypeId::of::<String>()
+        ));
+    }
+}
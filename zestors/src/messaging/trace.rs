@@ -0,0 +1,238 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+#[cfg(feature = "message-tracing")]
+use std::{
+    cell::Cell,
+    fmt::{Debug, Display},
+    sync::atomic::{AtomicU64, Ordering},
+};
+#[cfg(feature = "message-tracing")]
+use tokio::task::futures::TaskLocalFuture;
+
+#[cfg(feature = "message-tracing")]
+tokio::task_local! {
+    /// The trace of the message that an actor has received last.
+    static CURRENT_TRACE: Cell<Option<TraceId>>;
+
+    /// The trace that is started by a request, while it's message is being created.
+    static STARTED_TRACE: Cell<Option<TraceId>>;
+}
+
+/// The trace a message is sent with. Without the `message-tracing` feature, messages are not
+/// traced and this takes up no space in the inbox.
+#[cfg(feature = "message-tracing")]
+pub(crate) type MessageTrace = Option<TraceId>;
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) type MessageTrace = Untraced;
+
+/// The trace of every message without the `message-tracing` feature.
+#[cfg(not(feature = "message-tracing"))]
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Untraced;
+
+/// The future of [`trace_scope`].
+#[cfg(feature = "message-tracing")]
+pub(crate) type TraceScope<F> = TaskLocalFuture<Cell<Option<TraceId>>, F>;
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) type TraceScope<F> = F;
+
+/// A trace-id is an incrementally-generated id, which is shared by all messages that are sent
+/// while handling a single request.
+///
+/// A trace is started by the first request that is created outside of a trace, and is carried with
+/// every message in the inbox of an actor. When an actor receives a message, the trace of that
+/// message becomes it's [`current_trace_id`], and all messages and requests it sends from then on
+/// carry the same trace. When `A` requests `B`, which forwards to `C`, all three actors can
+/// therefore log the same trace-id.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{messaging::*, prelude::*, protocol};
+///
+/// #[derive(Message, Debug)]
+/// #[request(Option<TraceId>)]
+/// struct GetTrace;
+///
+/// #[protocol]
+/// enum MyProtocol {
+///     GetTrace(GetTrace),
+/// }
+///
+/// # async fn main() {
+/// let (child, address) = spawn(|mut inbox: Inbox<MyProtocol>| async move {
+///     let MyProtocol::GetTrace((GetTrace, tx)) = inbox.recv().await.unwrap();
+///     tx.send(current_trace_id()).unwrap();
+/// });
+/// assert!(address.request(GetTrace).await.unwrap().is_some());
+/// child.await.unwrap();
+/// # }
+/// ```
+#[cfg(feature = "message-tracing")]
+#[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct TraceId(u64);
+
+#[cfg(feature = "message-tracing")]
+impl TraceId {
+    /// Generate a new unique trace-id.
+    fn generate() -> Self {
+        static NEXT_TRACE_ID: AtomicU64 = AtomicU64::new(0);
+        TraceId(NEXT_TRACE_ID.fetch_add(1, Ordering::AcqRel))
+    }
+
+    /// Convert the trace-id to a u64.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(feature = "message-tracing")]
+impl Display for TraceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// Get the trace of the message that the current actor has received last.
+///
+/// Returns `None` if this is not called from within an actor, or if that message was not sent as
+/// part of a trace.
+#[cfg(feature = "message-tracing")]
+pub fn current_trace_id() -> Option<TraceId> {
+    CURRENT_TRACE.try_with(Cell::get).ok().flatten()
+}
+
+/// Get the trace that a message sent from here should carry.
+#[cfg(feature = "message-tracing")]
+pub(crate) fn current_trace() -> MessageTrace {
+    current_trace_id()
+}
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) fn current_trace() -> MessageTrace {
+    Untraced
+}
+
+/// Run the future with the given trace as the current one, for example the future of an actor
+/// which starts without a current trace.
+#[cfg(feature = "message-tracing")]
+pub(crate) fn trace_scope<F: Future>(trace: MessageTrace, fut: F) -> TraceScope<F> {
+    CURRENT_TRACE.scope(Cell::new(trace), fut)
+}
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) fn trace_scope<F: Future>(_trace: MessageTrace, fut: F) -> TraceScope<F> {
+    fut
+}
+
+/// Set the current trace to that of a message which has been received.
+#[cfg(feature = "message-tracing")]
+pub(crate) fn set_current_trace(trace: MessageTrace) {
+    let _ = CURRENT_TRACE.try_with(|current| current.set(trace));
+}
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) fn set_current_trace(_trace: MessageTrace) {}
+
+/// Run `f` with the given trace as the current one, for example while sending a message.
+#[cfg(feature = "message-tracing")]
+pub(crate) fn traced<T>(trace: MessageTrace, f: impl FnOnce() -> T) -> T {
+    CURRENT_TRACE.sync_scope(Cell::new(trace), f)
+}
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) fn traced<T>(_trace: MessageTrace, f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+/// Start a new trace if a request is created while creating a message with [`create_traced`].
+#[cfg(feature = "message-tracing")]
+pub(crate) fn start_trace() {
+    let _ = STARTED_TRACE.try_with(|started| {
+        if started.get().is_none() {
+            started.set(Some(TraceId::generate()))
+        }
+    });
+}
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) fn start_trace() {}
+
+/// Create the message, returning the trace it should be sent with.
+///
+/// This is the current trace, or a new one if the message is a request sent outside of a trace.
+#[cfg(feature = "message-tracing")]
+pub(crate) fn create_traced<M: Message>(msg: M) -> (M::Payload, M::Returned, MessageTrace) {
+    STARTED_TRACE.sync_scope(Cell::new(current_trace_id()), || {
+        let (sends, returns) = M::create(msg);
+        (sends, returns, STARTED_TRACE.with(Cell::get))
+    })
+}
+#[cfg(not(feature = "message-tracing"))]
+pub(crate) fn create_traced<M: Message>(msg: M) -> (M::Payload, M::Returned, MessageTrace) {
+    let (sends, returns) = M::create(msg);
+    (sends, returns, Untraced)
+}
+
+#[cfg(all(test, feature = "message-tracing"))]
+mod test {
+    use super::*;
+    use crate::protocol;
+    use futures::future::BoxFuture;
+
+    #[derive(Message, Debug)]
+    #[request(Vec<Option<TraceId>>)]
+    struct Traces;
+
+    #[protocol]
+    enum TraceProtocol {
+        Traces(Traces),
+    }
+
+    /// Replies with it's current trace, after that of the next actor if there is one.
+    fn tracing_actor(
+        next: Option<Address<Inbox<TraceProtocol>>>,
+    ) -> impl FnOnce(Inbox<TraceProtocol>) -> BoxFuture<'static, ()> {
+        move |mut inbox| {
+            Box::pin(async move {
+                while let Ok(TraceProtocol::Traces((Traces, tx))) = inbox.recv().await {
+                    let mut traces = match &next {
+                        Some(next) => next.request(Traces).await.unwrap(),
+                        None => Vec::new(),
+                    };
+                    traces.push(current_trace_id());
+                    tx.send(traces).unwrap();
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn trace_flows_through_forwarded_requests() {
+        let (c, c_address) = spawn(tracing_actor(None));
+        let (b, b_address) = spawn(tracing_actor(Some(c_address.clone())));
+        let (a, a_address) = spawn(tracing_actor(Some(b_address.clone())));
+        assert_eq!(current_trace_id(), None);
+
+        let first = a_address.request(Traces).await.unwrap();
+        assert_eq!(first.len(), 3);
+        assert!(first[0].is_some());
+        assert!(first.iter().all(|trace| *trace == first[0]));
+
+        let second = a_address.request(Traces).await.unwrap();
+        assert!(second.iter().all(|trace| *trace == second[0]));
+        assert_ne!(first[0], second[0]);
+
+        for (child, address) in [(a, a_address), (b, b_address), (c, c_address)] {
+            address.close();
+            child.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn lazy_request_starts_trace() {
+        let (child, address) = spawn(tracing_actor(None));
+        let rx = address.send_lazy(|| Traces).await.unwrap();
+        let traces = rx.await.unwrap();
+        assert!(traces[0].is_some());
+
+        address.close();
+        child.await.unwrap();
+    }
+}
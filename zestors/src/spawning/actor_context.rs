@@ -0,0 +1,60 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+use std::sync::Arc;
+use tokio::task::futures::TaskLocalFuture;
+
+/// The future of a process, run within it's [`ActorContext`].
+pub(crate) type ContextFuture<F> = TaskLocalFuture<ActorContext, TraceScope<F>>;
+
+tokio::task_local! {
+    /// The context of the actor that is currently being polled.
+    static ACTOR_CONTEXT: ActorContext;
+}
+
+/// The identity of the actor that is currently running, which is set by the spawn functions for
+/// every process of an actor.
+///
+/// This can be used to access the actor-id (and name) from anywhere within the actor's task, for
+/// example while logging, without passing it down manually. Tasks that are spawned from within an
+/// actor do not inherit it's context.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::spawning::ActorContext;
+///
+/// # async fn main() {
+/// assert!(ActorContext::current().is_none());
+/// let (child, address) = spawn(|_inbox: Inbox<()>| async move { ActorContext::current() });
+/// let context = child.await.unwrap().unwrap();
+/// assert_eq!(context.id, address.actor_id());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorContext {
+    /// The id of the actor.
+    pub id: ActorId,
+    /// The name of the actor, for example set with [`SpawnSpec::with_name`].
+    pub name: Option<Arc<str>>,
+}
+
+impl ActorContext {
+    /// Get the context of the actor that is currently running.
+    ///
+    /// Returns `None` if this is not called from within an actor.
+    pub fn current() -> Option<ActorContext> {
+        ACTOR_CONTEXT.try_with(Clone::clone).ok()
+    }
+
+    /// Run the future with this as the current context, and without a current trace.
+    pub(crate) fn scope<F: Future>(self, fut: F) -> ContextFuture<F> {
+        ACTOR_CONTEXT.scope(self, trace_scope(MessageTrace::default(), fut))
+    }
+
+    /// Run the future with an unnamed context for the actor.
+    pub(crate) fn unnamed<F: Future>(id: ActorId, fut: F) -> ContextFuture<F> {
+        Self { id, name: None }.scope(fut)
+    }
+}
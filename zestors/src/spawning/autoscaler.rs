@@ -0,0 +1,409 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+use std::time::Duration;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+
+/// The policy of an [`Autoscaler`].
+///
+/// The pressure of a pool is the amount of messages in it's inbox per process, rounded down. When
+/// the pressure stays above the high-water mark for the sustain-time, a process is added. When it
+/// stays below the low-water mark for the sustain-time, a process is removed. After every resize no
+/// other resize is done until the cooldown has passed.
+#[derive(Debug, Clone)]
+pub struct AutoscalePolicy {
+    min_processes: usize,
+    max_processes: usize,
+    low_water: usize,
+    high_water: usize,
+    sustain: Duration,
+    cooldown: Duration,
+    interval: Duration,
+}
+
+impl AutoscalePolicy {
+    /// Create a new policy that keeps the pool between `min_processes` and `max_processes`.
+    ///
+    /// By default the water marks are `1` and `10` messages per process, so that a pool with less
+    /// than one message per process is scaled down. The sustain-time is 1 second, the cooldown is
+    /// 5 seconds, and the pressure is checked every 100 milliseconds.
+    ///
+    /// Panics if `min_processes` is larger than `max_processes`.
+    pub fn new(min_processes: usize, max_processes: usize) -> Self {
+        assert!(min_processes <= max_processes);
+        Self {
+            min_processes,
+            max_processes,
+            low_water: 1,
+            high_water: 10,
+            sustain: Duration::from_secs(1),
+            cooldown: Duration::from_secs(5),
+            interval: Duration::from_millis(100),
+        }
+    }
+
+    /// Set the low- and high-water marks in messages per process.
+    ///
+    /// The pool is only scaled down while the pressure is strictly below `low_water`, so a
+    /// `low_water` of `0` never scales the pool down.
+    ///
+    /// Panics if `low_water` is larger than `high_water`.
+    pub fn with_water_marks(mut self, low_water: usize, high_water: usize) -> Self {
+        assert!(low_water <= high_water);
+        self.low_water = low_water;
+        self.high_water = high_water;
+        self
+    }
+
+    /// Set how long the pressure must stay past a water mark before the pool is resized.
+    pub fn with_sustain(mut self, sustain: Duration) -> Self {
+        self.sustain = sustain;
+        self
+    }
+
+    /// Set the minimum time between two resizes.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Set how often the pressure is checked.
+    ///
+    /// Panics if the interval is zero.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        assert!(!interval.is_zero());
+        self.interval = interval;
+        self
+    }
+}
+
+/// An actor that automatically grows and shrinks a [`ChildPool`] depending on the pressure on it's
+/// inbox, according to an [`AutoscalePolicy`].
+///
+/// The autoscaler only holds a [`WeakChildPool`], so the pool stays owned by whoever spawned it:
+/// the exits of all processes, including the ones added by the autoscaler, are received from the
+/// pool as usual. The pool is resized with [`WeakChildPool::resize`] and the default
+/// [`ResizePolicy`], so that idle processes are removed first. The autoscaler exits when it is
+/// halted, when the pool is dropped, or when the actor of the pool exits.
+///
+/// An autoscaler spawned with [`Autoscaler::spawn`] is not supervised. To restart it when it
+/// panics, it can be supervised as an [`AutoscalerSpec`](crate::supervision::AutoscalerSpec)
+/// instead.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::prelude::*;
+/// use zestors::spawning::{AutoscalePolicy, Autoscaler};
+///
+/// # async fn main() {
+/// let (mut pool, address) = spawn_many(0..1, |_, mut inbox: Inbox<()>| async move {
+///     while let Ok(_msg) = inbox.recv().await {}
+/// });
+/// let policy = AutoscalePolicy::new(1, 4).with_cooldown(Duration::from_millis(100));
+/// let worker = |mut inbox: Inbox<()>| async move {
+///     while let Ok(_msg) = inbox.recv().await {}
+/// };
+/// let (autoscaler, _) = Autoscaler::new(pool.downgrade(), policy, worker).spawn();
+///
+/// address.send(()).await.unwrap();
+/// autoscaler.halt();
+/// autoscaler.await.unwrap();
+/// assert!(pool.process_count() >= 1);
+/// # }
+/// ```
+pub struct Autoscaler<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+    pool: WeakChildPool<E, Inbox<P>>,
+    policy: AutoscalePolicy,
+    spawn_fn: F,
+}
+
+impl<E, P, F, Fut> Autoscaler<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol + Send,
+    F: Fn(Inbox<P>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send + 'static,
+{
+    /// Create a new autoscaler for the pool, see [`ChildPool::downgrade`].
+    pub fn new(pool: WeakChildPool<E, Inbox<P>>, policy: AutoscalePolicy, spawn_fn: F) -> Self {
+        Self {
+            pool,
+            policy,
+            spawn_fn,
+        }
+    }
+
+    /// Spawn the autoscaler.
+    pub fn spawn(self) -> (Child<(), Halter>, Address<Halter>) {
+        spawn(|halter: Halter| self.run(halter))
+    }
+
+    /// Resize the pool until the autoscaler is halted, or the pool is dropped or has exited.
+    pub(crate) async fn run(self, mut halter: Halter) {
+        let mut interval = interval(self.policy.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut state = AutoscaleState::new();
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = &mut halter => return,
+                _ = interval.tick() => (),
+            }
+
+            if self.pool.is_dropped() || self.pool.has_exited() {
+                return;
+            }
+
+            let process_count = self.pool.process_count();
+            let size = match state.decide(
+                &self.policy,
+                Instant::now(),
+                self.pool.msg_count(),
+                process_count,
+            ) {
+                Scale::Up => process_count + 1,
+                Scale::Down => process_count - 1,
+                Scale::None => continue,
+            };
+
+            let resize = self
+                .pool
+                .resize(size, ResizePolicy::default(), self.spawn_fn.clone());
+            tokio::select! {
+                biased;
+                _ = &mut halter => return,
+                res = resize => if res.is_err() {
+                    return;
+                },
+            }
+        }
+    }
+}
+
+impl<E, P, F> Autoscaler<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+    /// The policy of the autoscaler.
+    pub fn policy(&self) -> &AutoscalePolicy {
+        &self.policy
+    }
+}
+
+impl<E, P, F> Clone for Autoscaler<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            policy: self.policy.clone(),
+            spawn_fn: self.spawn_fn.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scale {
+    Up,
+    Down,
+    None,
+}
+
+/// Tracks how long the pressure has been past a water mark, and when the last resize was.
+#[derive(Debug)]
+struct AutoscaleState {
+    above_since: Option<Instant>,
+    below_since: Option<Instant>,
+    last_resize: Option<Instant>,
+}
+
+impl AutoscaleState {
+    fn new() -> Self {
+        Self {
+            above_since: None,
+            below_since: None,
+            last_resize: None,
+        }
+    }
+
+    fn decide(
+        &mut self,
+        policy: &AutoscalePolicy,
+        now: Instant,
+        msg_count: usize,
+        process_count: usize,
+    ) -> Scale {
+        let pressure = msg_count / process_count.max(1);
+        let above_since = *self.above_since.get_or_insert(now);
+        let below_since = *self.below_since.get_or_insert(now);
+        if pressure <= policy.high_water {
+            self.above_since = None;
+        }
+        if pressure >= policy.low_water {
+            self.below_since = None;
+        }
+
+        let scale = if process_count < policy.min_processes {
+            Scale::Up
+        } else if self
+            .last_resize
+            .is_some_and(|last| now < last + policy.cooldown)
+        {
+            Scale::None
+        } else if self.above_since.is_some()
+            && now >= above_since + policy.sustain
+            && process_count < policy.max_processes
+        {
+            Scale::Up
+        } else if self.below_since.is_some()
+            && now >= below_since + policy.sustain
+            && process_count > policy.min_processes
+        {
+            Scale::Down
+        } else {
+            Scale::None
+        };
+
+        if scale != Scale::None {
+            self.last_resize = Some(now);
+            self.above_since = None;
+            self.below_since = None;
+        }
+        scale
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::StreamExt;
+
+    fn policy() -> AutoscalePolicy {
+        AutoscalePolicy::new(1, 3)
+            .with_water_marks(1, 5)
+            .with_sustain(Duration::from_secs(1))
+            .with_cooldown(Duration::from_secs(10))
+    }
+
+    #[test]
+    fn sustained_pressure_scales_up_once_per_cooldown() {
+        let (policy, start) = (policy(), Instant::now());
+        let mut state = AutoscaleState::new();
+        assert_eq!(state.decide(&policy, start, 20, 2), Scale::None);
+        let now = start + Duration::from_secs(1);
+        assert_eq!(state.decide(&policy, now, 20, 2), Scale::Up);
+        let now = now + Duration::from_secs(5);
+        assert_eq!(state.decide(&policy, now, 30, 3), Scale::None);
+        let now = now + Duration::from_secs(10);
+        assert_eq!(state.decide(&policy, now, 30, 3), Scale::None);
+    }
+
+    #[test]
+    fn short_spikes_are_ignored() {
+        let (policy, start) = (policy(), Instant::now());
+        let mut state = AutoscaleState::new();
+        assert_eq!(state.decide(&policy, start, 20, 2), Scale::None);
+        let now = start + Duration::from_millis(500);
+        assert_eq!(state.decide(&policy, now, 4, 2), Scale::None);
+        let now = start + Duration::from_secs(1);
+        assert_eq!(state.decide(&policy, now, 20, 2), Scale::None);
+    }
+
+    #[test]
+    fn low_pressure_scales_down_to_min() {
+        let (policy, start) = (policy(), Instant::now());
+        let mut state = AutoscaleState::new();
+        assert_eq!(state.decide(&policy, start, 0, 2), Scale::None);
+        let now = start + Duration::from_secs(1);
+        assert_eq!(state.decide(&policy, now, 0, 2), Scale::Down);
+        let now = now + Duration::from_secs(20);
+        assert_eq!(state.decide(&policy, now, 0, 1), Scale::None);
+        assert_eq!(state.decide(&policy, now, 0, 0), Scale::Up);
+    }
+
+    #[test]
+    fn default_policy_scales_idle_pool_down() {
+        let (policy, start) = (AutoscalePolicy::new(1, 3), Instant::now());
+        let mut state = AutoscaleState::new();
+        assert_eq!(state.decide(&policy, start, 1, 2), Scale::None);
+        let now = start + Duration::from_secs(1);
+        assert_eq!(state.decide(&policy, now, 1, 2), Scale::Down);
+    }
+
+    #[tokio::test]
+    async fn autoscaler_grows_pool() {
+        let worker = |mut inbox: Inbox<()>| async move {
+            while inbox.recv().await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        let (mut pool, address) = spawn_many(0..1, move |_, inbox| worker(inbox));
+        let policy = AutoscalePolicy::new(1, 3)
+            .with_water_marks(0, 2)
+            .with_sustain(Duration::from_millis(10))
+            .with_cooldown(Duration::from_millis(10))
+            .with_interval(Duration::from_millis(5));
+        let (autoscaler, _) = Autoscaler::new(pool.downgrade(), policy, worker).spawn();
+
+        for _ in 0..100 {
+            address.force_send(()).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(address.process_count(), 3);
+
+        autoscaler.halt();
+        autoscaler.await.unwrap();
+        assert_eq!(pool.process_count(), 3);
+        assert_eq!(pool.handle_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn pool_receives_exit_of_autoscaled_process() {
+        let (mut pool, _address) = spawn_many(0..1, |_, mut inbox: Inbox<()>| async move {
+            while inbox.recv().await.is_ok() {}
+            0
+        });
+        let policy = AutoscalePolicy::new(2, 2).with_interval(Duration::from_millis(5));
+        let (autoscaler, _) =
+            Autoscaler::new(pool.downgrade(), policy, |_inbox| async move { 1 }).spawn();
+
+        let exit = tokio::select! {
+            biased;
+            _ = tokio::time::sleep(Duration::from_secs(1)) => panic!("the exit was not received"),
+            exit = pool.next() => exit.unwrap(),
+        };
+        assert_eq!(exit.unwrap(), 1);
+
+        autoscaler.halt();
+        autoscaler.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn autoscaler_exits_when_pool_is_dropped() {
+        let (mut pool, _address) = spawn_many(0..1, |_, mut inbox: Inbox<()>| async move {
+            while inbox.recv().await.is_ok() {}
+        });
+        let policy = AutoscalePolicy::new(1, 3).with_interval(Duration::from_millis(5));
+        let (autoscaler, _) = Autoscaler::new(pool.downgrade(), policy, |mut inbox| async move {
+            while inbox.recv().await.is_ok() {}
+        })
+        .spawn();
+
+        drop(pool);
+        tokio::time::timeout(Duration::from_secs(1), autoscaler)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
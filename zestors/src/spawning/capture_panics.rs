@@ -0,0 +1,201 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+use pin_project::pin_project;
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::{Cell, RefCell},
+    fmt::{Debug, Display},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::Once,
+    task::{Context, Poll},
+};
+
+thread_local! {
+    /// Whether the task that is currently being polled on this thread captures it's panics.
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    /// The location and backtrace of the last panic on this thread, set by the panic hook.
+    static LAST_PANIC: RefCell<Option<(Option<String>, Backtrace)>> = const { RefCell::new(None) };
+}
+
+/// Install the panic hook that captures the backtraces of panics, if it is not installed yet.
+///
+/// The hook wraps the hook that was installed before, which is still called for every panic.
+pub(crate) fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CAPTURING.with(Cell::get) {
+                let location = info.location().map(|location| location.to_string());
+                LAST_PANIC
+                    .with(|last| *last.borrow_mut() = Some((location, Backtrace::force_capture())));
+            }
+            previous(info)
+        }));
+    })
+}
+
+/// Whether tasks created on this thread currently capture their panics.
+pub(crate) fn is_capturing() -> bool {
+    CAPTURING.with(Cell::get)
+}
+
+/// Run the function while capturing panics, so that all actors spawned from it capture their
+/// panics as well.
+pub(crate) fn with_captured_panics<R>(f: impl FnOnce() -> R) -> R {
+    let _restore = RestoreCapturing(CAPTURING.with(|capturing| capturing.replace(true)));
+    f()
+}
+
+/// Restores the previous value of `CAPTURING` when dropped, also when unwinding from a panic.
+struct RestoreCapturing(bool);
+
+impl Drop for RestoreCapturing {
+    fn drop(&mut self) {
+        let _ = CAPTURING.try_with(|capturing| capturing.set(self.0));
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  CapturePanics
+//------------------------------------------------------------------------------------------------
+
+/// A future that turns the panics of the inner future into a [`PanicReport`].
+///
+/// Panics are only captured if the future was created while capturing panics, which is the case
+/// when spawning from within another capturing task, or from [`with_captured_panics`].
+#[pin_project]
+pub(crate) struct CapturePanics<F> {
+    #[pin]
+    fut: F,
+    capture: bool,
+}
+
+impl<F> CapturePanics<F> {
+    pub(crate) fn new(fut: F) -> Self {
+        Self::with_capture(fut, is_capturing())
+    }
+
+    /// Same as [`CapturePanics::new`], but for a future that is created on another thread than
+    /// the one it was spawned from.
+    pub(crate) fn with_capture(fut: F, capture: bool) -> Self {
+        Self { fut, capture }
+    }
+}
+
+impl<F: Future> Future for CapturePanics<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if !*this.capture {
+            return this.fut.poll(cx);
+        }
+
+        let fut = this.fut;
+        match panic::catch_unwind(AssertUnwindSafe(|| with_captured_panics(|| fut.poll(cx)))) {
+            Ok(poll) => poll,
+            Err(payload) => {
+                // A panic of a nested capturing future has already been reported.
+                let payload = match payload.downcast::<PanicReport>() {
+                    Ok(report) => report,
+                    Err(payload) => {
+                        let (location, backtrace) = LAST_PANIC
+                            .with(|last| last.borrow_mut().take())
+                            .unwrap_or_else(|| (None, Backtrace::disabled()));
+                        Box::new(PanicReport {
+                            payload,
+                            location,
+                            backtrace,
+                        })
+                    }
+                };
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  PanicReport
+//------------------------------------------------------------------------------------------------
+
+/// The payload of a panic that was captured together with it's backtrace.
+///
+/// When a supervisor is spawned with [`SupervisorBuilder::capture_panics`], the
+/// [`ExitError::Panic`] of it's children contains a `PanicReport` instead of the original
+/// payload; it can be retrieved with [`ExitError::panic_report`].
+pub struct PanicReport {
+    payload: Box<dyn Any + Send>,
+    location: Option<String>,
+    backtrace: Backtrace,
+}
+
+impl PanicReport {
+    /// The message of the panic, if it was a string.
+    pub fn message(&self) -> Option<&str> {
+        if let Some(msg) = self.payload.downcast_ref::<&'static str>() {
+            Some(msg)
+        } else {
+            self.payload.downcast_ref::<String>().map(String::as_str)
+        }
+    }
+
+    /// The source-location where the panic occurred.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// The backtrace captured when the panic occurred.
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// Get the original payload of the panic.
+    pub fn into_payload(self) -> Box<dyn Any + Send> {
+        self.payload
+    }
+}
+
+impl Debug for PanicReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PanicReport")
+            .field("message", &self.message())
+            .field("location", &self.location)
+            .field("backtrace", &self.backtrace)
+            .finish()
+    }
+}
+
+impl Display for PanicReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "panicked at ")?;
+        match &self.location {
+            Some(location) => write!(f, "{location}")?,
+            None => write!(f, "unknown location")?,
+        }
+        if let Some(msg) = self.message() {
+            write!(f, ": {msg}")?;
+        }
+        write!(f, "\n{}", self.backtrace)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn capturing_is_restored_after_captured_panic() {
+        install_panic_hook();
+        let fut = with_captured_panics(|| CapturePanics::new(async { panic!("captured") }));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| futures::executor::block_on(fut)));
+
+        let report = result.unwrap_err().downcast::<PanicReport>().unwrap();
+        assert_eq!(report.message(), Some("captured"));
+        assert!(!is_capturing());
+    }
+}
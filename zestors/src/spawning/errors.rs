@@ -32,3 +32,8 @@ impl<T> std::fmt::Debug for SpawnError<T> {
         f.debug_tuple("SpawnError").finish()
     }
 }
+
+/// An error returned by [`ChildPool::ready`] when a process exited before it was ready.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't become ready because a process exited before signaling it was ready")]
+pub struct ReadyError;
@@ -53,9 +53,29 @@ where
     I: InboxType,
     E: Send + 'static,
 {
-    let (channel, inbox) = I::init_single_inbox(config, 1, ActorId::generate());
-    // let inbox = I::from_channel(channel.clone());
-    let handle = tokio::task::spawn(async move { function(inbox).await });
+    spawn_with_spawner(&TokioSpawner, link, config, function)
+}
+
+/// Same as [`spawn_with`], but the actor is spawned with the given [`Spawner`] instead of onto
+/// the ambient runtime.
+pub fn spawn_with_spawner<I, E, Fun, Fut>(
+    spawner: &impl Spawner,
+    link: Link,
+    config: I::Config,
+    function: Fun,
+) -> (Child<E, I>, Address<I>)
+where
+    Fun: FnOnce(I) -> Fut + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    I: InboxType,
+    E: Send + 'static,
+{
+    let actor_id = ActorId::generate();
+    let (channel, inbox) = I::init_single_inbox(config, 1, actor_id);
+    let handle = spawner.spawn(CapturePanics::new(ActorContext::unnamed(
+        actor_id,
+        async move { function(inbox).await },
+    )));
     (
         Child::new(channel.clone(), handle, link),
         Address::from_channel(channel),
@@ -123,12 +143,35 @@ where
     E: Send + 'static,
     Itm: Send + 'static,
 {
-    let channel = I::init_multi_inbox(config, iter.len(), 1, ActorId::generate());
+    spawn_many_with_spawner(&TokioSpawner, link, config, iter, function)
+}
+
+/// Same as [`spawn_many_with`], but the processes are spawned with the given [`Spawner`] instead
+/// of onto the ambient runtime.
+pub fn spawn_many_with_spawner<I, E, Itm, Fun, Fut>(
+    spawner: &impl Spawner,
+    link: Link,
+    config: I::Config,
+    iter: impl ExactSizeIterator<Item = Itm>,
+    function: Fun,
+) -> (ChildPool<E, I>, Address<I>)
+where
+    Fun: FnOnce(Itm, I) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    I: MultiProcessInbox,
+    E: Send + 'static,
+    Itm: Send + 'static,
+{
+    let actor_id = ActorId::generate();
+    let channel = I::init_multi_inbox(config, iter.len(), 1, actor_id);
     let handles = iter
         .map(|i| {
             let fun = function.clone();
             let inbox = I::from_channel(channel.clone());
-            tokio::task::spawn(async move { fun(i, inbox).await })
+            spawner.spawn(CapturePanics::new(ActorContext::unnamed(
+                actor_id,
+                async move { fun(i, inbox).await },
+            )))
         })
         .collect::<Vec<_>>();
     (
@@ -136,3 +179,162 @@ where
         Address::from_channel(channel),
     )
 }
+
+/// Same as [`spawn_many`], but every process is given a [`ReadySignal`] as well. The returned
+/// pool can be awaited with [`ChildPool::ready`] until all processes have signaled that they are
+/// ready.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::spawning::{ready_spawn_many, ReadySignal};
+///
+/// # async fn main() {
+/// let (child, address) = ready_spawn_many(
+///     0..5,
+///     |_i: u32, mut inbox: Inbox<()>, ready: ReadySignal| async move {
+///         // Set up the process before signaling that it is ready.
+///         ready.signal();
+///         while inbox.recv().await.is_ok() {}
+///     },
+/// );
+/// child.ready().await.unwrap();
+/// # }
+/// ```
+pub fn ready_spawn_many<I, E, Itm, Fun, Fut>(
+    iter: impl ExactSizeIterator<Item = Itm>,
+    function: Fun,
+) -> (ChildPool<E, I>, Address<I>)
+where
+    Fun: FnOnce(Itm, I, ReadySignal) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    I: MultiProcessInbox,
+    I::Config: Default,
+    E: Send + 'static,
+    Itm: Send + 'static,
+{
+    ready_spawn_many_with(Default::default(), Default::default(), iter, function)
+}
+
+/// Same as [`ready_spawn_many`], but with a custom [`Link`] and [`InboxType::Config`].
+pub fn ready_spawn_many_with<I, E, Itm, Fun, Fut>(
+    link: Link,
+    config: I::Config,
+    iter: impl ExactSizeIterator<Item = Itm>,
+    function: Fun,
+) -> (ChildPool<E, I>, Address<I>)
+where
+    Fun: FnOnce(Itm, I, ReadySignal) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    I: MultiProcessInbox,
+    E: Send + 'static,
+    Itm: Send + 'static,
+{
+    let barrier = ReadyBarrier::new(iter.len());
+    let iter = iter.map(|i| (i, ReadySignal::new(barrier.clone())));
+    let (child, address) = spawn_many_with(link, config, iter, move |(i, ready), inbox| {
+        function(i, inbox, ready)
+    });
+    (child.with_ready_barrier(barrier), address)
+}
+
+/// Same as [`spawn`], but spawns the actor with a [`BoundedInbox<P, N>`], of which the capacity
+/// is part of the type.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::spawning::spawn_bounded;
+///
+/// # async fn main() {
+/// let (child, address) = spawn_bounded::<(), 16, _, _, _>(|inbox| async move {
+///     todo!()
+/// });
+/// assert_eq!(address.capacity(), Capacity::Bounded(16));
+/// # }
+/// ```
+pub fn spawn_bounded<P, const N: usize, E, Fun, Fut>(
+    function: Fun,
+) -> (Child<E, BoundedInbox<P, N>>, Address<BoundedInbox<P, N>>)
+where
+    Fun: FnOnce(BoundedInbox<P, N>) -> Fut + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    P: Protocol + Send,
+    E: Send + 'static,
+{
+    spawn_with(Default::default(), (), function)
+}
+
+/// Spawn an actor with an [`Inbox<P>`] of the given [`Capacity`], returning both the [`Child`]
+/// that owns the actor and an [`Address`] to send it messages.
+///
+/// This is the same as [`spawn_with`] with a default [`Link`], for the common case of an actor
+/// that receives messages from an [`Inbox`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::spawning::spawn_actor;
+///
+/// # async fn main() {
+/// let (child, address) = spawn_actor(Capacity::default(), |mut inbox: Inbox<()>| async move {
+///     let mut received = 0;
+///     while inbox.recv().await.is_ok() {
+///         received += 1;
+///     }
+///     received
+/// });
+/// address.send(()).await.unwrap();
+/// address.close();
+/// assert_eq!(child.await.unwrap(), 1);
+/// # }
+/// ```
+pub fn spawn_actor<P, E, Fun, Fut>(
+    capacity: Capacity,
+    function: Fun,
+) -> (Child<E, Inbox<P>>, Address<Inbox<P>>)
+where
+    Fun: FnOnce(Inbox<P>) -> Fut + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    P: Protocol + Send,
+    E: Send + 'static,
+{
+    spawn_with(Link::default(), capacity, function)
+}
+
+/// Same as [`spawn_actor`], but spawns a process for every item of the iterator, returning a
+/// [`ChildPool`] instead.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::spawning::spawn_actor_pool;
+///
+/// # async fn main() {
+/// let (pool, address) = spawn_actor_pool(
+///     Capacity::default(),
+///     0..3,
+///     |_i: u32, mut inbox: Inbox<()>| async move { while inbox.recv().await.is_ok() {} },
+/// );
+/// assert_eq!(pool.process_count(), 3);
+/// address.close();
+/// # }
+/// ```
+pub fn spawn_actor_pool<P, E, Itm, Fun, Fut>(
+    capacity: Capacity,
+    iter: impl ExactSizeIterator<Item = Itm>,
+    function: Fun,
+) -> (ChildPool<E, Inbox<P>>, Address<Inbox<P>>)
+where
+    Fun: FnOnce(Itm, Inbox<P>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    P: Protocol + Send,
+    E: Send + 'static,
+    Itm: Send + 'static,
+{
+    spawn_many_with(Link::default(), capacity, iter, function)
+}
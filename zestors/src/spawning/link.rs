@@ -6,7 +6,7 @@ use crate::all::*;
 /// The [`Link`] of an actor specifies how it should behave when the [`Child`] is dropped.
 /// - If the link is [`Link::Detached`], then the actor will continue execution.
 /// - If the link is [`Link::Attached(Duration)`], then the actor will be shut-down with the
-/// given duration.
+///   given duration.
 ///
 /// # Default
 /// The default value for a link is `Link::Attached(get_default_shutdown_time)`
@@ -71,6 +71,6 @@ impl Default for Link {
 
 impl From<Duration> for Link {
     fn from(value: Duration) -> Self {
-        Self::Attached(value.into())
+        Self::Attached(value)
     }
 }
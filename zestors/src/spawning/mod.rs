@@ -7,9 +7,29 @@
 //! the iterator as the first argument to the function.
 //! - [`spawn_many_with(iter, link, cfg, FnOnce)`](spawn_many_with) - Same as `spawn_many`, but allows for a
 //! custom link and config.
+//! - [`ready_spawn_many(iter, FnOnce)`](ready_spawn_many) - Same as `spawn_many`, but every process is
+//! given a [`ReadySignal`], so that [`ChildPool::ready`] can be awaited until all processes are running.
+//! - [`spawn_bounded::<P, N, ..>(FnOnce)`](spawn_bounded) - Same as `spawn`, but spawns the actor with a
+//! [`BoundedInbox<P, N>`] that has a fixed capacity of `N`.
+//! - [`spawn_actor(capacity, FnOnce)`](spawn_actor) - Same as `spawn`, but spawns the actor with an
+//! [`Inbox`] of the given [`Capacity`]. The pooled variant is [`spawn_actor_pool`].
 //!
 //! It is also possible to spawn more processes onto an actor that is already running with
-//! [`ChildPool::spawn_onto`] and [`ChildPool::try_spawn_onto`].
+//! [`ChildPool::spawn_onto`] and [`ChildPool::try_spawn_onto`]. An [`Autoscaler`] resizes a pool
+//! automatically through a [`WeakChildPool`], depending on the pressure on it's inbox.
+//! Processes of which the future is not [`Send`] can be spawned onto a [`LocalSet`](tokio::task::LocalSet)
+//! with [`ChildPool::spawn_on`], or onto the main thread with [`ChildPool::spawn_on_main_thread`].
+//!
+//! By default actors are spawned onto the ambient tokio runtime. A different executor can be used by
+//! implementing a [`Spawner`] and spawning with [`spawn_with_spawner`] or [`spawn_many_with_spawner`].
+//! Actors that must run on the main thread can be spawned with the [`MainThreadSpawner`], while they
+//! are still supervised from other threads.
+//!
+//! Pools can be connected into a dataflow with a [`Pipeline`], where the [`Output`] of every stage is
+//! routed to the pools of the next stage.
+//!
+//! Every process runs with an [`ActorContext`], so that the id of the actor it is part of can be
+//! retrieved from anywhere within it's task with [`ActorContext::current`].
 //!
 //! # Link
 //! Every actor is spawned with a [`Link`] that indicates whether the actor is attached
@@ -37,10 +57,19 @@
 #![doc = include_str!("../../examples/spawning.rs")]
 //! ```
 
+mod actor_context;
+mod autoscaler;
 mod capacity;
+mod capture_panics;
 mod errors;
 mod functions;
 mod link;
+mod pipeline;
+mod ready;
+mod spawner;
 #[allow(unused)]
 use crate::all::*;
-pub use {capacity::*, errors::*, functions::*, link::*};
+pub use {
+    actor_context::*, autoscaler::*, capacity::*, capture_panics::*, errors::*, functions::*,
+    link::*, pipeline::*, ready::*, spawner::*,
+};
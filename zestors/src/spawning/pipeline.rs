@@ -0,0 +1,530 @@
+use crate::{all::*, DynActor};
+use futures::{future::poll_fn, Future, StreamExt};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+};
+
+//------------------------------------------------------------------------------------------------
+//  Routing
+//------------------------------------------------------------------------------------------------
+
+/// Decides to which downstream pool(s) an [`Output`] sends a message.
+pub enum Routing<M> {
+    /// Every message is sent to all pools.
+    Broadcast,
+    /// Messages are sent to the pools in turn, skipping pools that have closed.
+    RoundRobin,
+    /// Messages with the same key are always sent to the same pool.
+    HashByKey(Arc<dyn Fn(&M) -> u64 + Send + Sync>),
+}
+
+impl<M> Routing<M> {
+    /// Route by the key returned from the function, see [`Routing::HashByKey`].
+    pub fn by_key<K: Hash>(key: impl Fn(&M) -> K + Send + Sync + 'static) -> Self {
+        Self::HashByKey(Arc::new(move |msg| {
+            let mut hasher = DefaultHasher::new();
+            key(msg).hash(&mut hasher);
+            hasher.finish()
+        }))
+    }
+}
+
+impl<M> Clone for Routing<M> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Broadcast => Self::Broadcast,
+            Self::RoundRobin => Self::RoundRobin,
+            Self::HashByKey(key) => Self::HashByKey(key.clone()),
+        }
+    }
+}
+
+impl<M> std::fmt::Debug for Routing<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Broadcast => write!(f, "Broadcast"),
+            Self::RoundRobin => write!(f, "RoundRobin"),
+            Self::HashByKey(_) => f.debug_tuple("HashByKey").finish(),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Output
+//------------------------------------------------------------------------------------------------
+
+/// The output of a [`Pipeline`] stage, which sends messages to the pools of the next stage
+/// according to it's [`Routing`].
+pub struct Output<M> {
+    targets: Arc<[Address<DynActor!(M)>]>,
+    routing: Routing<M>,
+    next: Arc<AtomicUsize>,
+}
+
+impl<M> Output<M>
+where
+    M: Message + Clone + Send + 'static,
+    M::Returned: Send,
+{
+    /// Create a new output, routing messages to the given addresses.
+    pub fn new(routing: Routing<M>, targets: Vec<Address<DynActor!(M)>>) -> Self {
+        Self {
+            targets: targets.into(),
+            routing,
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// The addresses this output sends to.
+    pub fn targets(&self) -> &[Address<DynActor!(M)>] {
+        &self.targets
+    }
+
+    /// Send the message downstream.
+    ///
+    /// This fails if the message could not be delivered to any of the pools; when
+    /// [broadcasting](Routing::Broadcast), it succeeds as long as one pool has received it.
+    pub async fn send(&self, msg: M) -> Result<(), SendError<M>> {
+        match &self.routing {
+            Routing::Broadcast => {
+                let mut delivered = false;
+                for target in self.targets.iter() {
+                    delivered |= target.send(msg.clone()).await.is_ok();
+                }
+                if delivered {
+                    Ok(())
+                } else {
+                    Err(SendError(msg))
+                }
+            }
+            Routing::RoundRobin => {
+                let mut msg = msg;
+                for _ in 0..self.targets.len() {
+                    let index = self.next.fetch_add(1, Ordering::Relaxed) % self.targets.len();
+                    match self.targets[index].send(msg).await {
+                        Ok(_) => return Ok(()),
+                        Err(SendError(returned)) => msg = returned,
+                    }
+                }
+                Err(SendError(msg))
+            }
+            Routing::HashByKey(key) => {
+                if self.targets.is_empty() {
+                    return Err(SendError(msg));
+                }
+                let index = (key(&msg) % self.targets.len() as u64) as usize;
+                self.targets[index].send(msg).await.map(|_| ())
+            }
+        }
+    }
+}
+
+impl<M> Clone for Output<M> {
+    fn clone(&self) -> Self {
+        Self {
+            targets: self.targets.clone(),
+            routing: self.routing.clone(),
+            next: self.next.clone(),
+        }
+    }
+}
+
+impl<M> std::fmt::Debug for Output<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Output")
+            .field("targets", &self.targets)
+            .field("routing", &self.routing)
+            .finish()
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Pipeline
+//------------------------------------------------------------------------------------------------
+
+/// A group of [`ChildPool`]s connected in stages, where the [`Output`] of every stage is wired to
+/// the inboxes of the next one.
+///
+/// A pipeline is built with [`Pipeline::source`], adding stages with [`PipelineBuilder::stage`]
+/// and finishing it with [`PipelineBuilder::sink`]. Every stage consists of one or more pools,
+/// each with a number of processes sharing the same inbox. The messages sent to an [`Output`] are
+/// routed over the pools of the next stage according to the stage's [`Routing`].
+///
+/// The pipeline owns the pools of all stages, which can be halted, aborted or shut down together.
+/// [`Pipeline::shutdown`] shuts down the stages one by one, starting at the source, so that every
+/// stage can process the messages sent by the previous one.
+///
+/// The stages are supervised together with [`Pipeline::supervise`]: once a stage fails, the whole
+/// pipeline is shut down, so that it never keeps running with a stage missing. Processes are not
+/// restarted individually. Until then, pools that have exited are skipped by the [`Output`] of the
+/// previous stage if it's routing allows, see [`Output::send`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, protocol, spawning::{Output, Pipeline, Routing}};
+///
+/// #[protocol]
+/// enum Numbers {
+///     Number(u32),
+/// }
+///
+/// # async fn main() {
+/// let (mut pipeline, sources) = Pipeline::source(1, 1, |mut inbox: Inbox<Numbers>, output: Output<u32>| async move {
+///     while let Ok(Numbers::Number(n)) = inbox.recv().await {
+///         output.send(n * 2).await.unwrap();
+///     }
+/// })
+/// .sink(Routing::RoundRobin, 2, 1, |mut inbox: Inbox<Numbers>| async move {
+///     while let Ok(Numbers::Number(n)) = inbox.recv().await {
+///         println!("{n}");
+///     }
+/// });
+///
+/// sources[0].send(10u32).await.unwrap();
+/// pipeline.shutdown().await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Pipeline<E: Send + 'static> {
+    stages: Vec<Vec<ChildPool<E>>>,
+}
+
+impl<E: Send + 'static> Pipeline<E> {
+    /// Start building a pipeline with it's source-stage: `pools` pools of `processes` each.
+    pub fn source<I, M, Fun, Fut>(
+        pools: usize,
+        processes: usize,
+        function: Fun,
+    ) -> PipelineBuilder<E, I, M>
+    where
+        Fun: FnOnce(I, Output<M>) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = E> + Send,
+        I: MultiProcessInbox,
+        I::Config: Default,
+        M: Message + Clone + Send + 'static,
+    {
+        PipelineBuilder {
+            connect: Box::new(move |output| {
+                let (children, addresses) = spawn_stage(pools, processes, move |inbox: I| {
+                    function(inbox, output.clone())
+                });
+                (addresses, vec![children])
+            }),
+        }
+    }
+
+    /// The stages of this pipeline, from source to sink.
+    pub fn stages(&self) -> &[Vec<ChildPool<E>>] {
+        &self.stages
+    }
+
+    /// Halt all stages at once.
+    pub fn halt(&self) {
+        for pool in self.stages.iter().flatten() {
+            pool.halt();
+        }
+    }
+
+    /// Abort all stages at once.
+    pub fn abort(&mut self) {
+        for pool in self.stages.iter_mut().flatten() {
+            pool.abort();
+        }
+    }
+
+    /// Whether all processes of all stages have exited.
+    pub fn is_finished(&self) -> bool {
+        self.stages.iter().flatten().all(|pool| pool.is_finished())
+    }
+
+    /// Supervise the stages together until one of them fails, after which the pipeline is shut
+    /// down with [`Pipeline::shutdown`]. Returns all exits, including the ones before the failure.
+    ///
+    /// A stage fails when one of it's processes exits with an [`ExitError`], for example because
+    /// it panicked, or once all processes of one of it's pools have exited. A process that exits
+    /// successfully while the rest of it's pool keeps running does not fail the stage.
+    pub async fn supervise(&mut self) -> Vec<Result<E, ExitError>> {
+        let mut exits = Vec::new();
+        poll_fn(|cx| {
+            for pool in self.stages.iter_mut().flatten() {
+                while let Poll::Ready(exit) = pool.poll_next_unpin(cx) {
+                    match exit {
+                        Some(Ok(exit)) => exits.push(Ok(exit)),
+                        Some(Err(e)) => {
+                            exits.push(Err(e));
+                            return Poll::Ready(());
+                        }
+                        None => return Poll::Ready(()),
+                    }
+                }
+            }
+            Poll::Pending
+        })
+        .await;
+        exits.extend(self.shutdown().await);
+        exits
+    }
+
+    /// Shut down the stages one by one, starting at the source, and return all exits.
+    pub async fn shutdown(&mut self) -> Vec<Result<E, ExitError>> {
+        let mut exits = Vec::new();
+        for stage in &mut self.stages {
+            for pool in stage.iter_mut() {
+                pool.halt();
+            }
+            for pool in stage.iter_mut() {
+                exits.extend(pool.shutdown().collect::<Vec<_>>().await);
+            }
+        }
+        exits
+    }
+}
+
+/// A [`Pipeline`] of which the last stage still has to be connected, where `I` is the inbox of
+/// the source and `M` the output of the last stage.
+#[allow(clippy::type_complexity)]
+pub struct PipelineBuilder<E: Send + 'static, I: ActorType, M> {
+    connect: Box<dyn FnOnce(Output<M>) -> (Vec<Address<I>>, Vec<Vec<ChildPool<E>>>) + Send>,
+}
+
+impl<E, I, M> PipelineBuilder<E, I, M>
+where
+    E: Send + 'static,
+    I: ActorType + 'static,
+    M: Message + Clone + Send + 'static,
+    M::Returned: Send,
+{
+    /// Add a stage of `pools` pools of `processes` each, receiving the output of the previous
+    /// stage with the given [`Routing`].
+    pub fn stage<I2, M2, Fun, Fut>(
+        self,
+        routing: Routing<M>,
+        pools: usize,
+        processes: usize,
+        function: Fun,
+    ) -> PipelineBuilder<E, I, M2>
+    where
+        Fun: FnOnce(I2, Output<M2>) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = E> + Send,
+        I2: MultiProcessInbox + TransformInto<DynActor!(M)>,
+        I2::Config: Default,
+        M2: Message + Clone + Send + 'static,
+    {
+        PipelineBuilder {
+            connect: Box::new(move |output| {
+                let (children, addresses) = spawn_stage(pools, processes, move |inbox: I2| {
+                    function(inbox, output.clone())
+                });
+                self.connect_to(routing, addresses, children)
+            }),
+        }
+    }
+
+    /// Finish the pipeline with a stage of `pools` pools of `processes` each, receiving the
+    /// output of the previous stage with the given [`Routing`].
+    ///
+    /// Returns the pipeline and the addresses of the source-pools.
+    pub fn sink<I2, Fun, Fut>(
+        self,
+        routing: Routing<M>,
+        pools: usize,
+        processes: usize,
+        function: Fun,
+    ) -> (Pipeline<E>, Vec<Address<I>>)
+    where
+        Fun: FnOnce(I2) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = E> + Send,
+        I2: MultiProcessInbox + TransformInto<DynActor!(M)>,
+        I2::Config: Default,
+    {
+        let (children, addresses) = spawn_stage(pools, processes, function);
+        let (sources, stages) = self.connect_to(routing, addresses, children);
+        (Pipeline { stages }, sources)
+    }
+
+    fn connect_to<I2>(
+        self,
+        routing: Routing<M>,
+        addresses: Vec<Address<I2>>,
+        children: Vec<ChildPool<E>>,
+    ) -> (Vec<Address<I>>, Vec<Vec<ChildPool<E>>>)
+    where
+        I2: ActorType + TransformInto<DynActor!(M)>,
+    {
+        let targets = addresses
+            .into_iter()
+            .map(|address| address.transform_into())
+            .collect();
+        let (sources, mut stages) = (self.connect)(Output::new(routing, targets));
+        stages.push(children);
+        (sources, stages)
+    }
+}
+
+impl<E: Send + 'static, I: ActorType, M> std::fmt::Debug for PipelineBuilder<E, I, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PipelineBuilder").finish()
+    }
+}
+
+/// Spawn `pools` pools of `processes` each.
+fn spawn_stage<I, E, Fun, Fut>(
+    pools: usize,
+    processes: usize,
+    function: Fun,
+) -> (Vec<ChildPool<E>>, Vec<Address<I>>)
+where
+    Fun: FnOnce(I) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send,
+    I: MultiProcessInbox,
+    I::Config: Default,
+    E: Send + 'static,
+{
+    (0..pools)
+        .map(|_| {
+            let function = function.clone();
+            let (child, address) = spawn_many(0..processes, move |_, inbox| function(inbox));
+            (child.into_dyn(), address)
+        })
+        .unzip()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{collections::HashMap, time::Duration};
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    #[protocol]
+    enum Numbers {
+        Number(u32),
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn collect_pipeline(
+        routing: Routing<u32>,
+    ) -> (
+        Pipeline<()>,
+        Address<Inbox<Numbers>>,
+        UnboundedReceiver<(ActorId, u32)>,
+    ) {
+        let (tx, rx) = unbounded_channel();
+        let (pipeline, mut sources) = Pipeline::source(
+            1,
+            2,
+            |mut inbox: Inbox<Numbers>, output: Output<u32>| async move {
+                while let Ok(Numbers::Number(n)) = inbox.recv().await {
+                    output.send(n).await.unwrap();
+                }
+            },
+        )
+        .stage(
+            Routing::RoundRobin,
+            1,
+            1,
+            |mut inbox: Inbox<Numbers>, output: Output<u32>| async move {
+                while let Ok(Numbers::Number(n)) = inbox.recv().await {
+                    output.send(n * 10).await.unwrap();
+                }
+            },
+        )
+        .sink(routing, 2, 1, move |mut inbox: Inbox<Numbers>| async move {
+            while let Ok(Numbers::Number(n)) = inbox.recv().await {
+                tx.send((inbox.actor_id(), n)).unwrap();
+            }
+        });
+        (pipeline, sources.pop().unwrap(), rx)
+    }
+
+    async fn received(
+        rx: &mut UnboundedReceiver<(ActorId, u32)>,
+        n: usize,
+    ) -> HashMap<ActorId, Vec<u32>> {
+        let mut received = HashMap::<_, Vec<_>>::new();
+        for _ in 0..n {
+            let (id, n) = rx.recv().await.unwrap();
+            received.entry(id).or_default().push(n);
+        }
+        received.values_mut().for_each(|values| values.sort());
+        received
+    }
+
+    #[tokio::test]
+    async fn round_robin() {
+        let (mut pipeline, source, mut rx) = collect_pipeline(Routing::RoundRobin);
+        for n in 0..4 {
+            source.send(n).await.unwrap();
+        }
+
+        let received = received(&mut rx, 4).await;
+        assert_eq!(received.len(), 2);
+        assert!(received.values().all(|values| values.len() == 2));
+        assert_eq!(pipeline.stages().len(), 3);
+        assert_eq!(pipeline.shutdown().await.len(), 5);
+        assert!(pipeline.is_finished());
+    }
+
+    #[tokio::test]
+    async fn broadcast() {
+        let (mut pipeline, source, mut rx) = collect_pipeline(Routing::Broadcast);
+        source.send(1u32).await.unwrap();
+        source.send(2u32).await.unwrap();
+
+        let received = received(&mut rx, 4).await;
+        assert_eq!(received.len(), 2);
+        assert!(received.values().all(|values| values == &vec![10, 20]));
+        pipeline.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn failed_stage_shuts_down_pipeline() {
+        let (mut pipeline, mut sources) = Pipeline::source(
+            1,
+            1,
+            |mut inbox: Inbox<Numbers>, output: Output<u32>| async move {
+                while let Ok(Numbers::Number(n)) = inbox.recv().await {
+                    output.send(n).await.unwrap();
+                }
+            },
+        )
+        .sink(
+            Routing::RoundRobin,
+            1,
+            2,
+            |mut inbox: Inbox<Numbers>| async move {
+                while let Ok(Numbers::Number(n)) = inbox.recv().await {
+                    assert_ne!(n, 0, "zero");
+                }
+            },
+        );
+        let source = sources.pop().unwrap();
+        source.send(0u32).await.unwrap();
+
+        let exits = tokio::time::timeout(Duration::from_secs(1), pipeline.supervise())
+            .await
+            .unwrap();
+        assert_eq!(exits.len(), 3);
+        assert_eq!(exits.iter().filter(|exit| exit.is_err()).count(), 1);
+        assert!(pipeline.is_finished());
+        assert!(source.has_exited());
+    }
+
+    #[tokio::test]
+    async fn hash_by_key() {
+        let (mut pipeline, source, mut rx) = collect_pipeline(Routing::by_key(|n: &u32| n % 20));
+        for n in [1, 21, 1, 21] {
+            source.send(n).await.unwrap();
+        }
+
+        let received = received(&mut rx, 4).await;
+        assert!(received.values().all(|values| values.len() % 2 == 0));
+        pipeline.shutdown().await;
+    }
+}
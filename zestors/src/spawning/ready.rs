@@ -0,0 +1,127 @@
+#[allow(unused)]
+use crate::all::*;
+use event_listener::Event;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+};
+
+/// Given to every process spawned with [`ready_spawn_many`], which signals that the process is
+/// ready once [`ReadySignal::signal`] is called. See [`ChildPool::ready`].
+///
+/// If the signal is dropped without being sent, for example because the process exited or
+/// panicked during it's setup, then [`ChildPool::ready`] returns a [`ReadyError`].
+#[derive(Debug)]
+pub struct ReadySignal {
+    barrier: Arc<ReadyBarrier>,
+    signaled: bool,
+}
+
+impl ReadySignal {
+    pub(crate) fn new(barrier: Arc<ReadyBarrier>) -> Self {
+        Self {
+            barrier,
+            signaled: false,
+        }
+    }
+
+    /// Signal that this process is ready.
+    pub fn signal(mut self) {
+        self.signaled = true;
+        self.barrier.signal(true);
+    }
+}
+
+impl Drop for ReadySignal {
+    fn drop(&mut self) {
+        if !self.signaled {
+            self.barrier.signal(false);
+        }
+    }
+}
+
+/// The barrier of a [`ChildPool`] spawned with [`ready_spawn_many`], counting the processes that
+/// still have to send their [`ReadySignal`].
+#[derive(Debug)]
+pub(crate) struct ReadyBarrier {
+    remaining: AtomicUsize,
+    failed: AtomicBool,
+    event: Event,
+}
+
+impl ReadyBarrier {
+    pub(crate) fn new(processes: usize) -> Arc<Self> {
+        Arc::new(Self {
+            remaining: AtomicUsize::new(processes),
+            failed: AtomicBool::new(false),
+            event: Event::new(),
+        })
+    }
+
+    fn signal(&self, ready: bool) {
+        if !ready {
+            self.failed.store(true, Ordering::Release);
+        }
+        if self.remaining.fetch_sub(1, Ordering::AcqRel) == 1 || !ready {
+            self.event.notify(usize::MAX);
+        }
+    }
+
+    fn check(&self) -> Option<Result<(), ReadyError>> {
+        if self.failed.load(Ordering::Acquire) {
+            Some(Err(ReadyError))
+        } else if self.remaining.load(Ordering::Acquire) == 0 {
+            Some(Ok(()))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) async fn wait(&self) -> Result<(), ReadyError> {
+        loop {
+            if let Some(res) = self.check() {
+                return res;
+            }
+            let listener = self.event.listen();
+            if let Some(res) = self.check() {
+                return res;
+            }
+            listener.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn ready_waits_for_all_processes() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let (child, _address) = ready_spawn_many(0..4u32, {
+            let started = started.clone();
+            move |i, mut inbox: Inbox<()>, ready: ReadySignal| async move {
+                tokio::time::sleep(Duration::from_millis(i as u64 * 5)).await;
+                started.fetch_add(1, Ordering::SeqCst);
+                ready.signal();
+                while inbox.recv().await.is_ok() {}
+            }
+        });
+        child.ready().await.unwrap();
+        assert_eq!(started.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn exit_before_signal_is_an_error() {
+        let (child, _address) =
+            ready_spawn_many(0..2, |i, mut inbox: Inbox<()>, ready| async move {
+                if i == 0 {
+                    panic!("failed to set up");
+                }
+                ready.signal();
+                while inbox.recv().await.is_ok() {}
+            });
+        assert_eq!(child.ready().await, Err(ReadyError));
+    }
+}
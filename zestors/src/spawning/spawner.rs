@@ -0,0 +1,307 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+use std::{
+    fmt::Debug,
+    panic,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    runtime::Handle,
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+/// The executor onto which the tasks of actors are spawned.
+///
+/// By default actors are spawned with the [`TokioSpawner`] onto the ambient tokio runtime. A
+/// custom spawner can be given to [`spawn_with_spawner`], [`spawn_many_with_spawner`],
+/// [`ChildPool::spawn_onto_with`] and [`SpawnSpec::with_spawner`]. A [`Handle`] spawns onto the
+/// runtime it belongs to, and the [`LocalSpawner`] spawns onto the current
+/// [`LocalSet`](tokio::task::LocalSet) for deterministic, single-threaded tests. The
+/// [`MainThreadSpawner`] spawns onto a local-set of the main thread from any thread.
+///
+/// The spawner is cloned whenever the actor of a [`SpawnSpec`] is restarted.
+pub trait Spawner: Clone + Send + Sync + 'static {
+    /// Spawn the future as a new task.
+    fn spawn<F>(&self, fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static;
+}
+
+/// The default [`Spawner`], which spawns onto the ambient tokio runtime with
+/// [`tokio::task::spawn`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSpawner;
+
+impl Spawner for TokioSpawner {
+    fn spawn<F>(&self, fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        tokio::task::spawn(fut)
+    }
+}
+
+impl Spawner for Handle {
+    fn spawn<F>(&self, fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        Handle::spawn(self, fut)
+    }
+}
+
+/// A [`Spawner`] that spawns onto the current [`LocalSet`](tokio::task::LocalSet) with
+/// [`tokio::task::spawn_local`].
+///
+/// All tasks are run on the thread of the local-set, which makes the execution of a test
+/// deterministic. Combined with [`tokio::time::pause`] the clock can be controlled as well.
+///
+/// Spawning panics if it is not done from within a local-set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalSpawner;
+
+impl Spawner for LocalSpawner {
+    fn spawn<F>(&self, fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        tokio::task::spawn_local(fut)
+    }
+}
+
+/// A [`Spawner`] that spawns onto a [`LocalSet`](tokio::task::LocalSet) of the main thread, for
+/// actors that must run there, such as GUI or FFI actors. Created with [`MainThreadSpawner::new`].
+///
+/// Contrary to the [`LocalSpawner`], this can spawn from any thread, so that the actor can be part
+/// of a supervision tree of which the [`SupervisorHandle`] lives on other threads. The tasks of
+/// the actors are always polled on the main thread, but the [`Child`] and [`Supervisee`] of such
+/// an actor can be polled anywhere.
+///
+/// The actors are only spawned and run while the [`MainThreadDriver`] is being run on a local-set
+/// of the main thread. Spawning requires the ambient tokio runtime, and the [`JoinHandle`] that is
+/// returned aborts the actor on the main thread when it is aborted.
+///
+/// As a [`Spawner`], the futures it is given must be [`Send`], since they are created before
+/// being sent to the main thread. Processes of which the future is not `Send` can be added to a
+/// [`ChildPool`] with [`ChildPool::spawn_on_main_thread`] instead, which creates the future on
+/// the main thread.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, spawning::*};
+///
+/// # async fn main() {
+/// let (spawner, driver) = MainThreadSpawner::new();
+/// let local = tokio::task::LocalSet::new();
+/// local.spawn_local(driver.run());
+///
+/// let main_thread = std::thread::current().id();
+/// let (child, _address) = spawn_with_spawner(
+///     &spawner,
+///     Link::default(),
+///     Capacity::default(),
+///     |_inbox: Inbox<()>| async move { std::thread::current().id() },
+/// );
+/// assert_eq!(local.run_until(child).await.unwrap(), main_thread);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MainThreadSpawner {
+    sender: mpsc::UnboundedSender<Box<dyn FnOnce() + Send>>,
+}
+
+/// Spawns the tasks of a [`MainThreadSpawner`] onto the current
+/// [`LocalSet`](tokio::task::LocalSet), which should belong to the main thread.
+pub struct MainThreadDriver {
+    receiver: mpsc::UnboundedReceiver<Box<dyn FnOnce() + Send>>,
+}
+
+impl MainThreadSpawner {
+    /// Create a new spawner, together with the driver that must be run on the main thread.
+    pub fn new() -> (Self, MainThreadDriver) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self { sender }, MainThreadDriver { receiver })
+    }
+
+    /// Spawn the future that is created by the function onto the main thread. The function is
+    /// called on the main thread, so the future does not have to be [`Send`].
+    pub fn spawn_local<Fun, Fut>(&self, fun: Fun) -> JoinHandle<Fut::Output>
+    where
+        Fun: FnOnce() -> Fut + Send + 'static,
+        Fut: Future + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.sender.send(Box::new(move || {
+            let _ = tx.send(tokio::task::spawn_local(fun()));
+        }));
+        tokio::task::spawn(async move {
+            let Ok(handle) = rx.await else {
+                panic!("Couldn't spawn onto the main thread, because the driver was dropped")
+            };
+            match AbortOnDrop(handle).await {
+                Ok(output) => output,
+                Err(e) if e.is_panic() => panic::resume_unwind(e.into_panic()),
+                Err(_) => panic!("The task on the main thread was cancelled"),
+            }
+        })
+    }
+}
+
+impl Spawner for MainThreadSpawner {
+    fn spawn<F>(&self, fut: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.spawn_local(move || fut)
+    }
+}
+
+impl MainThreadDriver {
+    /// Spawn the tasks of the spawner onto the current local-set, until all spawners have been
+    /// dropped.
+    ///
+    /// Panics if it is not run from within a local-set.
+    pub async fn run(mut self) {
+        while let Some(spawn) = self.receiver.recv().await {
+            spawn()
+        }
+    }
+}
+
+impl Debug for MainThreadDriver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MainThreadDriver").finish_non_exhaustive()
+    }
+}
+
+/// Aborts the task on the main thread when the task awaiting it is aborted.
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, tokio::task::JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        rc::Rc,
+        thread::{self, ThreadId},
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_spawner_runs_all_processes_on_one_thread() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let (mut child, address) = spawn_many_with_spawner::<Inbox<()>, _, _, _, _>(
+                    &LocalSpawner,
+                    Link::default(),
+                    Capacity::default(),
+                    0..2,
+                    |_, _inbox| async move { thread::current().id() },
+                );
+                child
+                    .spawn_onto_with(
+                        &LocalSpawner,
+                        |_inbox| async move { thread::current().id() },
+                    )
+                    .unwrap();
+                address.halt();
+
+                let mut threads = Vec::<ThreadId>::new();
+                while let Some(exit) = futures::StreamExt::next(&mut child).await {
+                    threads.push(exit.unwrap());
+                }
+                assert_eq!(threads, vec![thread::current().id(); 3]);
+            })
+            .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn main_thread_actor_is_supervised_from_another_thread() {
+        let (spawner, driver) = MainThreadSpawner::new();
+        let local = tokio::task::LocalSet::new();
+        local.spawn_local(driver.run());
+
+        let (tx, rx) = oneshot::channel();
+        let spec = SpawnSpec::new(
+            |halter: Halter, tx: oneshot::Sender<ThreadId>| async move {
+                tx.send(thread::current().id()).unwrap();
+                halter.await
+            },
+            |_exit| async move { Ok(None) },
+            tx,
+        )
+        .with_spawner(spawner);
+        let (child, handle) = SupervisorBuilder::new(spec).spawn();
+
+        let main_thread = thread::current().id();
+        local
+            .run_until(async move {
+                assert_eq!(rx.await.unwrap(), main_thread);
+                let controller = tokio::spawn(async move {
+                    handle.halt();
+                    thread::current().id()
+                });
+                assert_ne!(controller.await.unwrap(), main_thread);
+                assert!(child.await.unwrap().unwrap().is_none());
+            })
+            .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn main_thread_process_does_not_have_to_be_send() {
+        let (spawner, driver) = MainThreadSpawner::new();
+        let local = tokio::task::LocalSet::new();
+        local.spawn_local(driver.run());
+
+        let (mut child, address) = spawn_many(0..1, |_, mut inbox: Inbox<()>| async move {
+            let _ = inbox.recv().await;
+            thread::current().id()
+        });
+        let mut child = tokio::spawn(async move {
+            child
+                .spawn_on_main_thread(&spawner, |_inbox| async move {
+                    let id = Rc::new(thread::current().id());
+                    tokio::task::yield_now().await;
+                    *id
+                })
+                .unwrap();
+            child
+        })
+        .await
+        .unwrap();
+
+        let main_thread = thread::current().id();
+        local
+            .run_until(async move {
+                let exit = futures::StreamExt::next(&mut child).await.unwrap();
+                assert_eq!(exit.unwrap(), main_thread);
+                address.halt();
+                let exit = futures::StreamExt::next(&mut child).await.unwrap();
+                assert_ne!(exit.unwrap(), main_thread);
+            })
+            .await;
+    }
+}
@@ -0,0 +1,63 @@
+use super::labeled_spec::merge_labels;
+#[allow(unused)]
+use crate::all::*;
+use std::{pin::Pin, time::Duration};
+
+/// A snapshot of a running actor in a supervision-tree, see [`SupervisorHandle::list_actors`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorInfo {
+    /// The position of the actor in the tree: the indices of the children leading up to it,
+    /// separated by `/`. For example, `root/1/0` is the first child of the second child of the
+    /// supervised spec.
+    pub name: String,
+    /// The type-name of the [`Specification`] the actor was started from.
+    pub kind: &'static str,
+    /// The time since the actor was last started.
+    pub uptime: Duration,
+    /// How many times the actor has been restarted by it's parent.
+    pub restart_count: usize,
+    /// Whether the actor is running, or shutting down.
+    pub status: ActorStatus,
+    /// The labels of the actor and the groups it is part of, set with
+    /// [`SpecificationExt::with_labels`].
+    pub labels: Vec<(String, String)>,
+}
+
+/// The status of an actor in an [`ActorInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActorStatus {
+    /// The actor is running.
+    Running,
+    /// The actor, or one of the groups it is part of, is shutting down.
+    ShuttingDown,
+}
+
+impl ActorInfo {
+    /// Get the value of the label with the given key.
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// List the actors of a child, described by `info`: the actors it supervises if it is a
+    /// group, and otherwise the child itself.
+    pub(super) fn list_child<S: Supervisee>(child: Pin<&S>, info: ActorInfo) -> Vec<ActorInfo> {
+        let Some(actors) = child.list_actors() else {
+            return vec![info];
+        };
+        actors
+            .into_iter()
+            .map(|actor| ActorInfo {
+                name: format!("{}/{}", info.name, actor.name),
+                status: match info.status {
+                    ActorStatus::ShuttingDown => ActorStatus::ShuttingDown,
+                    ActorStatus::Running => actor.status,
+                },
+                labels: merge_labels(&info.labels, actor.labels),
+                ..actor
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,180 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{ready, Future, FutureExt};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] that spawns an [`Autoscaler`], and restarts it if it panics.
+///
+/// The autoscaler completes when it is halted, when it's pool is dropped, or when the actor of the
+/// pool exits. Since it only holds a [`WeakChildPool`], the pool itself is not supervised by this
+/// spec: it stays owned by whoever spawned it.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::spawning::{AutoscalePolicy, Autoscaler};
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let worker = |mut inbox: Inbox<()>| async move {
+///     while let Ok(_msg) = inbox.recv().await {}
+/// };
+/// let (mut pool, _address) = spawn_many(0..1, move |_, inbox| worker(inbox));
+/// let autoscaler = Autoscaler::new(pool.downgrade(), AutoscalePolicy::new(1, 4), worker);
+/// let (supervisee, address) = AutoscalerSpec::new(autoscaler)
+///     .start_supervised()
+///     .await
+///     .unwrap();
+/// address.halt();
+/// assert!(supervisee.supervise().await.unwrap().is_none());
+/// # }
+/// ```
+pub struct AutoscalerSpec<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+    autoscaler: Autoscaler<E, P, F>,
+}
+
+impl<E, P, F> AutoscalerSpec<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+    /// Create a new spec that spawns the autoscaler.
+    pub fn new(autoscaler: Autoscaler<E, P, F>) -> Self {
+        Self { autoscaler }
+    }
+}
+
+impl<E, P, F> Debug for AutoscalerSpec<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AutoscalerSpec")
+            .field("policy", self.autoscaler.policy())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<E, P, F, Fut> Specification for AutoscalerSpec<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol + Send,
+    F: Fn(Inbox<P>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send + 'static,
+{
+    type Ref = Address<Halter>;
+    type Supervisee = AutoscalerSupervisee<E, P, F>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let autoscaler = self.autoscaler.clone();
+        let link = Link::Attached(get_default_shutdown_time());
+        let (child, address) = spawn_with(link, (), |halter: Halter| autoscaler.run(halter));
+        Ok((
+            AutoscalerSupervisee {
+                spec: Some(self),
+                child,
+            },
+            address,
+        ))
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of an [`AutoscalerSpec`].
+pub struct AutoscalerSupervisee<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+    spec: Option<AutoscalerSpec<E, P, F>>,
+    child: Child<(), Halter>,
+}
+
+impl<E, P, F> Unpin for AutoscalerSupervisee<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+}
+
+impl<E, P, F, Fut> Supervisee for AutoscalerSupervisee<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol + Send,
+    F: Fn(Inbox<P>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = E> + Send + 'static,
+{
+    type Spec = AutoscalerSpec<E, P, F>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let exit = ready!(self.child.poll_unpin(cx));
+        let spec = self.spec.take().expect("Polled after completion");
+        Poll::Ready(match exit {
+            Ok(()) => Ok(None),
+            Err(_) => Ok(Some(spec)),
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        get_default_shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.child.halt();
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        self.child.abort();
+    }
+}
+
+impl<E, P, F> Debug for AutoscalerSupervisee<E, P, F>
+where
+    E: Send + 'static,
+    P: Protocol,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AutoscalerSupervisee")
+            .field("spec", &self.spec)
+            .field("actor_id", &self.child.actor_id())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn completes_when_pool_is_dropped() {
+        let worker = |mut inbox: Inbox<()>| async move { while inbox.recv().await.is_ok() {} };
+        let (mut pool, _address) = spawn_many(0..1, move |_, inbox| worker(inbox));
+        let policy = AutoscalePolicy::new(1, 3).with_interval(Duration::from_millis(5));
+        let spec = AutoscalerSpec::new(Autoscaler::new(pool.downgrade(), policy, worker));
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+
+        drop(pool);
+        let exit = tokio::time::timeout(Duration::from_secs(1), supervisee.supervise())
+            .await
+            .unwrap();
+        assert!(exit.unwrap().is_none());
+    }
+}
@@ -0,0 +1,253 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::{
+    any::type_name,
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// A type-erased and boxed [`Specification`], created with [`SpecificationExt::into_dyn`].
+///
+/// This makes it possible to group specifications of different types together, as long as they
+/// return the same [`Specification::Ref`]. The box keeps track of how many times the spec has been
+/// restarted, which is shown in the [`ActorInfo`] of it's actor.
+pub struct BoxSpec<Ref = ()> {
+    spec: Box<dyn DynSpecification<Ref>>,
+    kind: &'static str,
+    restarts: usize,
+}
+
+impl<Ref: Send + 'static> BoxSpec<Ref> {
+    /// Box the specification.
+    pub fn new<S>(spec: S) -> Self
+    where
+        S: Specification<Ref = Ref>,
+        S::Supervisee: 'static,
+    {
+        Self {
+            spec: Box::new(spec),
+            kind: type_name::<S>(),
+            restarts: 0,
+        }
+    }
+}
+
+impl<Ref> Debug for BoxSpec<Ref> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BoxSpec").finish()
+    }
+}
+
+#[async_trait]
+impl<Ref: Send + 'static> Specification for BoxSpec<Ref> {
+    type Ref = Ref;
+    type Supervisee = BoxSupervisee<Ref>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let (kind, restarts) = (self.kind, self.restarts);
+        match self.spec.start_boxed().await {
+            Ok((mut supervisee, reference)) => {
+                supervisee.kind = kind;
+                supervisee.restarts = restarts;
+                Ok((supervisee, reference))
+            }
+            Err(e) => Err(e.map(|mut spec| {
+                spec.kind = kind;
+                spec.restarts = restarts + 1;
+                spec
+            })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.spec.validate_boxed()
+    }
+}
+
+/// The type-erased and boxed [`Supervisee`] of a [`BoxSpec`].
+pub struct BoxSupervisee<Ref = ()> {
+    supervisee: Pin<Box<dyn DynSupervisee<Ref>>>,
+    kind: &'static str,
+    restarts: usize,
+    started: Instant,
+}
+
+impl<Ref: Send + 'static> BoxSupervisee<Ref> {
+    /// Box the supervisee.
+    pub fn new<S>(supervisee: S) -> Self
+    where
+        S: Supervisee + 'static,
+        S::Spec: Specification<Ref = Ref>,
+    {
+        Self {
+            supervisee: Box::pin(supervisee),
+            kind: type_name::<S::Spec>(),
+            restarts: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// List the actors of this child, which is described by `name` in it's parent.
+    pub(super) fn list_child(&self, name: String, status: ActorStatus) -> Vec<ActorInfo> {
+        let info = ActorInfo {
+            name,
+            kind: self.kind,
+            uptime: self.started.elapsed(),
+            restart_count: self.restarts,
+            status,
+            labels: Pin::new(self).labels(),
+        };
+        ActorInfo::list_child(Pin::new(self), info)
+    }
+}
+
+impl<Ref> Debug for BoxSupervisee<Ref> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BoxSupervisee").finish()
+    }
+}
+
+impl<Ref: Send + 'static> Supervisee for BoxSupervisee<Ref> {
+    type Spec = BoxSpec<Ref>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let (kind, restarts) = (self.kind, self.restarts);
+        self.supervisee
+            .as_mut()
+            .poll_supervise_boxed(cx)
+            .map(|res| {
+                res.map(|spec| {
+                    spec.map(|mut spec| {
+                        spec.kind = kind;
+                        spec.restarts = restarts + 1;
+                        spec
+                    })
+                })
+            })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.get_ref().supervisee.as_ref().shutdown_time_boxed()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        self.supervisee.as_mut().halt_boxed()
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        self.supervisee.as_mut().abort_boxed()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.get_ref().supervisee.as_ref().list_actors_boxed()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.get_ref().supervisee.as_ref().labels_boxed()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.get_mut().supervisee.as_mut().one_for_one_mut_boxed()
+    }
+
+    fn take_exit_value(mut self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.supervisee.as_mut().take_exit_value_boxed()
+    }
+
+    fn take_backoff_reset(mut self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.supervisee.as_mut().take_backoff_reset_boxed()
+    }
+}
+
+/// Object-safe version of [`Specification`], implemented for every specification.
+trait DynSpecification<Ref: Send + 'static>: Send + 'static {
+    fn start_boxed(self: Box<Self>) -> BoxFuture<'static, StartResult<BoxSpec<Ref>>>;
+    fn validate_boxed(&self) -> Result<(), FatalError>;
+}
+
+impl<S> DynSpecification<S::Ref> for S
+where
+    S: Specification,
+    S::Supervisee: 'static,
+{
+    fn start_boxed(self: Box<Self>) -> BoxFuture<'static, StartResult<BoxSpec<S::Ref>>> {
+        Box::pin(async move {
+            match self.start_supervised().await {
+                Ok((supervisee, reference)) => Ok((BoxSupervisee::new(supervisee), reference)),
+                Err(e) => Err(e.map(BoxSpec::new)),
+            }
+        })
+    }
+
+    fn validate_boxed(&self) -> Result<(), FatalError> {
+        self.validate()
+    }
+}
+
+/// Object-safe version of [`Supervisee`], implemented for every supervisee.
+trait DynSupervisee<Ref: Send + 'static>: Send {
+    fn poll_supervise_boxed(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<BoxSpec<Ref>>>;
+    fn shutdown_time_boxed(self: Pin<&Self>) -> Duration;
+    fn halt_boxed(self: Pin<&mut Self>);
+    fn abort_boxed(self: Pin<&mut Self>);
+    fn list_actors_boxed(self: Pin<&Self>) -> Option<Vec<ActorInfo>>;
+    fn labels_boxed(self: Pin<&Self>) -> Vec<(String, String)>;
+    fn one_for_one_mut_boxed(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee>;
+    fn take_exit_value_boxed(self: Pin<&mut Self>) -> Option<ExitValue>;
+    fn take_backoff_reset_boxed(self: Pin<&mut Self>) -> Option<BackoffReset>;
+}
+
+impl<S> DynSupervisee<<S::Spec as Specification>::Ref> for S
+where
+    S: Supervisee + 'static,
+{
+    fn poll_supervise_boxed(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<BoxSpec<<S::Spec as Specification>::Ref>>> {
+        self.poll_supervise(cx)
+            .map(|res| res.map(|spec| spec.map(BoxSpec::new)))
+    }
+
+    fn shutdown_time_boxed(self: Pin<&Self>) -> Duration {
+        self.shutdown_time()
+    }
+
+    fn halt_boxed(self: Pin<&mut Self>) {
+        self.halt()
+    }
+
+    fn abort_boxed(self: Pin<&mut Self>) {
+        self.abort()
+    }
+
+    fn list_actors_boxed(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.list_actors()
+    }
+
+    fn labels_boxed(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.labels()
+    }
+
+    fn one_for_one_mut_boxed(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.one_for_one_mut()
+    }
+
+    fn take_exit_value_boxed(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.take_exit_value()
+    }
+
+    fn take_backoff_reset_boxed(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.take_backoff_reset()
+    }
+}
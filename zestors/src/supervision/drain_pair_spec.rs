@@ -0,0 +1,373 @@
+use super::one_for_one::{AbortTimer, SHUTDOWN_MARGIN};
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::future::poll_fn;
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+/// A [`Specification`] that supervises a producer `S` together with a drain `D`, which consumes
+/// the side-stream of events (such as metrics or audit-logs) the producer sends it.
+///
+/// Every time the pair is started, a new bounded channel is created. The producer is built with
+/// the sender and the drain with the receiver. The drain is started first, so that it is ready
+/// before the producer sends anything.
+///
+/// The pair shares it's fate: when either of them exits, the other is halted (and aborted if
+/// it does not exit within it's shutdown-time). The pair then exits as a whole:
+/// - If either has failed, the pair exits with it's [`FatalError`].
+/// - If the producer has completed, the pair has completed.
+/// - Otherwise the pair exits so that it can be restarted, with a new channel.
+///
+/// Since both specs are rebuilt on every start, the specs they exit with are dropped. When the
+/// pair has completed, it's exit-value is that of the producer.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use tokio::sync::mpsc;
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = DrainPairSpec::new(
+///     16,
+///     |events: mpsc::Sender<u32>| {
+///         SpawnSpec::new(
+///             |_halter: Halter, events: mpsc::Sender<u32>| async move {
+///                 events.send(10).await.unwrap();
+///             },
+///             |_exit| async move { Ok(None) },
+///             events,
+///         )
+///     },
+///     |events: mpsc::Receiver<u32>| {
+///         SpawnSpec::new(
+///             |_halter: Halter, mut events: mpsc::Receiver<u32>| async move {
+///                 while let Some(event) = events.recv().await {
+///                     println!("event: {event}");
+///                 }
+///             },
+///             |_exit| async move { Ok(None) },
+///             events,
+///         )
+///     },
+/// );
+/// let (supervisee, _references) = spec.start_supervised().await.unwrap();
+/// assert!(supervisee.supervise().await.unwrap().is_none());
+/// # }
+/// ```
+pub struct DrainPairSpec<S, D> {
+    build: Box<dyn FnMut() -> (S, D) + Send>,
+}
+
+impl<S: Specification, D: Specification> DrainPairSpec<S, D> {
+    /// Create a new spec, building the producer and the drain from the two ends of a channel with
+    /// the given capacity.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is `0`.
+    pub fn new<T, PFun, DFun>(capacity: usize, mut producer: PFun, mut drain: DFun) -> Self
+    where
+        T: Send + 'static,
+        PFun: FnMut(mpsc::Sender<T>) -> S + Send + 'static,
+        DFun: FnMut(mpsc::Receiver<T>) -> D + Send + 'static,
+    {
+        assert!(capacity > 0, "capacity must be greater than 0");
+        Self {
+            build: Box::new(move || {
+                let (sender, receiver) = mpsc::channel(capacity);
+                (producer(sender), drain(receiver))
+            }),
+        }
+    }
+}
+
+impl<S, D> Debug for DrainPairSpec<S, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DrainPairSpec").finish()
+    }
+}
+
+#[async_trait]
+impl<S: Specification, D: Specification> Specification for DrainPairSpec<S, D> {
+    type Ref = (S::Ref, D::Ref);
+    type Supervisee = DrainPairSupervisee<S, D>;
+
+    async fn start_supervised(mut self) -> StartResult<Self> {
+        let (producer, drain) = (self.build)();
+
+        let (drain, drain_ref) = match drain.start_supervised().await {
+            Ok((drain, drain_ref)) => (Box::pin(drain), drain_ref),
+            Err(e) => return Err(e.map(|_| self)),
+        };
+        match producer.start_supervised().await {
+            Ok((producer, producer_ref)) => Ok((
+                DrainPairSupervisee {
+                    producer: Box::pin(producer),
+                    drain,
+                    exits: (None, None),
+                    exit_value: None,
+                    abort_timer: None,
+                    build: Some(self.build),
+                },
+                (producer_ref, drain_ref),
+            )),
+            Err(e) => match shut_down(drain).await {
+                Err(drain_error) => Err(StartError::Fatal(drain_error)),
+                Ok(_) => Err(e.map(|_| self)),
+            },
+        }
+    }
+}
+
+/// Halt the supervisee and wait for it to exit, aborting it after it's shutdown-time.
+async fn shut_down<S: Supervisee>(mut supervisee: Pin<Box<S>>) -> SupervisionResult<S::Spec> {
+    supervisee.as_mut().halt();
+    let shutdown_time = supervisee.as_ref().shutdown_time();
+    let mut timer = AbortTimer::new(shutdown_time.saturating_add(SHUTDOWN_MARGIN));
+    let exit = poll_fn(|cx| match supervisee.as_mut().poll_supervise(cx) {
+        Poll::Ready(exit) => Poll::Ready(Some(exit)),
+        Poll::Pending if timer.poll_expired(cx) => Poll::Ready(None),
+        Poll::Pending => Poll::Pending,
+    })
+    .await;
+    match exit {
+        Some(exit) => exit,
+        None => {
+            supervisee.as_mut().abort();
+            poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`DrainPairSpec`].
+pub struct DrainPairSupervisee<S: Specification, D: Specification> {
+    producer: Pin<Box<S::Supervisee>>,
+    drain: Pin<Box<D::Supervisee>>,
+    exits: (Option<SupervisionResult<S>>, Option<SupervisionResult<D>>),
+    exit_value: Option<ExitValue>,
+    abort_timer: Option<AbortTimer>,
+    build: Option<Box<dyn FnMut() -> (S, D) + Send>>,
+}
+
+impl<S: Specification, D: Specification> Unpin for DrainPairSupervisee<S, D> {}
+
+impl<S: Specification, D: Specification> Supervisee for DrainPairSupervisee<S, D> {
+    type Spec = DrainPairSpec<S, D>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = &mut *self;
+
+        if this.exits.0.is_none() {
+            if let Poll::Ready(exit) = this.producer.as_mut().poll_supervise(cx) {
+                if let Ok(None) = exit {
+                    this.exit_value = this.producer.as_mut().take_exit_value();
+                }
+                this.exits.0 = Some(exit);
+            }
+        }
+        if this.exits.1.is_none() {
+            if let Poll::Ready(exit) = this.drain.as_mut().poll_supervise(cx) {
+                this.exits.1 = Some(exit);
+            }
+        }
+
+        match &this.exits {
+            (Some(producer), Some(drain)) => {
+                let exit = match (producer, drain) {
+                    (Err(_), _) => this.exits.0.take().unwrap().map(|_| None),
+                    (_, Err(_)) => this.exits.1.take().unwrap().map(|_| None),
+                    (Ok(None), _) => Ok(None),
+                    (Ok(Some(_)), Ok(_)) => Ok(Some(DrainPairSpec {
+                        build: this.build.take().unwrap(),
+                    })),
+                };
+                return Poll::Ready(exit);
+            }
+            (Some(_), None) if this.abort_timer.is_none() => {
+                let shutdown_time = this.drain.as_ref().shutdown_time();
+                this.drain.as_mut().halt();
+                this.abort_timer = Some(AbortTimer::new(
+                    shutdown_time.saturating_add(SHUTDOWN_MARGIN),
+                ));
+            }
+            (None, Some(_)) if this.abort_timer.is_none() => {
+                let shutdown_time = this.producer.as_ref().shutdown_time();
+                this.producer.as_mut().halt();
+                this.abort_timer = Some(AbortTimer::new(
+                    shutdown_time.saturating_add(SHUTDOWN_MARGIN),
+                ));
+            }
+            _ => (),
+        }
+
+        if let Some(timer) = &mut this.abort_timer {
+            if timer.poll_expired(cx) {
+                if this.exits.0.is_none() {
+                    this.producer.as_mut().abort();
+                }
+                if this.exits.1.is_none() {
+                    this.drain.as_mut().abort();
+                }
+            }
+        }
+        Poll::Pending
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.producer
+            .as_ref()
+            .shutdown_time()
+            .max(self.drain.as_ref().shutdown_time())
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        self.producer.as_mut().halt();
+        self.drain.as_mut().halt();
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        self.producer.as_mut().abort();
+        self.drain.as_mut().abort();
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        let producer = self.producer.as_ref().list_actors();
+        let drain = self.drain.as_ref().list_actors();
+        match (producer, drain) {
+            (None, None) => None,
+            (producer, drain) => Some(
+                producer
+                    .into_iter()
+                    .chain(drain)
+                    .flatten()
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.get_mut().exit_value.take()
+    }
+
+    fn take_backoff_reset(mut self: Pin<&mut Self>) -> Option<BackoffReset> {
+        let (child, reset) = match self.producer.as_mut().take_backoff_reset() {
+            Some(reset) => (0, reset),
+            None => (1, self.drain.as_mut().take_backoff_reset()?),
+        };
+        Some(BackoffReset {
+            child: Some(child),
+            ..reset
+        })
+    }
+}
+
+impl<S: Specification, D: Specification> Debug for DrainPairSupervisee<S, D>
+where
+    S::Supervisee: Debug,
+    D::Supervisee: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DrainPairSupervisee")
+            .field("producer", &self.producer)
+            .field("drain", &self.drain)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn pair_is_restarted_together() {
+        let (out, mut received) = mpsc::unbounded_channel();
+        let spec = DrainPairSpec::new(
+            1,
+            |events: mpsc::Sender<u32>| {
+                SpawnSpec::new(
+                    |halter: Halter, events: mpsc::Sender<u32>| async move {
+                        events.send(1).await.unwrap();
+                        halter.await;
+                        events
+                    },
+                    |exit: Result<_, ExitError>| async move { Ok(exit.ok()) },
+                    events,
+                )
+            },
+            move |events: mpsc::Receiver<u32>| {
+                let out = out.clone();
+                SpawnSpec::new(
+                    move |halter: Halter, mut events: mpsc::Receiver<u32>| {
+                        let out = out.clone();
+                        async move {
+                            tokio::select! {
+                                () = halter => (),
+                                () = async {
+                                    while let Some(event) = events.recv().await {
+                                        out.send(event).unwrap();
+                                    }
+                                } => (),
+                            }
+                            events
+                        }
+                    },
+                    |exit: Result<_, ExitError>| async move { Ok(exit.ok()) },
+                    events,
+                )
+            },
+        );
+
+        let (supervisee, (producer, drain)) = spec.start_supervised().await.unwrap();
+        assert_eq!(received.recv().await, Some(1));
+        drain.halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert!(producer.has_exited());
+
+        let (supervisee, (producer, _drain)) = spec.start_supervised().await.unwrap();
+        assert_eq!(received.recv().await, Some(1));
+        producer.halt();
+        assert!(supervisee.supervise().await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn forwards_exit_value_of_producer() {
+        let spec = DrainPairSpec::new(
+            1,
+            |_events: mpsc::Sender<()>| {
+                SpawnSpec::new(
+                    |_halter: Halter, ()| async move { 10u32 },
+                    |_exit: Result<u32, ExitError>| async move { Ok(None) },
+                    (),
+                )
+                .with_exit_value()
+            },
+            |_events: mpsc::Receiver<()>| {
+                SpawnSpec::new(
+                    |halter: Halter, ()| halter,
+                    |_exit| async move { Ok(None) },
+                    (),
+                )
+            },
+        );
+        let (supervisee, _references) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(exit.unwrap().is_none());
+        let exit_value = supervisee.as_mut().take_exit_value().unwrap();
+        assert_eq!(*exit_value.downcast::<u32>().unwrap(), 10);
+    }
+}
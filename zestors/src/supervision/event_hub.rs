@@ -0,0 +1,185 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Stream;
+use std::{
+    fmt::{Debug, Display},
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+/// An id for a supervision-tree that has been added to an [`EventHub`], unique per tree.
+#[derive(PartialEq, Eq, Debug, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct TreeId(u64);
+
+impl TreeId {
+    fn generate() -> Self {
+        static NEXT_TREE_ID: AtomicU64 = AtomicU64::new(0);
+        TreeId(NEXT_TREE_ID.fetch_add(1, Ordering::AcqRel))
+    }
+
+    /// Convert the tree-id to a u64.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for TreeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// Merges the [events](SupervisorHandle::events) of multiple supervision-trees into a single
+/// [`Stream`], tagging every event with the [`TreeId`] of the tree it came from.
+///
+/// Trees can be added and removed at any time. A tree is removed automatically once it's
+/// event-stream has ended, but the hub itself never ends: while it is empty, it waits for a
+/// tree to be added. The trees are polled in turn, so that a busy tree can't starve the others.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use futures::StreamExt;
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = || {
+///     SpawnSpec::new(
+///         |halter: Halter, ()| halter,
+///         |_exit| async move { Ok(Some(())) },
+///         (),
+///     )
+/// };
+/// let (_child1, handle1) = SupervisorBuilder::new(spec()).spawn();
+/// let (_child2, handle2) = SupervisorBuilder::new(spec()).spawn();
+///
+/// let mut hub = EventHub::new();
+/// let tree1 = hub.add(&handle1);
+/// let tree2 = hub.add(&handle2);
+///
+/// let (tree, _event) = hub.next().await.unwrap();
+/// assert!(tree == tree1 || tree == tree2);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct EventHub {
+    trees: Vec<(TreeId, StreamRx<SupervisionEvent>)>,
+    /// The index of the tree that is polled first.
+    next: usize,
+    waker: Option<Waker>,
+}
+
+impl EventHub {
+    /// Create a new hub without any trees.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the tree of the supervisor, receiving all of it's events from now on.
+    pub fn add(&mut self, handle: &SupervisorHandle) -> TreeId {
+        self.add_stream(handle.events())
+    }
+
+    /// Add a tree by it's event-stream, as returned by [`SupervisorHandle::events`].
+    pub fn add_stream(&mut self, events: StreamRx<SupervisionEvent>) -> TreeId {
+        let id = TreeId::generate();
+        self.trees.push((id, events));
+        if let Some(waker) = self.waker.take() {
+            waker.wake()
+        }
+        id
+    }
+
+    /// Remove the tree, returning it's event-stream if it was part of the hub.
+    ///
+    /// Events of the tree that have not been received yet can still be taken from the stream.
+    pub fn remove(&mut self, id: TreeId) -> Option<StreamRx<SupervisionEvent>> {
+        let index = self.trees.iter().position(|(tree, _)| *tree == id)?;
+        Some(self.trees.remove(index).1)
+    }
+
+    /// Whether the tree is part of the hub.
+    pub fn contains(&self, id: TreeId) -> bool {
+        self.trees.iter().any(|(tree, _)| *tree == id)
+    }
+
+    /// The ids of all trees that are part of the hub.
+    pub fn trees(&self) -> impl Iterator<Item = TreeId> + '_ {
+        self.trees.iter().map(|(tree, _)| *tree)
+    }
+
+    /// The amount of trees.
+    pub fn len(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Whether there are no trees.
+    pub fn is_empty(&self) -> bool {
+        self.trees.is_empty()
+    }
+}
+
+impl Unpin for EventHub {}
+
+impl Stream for EventHub {
+    type Item = (TreeId, SupervisionEvent);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let mut polled = 0;
+        while polled < this.trees.len() {
+            let index = (this.next + polled) % this.trees.len();
+            let (id, events) = &mut this.trees[index];
+            match Pin::new(events).poll_next(cx) {
+                Poll::Ready(Some(event)) => {
+                    this.next = index + 1;
+                    return Poll::Ready(Some((*id, event)));
+                }
+                Poll::Ready(None) => {
+                    this.trees.remove(index);
+                }
+                Poll::Pending => polled += 1,
+            }
+        }
+        this.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn events_are_tagged_and_ended_trees_removed() {
+        let mut hub = EventHub::new();
+        let (tx1, rx1) = new_stream_request();
+        let (tx2, rx2) = new_stream_request();
+        let tree1 = hub.add_stream(rx1);
+        let tree2 = hub.add_stream(rx2);
+
+        let event = |kind| SupervisionEvent {
+            time: std::time::SystemTime::now(),
+            kind,
+        };
+        tx2.send(event(SupervisionEventKind::Started)).unwrap();
+        tx1.send(event(SupervisionEventKind::Exited)).unwrap();
+        drop(tx1);
+
+        let mut received = [hub.next().await.unwrap(), hub.next().await.unwrap()];
+        received.sort_by_key(|(tree, _)| *tree);
+        assert_eq!(received[0].0, tree1);
+        assert_eq!(received[0].1.kind, SupervisionEventKind::Exited);
+        assert_eq!(received[1].0, tree2);
+        assert_eq!(received[1].1.kind, SupervisionEventKind::Started);
+
+        let pending = tokio::time::timeout(Duration::from_millis(10), hub.next()).await;
+        assert!(pending.is_err());
+        assert!(!hub.contains(tree1));
+        assert!(hub.remove(tree2).is_some());
+        assert!(hub.is_empty());
+    }
+}
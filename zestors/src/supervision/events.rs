@@ -0,0 +1,220 @@
+#[allow(unused)]
+use crate::all::*;
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+/// A lifecycle event of a supervision-tree, see [`SupervisorHandle::events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupervisionEvent {
+    /// The time at which the event occured.
+    pub time: SystemTime,
+    /// What happened.
+    pub kind: SupervisionEventKind,
+}
+
+/// The kind of [`SupervisionEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupervisionEventKind {
+    /// The supervisee has been started.
+    Started,
+    /// The supervisee has been restarted.
+    Restarted,
+    /// Starting the supervisee has failed, but it may be restarted.
+    StartFailed,
+    /// The supervisee has exited, but it may be restarted.
+    Exited,
+    /// The supervisee has completed and will not be restarted.
+    Completed,
+    /// The supervisee has failed with a fatal error.
+    Failed(String),
+    /// A child of a [`OneForAllSpec`] has caused all children to be restarted.
+    GroupRestarted(RestartCause),
+    /// The child of a [`OneForOneSpec`] with this id has exceeded the restart-limit, and has
+    /// been stopped, see [`LimitAction::StopChild`].
+    ChildGaveUp(usize),
+    /// The supervisee has exceeded the restart-limit and will not be restarted.
+    RestartLimitReached,
+    /// The supervisee has been halted, and is shutting down.
+    ShuttingDown,
+    /// The supervisee did not exit within it's shutdown-time and has been aborted.
+    Aborted,
+    /// The supervisee has been shut down.
+    Shutdown,
+    /// A child has reported a non-fatal error with an [`ErrorReporter`], without exiting.
+    ChildError(ChildError),
+    /// A child that was backing off has stayed up for it's minimum healthy uptime, and it's
+    /// backoff has been reset, see [`MinUptimeSpec`].
+    BackoffReset(BackoffReset),
+}
+
+/// A non-fatal error that was reported by a child, see [`ErrorReporter::report_error`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildError {
+    /// The actor that reported the error, if it was reported from within an actor.
+    pub actor_id: Option<ActorId>,
+    /// The message of the error.
+    pub error: String,
+}
+
+/// A child of a [`MinUptimeSpec`] that has recovered, see [`SupervisionEventKind::BackoffReset`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackoffReset {
+    /// The index of the child in it's tree, or it's id in a [`OneForOneSpec`]. This is `None`
+    /// if the child is supervised directly by a supervisor.
+    pub child: Option<usize>,
+    /// The labels of the child, set with [`SpecificationExt::with_labels`].
+    pub labels: Vec<(String, String)>,
+    /// The amount of unhealthy restarts before the child recovered.
+    pub unhealthy_restarts: u32,
+    /// The time from the first unhealthy restart until the child recovered.
+    pub incident_duration: Duration,
+}
+
+/// Reports non-fatal errors of the children of a tree as [`SupervisionEventKind::ChildError`],
+/// see [`OneForOneSpec::error_reporter`] and [`OneForAllSpec::error_reporter`].
+///
+/// Errors that an actor handles itself don't cause it to exit, so they would normally not be seen
+/// by it's supervisor. Reporting them surfaces them in the events of the tree, for example for
+/// alerting, without restarting the child. The reporter can be cloned and given to the children
+/// when their specs are created.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = OneForOneSpec::new();
+/// let mut events = spec.events();
+/// let reporter = spec.error_reporter();
+///
+/// reporter.report_error("connection reset");
+/// let SupervisionEventKind::ChildError(error) = events.try_recv().unwrap().kind else {
+///     panic!("Expected a child-error")
+/// };
+/// assert_eq!(error.error, "connection reset");
+/// assert_eq!(error.actor_id, None);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ErrorReporter {
+    events: Arc<EventLog>,
+}
+
+impl ErrorReporter {
+    pub(crate) fn new(events: Arc<EventLog>) -> Self {
+        Self { events }
+    }
+
+    /// Report a non-fatal error, tagged with the [`ActorId`] of the current actor.
+    pub fn report_error(&self, error: impl Display) {
+        self.report(SupervisionEventKind::ChildError(ChildError {
+            actor_id: ActorContext::current().map(|context| context.id),
+            error: error.to_string(),
+        }));
+    }
+
+    /// Report any event to the events of the tree.
+    pub(crate) fn report(&self, kind: SupervisionEventKind) {
+        self.events.push(kind);
+    }
+}
+
+/// Keeps the most recent events in a ring-buffer and sends events to all subscribers.
+#[derive(Debug)]
+pub(crate) struct EventLog {
+    capacity: usize,
+    inner: Mutex<EventLogInner>,
+}
+
+#[derive(Debug)]
+struct EventLogInner {
+    recent: VecDeque<SupervisionEvent>,
+    subscribers: Vec<StreamTx<SupervisionEvent>>,
+}
+
+impl EventLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(EventLogInner {
+                recent: VecDeque::with_capacity(capacity),
+                subscribers: Vec::new(),
+            }),
+        }
+    }
+
+    /// Record a new event, and send it to the subscribers.
+    pub(crate) fn push(&self, kind: SupervisionEventKind) {
+        let event = SupervisionEvent {
+            time: SystemTime::now(),
+            kind,
+        };
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+
+        if self.capacity > 0 {
+            if inner.recent.len() == self.capacity {
+                inner.recent.pop_front();
+            }
+            inner.recent.push_back(event);
+        }
+    }
+
+    /// Get the last `n` events, oldest first.
+    pub(crate) fn recent(&self, n: usize) -> Vec<SupervisionEvent> {
+        let inner = self.inner.lock().unwrap();
+        let skip = inner.recent.len().saturating_sub(n);
+        inner.recent.iter().skip(skip).cloned().collect()
+    }
+
+    /// Subscribe to all events from now on.
+    pub(crate) fn subscribe(&self) -> StreamRx<SupervisionEvent> {
+        let (tx, rx) = new_stream_request();
+        self.inner.lock().unwrap().subscribers.push(tx);
+        rx
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_keeps_most_recent() {
+        let log = EventLog::new(2);
+        log.push(SupervisionEventKind::Started);
+        log.push(SupervisionEventKind::Exited);
+        log.push(SupervisionEventKind::Restarted);
+
+        let kinds =
+            |events: Vec<SupervisionEvent>| events.into_iter().map(|e| e.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds(log.recent(5)),
+            vec![
+                SupervisionEventKind::Exited,
+                SupervisionEventKind::Restarted
+            ]
+        );
+        assert_eq!(kinds(log.recent(1)), vec![SupervisionEventKind::Restarted]);
+        assert_eq!(kinds(log.recent(0)), vec![]);
+    }
+
+    #[test]
+    fn subscribers_receive_events() {
+        let log = EventLog::new(0);
+        let mut events = log.subscribe();
+        log.push(SupervisionEventKind::Started);
+        assert_eq!(
+            events.try_recv().unwrap().kind,
+            SupervisionEventKind::Started
+        );
+        assert!(log.recent(1).is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::{Future, FutureExt};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use thiserror::Error;
+use tokio::sync::oneshot;
+
+/// Create an [`ExitCollector`] together with the [`CollectedExits`] it resolves.
+pub fn collect_exits<T: Send + 'static>() -> (ExitCollector<T>, CollectedExits<T>) {
+    let (tx, rx) = oneshot::channel();
+    let collector = ExitCollector(Arc::new(Mutex::new(CollectorInner {
+        values: Vec::new(),
+        tx: Some(tx),
+    })));
+    (collector, CollectedExits(rx))
+}
+
+/// Collects the exit-values of the children of a [`OneForOneSpec`], created with
+/// [`collect_exits`].
+///
+/// The collector is added to the tree with [`OneForOneSpec::with_collected_exits`]. Whenever a
+/// child completes, the tree takes it's exit-value with [`Supervisee::take_exit_value`] and adds
+/// it to the collector; once the tree completes, all values collected so far are sent to the
+/// [`CollectedExits`]. Exit-values that are not a `T` are ignored.
+///
+/// The exits of children are erased by their [`BoxSpec`], so a child only has an exit-value if
+/// it's supervisee keeps one: a [`SpawnSpec`] does so when created with
+/// [`SpawnSpec::with_exit_value`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*, actor_reference::ExitError};
+///
+/// # async fn main() {
+/// let (collector, exits) = collect_exits::<u32>();
+/// let square = |x: u32| {
+///     SpawnSpec::new(
+///         |_halter: Halter, x: u32| async move { x * x },
+///         |_exit: Result<u32, ExitError>| async move { Ok(None) },
+///         x,
+///     )
+///     .with_exit_value()
+/// };
+/// let spec = OneForOneSpec::new()
+///     .with_spec(square(2))
+///     .with_spec(square(3))
+///     .with_collected_exits(collector);
+///
+/// let (supervisee, ()) = spec.start_supervised().await.unwrap();
+/// assert!(supervisee.supervise().await.unwrap().is_none());
+/// let mut exits = exits.await.unwrap();
+/// exits.sort();
+/// assert_eq!(exits, vec![4, 9]);
+/// # }
+/// ```
+pub struct ExitCollector<T>(Arc<Mutex<CollectorInner<T>>>);
+
+struct CollectorInner<T> {
+    values: Vec<T>,
+    tx: Option<oneshot::Sender<Vec<T>>>,
+}
+
+impl<T> ExitCollector<T> {
+    /// The amount of exit-values collected so far.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().values.len()
+    }
+
+    /// Whether no exit-values have been collected yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for ExitCollector<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Debug for ExitCollector<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ExitCollector").field(&self.len()).finish()
+    }
+}
+
+/// A type-erased [`ExitCollector`] that collects the exit-values of a tree, and is completed by it.
+pub(super) trait CollectExits: Send + Debug {
+    fn collect(&self, exit_value: ExitValue);
+    fn complete(&self);
+}
+
+impl<T: Send + 'static> CollectExits for ExitCollector<T> {
+    fn collect(&self, exit_value: ExitValue) {
+        if let Ok(value) = exit_value.downcast::<T>() {
+            self.0.lock().unwrap().values.push(*value)
+        }
+    }
+
+    fn complete(&self) {
+        let mut inner = self.0.lock().unwrap();
+        if let Some(tx) = inner.tx.take() {
+            let _ = tx.send(std::mem::take(&mut inner.values));
+        }
+    }
+}
+
+/// A future that resolves to the exit-values of all children, once the tree that collects them
+/// has completed; see [`ExitCollector`].
+///
+/// If the tree fails, or is dropped before completing, this resolves to an [`ExitsIncomplete`]
+/// error once all collectors have been dropped.
+#[derive(Debug)]
+pub struct CollectedExits<T>(oneshot::Receiver<Vec<T>>);
+
+impl<T> Future for CollectedExits<T> {
+    type Output = Result<Vec<T>, ExitsIncomplete>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll_unpin(cx).map_err(|_| ExitsIncomplete)
+    }
+}
+
+/// Error returned by [`CollectedExits`] if the tree did not complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't collect the exits because the tree did not complete")]
+pub struct ExitsIncomplete;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn double(x: u32) -> impl Specification {
+        SpawnSpec::new(
+            |_halter: Halter, x: u32| async move { x * 2 },
+            |_exit: Result<u32, ExitError>| async move { Ok(None) },
+            x,
+        )
+        .with_exit_value()
+    }
+
+    #[tokio::test]
+    async fn completed_tree_collects_exits() {
+        let (collector, exits) = collect_exits::<u32>();
+        let spec = (1..=3)
+            .fold(OneForOneSpec::new(), |spec, x| spec.with_spec(double(x)))
+            .with_collected_exits(collector);
+
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+        let mut exits = exits.await.unwrap();
+        exits.sort();
+        assert_eq!(exits, vec![2, 4, 6]);
+    }
+
+    #[tokio::test]
+    async fn dropped_tree_is_incomplete() {
+        let (collector, exits) = collect_exits::<u32>();
+        let spec = OneForOneSpec::new()
+            .with_spec(double(1))
+            .with_collected_exits(collector);
+        drop(spec);
+        assert_eq!(exits.await, Err(ExitsIncomplete));
+    }
+
+    #[tokio::test]
+    async fn exits_that_are_not_kept_or_of_another_type_are_ignored() {
+        let (collector, exits) = collect_exits::<u32>();
+        let spec = OneForOneSpec::new()
+            .with_spec(double(1))
+            .with_spec(SpawnSpec::new(
+                |_halter: Halter, x: u32| async move { x * 2 },
+                |_exit: Result<u32, ExitError>| async move { Ok(None) },
+                2,
+            ))
+            .with_spec(
+                SpawnSpec::new(
+                    |_halter: Halter, x: u64| async move { x * 2 },
+                    |_exit: Result<u64, ExitError>| async move { Ok(None) },
+                    3,
+                )
+                .with_exit_value(),
+            )
+            .with_collected_exits(collector);
+
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+        assert_eq!(exits.await.unwrap(), vec![2]);
+    }
+}
@@ -0,0 +1,289 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::watch;
+
+/// A [`Specification`] that presents it's inner spec to the outside world through a single,
+/// stable facade-[`Address`].
+///
+/// The reference of the inner spec is usually the address of a router in front of a group of
+/// workers. The facade is an actor that forwards every message it receives to the reference of
+/// the inner spec, which is re-wired every time the inner spec is restarted. While the inner spec
+/// is not running, messages are kept in the inbox of the facade until it has been restarted.
+/// External holders of the facade-address therefore never see a dead address.
+///
+/// The facade is spawned when the spec is created, and keeps running across restarts of the spec.
+/// It exits when the spec (and it's supervisee) has been dropped. Messages that are not accepted
+/// by the inner reference are dropped.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, protocol, supervision::*};
+///
+/// #[protocol]
+/// enum MyProtocol {
+///     Number(u32),
+/// }
+///
+/// # async fn main() {
+/// let router = SpawnSpec::new(
+///     |mut inbox: Inbox<MyProtocol>, ()| async move {
+///         let _ = inbox.recv().await;
+///     },
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// );
+/// let spec = FacadeSpec::<_, MyProtocol>::new(router);
+/// let facade = spec.address();
+/// let (supervisee, address) = spec.start_supervised().await.unwrap();
+/// assert_eq!(address.actor_id(), facade.actor_id());
+/// facade.send(10u32).await.unwrap();
+/// # drop(supervisee);
+/// # }
+/// ```
+pub struct FacadeSpec<S: Specification, P: Protocol> {
+    inner_spec: S,
+    facade: Arc<Facade<S::Ref, P>>,
+}
+
+struct Facade<R, P: Protocol> {
+    address: Address<Inbox<P>>,
+    target: watch::Sender<Option<R>>,
+}
+
+impl<S, P> FacadeSpec<S, P>
+where
+    S: Specification,
+    S::Ref: ActorRef + Sync,
+    P: Protocol + Send,
+{
+    /// Create a new spec, spawning the facade with a default [`Capacity`].
+    ///
+    /// This must be called from within a tokio runtime.
+    pub fn new(spec: S) -> Self {
+        Self::new_with(spec, Capacity::default())
+    }
+
+    /// Create a new spec, spawning the facade with the given [`Capacity`].
+    ///
+    /// This must be called from within a tokio runtime.
+    pub fn new_with(spec: S, capacity: Capacity) -> Self {
+        let (target, receiver) = watch::channel(None);
+        let (child, address) = spawn_with(Link::Detached, capacity, |inbox| {
+            run_facade::<S::Ref, P>(inbox, receiver)
+        });
+        drop(child);
+        Self {
+            inner_spec: spec,
+            facade: Arc::new(Facade { address, target }),
+        }
+    }
+
+    /// Get the address of the facade, which is the same every time the spec is started.
+    pub fn address(&self) -> Address<Inbox<P>> {
+        self.facade.address.clone()
+    }
+}
+
+impl<S: Specification + Debug, P: Protocol> Debug for FacadeSpec<S, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacadeSpec")
+            .field("inner_spec", &self.inner_spec)
+            .field("facade", &self.facade.address.actor_id())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S, P> Specification for FacadeSpec<S, P>
+where
+    S: Specification,
+    S::Ref: ActorRef + Sync,
+    P: Protocol + Send,
+{
+    type Ref = Address<Inbox<P>>;
+    type Supervisee = FacadeSupervisee<S, P>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let facade = self.facade;
+        match self.inner_spec.start_supervised().await {
+            Ok((supervisee, reference)) => {
+                facade.target.send_replace(Some(reference));
+                let address = facade.address.clone();
+                Ok((FacadeSupervisee { supervisee, facade }, address))
+            }
+            Err(e) => Err(e.map(|inner_spec| Self { inner_spec, facade })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.inner_spec.validate()
+    }
+}
+
+/// Forward all messages to the current target, waiting for a target while there is none.
+async fn run_facade<R: ActorRef, P: Protocol>(
+    mut inbox: Inbox<P>,
+    mut target: watch::Receiver<Option<R>>,
+) {
+    loop {
+        let msg = tokio::select! {
+            msg = inbox.recv() => match msg {
+                Ok(msg) => msg,
+                Err(_) => return,
+            },
+            changed = target.changed() => match changed {
+                Ok(()) => continue,
+                Err(_) => return,
+            },
+        };
+
+        let mut payload = msg.into_boxed_payload();
+        loop {
+            let channel = target
+                .borrow_and_update()
+                .as_ref()
+                .map(|reference| <R as ActorRef>::channel_ref(reference).clone());
+            if let Some(channel) = channel {
+                match channel.send_box(payload).await {
+                    Ok(()) | Err(SendCheckedError::NotAccepted(_)) => break,
+                    Err(SendCheckedError::Closed(returned)) => payload = returned,
+                }
+            }
+            if target.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`FacadeSpec`].
+#[pin_project]
+pub struct FacadeSupervisee<S: Specification, P: Protocol> {
+    #[pin]
+    supervisee: S::Supervisee,
+    facade: Arc<Facade<S::Ref, P>>,
+}
+
+impl<S, P> Supervisee for FacadeSupervisee<S, P>
+where
+    S: Specification,
+    S::Ref: ActorRef + Sync,
+    P: Protocol + Send,
+{
+    type Spec = FacadeSpec<S, P>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+        this.supervisee.poll_supervise(cx).map(|res| {
+            this.facade.target.send_replace(None);
+            res.map(|spec| {
+                spec.map(|inner_spec| FacadeSpec {
+                    inner_spec,
+                    facade: this.facade.clone(),
+                })
+            })
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+impl<S: Specification, P: Protocol> Debug for FacadeSupervisee<S, P>
+where
+    S::Supervisee: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FacadeSupervisee")
+            .field("supervisee", &self.supervisee)
+            .field("facade", &self.facade.address.actor_id())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+    use futures::future::poll_fn;
+
+    #[tokio::test]
+    async fn facade_survives_restarts() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let router = SpawnSpec::new(
+            move |mut inbox: Inbox<U32Protocol>, tx: tokio::sync::mpsc::UnboundedSender<_>| async move {
+                let U32Protocol::U32(n) = inbox.recv().await.unwrap();
+                tx.send((inbox.actor_id(), n)).unwrap();
+                tx
+            },
+            |exit: Result<_, ExitError>| async move { Ok(Some(exit.unwrap())) },
+            tx,
+        );
+        let spec = FacadeSpec::<_, U32Protocol>::new(router);
+        let facade = spec.address();
+
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        facade.send(1u32).await.unwrap();
+        let (first, n) = rx.recv().await.unwrap();
+        assert_eq!(n, 1);
+
+        // The router has exited, so this is kept by the facade until the restart.
+        facade.send(2u32).await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        let spec = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        let (_supervisee, _address) = spec.start_supervised().await.unwrap();
+        let (second, n) = rx.recv().await.unwrap();
+        assert_eq!(n, 2);
+        assert_ne!(first, second);
+        assert!(!facade.has_exited());
+    }
+}
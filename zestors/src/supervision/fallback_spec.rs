@@ -0,0 +1,428 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{future::BoxFuture, ready, FutureExt};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{sleep, Sleep};
+
+/// The default interval at which a [`FallbackSpec`] retries it's primary spec.
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A [`Specification`] that falls back to a degraded backup `B`, for example a read-only cache,
+/// when it's primary spec `P` fails to start with a [`FatalError`].
+///
+/// The primary spec is built with a function every time it is started, since a spec that has
+/// failed irrecoverably is lost. While the backup is running, the primary is retried in the
+/// background at the retry-interval. Once it has started, the backup is halted and this exits
+/// with a spec that resumes the started primary. The [`FallbackRef`] shows which of the two is
+/// active.
+///
+/// Swapping back is therefore seen by the parent as a restart. If the backup completes or fails
+/// while being halted for the swap, that is the exit of this spec instead. When this is restarted
+/// for any other reason, the primary is always tried first.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let unreachable = || ProcessSpec::new(|| tokio::process::Command::new("/does/not/exist"));
+/// let cache = SpawnSpec::new(
+///     |halter: Halter, ()| halter,
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// );
+/// let spec = FallbackSpec::new(unreachable, cache);
+/// let (_supervisee, reference) = spec.start_supervised().await.unwrap();
+/// assert!(reference.is_backup());
+/// # }
+/// ```
+pub struct FallbackSpec<P: Specification, B> {
+    build_primary: Box<dyn FnMut() -> P + Send>,
+    primary: Option<Primary<P>>,
+    backup: B,
+    retry_interval: Duration,
+}
+
+/// A primary spec that is started before it's builder is used.
+enum Primary<P: Specification> {
+    Spec(P),
+    Started(P::Supervisee, P::Ref),
+}
+
+/// The reference of a [`FallbackSpec`], which is the reference of the active spec.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FallbackRef<P, B> {
+    /// The primary spec is active.
+    Primary(P),
+    /// The primary spec failed to start, and the backup is active.
+    Backup(B),
+}
+
+impl<P, B> FallbackRef<P, B> {
+    /// Whether the primary spec is active.
+    pub fn is_primary(&self) -> bool {
+        matches!(self, Self::Primary(_))
+    }
+
+    /// Whether the backup is active.
+    pub fn is_backup(&self) -> bool {
+        matches!(self, Self::Backup(_))
+    }
+}
+
+impl<P: Specification, B: Specification> FallbackSpec<P, B> {
+    /// Create a new spec, building the primary spec with `build_primary` every time it is started.
+    pub fn new(build_primary: impl FnMut() -> P + Send + 'static, backup: B) -> Self {
+        Self {
+            build_primary: Box::new(build_primary),
+            primary: None,
+            backup,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+        }
+    }
+
+    /// Set the interval at which the primary spec is retried while the backup is running.
+    ///
+    /// The default is [`DEFAULT_RETRY_INTERVAL`].
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+}
+
+impl<P: Specification, B: Debug> Debug for FallbackSpec<P, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackSpec")
+            .field("backup", &self.backup)
+            .field("retry_interval", &self.retry_interval)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<P: Specification, B: Specification> Specification for FallbackSpec<P, B> {
+    type Ref = FallbackRef<P::Ref, B::Ref>;
+    type Supervisee = FallbackSupervisee<P, B>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let Self {
+            mut build_primary,
+            primary,
+            backup,
+            retry_interval,
+        } = self;
+
+        let started = match primary {
+            Some(Primary::Started(supervisee, reference)) => Ok((supervisee, reference)),
+            Some(Primary::Spec(spec)) => start_primary(spec).await,
+            None => start_primary(build_primary()).await,
+        };
+        match started {
+            Ok((supervisee, reference)) => {
+                let active = Active::Primary {
+                    supervisee: Box::pin(supervisee),
+                    backup: Some(backup),
+                };
+                let supervisee = FallbackSupervisee::new(active, build_primary, retry_interval);
+                return Ok((supervisee, FallbackRef::Primary(reference)));
+            }
+            Err(StartError::StartFailed(spec)) => {
+                return Err(StartError::StartFailed(Self {
+                    build_primary,
+                    primary: Some(Primary::Spec(spec)),
+                    backup,
+                    retry_interval,
+                }))
+            }
+            Err(StartError::Completed) => return Err(StartError::Completed),
+            // The error of the primary is dropped, since the backup is started instead.
+            Err(StartError::Fatal(_)) => (),
+        }
+
+        match backup.start_supervised().await {
+            Ok((supervisee, reference)) => {
+                let active = Active::Backup {
+                    supervisee: Box::pin(supervisee),
+                    retry: Retry::waiting(retry_interval),
+                };
+                let supervisee = FallbackSupervisee::new(active, build_primary, retry_interval);
+                Ok((supervisee, FallbackRef::Backup(reference)))
+            }
+            Err(e) => Err(e.map(|backup| Self {
+                build_primary,
+                primary: None,
+                backup,
+                retry_interval,
+            })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.backup.validate()
+    }
+}
+
+/// Start the primary spec, treating a spec rejected by [`Specification::validate`] as failed.
+async fn start_primary<P: Specification>(spec: P) -> StartResult<P> {
+    match spec.try_start_supervised() {
+        Ok(start_fut) => start_fut.await,
+        Err((_spec, e)) => Err(StartError::Fatal(e)),
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`FallbackSpec`].
+pub struct FallbackSupervisee<P: Specification, B: Specification> {
+    active: Active<P, B>,
+    build_primary: Option<Box<dyn FnMut() -> P + Send>>,
+    retry_interval: Duration,
+}
+
+enum Active<P: Specification, B: Specification> {
+    Primary {
+        supervisee: Pin<Box<P::Supervisee>>,
+        backup: Option<B>,
+    },
+    Backup {
+        supervisee: Pin<Box<B::Supervisee>>,
+        retry: Retry<P>,
+    },
+}
+
+/// The state of retrying the primary spec while the backup is running.
+enum Retry<P: Specification> {
+    Waiting(Pin<Box<Sleep>>),
+    Starting(BoxFuture<'static, StartResult<P>>),
+    Started(P::Supervisee, P::Ref),
+    /// The supervisee has been halted or aborted, so the primary is not retried anymore.
+    Stopped,
+}
+
+impl<P: Specification> Retry<P> {
+    fn waiting(retry_interval: Duration) -> Self {
+        Self::Waiting(Box::pin(sleep(retry_interval)))
+    }
+}
+
+impl<P: Specification, B: Specification> FallbackSupervisee<P, B> {
+    fn new(
+        active: Active<P, B>,
+        build_primary: Box<dyn FnMut() -> P + Send>,
+        retry_interval: Duration,
+    ) -> Self {
+        Self {
+            active,
+            build_primary: Some(build_primary),
+            retry_interval,
+        }
+    }
+
+    /// Whether the primary spec is active.
+    pub fn is_primary(&self) -> bool {
+        matches!(self.active, Active::Primary { .. })
+    }
+
+    /// Poll the retry of the primary spec, halting the backup once it has started.
+    fn poll_retry(&mut self, cx: &mut Context) {
+        let Active::Backup { supervisee, retry } = &mut self.active else {
+            return;
+        };
+        loop {
+            match retry {
+                Retry::Waiting(timer) => match timer.poll_unpin(cx) {
+                    Poll::Ready(()) => {
+                        let build_primary = self.build_primary.as_mut().unwrap();
+                        *retry = Retry::Starting(start_primary(build_primary()).boxed());
+                    }
+                    Poll::Pending => return,
+                },
+                Retry::Starting(start_fut) => match start_fut.poll_unpin(cx) {
+                    Poll::Ready(Ok((primary, reference))) => {
+                        *retry = Retry::Started(primary, reference);
+                        supervisee.as_mut().halt();
+                    }
+                    Poll::Ready(Err(_)) => *retry = Retry::waiting(self.retry_interval),
+                    Poll::Pending => return,
+                },
+                Retry::Started(..) | Retry::Stopped => return,
+            }
+        }
+    }
+
+    /// The spec to exit with, which takes the builder of the primary spec.
+    fn exit_spec(&mut self, primary: Option<Primary<P>>, backup: B) -> FallbackSpec<P, B> {
+        FallbackSpec {
+            build_primary: self.build_primary.take().unwrap(),
+            primary,
+            backup,
+            retry_interval: self.retry_interval,
+        }
+    }
+}
+
+impl<P: Specification, B: Specification> Supervisee for FallbackSupervisee<P, B> {
+    type Spec = FallbackSpec<P, B>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.get_mut();
+        this.poll_retry(cx);
+
+        match &mut this.active {
+            Active::Primary { supervisee, backup } => {
+                let exit = ready!(supervisee.as_mut().poll_supervise(cx));
+                let backup = backup.take().unwrap();
+                Poll::Ready(
+                    exit.map(|spec| {
+                        spec.map(|spec| this.exit_spec(Some(Primary::Spec(spec)), backup))
+                    }),
+                )
+            }
+            Active::Backup { supervisee, retry } => {
+                let exit = ready!(supervisee.as_mut().poll_supervise(cx));
+                let primary = match std::mem::replace(retry, Retry::Stopped) {
+                    Retry::Started(primary, reference) => {
+                        Some(Primary::Started(primary, reference))
+                    }
+                    _ => None,
+                };
+                Poll::Ready(exit.map(|spec| spec.map(|backup| this.exit_spec(primary, backup))))
+            }
+        }
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        match &self.get_ref().active {
+            Active::Primary { supervisee, .. } => supervisee.as_ref().shutdown_time(),
+            Active::Backup { supervisee, .. } => supervisee.as_ref().shutdown_time(),
+        }
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        match &mut self.get_mut().active {
+            Active::Primary { supervisee, .. } => supervisee.as_mut().halt(),
+            Active::Backup { supervisee, retry } => {
+                *retry = Retry::Stopped;
+                supervisee.as_mut().halt()
+            }
+        }
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        match &mut self.get_mut().active {
+            Active::Primary { supervisee, .. } => supervisee.as_mut().abort(),
+            Active::Backup { supervisee, retry } => {
+                *retry = Retry::Stopped;
+                supervisee.as_mut().abort()
+            }
+        }
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        match &self.get_ref().active {
+            Active::Primary { supervisee, .. } => supervisee.as_ref().list_actors(),
+            Active::Backup { supervisee, .. } => supervisee.as_ref().list_actors(),
+        }
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        match &self.get_ref().active {
+            Active::Primary { supervisee, .. } => supervisee.as_ref().labels(),
+            Active::Backup { supervisee, .. } => supervisee.as_ref().labels(),
+        }
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        match &mut self.get_mut().active {
+            Active::Primary { supervisee, .. } => supervisee.as_mut().one_for_one_mut(),
+            Active::Backup { supervisee, .. } => supervisee.as_mut().one_for_one_mut(),
+        }
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        match &mut self.get_mut().active {
+            Active::Primary { supervisee, .. } => supervisee.as_mut().take_exit_value(),
+            Active::Backup { supervisee, .. } => supervisee.as_mut().take_exit_value(),
+        }
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        match &mut self.get_mut().active {
+            Active::Primary { supervisee, .. } => supervisee.as_mut().take_backoff_reset(),
+            Active::Backup { supervisee, .. } => supervisee.as_mut().take_backoff_reset(),
+        }
+    }
+}
+
+// The supervisees are pinned in boxes, and nothing else is pinned.
+impl<P: Specification, B: Specification> Unpin for FallbackSupervisee<P, B> {}
+
+impl<P: Specification, B: Specification> Debug for FallbackSupervisee<P, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackSupervisee")
+            .field("is_primary", &self.is_primary())
+            .field("retry_interval", &self.retry_interval)
+            .finish()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use futures::future::poll_fn;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+    use tokio::process::Command;
+
+    #[tokio::test]
+    async fn swaps_back_to_recovered_primary() {
+        let reachable = Arc::new(AtomicBool::new(false));
+        let backend = reachable.clone();
+        let primary = move || {
+            let reachable = backend.load(Ordering::SeqCst);
+            ProcessSpec::new(move || match reachable {
+                true => {
+                    let mut command = Command::new("sleep");
+                    command.arg("10");
+                    command
+                }
+                false => Command::new("/does/not/exist"),
+            })
+        };
+        let cache = SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        );
+        let spec = FallbackSpec::new(primary, cache).with_retry_interval(Duration::from_millis(10));
+
+        let (supervisee, reference) = spec.start_supervised().await.unwrap();
+        assert!(reference.is_backup());
+        let mut supervisee = Box::pin(supervisee);
+        let poll = futures::poll!(poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)));
+        assert!(poll.is_pending());
+
+        reachable.store(true, Ordering::SeqCst);
+        let spec = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        let (supervisee, reference) = spec.start_supervised().await.unwrap();
+        assert!(reference.is_primary());
+        assert!(supervisee.is_primary());
+    }
+}
@@ -0,0 +1,200 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] that attaches key-value labels to it's inner spec, created with
+/// [`SpecificationExt::with_labels`].
+///
+/// The labels are exposed through [`ActorInfo::labels`] of every actor in the inner spec, so that
+/// an external exporter can, for example, group metrics by them. Labels are kept across restarts,
+/// and are inherited by all actors supervised by the inner spec. When the same key is set more
+/// than once, the innermost label wins.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = SpawnSpec::new(
+///     |halter: Halter, ()| halter,
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// )
+/// .with_labels([("role", "worker"), ("shard", "3")]);
+/// let (_child, handle) = SupervisorBuilder::new(spec).spawn();
+/// # tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+///
+/// let actors = handle.list_actors().await;
+/// assert_eq!(actors[0].label("role"), Some("worker"));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct LabeledSpec<S> {
+    spec: S,
+    labels: Vec<(String, String)>,
+}
+
+impl<S: Specification> LabeledSpec<S> {
+    /// Create a new spec, attaching the labels to the inner spec.
+    pub fn new<K, V>(spec: S, labels: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let labels = labels
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect::<Vec<_>>();
+        Self {
+            spec,
+            labels: merge_labels(&[], labels),
+        }
+    }
+
+    /// The labels of the spec.
+    pub fn labels(&self) -> &[(String, String)] {
+        &self.labels
+    }
+
+    /// Convert this back into the inner spec.
+    pub fn into_inner(self) -> S {
+        self.spec
+    }
+}
+
+#[async_trait]
+impl<S: Specification> Specification for LabeledSpec<S> {
+    type Ref = S::Ref;
+    type Supervisee = LabeledSupervisee<S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let labels = self.labels;
+        match self.spec.start_supervised().await {
+            Ok((supervisee, reference)) => {
+                Ok((LabeledSupervisee { supervisee, labels }, reference))
+            }
+            Err(e) => Err(e.map(|spec| Self { spec, labels })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.spec.validate()
+    }
+}
+
+/// The [`Supervisee`] of a [`LabeledSpec`].
+#[pin_project]
+#[derive(Debug)]
+pub struct LabeledSupervisee<S: Specification> {
+    #[pin]
+    supervisee: S::Supervisee,
+    labels: Vec<(String, String)>,
+}
+
+impl<S: Specification> Supervisee for LabeledSupervisee<S> {
+    type Spec = LabeledSpec<S>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+        this.supervisee.poll_supervise(cx).map(|res| {
+            res.map(|spec| {
+                spec.map(|spec| LabeledSpec {
+                    spec,
+                    labels: std::mem::take(this.labels),
+                })
+            })
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        let this = self.project_ref();
+        merge_labels(this.labels, this.supervisee.labels())
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+/// Merge the labels of a child into those of it's parent, overriding labels with the same key.
+pub(super) fn merge_labels(
+    parent: &[(String, String)],
+    child: Vec<(String, String)>,
+) -> Vec<(String, String)> {
+    let mut labels = parent.to_vec();
+    for (key, value) in child {
+        match labels.iter_mut().find(|(other, _)| *other == key) {
+            Some(label) => label.1 = value,
+            None => labels.push((key, value)),
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn labels_are_kept_across_restarts() {
+        let exits_once = SpawnSpec::new(
+            |halter: Halter, exit: bool| async move {
+                if !exit {
+                    halter.await;
+                }
+            },
+            |_exit| async move { Ok(Some(false)) },
+            true,
+        )
+        .with_labels([("role", "worker"), ("shard", "3")]);
+        let tree = OneForOneSpec::new()
+            .with_spec(exits_once)
+            .with_labels([("shard", "1"), ("tree", "main")]);
+        let (child, handle) = SupervisorBuilder::new(tree).spawn();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let actors = handle.list_actors().await;
+        assert_eq!(actors.len(), 1);
+        assert_eq!(actors[0].restart_count, 1);
+        assert_eq!(actors[0].label("role"), Some("worker"));
+        assert_eq!(actors[0].label("shard"), Some("3"));
+        assert_eq!(actors[0].label("tree"), Some("main"));
+
+        handle.halt();
+        child.await.unwrap().unwrap();
+    }
+}
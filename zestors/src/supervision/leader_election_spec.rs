@@ -0,0 +1,392 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::ready;
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::watch;
+
+/// A [`Specification`] that supervises a group of replicas, of which exactly one is the leader
+/// while the others are standbys.
+///
+/// The replicas are supervised by a [`OneForOneSpec`], which restarts every replica individually.
+/// When the leader exits, the next running standby is promoted to leader, while the old leader
+/// restarts as a standby. The old leader is only elected again if no standby is running.
+///
+/// The reference of this spec is a [`Leader`], which gives access to the reference of the current
+/// leader. The same handle can be retrieved before starting with [`LeaderElectionSpec::leader`],
+/// so that a replica can find out whether it is the leader with [`Leader::index`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let replica = || {
+///     SpawnSpec::new(
+///         |halter: Halter, ()| halter,
+///         |_exit| async move { Ok(Some(())) },
+///         (),
+///     )
+/// };
+/// let spec = LeaderElectionSpec::new()
+///     .with_replica(replica())
+///     .with_replica(replica());
+/// let (supervisee, leader) = spec.start_supervised().await.unwrap();
+/// assert_eq!(leader.index(), Some(0));
+/// let _address: Address<Halter> = leader.get().unwrap();
+/// # drop(supervisee);
+/// # }
+/// ```
+pub struct LeaderElectionSpec<R> {
+    spec: OneForOneSpec,
+    election: Arc<Election<R>>,
+}
+
+struct Election<R> {
+    slots: Mutex<Vec<Slot<R>>>,
+    leader: watch::Sender<Option<usize>>,
+}
+
+struct Slot<R> {
+    reference: Option<R>,
+    starts: usize,
+}
+
+impl<R: Clone + Send + Sync + 'static> LeaderElectionSpec<R> {
+    /// Create a new spec without any replicas.
+    pub fn new() -> Self {
+        Self {
+            spec: OneForOneSpec::new(),
+            election: Arc::new(Election {
+                slots: Mutex::new(Vec::new()),
+                leader: watch::channel(None).0,
+            }),
+        }
+    }
+
+    /// Use the given [`RestartLimiter`] for restarting the replicas.
+    pub fn with_limiter(mut self, limiter: RestartLimiter) -> Self {
+        self.spec = self.spec.with_limiter(limiter);
+        self
+    }
+
+    /// Add a replica.
+    pub fn with_replica<S>(mut self, spec: S) -> Self
+    where
+        S: Specification<Ref = R>,
+        S::Supervisee: 'static,
+    {
+        self.add_replica(spec);
+        self
+    }
+
+    /// Add a replica.
+    pub fn add_replica<S>(&mut self, spec: S)
+    where
+        S: Specification<Ref = R>,
+        S::Supervisee: 'static,
+    {
+        let mut slots = self.election.slots.lock().unwrap();
+        let index = slots.len();
+        slots.push(Slot {
+            reference: None,
+            starts: 0,
+        });
+        drop(slots);
+
+        let election = self.election.clone();
+        self.spec.add_spec(spec.on_start(move |reference| {
+            let slot = &mut election.slots.lock().unwrap()[index];
+            slot.reference = Some(reference);
+            slot.starts += 1;
+        }));
+    }
+
+    /// The amount of replicas.
+    pub fn len(&self) -> usize {
+        self.spec.len()
+    }
+
+    /// Whether there are no replicas.
+    pub fn is_empty(&self) -> bool {
+        self.spec.is_empty()
+    }
+
+    /// Get a handle to the leader, which is kept across restarts of this spec.
+    pub fn leader(&self) -> Leader<R> {
+        Leader {
+            election: self.election.clone(),
+            index: self.election.leader.subscribe(),
+        }
+    }
+}
+
+impl<R: Clone + Send + Sync + 'static> Default for LeaderElectionSpec<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> Debug for LeaderElectionSpec<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaderElectionSpec")
+            .field("spec", &self.spec)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<R: Clone + Send + Sync + 'static> Specification for LeaderElectionSpec<R> {
+    type Ref = Leader<R>;
+    type Supervisee = LeaderElectionSupervisee<R>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let election = self.election;
+        match self.spec.start_supervised().await {
+            Ok((supervisee, ())) => {
+                let mut supervisee = LeaderElectionSupervisee {
+                    supervisee,
+                    election,
+                    leader: None,
+                };
+                supervisee.elect();
+                let leader = Leader {
+                    election: supervisee.election.clone(),
+                    index: supervisee.election.leader.subscribe(),
+                };
+                Ok((supervisee, leader))
+            }
+            Err(e) => Err(e.map(|spec| Self { spec, election })),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Leader
+//------------------------------------------------------------------------------------------------
+
+/// A handle to the leader of a [`LeaderElectionSpec`].
+pub struct Leader<R> {
+    election: Arc<Election<R>>,
+    index: watch::Receiver<Option<usize>>,
+}
+
+impl<R: Clone> Leader<R> {
+    /// The index of the current leader, in the order the replicas were added.
+    ///
+    /// Returns `None` if the spec is not running, or none of it's replicas are.
+    pub fn index(&self) -> Option<usize> {
+        *self.index.borrow()
+    }
+
+    /// The reference of the current leader.
+    pub fn get(&self) -> Option<R> {
+        let index = self.index()?;
+        self.election.slots.lock().unwrap()[index].reference.clone()
+    }
+
+    /// Wait until a new leader has been elected.
+    pub async fn changed(&mut self) {
+        let _ = self.index.changed().await;
+    }
+}
+
+impl<R> Clone for Leader<R> {
+    fn clone(&self) -> Self {
+        Self {
+            election: self.election.clone(),
+            index: self.index.clone(),
+        }
+    }
+}
+
+impl<R> Debug for Leader<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Leader")
+            .field("index", &*self.index.borrow())
+            .finish()
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`LeaderElectionSpec`].
+pub struct LeaderElectionSupervisee<R> {
+    supervisee: OneForOneSupervisee,
+    election: Arc<Election<R>>,
+    /// The index of the leader, and the amount of times it had been started when it was elected.
+    leader: Option<(usize, usize)>,
+}
+
+impl<R> LeaderElectionSupervisee<R> {
+    /// The current [`ChildKind`] of every replica, see [`OneForOneSpec::children`].
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.supervisee.children()
+    }
+
+    /// Elect a new leader if the current one is not running, or has been restarted since it was
+    /// elected.
+    fn elect(&mut self) {
+        let kinds = self.supervisee.children().collect::<Vec<_>>();
+        let slots = self.election.slots.lock().unwrap();
+        let running = |index: usize| kinds[index] == ChildKind::Supervisee;
+
+        if let Some((index, starts)) = self.leader {
+            if running(index) && slots[index].starts == starts {
+                return;
+            }
+        }
+
+        // Search from the replica after the old leader, so that it is the last to be elected.
+        let first = self.leader.map(|(index, _)| index + 1).unwrap_or(0);
+        let leader = (0..kinds.len())
+            .map(|offset| (first + offset) % kinds.len())
+            .find(|&index| running(index));
+        self.leader = leader.map(|index| (index, slots[index].starts));
+        drop(slots);
+        self.set_leader(leader);
+    }
+
+    fn set_leader(&self, leader: Option<usize>) {
+        self.election.leader.send_if_modified(|current| {
+            let modified = *current != leader;
+            *current = leader;
+            modified
+        });
+    }
+}
+
+impl<R> Unpin for LeaderElectionSupervisee<R> {}
+
+impl<R: Clone + Send + Sync + 'static> Supervisee for LeaderElectionSupervisee<R> {
+    type Spec = LeaderElectionSpec<R>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = &mut *self;
+        let poll = Pin::new(&mut this.supervisee).poll_supervise(cx);
+        if poll.is_pending() {
+            this.elect();
+        }
+        let exit = ready!(poll);
+
+        this.leader = None;
+        this.set_leader(None);
+        let election = this.election.clone();
+        Poll::Ready(exit.map(|spec| spec.map(|spec| LeaderElectionSpec { spec, election })))
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        Pin::new(&self.supervisee).shutdown_time()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        Pin::new(&mut self.supervisee).halt()
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        Pin::new(&mut self.supervisee).abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        Pin::new(&self.supervisee).list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        Pin::new(&self.supervisee).labels()
+    }
+
+    // The replicas are not exposed, because a child added to the tree would not have a slot.
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        None
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        Pin::new(&mut self.get_mut().supervisee).take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        Pin::new(&mut self.get_mut().supervisee).take_backoff_reset()
+    }
+}
+
+impl<R> Debug for LeaderElectionSupervisee<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaderElectionSupervisee")
+            .field("supervisee", &self.supervisee)
+            .field("leader", &self.leader.map(|(index, _)| index))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::future::poll_fn;
+
+    fn replica() -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+    }
+
+    #[tokio::test]
+    async fn standby_is_promoted_when_leader_exits() {
+        let spec = LeaderElectionSpec::new()
+            .with_replica(replica())
+            .with_replica(replica())
+            .with_replica(replica());
+        let (mut supervisee, mut leader) = spec.start_supervised().await.unwrap();
+        assert_eq!(leader.index(), Some(0));
+
+        leader.get().unwrap().halt();
+        tokio::select! {
+            () = leader.changed() => (),
+            _ = poll_fn(|cx| Pin::new(&mut supervisee).poll_supervise(cx)) => {
+                panic!("The tree should not exit")
+            }
+        }
+        assert_eq!(leader.index(), Some(1));
+
+        Pin::new(&mut supervisee).halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(leader.index(), None);
+        assert_eq!(spec.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn replicas_can_not_be_added_through_supervisor() {
+        let spec = LeaderElectionSpec::new().with_replica(replica());
+        let mut leader = spec.leader();
+        let (child, handle) = SupervisorBuilder::new(spec).spawn();
+        leader.changed().await;
+        assert_eq!(
+            handle.add_child(replica()).await,
+            Err(AddChildError::NoTree)
+        );
+
+        let address = leader.get().unwrap();
+        address.halt();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(address.has_exited());
+        assert_eq!(leader.index(), Some(0));
+        assert!(!leader.get().unwrap().has_exited());
+
+        handle.halt();
+        child.await.unwrap().unwrap();
+    }
+}
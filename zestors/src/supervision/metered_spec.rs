@@ -0,0 +1,437 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+const RESTARTS: &str = "zestors_restarts_total";
+const BACKOFF: &str = "zestors_backoff_seconds";
+const START_DURATION: &str = "zestors_start_duration_seconds";
+const STATE: &str = "zestors_supervisee_state";
+
+/// A backend that records the numeric metrics of a [`MeteredSpec`], for example to export them
+/// to Prometheus or OpenTelemetry.
+///
+/// Every metric has a name and a set of labels. The sink is called from within the supervisor,
+/// so recording a metric should be cheap and never block.
+///
+/// # Example
+/// A sink that keeps the metrics in the Prometheus text-format:
+/// ```
+/// use std::{collections::BTreeMap, sync::Mutex};
+/// use zestors::supervision::MetricsSink;
+///
+/// #[derive(Default)]
+/// struct PrometheusSink {
+///     series: Mutex<BTreeMap<String, f64>>,
+/// }
+///
+/// impl PrometheusSink {
+///     fn series(name: &str, labels: &[(String, String)]) -> String {
+///         let labels = labels
+///             .iter()
+///             .map(|(key, value)| format!("{key}=\"{value}\""))
+///             .collect::<Vec<_>>();
+///         format!("{name}{{{}}}", labels.join(","))
+///     }
+///
+///     fn render(&self) -> String {
+///         let series = self.series.lock().unwrap();
+///         series.iter().map(|(series, value)| format!("{series} {value}\n")).collect()
+///     }
+/// }
+///
+/// impl MetricsSink for PrometheusSink {
+///     fn counter(&self, name: &str, labels: &[(String, String)], increment: u64) {
+///         let mut series = self.series.lock().unwrap();
+///         *series.entry(Self::series(name, labels)).or_default() += increment as f64;
+///     }
+///
+///     fn gauge(&self, name: &str, labels: &[(String, String)], value: f64) {
+///         let mut series = self.series.lock().unwrap();
+///         series.insert(Self::series(name, labels), value);
+///     }
+///
+///     fn histogram(&self, name: &str, labels: &[(String, String)], value: f64) {
+///         let mut series = self.series.lock().unwrap();
+///         *series.entry(Self::series(&format!("{name}_sum"), labels)).or_default() += value;
+///         *series.entry(Self::series(&format!("{name}_count"), labels)).or_default() += 1.0;
+///     }
+/// }
+///
+/// let sink = PrometheusSink::default();
+/// sink.counter("zestors_restarts_total", &[("spec".into(), "worker".into())], 1);
+/// assert_eq!(sink.render(), "zestors_restarts_total{spec=\"worker\"} 1\n");
+/// ```
+pub trait MetricsSink: Send + Sync + 'static {
+    /// Increment the counter with the given name and labels.
+    fn counter(&self, name: &str, labels: &[(String, String)], increment: u64);
+
+    /// Set the gauge with the given name and labels.
+    fn gauge(&self, name: &str, labels: &[(String, String)], value: f64);
+
+    /// Record a sample in the histogram with the given name and labels.
+    fn histogram(&self, name: &str, labels: &[(String, String)], value: f64);
+}
+
+/// A [`MetricsSink`] that drops all metrics. This is the default sink of a [`MeteredSpec`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NoopMetrics;
+
+impl MetricsSink for NoopMetrics {
+    fn counter(&self, _name: &str, _labels: &[(String, String)], _increment: u64) {}
+    fn gauge(&self, _name: &str, _labels: &[(String, String)], _value: f64) {}
+    fn histogram(&self, _name: &str, _labels: &[(String, String)], _value: f64) {}
+}
+
+impl<T: MetricsSink> MetricsSink for Arc<T> {
+    fn counter(&self, name: &str, labels: &[(String, String)], increment: u64) {
+        T::counter(self, name, labels, increment)
+    }
+
+    fn gauge(&self, name: &str, labels: &[(String, String)], value: f64) {
+        T::gauge(self, name, labels, value)
+    }
+
+    fn histogram(&self, name: &str, labels: &[(String, String)], value: f64) {
+        T::histogram(self, name, labels, value)
+    }
+}
+
+/// The state of a [`MeteredSpec`], which is recorded as the `zestors_supervisee_state` gauge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeteredState {
+    /// The spec is waiting to be restarted.
+    Stopped,
+    /// The spec is being started.
+    Starting,
+    /// The supervisee is running.
+    Running,
+    /// The supervisee has completed, and will not be restarted.
+    Completed,
+    /// The supervisee has exited with a [`FatalError`].
+    Failed,
+}
+
+impl MeteredState {
+    /// The value of the `state` label of this state.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MeteredState::Stopped => "stopped",
+            MeteredState::Starting => "starting",
+            MeteredState::Running => "running",
+            MeteredState::Completed => "completed",
+            MeteredState::Failed => "failed",
+        }
+    }
+}
+
+/// A [`Specification`] that records metrics about the supervision of it's inner spec into a
+/// [`MetricsSink`], created with [`SpecificationExt::with_metrics`].
+///
+/// Where the events of a supervisor describe what happened, these metrics are numeric
+/// time-series that can be used for dashboards and alerts. The inner spec is not aware that it
+/// is metered. All metrics are labelled with `spec` set to the name of the spec:
+/// - `zestors_restarts_total` : A counter of the amount of times the spec has been restarted.
+/// - `zestors_backoff_seconds` : A histogram of the seconds between an exit or failed start, and
+///   the next start. This is the time spent in the backoff of the supervisor.
+/// - `zestors_start_duration_seconds` : A histogram of the seconds it took to start successfully.
+/// - `zestors_supervisee_state` : A gauge with an extra `state` label for every [`MeteredState`],
+///   which is `1` for the current state and `0` for the previous one.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = SpawnSpec::new(
+///     |halter: Halter, ()| halter,
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// )
+/// .with_metrics("worker", NoopMetrics);
+/// let (_child, handle) = SupervisorBuilder::new(spec).spawn();
+/// # drop(handle);
+/// # }
+/// ```
+pub struct MeteredSpec<S> {
+    spec: S,
+    meter: Meter,
+}
+
+/// The sink and restart-bookkeeping shared by a [`MeteredSpec`] and it's supervisee.
+#[derive(Clone)]
+struct Meter {
+    sink: Arc<dyn MetricsSink>,
+    labels: Vec<(String, String)>,
+    starts: u64,
+    stopped_at: Option<Instant>,
+}
+
+impl Meter {
+    /// Record the transition from one state to another.
+    fn transition(&self, from: Option<MeteredState>, to: MeteredState) {
+        let state_labels = |state: MeteredState| {
+            let mut labels = self.labels.clone();
+            labels.push(("state".to_string(), state.as_str().to_string()));
+            labels
+        };
+        if let Some(from) = from {
+            self.sink.gauge(STATE, &state_labels(from), 0.0);
+        }
+        self.sink.gauge(STATE, &state_labels(to), 1.0);
+    }
+
+    /// Record that the spec has stopped, and will be restarted.
+    fn stopped(mut self, from: MeteredState) -> Self {
+        self.transition(Some(from), MeteredState::Stopped);
+        self.stopped_at = Some(Instant::now());
+        self
+    }
+}
+
+impl<S: Specification> MeteredSpec<S> {
+    /// Create a new spec with the given name, which records it's metrics into a [`NoopMetrics`].
+    pub fn new(spec: S, name: impl Into<String>) -> Self {
+        Self {
+            spec,
+            meter: Meter {
+                sink: Arc::new(NoopMetrics),
+                labels: vec![("spec".to_string(), name.into())],
+                starts: 0,
+                stopped_at: None,
+            },
+        }
+    }
+
+    /// Record the metrics into the sink.
+    pub fn with_sink(mut self, sink: impl MetricsSink) -> Self {
+        self.meter.sink = Arc::new(sink);
+        self
+    }
+
+    /// The labels of all metrics.
+    pub fn labels(&self) -> &[(String, String)] {
+        &self.meter.labels
+    }
+
+    /// The amount of times the spec has been restarted.
+    pub fn restarts(&self) -> u64 {
+        self.meter.starts.saturating_sub(1)
+    }
+
+    /// Convert this back into the inner spec.
+    pub fn into_inner(self) -> S {
+        self.spec
+    }
+}
+
+impl<S: Debug> Debug for MeteredSpec<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MeteredSpec")
+            .field("spec", &self.spec)
+            .field("labels", &self.meter.labels)
+            .field("starts", &self.meter.starts)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<S: Specification> Specification for MeteredSpec<S> {
+    type Ref = S::Ref;
+    type Supervisee = MeteredSupervisee<S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let mut meter = self.meter;
+        if meter.starts > 0 {
+            meter.sink.counter(RESTARTS, &meter.labels, 1);
+        }
+        if let Some(stopped_at) = meter.stopped_at.take() {
+            let backoff = stopped_at.elapsed().as_secs_f64();
+            meter.sink.histogram(BACKOFF, &meter.labels, backoff);
+        }
+        meter.starts += 1;
+        let from = (meter.starts > 1).then_some(MeteredState::Stopped);
+        meter.transition(from, MeteredState::Starting);
+
+        let started = Instant::now();
+        match self.spec.start_supervised().await {
+            Ok((supervisee, reference)) => {
+                let duration = started.elapsed().as_secs_f64();
+                meter
+                    .sink
+                    .histogram(START_DURATION, &meter.labels, duration);
+                meter.transition(Some(MeteredState::Starting), MeteredState::Running);
+                Ok((MeteredSupervisee { supervisee, meter }, reference))
+            }
+            Err(StartError::StartFailed(spec)) => Err(StartError::StartFailed(Self {
+                spec,
+                meter: meter.stopped(MeteredState::Starting),
+            })),
+            Err(StartError::Completed) => {
+                meter.transition(Some(MeteredState::Starting), MeteredState::Completed);
+                Err(StartError::Completed)
+            }
+            Err(StartError::Fatal(e)) => {
+                meter.transition(Some(MeteredState::Starting), MeteredState::Failed);
+                Err(StartError::Fatal(e))
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.spec.validate()
+    }
+}
+
+/// The [`Supervisee`] of a [`MeteredSpec`].
+#[pin_project]
+pub struct MeteredSupervisee<S: Specification> {
+    #[pin]
+    supervisee: S::Supervisee,
+    meter: Meter,
+}
+
+impl<S: Specification> Supervisee for MeteredSupervisee<S> {
+    type Spec = MeteredSpec<S>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+        this.supervisee.poll_supervise(cx).map(|res| match res {
+            Ok(Some(spec)) => Ok(Some(MeteredSpec {
+                spec,
+                meter: this.meter.clone().stopped(MeteredState::Running),
+            })),
+            Ok(None) => {
+                this.meter
+                    .transition(Some(MeteredState::Running), MeteredState::Completed);
+                Ok(None)
+            }
+            Err(e) => {
+                this.meter
+                    .transition(Some(MeteredState::Running), MeteredState::Failed);
+                Err(e)
+            }
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<(String, Option<String>, f64)>>);
+
+    impl RecordingSink {
+        fn record(&self, name: &str, labels: &[(String, String)], value: f64) {
+            assert_eq!(labels[0], ("spec".to_string(), "worker".to_string()));
+            let state = labels.get(1).map(|(_, state)| state.clone());
+            self.0
+                .lock()
+                .unwrap()
+                .push((name.to_string(), state, value));
+        }
+    }
+
+    impl MetricsSink for RecordingSink {
+        fn counter(&self, name: &str, labels: &[(String, String)], increment: u64) {
+            self.record(name, labels, increment as f64)
+        }
+
+        fn gauge(&self, name: &str, labels: &[(String, String)], value: f64) {
+            self.record(name, labels, value)
+        }
+
+        fn histogram(&self, name: &str, labels: &[(String, String)], _value: f64) {
+            self.record(name, labels, 1.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn supervision_transitions_are_metered() {
+        let sink = Arc::new(RecordingSink::default());
+        let mut spec = SpawnSpec::new(
+            |_halter: Halter, n: u32| async move { n },
+            |exit: Result<u32, ExitError>| async move { Ok((exit.unwrap() == 0).then_some(1)) },
+            0,
+        )
+        .with_metrics("worker", sink.clone());
+
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.restarts(), 0);
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+
+        let state = |state: &str, value| {
+            let state = Some(state.to_string());
+            (STATE.to_string(), state, value)
+        };
+        let metric = |name: &str| (name.to_string(), None, 1.0);
+        assert_eq!(
+            *sink.0.lock().unwrap(),
+            vec![
+                state("starting", 1.0),
+                metric(START_DURATION),
+                state("starting", 0.0),
+                state("running", 1.0),
+                state("running", 0.0),
+                state("stopped", 1.0),
+                metric(RESTARTS),
+                metric(BACKOFF),
+                state("stopped", 0.0),
+                state("starting", 1.0),
+                metric(START_DURATION),
+                state("starting", 0.0),
+                state("running", 1.0),
+                state("running", 0.0),
+                state("completed", 1.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,357 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::FutureExt;
+use pin_project::pin_project;
+use std::{
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{sleep_until, Instant, Sleep};
+
+/// A [`Specification`] that only considers a restart of it's inner spec successful once the
+/// supervisee has been running for at least the `min_healthy_uptime`, created with
+/// [`SpecificationExt::with_min_healthy_uptime`].
+///
+/// A supervisee that exits (or fails to start) before the `min_healthy_uptime` has passed counts
+/// as an unhealthy restart. Every consecutive unhealthy restart doubles the backoff before the
+/// next start, starting at the initial backoff and capped at the maximum backoff. A child that
+/// crashes right after every restart therefore escalates through the backoff, instead of being
+/// restarted in a hot loop. Once a supervisee has been running for the `min_healthy_uptime`, it
+/// has recovered and the backoff is reset.
+///
+/// The backoff is waited for while starting the spec, and every restart still counts against the
+/// [`RestartLimiter`] of the supervisor. The recovery of a spec that was backing off is reported
+/// by it's tree or supervisor as a [`SupervisionEventKind::BackoffReset`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = SpawnSpec::new(
+///     |_halter: Halter, ()| async move {},
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// )
+/// .with_min_healthy_uptime(Duration::from_secs(1))
+/// .with_backoff(Duration::from_millis(1), Duration::from_millis(10));
+///
+/// let (supervisee, _address) = spec.start_supervised().await.unwrap();
+/// let spec = supervisee.supervise().await.unwrap().unwrap();
+/// assert_eq!(spec.unhealthy_restarts(), 1);
+/// assert_eq!(spec.backoff(), Duration::from_millis(1));
+/// # }
+/// ```
+pub struct MinUptimeSpec<S> {
+    inner_spec: S,
+    uptime: Uptime,
+}
+
+/// The configuration and restart-bookkeeping shared by a [`MinUptimeSpec`] and it's supervisee.
+#[derive(Clone, Copy, Debug)]
+struct Uptime {
+    min_healthy_uptime: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    unhealthy_restarts: u32,
+    incident_started: Option<Instant>,
+}
+
+impl Uptime {
+    /// The bookkeeping for the restart of a supervisee that has been running since `started`.
+    fn restarted(mut self, started: Instant) -> Self {
+        if started.elapsed() >= self.min_healthy_uptime {
+            self.unhealthy_restarts = 0;
+            self.incident_started = None;
+        } else {
+            self.unhealthy_restarts = self.unhealthy_restarts.saturating_add(1);
+            self.incident_started.get_or_insert_with(Instant::now);
+        }
+        self
+    }
+}
+
+impl<S: Specification> MinUptimeSpec<S> {
+    /// Create a new spec with an initial backoff of 100 milliseconds and a maximum backoff of 10
+    /// seconds.
+    pub fn new(spec: S, min_healthy_uptime: Duration) -> Self {
+        Self {
+            inner_spec: spec,
+            uptime: Uptime {
+                min_healthy_uptime,
+                initial_backoff: Duration::from_millis(100),
+                max_backoff: Duration::from_secs(10),
+                unhealthy_restarts: 0,
+                incident_started: None,
+            },
+        }
+    }
+
+    /// Set the backoff after the first unhealthy restart, and the maximum it is doubled up to.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.uptime.initial_backoff = initial;
+        self.uptime.max_backoff = max;
+        self
+    }
+
+    /// The time a supervisee must be running before it's restart is considered successful.
+    pub fn min_healthy_uptime(&self) -> Duration {
+        self.uptime.min_healthy_uptime
+    }
+
+    /// The amount of consecutive restarts that happened before the `min_healthy_uptime`.
+    pub fn unhealthy_restarts(&self) -> u32 {
+        self.uptime.unhealthy_restarts
+    }
+
+    /// The backoff that is waited for before the spec is started.
+    pub fn backoff(&self) -> Duration {
+        match self.uptime.unhealthy_restarts {
+            0 => Duration::ZERO,
+            n => {
+                let factor = 2u32.saturating_pow(n - 1);
+                self.uptime
+                    .initial_backoff
+                    .saturating_mul(factor)
+                    .min(self.uptime.max_backoff)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Specification> Specification for MinUptimeSpec<S> {
+    type Ref = S::Ref;
+    type Supervisee = MinUptimeSupervisee<S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let backoff = self.backoff();
+        if !backoff.is_zero() {
+            tokio::time::sleep(backoff).await;
+        }
+
+        let uptime = self.uptime;
+        let started = Instant::now();
+        match self.inner_spec.start_supervised().await {
+            Ok((supervisee, reference)) => Ok((
+                MinUptimeSupervisee {
+                    supervisee,
+                    uptime,
+                    started,
+                    recovery_timer: (uptime.unhealthy_restarts > 0)
+                        .then(|| Box::pin(sleep_until(started + uptime.min_healthy_uptime))),
+                },
+                reference,
+            )),
+            Err(e) => Err(e.map(|inner_spec| Self {
+                inner_spec,
+                uptime: uptime.restarted(started),
+            })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.inner_spec.validate()
+    }
+}
+
+/// The [`Supervisee`] of a [`MinUptimeSpec`].
+#[pin_project]
+pub struct MinUptimeSupervisee<S: Specification> {
+    #[pin]
+    supervisee: S::Supervisee,
+    uptime: Uptime,
+    started: Instant,
+    recovery_timer: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S: Specification> MinUptimeSupervisee<S> {
+    /// Whether the supervisee has been running for at least the `min_healthy_uptime`.
+    pub fn is_healthy(&self) -> bool {
+        self.started.elapsed() >= self.uptime.min_healthy_uptime
+    }
+}
+
+impl<S: Specification> Supervisee for MinUptimeSupervisee<S> {
+    type Spec = MinUptimeSpec<S>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+
+        // The timer only wakes the task once the supervisee has recovered, so that the
+        // backoff-reset is taken by the supervisor.
+        if let Some(recovery_timer) = this.recovery_timer {
+            if recovery_timer.poll_unpin(cx).is_ready() {
+                *this.recovery_timer = None;
+            }
+        }
+
+        this.supervisee.poll_supervise(cx).map(|res| {
+            res.map(|spec| {
+                spec.map(|inner_spec| MinUptimeSpec {
+                    inner_spec,
+                    uptime: this.uptime.restarted(*this.started),
+                })
+            })
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        if !self.is_healthy() {
+            return None;
+        }
+        let this = self.project();
+        let incident_started = this.uptime.incident_started.take()?;
+        let unhealthy_restarts = mem::take(&mut this.uptime.unhealthy_restarts);
+        Some(BackoffReset {
+            child: None,
+            labels: this.supervisee.as_ref().labels(),
+            unhealthy_restarts,
+            incident_duration: incident_started.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn backoff_escalates_until_supervisee_is_healthy() {
+        let mut spec = SpawnSpec::new(
+            |_halter: Halter, uptime: Duration| async move {
+                tokio::time::sleep(uptime).await;
+                uptime
+            },
+            |exit: Result<Duration, ExitError>| async move { Ok(Some(exit.unwrap())) },
+            Duration::ZERO,
+        )
+        .with_min_healthy_uptime(Duration::from_millis(20))
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(4));
+
+        for (unhealthy_restarts, backoff) in [(1, 1), (2, 2), (3, 4), (4, 4)] {
+            let (supervisee, _address) = spec.start_supervised().await.unwrap();
+            assert!(!supervisee.is_healthy());
+            spec = supervisee.supervise().await.unwrap().unwrap();
+            assert_eq!(spec.unhealthy_restarts(), unhealthy_restarts);
+            assert_eq!(spec.backoff(), Duration::from_millis(backoff));
+        }
+
+        *spec.inner_spec.data_mut() = Duration::from_millis(30);
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.unhealthy_restarts(), 0);
+        assert_eq!(spec.backoff(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn recovery_is_reported_by_tree() {
+        let spec = SpawnSpec::new(
+            |halter: Halter, restarts: u32| async move {
+                if restarts == 2 {
+                    halter.await
+                }
+                restarts
+            },
+            |exit: Result<u32, ExitError>| async move { Ok(Some(exit.unwrap() + 1)) },
+            0,
+        )
+        .with_labels([("app", "worker")])
+        .with_min_healthy_uptime(Duration::from_millis(20))
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+        let tree = OneForOneSpec::new().with_spec(spec);
+        let mut events = tree.events();
+
+        let (supervisee, ()) = tree.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        let poll = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx));
+        assert!(tokio::time::timeout(Duration::from_millis(50), poll)
+            .await
+            .is_err());
+
+        let SupervisionEventKind::BackoffReset(reset) = events.try_recv().unwrap().kind else {
+            panic!("Expected a backoff-reset")
+        };
+        assert_eq!(reset.child, Some(0));
+        assert_eq!(
+            reset.labels,
+            vec![("app".to_string(), "worker".to_string())]
+        );
+        assert_eq!(reset.unhealthy_restarts, 2);
+        assert!(reset.incident_duration >= Duration::from_millis(20));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn backoff_reset_is_taken_once() {
+        let mut spec = SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+        .with_min_healthy_uptime(Duration::from_millis(20))
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        assert!(supervisee.as_mut().take_backoff_reset().is_none());
+        address.halt();
+        spec = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(spec.unhealthy_restarts(), 1);
+
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        assert!(supervisee.as_mut().take_backoff_reset().is_none());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let reset = supervisee.as_mut().take_backoff_reset().unwrap();
+        assert_eq!(reset.child, None);
+        assert_eq!(reset.unhealthy_restarts, 1);
+        assert!(supervisee.as_mut().take_backoff_reset().is_none());
+
+        address.halt();
+        let spec = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(spec.unhealthy_restarts(), 0);
+    }
+}
@@ -1,14 +1,162 @@
 /*!
-# TODO 
-Supervision is the current focus area. I have tried a lot of solutions, but nothing I am truly happy
-with yet.
+# Overview
+Actors can be supervised by describing them with a [`Specification`]. A specification can be
+started, which returns a [`Supervisee`] that can be supervised until it exits. When the supervisee
+exits, it can hand back it's specification so that it can be restarted.
 
-(It is already possible to build supervision-trees using [children](`Child`) with something like [`tokio::select`],
-and manually restarting the process upon exit.)
+# Supervisee lifecycle
+
+#### __`(1)` Specification__:
+Every supervisee starts out as a [`Specification`]. The supervisee is not running, and
+can be started using [`Specification::start_supervised`].
+
+#### __`(2)` Starting__:
+The supervisee is currently starting. If starting completes successfully then we get a
+[`Supervisee`] `(3)` that can be supervised. If however the supervisee fails to start, it returns
+one of three errors:
+- [`StartError::Fatal`] : An unrecoverable error occured during starting.
+  This should backpropagate to supervisors until it can be handled or until the application
+  as a whole shuts down `(5)`.
+- [`StartError::StartFailed`] : Starting has failed, and the original specification
+  is returned `(1)`. This specification may be used to restart the supervisee.
+- [`StartError::Completed`] : Starting has failed because the supervisee does not have
+  any work left to be done and is therefore completed `(4)`.
+
+#### __`(3)` Supervisee__:
+The supervisee is running and can be supervised with [`SuperviseeExt::supervise`].
+The supervisee can exit successfully in two ways:
+- `Some(Specification)` : The supervisee has exited, but is not yet completed. It would like to be
+  restarted with it's [`Specification`] `(1)`.
+- `None` : The supervisee has exited and has completed all it's tasks `(4)`.
+
+The supervisee can also exit with a [`FatalError`]. This indicates an unrecoverable error `(5)`.
+
+The supervisee can be halted and aborted using [`Supervisee::halt`] and [`Supervisee::abort`]. The
+supervisee also defines a [`Supervisee::shutdown_time`] which gives an upper limit how long the
+supervisee needs to shut down after being halted; if the supervisee exceeds this limit, it may
+be aborted `(5)`.
+
+#### __`(4)` Completed__:
+The supervisee has completed it's task successfully and can not be restarted.
+
+#### __`(5)` Unrecoverable__:
+The supervisee is not completed but is not able to restart either.
+
+# Specifications
+- [`SpawnSpec`] - Spawns a single actor, and decides whether to restart it upon exit.
+- [`OneForOneSpec`] - Supervises a group of children, restarting each of them individually.
+- [`OneForAllSpec`] - Supervises a group of children, restarting all of them when one exits.
+- [`QuorumStartSpec`] - Starts a [`OneForOneSpec`] once a quorum of it's children has started.
+- [`LeaderElectionSpec`] - Supervises a group of replicas, promoting a standby when the leader exits.
+- [`SequenceSpec`] - Starts a chain of children in order, and shuts them down in reverse.
+- [`OnStartSpec`] - Maps the reference of a spec whenever it is started.
+- [`OnRestartSpec`] - Transforms a spec before every restart, for example to try another port.
+- [`OnceSpec`] - Starts a spec only once, escalating instead of restarting it.
+- [`OptionalSpec`] - A child that may be absent, which completes immediately without running.
+- [`MinUptimeSpec`] - Backs off restarts of a spec until it stays up for a minimum healthy uptime.
+- [`MeteredSpec`] - Records metrics about the restarts and state of a spec into a [`MetricsSink`].
+- [`LabeledSpec`] - Attaches key-value labels to a spec, which are exposed through [`ActorInfo`].
+- [`BoxSpec`] - A type-erased spec, created with [`SpecificationExt::into_dyn`].
+- [`StatefulSpec`] - Restarts a specification from the last [`Checkpoint`] of it's state.
+- [`SharedResourceSpec`] - Creates a shared resource, and starts it's child with a clone of it.
+- [`StreamActorSpec`] - Spawns an actor that drives a [`Stream`](futures::Stream) with a handler.
+- [`TickerSpec`] - Spawns an actor that calls an async closure periodically.
+- [`WatchedSpec`] - Supervises a child together with a watchdog that halts it when unresponsive.
+- [`ProcessSpec`] - Spawns an OS-process, and restarts it when it exits unsuccessfully.
+- [`ResourceGuardSpec`] - Halts a child once a sampled resource-metric exceeds it's budget.
+- [`FacadeSpec`] - Exposes a spec through a stable address that is re-wired on every restart.
+- [`DrainPairSpec`] - Supervises a producer together with a drain of it's side-stream of events.
+- [`ProducerSpec`] - Exposes the outputs of a supervised producer as a stream that continues across restarts.
+- [`FallbackSpec`] - Falls back to a degraded backup while the primary spec can't be started.
+- [`AutoscalerSpec`] - Spawns an [`Autoscaler`] that resizes a pool, and restarts it if it panics.
+
+# Supervisor
+A specification can be supervised by spawning a supervisor with the [`SupervisorBuilder`]. The
+supervisor restarts the specification until it completes, fails or exceeds it's [`RestartLimiter`].
+The [`SupervisorHandle`] can be used to halt the supervisor and to observe it's [events](SupervisionEvent),
+either live with [`SupervisorHandle::events`] or afterwards with [`SupervisorHandle::recent_events`].
+The events of multiple trees can be merged into a single stream with an [`EventHub`].
+A snapshot of all running actors in the tree can be taken with [`SupervisorHandle::list_actors`].
+If the supervisor runs a [`OneForOneSpec`], children can be added to it with
+[`SupervisorHandle::add_child`], up to it's [maximum](OneForOneSpec::with_max_children).
+
+A leaf actor that does not need an external supervisor can instead recover from errors within it's
+own task with [`self_supervise`], which resets it's state whenever the handler fails.
 
 | __<--__ [`runtime`](crate::runtime) | [`distribution`](crate::distribution) __-->__ |
 |---|---|
 */
 
+mod actor_info;
+mod autoscaler_spec;
+mod box_spec;
+mod drain_pair_spec;
+mod event_hub;
+mod events;
+mod exit_collector;
+mod facade_spec;
+mod fallback_spec;
+mod labeled_spec;
+mod leader_election_spec;
+mod metered_spec;
+mod min_uptime_spec;
+mod on_restart_spec;
+mod on_start_spec;
+mod once_spec;
+mod one_for_all;
+mod one_for_one;
+mod optional_spec;
+mod process_spec;
+mod producer_spec;
+mod quorum_start_spec;
+mod resource_guard_spec;
+mod restart_limiter;
+mod self_supervise;
+mod sequence_spec;
+mod shared_resource_spec;
+mod spawn_spec;
+mod stateful_spec;
+mod stream_actor_spec;
+mod supervisor;
+mod ticker_spec;
+mod traits;
+mod traits_ext;
+mod watched_spec;
+pub use actor_info::*;
+pub use autoscaler_spec::*;
+pub use box_spec::*;
+pub use drain_pair_spec::*;
+pub use event_hub::*;
+pub use events::*;
+pub use exit_collector::*;
+pub use facade_spec::*;
+pub use fallback_spec::*;
+pub use labeled_spec::*;
+pub use leader_election_spec::*;
+pub use metered_spec::*;
+pub use min_uptime_spec::*;
+pub use on_restart_spec::*;
+pub use on_start_spec::*;
+pub use once_spec::*;
+pub use one_for_all::*;
+pub use one_for_one::*;
+pub use optional_spec::*;
+pub use process_spec::*;
+pub use producer_spec::*;
+pub use quorum_start_spec::*;
+pub use resource_guard_spec::*;
+pub use restart_limiter::*;
+pub use self_supervise::*;
+pub use sequence_spec::*;
+pub use shared_resource_spec::*;
+pub use spawn_spec::*;
+pub use stateful_spec::*;
+pub use stream_actor_spec::*;
+pub use supervisor::*;
+pub use ticker_spec::*;
+pub use traits::*;
+pub use traits_ext::*;
+pub use watched_spec::*;
+
 #[allow(unused)]
-use crate::all::*;
\ No newline at end of file
+use crate::all::*;
@@ -0,0 +1,191 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] that transforms it's inner spec before every restart, created with
+/// [`SpecificationExt::on_restart`].
+///
+/// Whenever the inner spec exits with it's spec, or fails to start, `on_restart` is called with
+/// the spec and the number of the restart-attempt, starting at `1`. The spec that is returned is
+/// used for the next start, which turns blind retries into adaptive ones: A child that fails to
+/// bind to a port could for example try the next port.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let spec = SpawnSpec::new(
+///     |_halter: Halter, port: u16| async move { port },
+///     |_exit| async move { Ok(Some(8080)) },
+///     8080,
+/// )
+/// .on_restart(|mut spec, attempt| {
+///     *spec.data_mut() = 8080 + attempt as u16;
+///     spec
+/// });
+/// # drop(spec);
+/// # }
+/// ```
+pub struct OnRestartSpec<S, F> {
+    inner_spec: S,
+    on_restart: F,
+    attempt: usize,
+}
+
+impl<S, F> OnRestartSpec<S, F>
+where
+    S: Specification,
+    F: FnMut(S, usize) -> S + Send + 'static,
+{
+    /// Create a new spec, calling `on_restart` with the spec before every restart.
+    pub fn new(spec: S, on_restart: F) -> Self {
+        Self {
+            inner_spec: spec,
+            on_restart,
+            attempt: 0,
+        }
+    }
+
+    /// The amount of times the spec has been restarted.
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    fn restart(mut on_restart: F, inner_spec: S, attempt: usize) -> Self {
+        let attempt = attempt + 1;
+        Self {
+            inner_spec: on_restart(inner_spec, attempt),
+            on_restart,
+            attempt,
+        }
+    }
+}
+
+#[async_trait]
+impl<S, F> Specification for OnRestartSpec<S, F>
+where
+    S: Specification,
+    F: FnMut(S, usize) -> S + Send + 'static,
+{
+    type Ref = S::Ref;
+    type Supervisee = OnRestartSupervisee<S, F>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let (on_restart, attempt) = (self.on_restart, self.attempt);
+        match self.inner_spec.start_supervised().await {
+            Ok((supervisee, reference)) => Ok((
+                OnRestartSupervisee {
+                    supervisee,
+                    on_restart: Some(on_restart),
+                    attempt,
+                },
+                reference,
+            )),
+            Err(e) => Err(e.map(|inner_spec| Self::restart(on_restart, inner_spec, attempt))),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.inner_spec.validate()
+    }
+}
+
+/// The [`Supervisee`] of an [`OnRestartSpec`].
+#[pin_project]
+pub struct OnRestartSupervisee<S: Specification, F> {
+    #[pin]
+    supervisee: S::Supervisee,
+    on_restart: Option<F>,
+    attempt: usize,
+}
+
+impl<S, F> Supervisee for OnRestartSupervisee<S, F>
+where
+    S: Specification,
+    F: FnMut(S, usize) -> S + Send + 'static,
+{
+    type Spec = OnRestartSpec<S, F>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+        this.supervisee.poll_supervise(cx).map(|res| {
+            res.map(|spec| {
+                spec.map(|inner_spec| {
+                    let on_restart = this.on_restart.take().expect("Polled after completion");
+                    OnRestartSpec::restart(on_restart, inner_spec, *this.attempt)
+                })
+            })
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn spec_is_transformed_before_every_restart() {
+        let spec = SpawnSpec::new(
+            |_halter: Halter, port: u16| async move { port },
+            |exit: Result<u16, ExitError>| async move { Ok(Some(exit.unwrap())) },
+            8080,
+        )
+        .on_restart(|mut spec, attempt| {
+            *spec.data_mut() = 8080 + attempt as u16;
+            spec
+        });
+
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.attempt(), 1);
+
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.attempt(), 2);
+        assert_eq!(spec.inner_spec.data(), &8082);
+    }
+}
@@ -0,0 +1,136 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] that maps the reference of it's inner spec every time it is started,
+/// created with [`SpecificationExt::on_start`].
+pub struct OnStartSpec<S, F, T> {
+    inner_spec: S,
+    on_start: F,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<S, F, T> OnStartSpec<S, F, T>
+where
+    S: Specification,
+    F: FnMut(S::Ref) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    /// Create a new spec, calling `on_start` with the reference whenever it is started.
+    pub fn new(spec: S, on_start: F) -> Self {
+        Self {
+            inner_spec: spec,
+            on_start,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<S, F, T> Specification for OnStartSpec<S, F, T>
+where
+    S: Specification,
+    F: FnMut(S::Ref) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    type Ref = T;
+    type Supervisee = OnStartSupervisee<S, F, T>;
+
+    async fn start_supervised(mut self) -> StartResult<Self> {
+        match self.inner_spec.start_supervised().await {
+            Ok((supervisee, reference)) => {
+                let reference = (self.on_start)(reference);
+                Ok((
+                    OnStartSupervisee {
+                        supervisee,
+                        on_start: Some(self.on_start),
+                        phantom: PhantomData,
+                    },
+                    reference,
+                ))
+            }
+            Err(e) => Err(e.map(|inner_spec| Self {
+                inner_spec,
+                on_start: self.on_start,
+                phantom: PhantomData,
+            })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.inner_spec.validate()
+    }
+}
+
+/// The [`Supervisee`] of an [`OnStartSpec`].
+#[pin_project]
+pub struct OnStartSupervisee<S: Specification, F, T> {
+    #[pin]
+    supervisee: S::Supervisee,
+    on_start: Option<F>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<S, F, T> Supervisee for OnStartSupervisee<S, F, T>
+where
+    S: Specification,
+    F: FnMut(S::Ref) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    type Spec = OnStartSpec<S, F, T>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+        this.supervisee.poll_supervise(cx).map(|res| {
+            res.map(|spec| {
+                spec.map(|inner_spec| OnStartSpec {
+                    inner_spec,
+                    on_start: this.on_start.take().unwrap(),
+                    phantom: PhantomData,
+                })
+            })
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
@@ -0,0 +1,190 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// A [`Specification`] that is never restarted, created with [`SpecificationExt::once`].
+///
+/// When the inner supervisee completes, this completes as well. Any other exit is escalated
+/// immediately as a [`FatalError`], without consulting a [`RestartLimiter`]:
+/// - If the inner spec fails to start, this exits with [`OnceSpecError::StartFailed`].
+/// - If the inner supervisee would like to be restarted, this exits with
+///   [`OnceSpecError::RestartRequested`].
+/// - If the inner supervisee exits with a [`FatalError`], this exits with the same error.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = SpawnSpec::new(
+///     |_halter: Halter, ()| async move { /* one-time initialization */ },
+///     |exit| async move { exit.map(|()| None).map_err(|e| Box::new(e) as FatalError) },
+///     (),
+/// )
+/// .once();
+/// let (supervisee, _address) = spec.start_supervised().await.unwrap();
+/// assert!(supervisee.supervise().await.unwrap().is_none());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OnceSpec<S> {
+    spec: S,
+}
+
+impl<S: Specification> OnceSpec<S> {
+    /// Create a new spec, that starts the inner spec only once.
+    pub fn new(spec: S) -> Self {
+        Self { spec }
+    }
+
+    /// Convert this back into the inner spec.
+    pub fn into_inner(self) -> S {
+        self.spec
+    }
+}
+
+#[async_trait]
+impl<S: Specification> Specification for OnceSpec<S> {
+    type Ref = S::Ref;
+    type Supervisee = OnceSupervisee<S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        match self.spec.start_supervised().await {
+            Ok((supervisee, reference)) => Ok((OnceSupervisee { supervisee }, reference)),
+            Err(StartError::StartFailed(_)) => {
+                Err(StartError::Fatal(Box::new(OnceSpecError::StartFailed)))
+            }
+            Err(StartError::Fatal(e)) => Err(StartError::Fatal(e)),
+            Err(StartError::Completed) => Err(StartError::Completed),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.spec.validate()
+    }
+}
+
+/// The [`Supervisee`] of a [`OnceSpec`].
+#[pin_project]
+#[derive(Debug)]
+pub struct OnceSupervisee<S: Specification> {
+    #[pin]
+    supervisee: S::Supervisee,
+}
+
+impl<S: Specification> Supervisee for OnceSupervisee<S> {
+    type Spec = OnceSpec<S>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        self.project()
+            .supervisee
+            .poll_supervise(cx)
+            .map(|res| match res {
+                Ok(None) => Ok(None),
+                Ok(Some(_)) => Err(Box::new(OnceSpecError::RestartRequested) as FatalError),
+                Err(e) => Err(e),
+            })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+/// The [`FatalError`] a [`OnceSpec`] exits with when it's child did not complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum OnceSpecError {
+    /// The inner spec failed to start.
+    #[error("Couldn't start the child because it may only be started once")]
+    StartFailed,
+    /// The inner supervisee exited and would like to be restarted.
+    #[error("Couldn't restart the child because it may only run once")]
+    RestartRequested,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec(restart: bool) -> OnceSpec<impl Specification<Ref = Address<Halter>>> {
+        SpawnSpec::new(
+            |_halter: Halter, ()| async move {},
+            move |_exit| async move { Ok(restart.then_some(())) },
+            (),
+        )
+        .once()
+    }
+
+    #[tokio::test]
+    async fn completion_completes() {
+        let (supervisee, _address) = spec(false).start_supervised().await.unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn restart_is_escalated() {
+        let (supervisee, _address) = spec(true).start_supervised().await.unwrap();
+        let Err(error) = supervisee.supervise().await else {
+            panic!("expected a fatal error");
+        };
+        assert_eq!(
+            error.downcast_ref::<OnceSpecError>(),
+            Some(&OnceSpecError::RestartRequested)
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_start_is_escalated() {
+        let spec = SharedResourceSpec::new(
+            || async { Err::<(), _>("unavailable") },
+            |()| spec(false).into_inner(),
+        )
+        .once();
+        match spec.start_supervised().await {
+            Err(StartError::Fatal(error)) => assert_eq!(
+                error.downcast_ref::<OnceSpecError>(),
+                Some(&OnceSpecError::StartFailed)
+            ),
+            _ => panic!("expected a fatal error"),
+        }
+    }
+}
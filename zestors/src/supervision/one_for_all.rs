@@ -0,0 +1,525 @@
+use super::{
+    one_for_one::{list_items, AbortTimer, OneForOneItem, SHUTDOWN_MARGIN},
+    EventLog,
+};
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{future::poll_fn, ready};
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+//------------------------------------------------------------------------------------------------
+//  Spec
+//------------------------------------------------------------------------------------------------
+
+/// A [`Specification`] that supervises a group of children, restarting all of them whenever one
+/// of them exits.
+///
+/// When a child exits or fails to start, all other children are halted, and once they have exited
+/// all children are started again. Children that have completed are not restarted. Every group
+/// restart counts against the [`RestartLimiter`]. If a child fails with a [`FatalError`], or the
+/// limit is exceeded, the whole tree is shut down in the same way as a [`OneForOneSpec`].
+///
+/// The child that caused the last group restart is available with
+/// [`OneForAllSpec::last_restart_cause`], and every group restart is sent as a
+/// [`SupervisionEventKind::GroupRestarted`] event to the subscribers of [`OneForAllSpec::events`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let spec = OneForAllSpec::new();
+/// assert!(matches!(spec.start_supervised().await, Err(StartError::Completed)));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OneForAllSpec {
+    items: Vec<OneForOneItem>,
+    limiter: RestartLimiter,
+    last_restart_cause: Option<RestartCause>,
+    events: Arc<EventLog>,
+}
+
+impl Default for OneForAllSpec {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            limiter: RestartLimiter::default(),
+            last_restart_cause: None,
+            events: Arc::new(EventLog::new(0)),
+        }
+    }
+}
+
+impl OneForAllSpec {
+    /// Create a new, empty spec with a default [`RestartLimiter`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given [`RestartLimiter`] for restarting the group.
+    pub fn with_limiter(mut self, limiter: RestartLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Add a child to the tree.
+    pub fn with_spec<S>(mut self, spec: S) -> Self
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.add_spec(spec);
+        self
+    }
+
+    /// Add a child to the tree.
+    pub fn add_spec<S>(&mut self, spec: S)
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.items
+            .push(OneForOneItem::Spec(spec.on_start(|_| ()).into_dyn()))
+    }
+
+    /// The amount of children in the tree, including the ones that have completed.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the tree has no children.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The current [`ChildKind`] of every child, in the order they were added.
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.items.iter().map(OneForOneItem::kind)
+    }
+
+    /// The child that caused the last group restart, if there has been one.
+    pub fn last_restart_cause(&self) -> Option<&RestartCause> {
+        self.last_restart_cause.as_ref()
+    }
+
+    /// Subscribe to the events of this tree from now on. These are kept across restarts.
+    pub fn events(&self) -> StreamRx<SupervisionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Get an [`ErrorReporter`] with which the children can report non-fatal errors to the
+    /// events of this tree.
+    pub fn error_reporter(&self) -> ErrorReporter {
+        ErrorReporter::new(self.events.clone())
+    }
+
+    fn start_all(&mut self) {
+        for item in &mut self.items {
+            item.start(None);
+        }
+    }
+
+    /// Poll all running children, returning what should happen if any of them has exited.
+    fn poll_running(&mut self, cx: &mut Context) -> Option<GroupChange> {
+        loop {
+            let mut progressed = false;
+            for (child, item) in self.items.iter_mut().enumerate() {
+                // A child that was rejected by `Specification::validate` has failed without
+                // starting.
+                if let OneForOneItem::Irrecoverable(_) = item {
+                    return Some(GroupChange::Shutdown);
+                }
+                if let Some(reset) = item.take_backoff_reset(child) {
+                    self.events.push(SupervisionEventKind::BackoffReset(reset));
+                }
+                let reason = match item.is_starting() {
+                    true => ExitReason::StartFailed,
+                    false => ExitReason::Exited,
+                };
+                if item.poll_change(cx).is_ready() {
+                    progressed = true;
+                    match item {
+                        OneForOneItem::Spec(_) => {
+                            return Some(GroupChange::Restart(RestartCause { child, reason }))
+                        }
+                        OneForOneItem::Irrecoverable(_) => return Some(GroupChange::Shutdown),
+                        _ => (),
+                    }
+                }
+            }
+            if !progressed {
+                return None;
+            }
+        }
+    }
+
+    /// Halt all running children, returning the timer after which they should be aborted.
+    fn begin_shutdown(&mut self) -> AbortTimer {
+        for item in &mut self.items {
+            item.halt();
+        }
+        AbortTimer::new(self.shutdown_time())
+    }
+
+    /// Poll all children until they have exited, aborting them once the timer has expired.
+    fn poll_shutdown(&mut self, timer: &mut AbortTimer, cx: &mut Context) -> Poll<()> {
+        let expired = timer.poll_expired(cx);
+
+        loop {
+            let mut progressed = false;
+            for item in &mut self.items {
+                if item.poll_change(cx).is_ready() {
+                    progressed = true;
+                    item.halt();
+                }
+                if expired {
+                    item.abort();
+                }
+            }
+            if !progressed {
+                break;
+            }
+        }
+
+        if self.items.iter().any(OneForOneItem::is_running) {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+
+    fn shutdown_time(&self) -> Duration {
+        self.items
+            .iter()
+            .filter_map(OneForOneItem::shutdown_time)
+            .fold(Duration::ZERO, Duration::max)
+            .saturating_add(SHUTDOWN_MARGIN)
+    }
+
+    fn is_failed(&self) -> bool {
+        self.items
+            .iter()
+            .any(|item| matches!(item, OneForOneItem::Irrecoverable(_)))
+    }
+
+    /// Get the exit-value of this tree, once none of the children are running anymore.
+    fn into_exit(mut self) -> SupervisionResult<Self> {
+        let failed = self
+            .items
+            .iter()
+            .position(|item| matches!(item, OneForOneItem::Irrecoverable(_)));
+
+        if let Some(index) = failed {
+            let OneForOneItem::Irrecoverable(e) = self.items.swap_remove(index) else {
+                unreachable!()
+            };
+            Err(e)
+        } else if self
+            .items
+            .iter()
+            .any(|item| matches!(item, OneForOneItem::Spec(_)))
+        {
+            self.limiter.reset();
+            Ok(Some(self))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[async_trait]
+impl Specification for OneForAllSpec {
+    type Ref = ();
+    type Supervisee = OneForAllSupervisee;
+
+    async fn start_supervised(mut self) -> StartResult<Self> {
+        self.start_all();
+        let mut supervisee = OneForAllSupervisee {
+            spec: Some(self),
+            phase: Phase::Running,
+        };
+
+        let exit = poll_fn(|cx| match Pin::new(&mut supervisee).poll_supervise(cx) {
+            Poll::Ready(exit) => Poll::Ready(Some(exit)),
+            Poll::Pending if supervisee.is_started() => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        })
+        .await;
+
+        match exit {
+            None => Ok((supervisee, ())),
+            Some(Ok(Some(spec))) => Err(StartError::StartFailed(spec)),
+            Some(Ok(None)) => Err(StartError::Completed),
+            Some(Err(e)) => Err(StartError::Fatal(e)),
+        }
+    }
+}
+
+/// The child of a [`OneForAllSpec`] that caused all children to be restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RestartCause {
+    /// The index of the child, in the order the children were added.
+    pub child: usize,
+    /// Why the child caused a restart.
+    pub reason: ExitReason,
+}
+
+/// Why a child of a [`OneForAllSpec`] caused all children to be restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    /// The child has exited.
+    Exited,
+    /// Starting the child has failed.
+    StartFailed,
+}
+
+enum GroupChange {
+    Restart(RestartCause),
+    Shutdown,
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`OneForAllSpec`].
+#[derive(Debug)]
+pub struct OneForAllSupervisee {
+    spec: Option<OneForAllSpec>,
+    phase: Phase,
+}
+
+#[derive(Debug)]
+enum Phase {
+    Running,
+    Restarting(AbortTimer),
+    ShuttingDown(AbortTimer),
+}
+
+impl OneForAllSupervisee {
+    fn spec_mut(&mut self) -> &mut OneForAllSpec {
+        self.spec.as_mut().unwrap()
+    }
+
+    /// Whether all children are running, and no group restart is in progress.
+    fn is_started(&self) -> bool {
+        matches!(self.phase, Phase::Running)
+            && !self
+                .spec
+                .iter()
+                .flat_map(|spec| &spec.items)
+                .any(OneForOneItem::is_starting)
+    }
+
+    /// The current [`ChildKind`] of every child, see [`OneForAllSpec::children`].
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.spec.iter().flat_map(OneForAllSpec::children)
+    }
+
+    /// See [`OneForAllSpec::last_restart_cause`].
+    pub fn last_restart_cause(&self) -> Option<&RestartCause> {
+        self.spec.as_ref().unwrap().last_restart_cause()
+    }
+
+    /// See [`OneForAllSpec::events`].
+    pub fn events(&self) -> StreamRx<SupervisionEvent> {
+        self.spec.as_ref().unwrap().events()
+    }
+}
+
+impl Supervisee for OneForAllSupervisee {
+    type Spec = OneForAllSpec;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = &mut *self;
+        let spec = this.spec.as_mut().unwrap();
+
+        loop {
+            match &mut this.phase {
+                Phase::Running => match spec.poll_running(cx) {
+                    Some(GroupChange::Restart(cause)) => {
+                        spec.last_restart_cause = Some(cause);
+                        spec.events
+                            .push(SupervisionEventKind::GroupRestarted(cause));
+                        this.phase = Phase::Restarting(spec.begin_shutdown());
+                    }
+                    Some(GroupChange::Shutdown) => {
+                        this.phase = Phase::ShuttingDown(spec.begin_shutdown());
+                    }
+                    None if spec.items.iter().any(OneForOneItem::is_running) => {
+                        return Poll::Pending
+                    }
+                    None => break,
+                },
+                Phase::Restarting(timer) => {
+                    ready!(spec.poll_shutdown(timer, cx));
+                    if spec.is_failed() || !spec.limiter.within_limit() {
+                        break;
+                    }
+                    spec.start_all();
+                    this.phase = Phase::Running;
+                }
+                Phase::ShuttingDown(timer) => {
+                    ready!(spec.poll_shutdown(timer, cx));
+                    break;
+                }
+            }
+        }
+
+        Poll::Ready(this.spec.take().unwrap().into_exit())
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.spec.as_ref().unwrap().shutdown_time()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        if !matches!(self.phase, Phase::ShuttingDown(_)) {
+            let timer = self.spec_mut().begin_shutdown();
+            self.phase = Phase::ShuttingDown(timer);
+        }
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        for item in &mut self.spec_mut().items {
+            item.abort();
+        }
+        self.phase = Phase::ShuttingDown(AbortTimer::new(Duration::ZERO));
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        let status = match self.phase {
+            Phase::Running => ActorStatus::Running,
+            Phase::Restarting(_) | Phase::ShuttingDown(_) => ActorStatus::ShuttingDown,
+        };
+        Some(list_items(
+            self.spec.as_ref()?.items.iter().enumerate(),
+            status,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn halter_spec() -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+    }
+
+    /// A child that exits `exits` times before waiting to be halted.
+    fn exiting_spec(exits: u32) -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |halter: Halter, exits: u32| async move {
+                if exits == 0 {
+                    halter.await;
+                }
+                exits
+            },
+            |exit: Result<u32, ExitError>| async move { Ok(Some(exit.unwrap().saturating_sub(1))) },
+            exits,
+        )
+    }
+
+    #[tokio::test]
+    async fn empty_tree_is_completed() {
+        let spec = OneForAllSpec::new();
+        let res = spec.start_supervised().await;
+        assert!(matches!(res, Err(StartError::Completed)));
+    }
+
+    #[tokio::test]
+    async fn group_starts_without_completed_children() {
+        let (supervisee, ()) = OneForAllSpec::new()
+            .with_spec(OneForAllSpec::new())
+            .with_spec(halter_spec())
+            .start_supervised()
+            .await
+            .unwrap();
+        assert_eq!(
+            supervisee.children().collect::<Vec<_>>(),
+            vec![ChildKind::Completed, ChildKind::Supervisee]
+        );
+    }
+
+    #[tokio::test]
+    async fn exiting_child_restarts_group() {
+        let spec = OneForAllSpec::new()
+            .with_spec(halter_spec())
+            .with_spec(exiting_spec(2));
+        let mut events = spec.events();
+        let mut supervisee = Box::pin(start(spec).await.unwrap());
+
+        let mut restarts = Vec::new();
+        poll_fn(|cx| {
+            assert!(supervisee.as_mut().poll_supervise(cx).is_pending());
+            while let Ok(event) = events.try_recv() {
+                restarts.push(event.kind);
+            }
+            match restarts.len() == 2 && supervisee.is_started() {
+                true => Poll::Ready(()),
+                false => Poll::Pending,
+            }
+        })
+        .await;
+
+        let cause = RestartCause {
+            child: 1,
+            reason: ExitReason::Exited,
+        };
+        assert_eq!(
+            restarts,
+            vec![SupervisionEventKind::GroupRestarted(cause); 2]
+        );
+        assert_eq!(supervisee.last_restart_cause(), Some(&cause));
+        assert!(supervisee
+            .children()
+            .all(|kind| kind == ChildKind::Supervisee));
+
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        let spec = exit.unwrap().unwrap();
+        assert_eq!(spec.last_restart_cause(), Some(&cause));
+    }
+
+    #[tokio::test]
+    async fn restart_limit_exits_with_spec() {
+        let spec = OneForAllSpec::new()
+            .with_limiter(RestartLimiter::new(1, Duration::from_secs(10)))
+            .with_spec(halter_spec())
+            .with_spec(exiting_spec(5));
+        let spec = match start(spec).await {
+            Ok(supervisee) => supervisee.supervise().await.unwrap().unwrap(),
+            Err(spec) => spec,
+        };
+        assert_eq!(
+            spec.children().collect::<Vec<_>>(),
+            vec![ChildKind::Spec, ChildKind::Spec]
+        );
+    }
+
+    /// Start the spec, returning it if the restart-limit was exceeded while starting.
+    async fn start(spec: OneForAllSpec) -> Result<OneForAllSupervisee, OneForAllSpec> {
+        match spec.start_supervised().await {
+            Ok((supervisee, ())) => Ok(supervisee),
+            Err(StartError::StartFailed(spec)) => Err(spec),
+            Err(_) => panic!("The tree should not complete or fail"),
+        }
+    }
+}
@@ -0,0 +1,2124 @@
+use super::{exit_collector::CollectExits, EventLog};
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{future::BoxFuture, ready, Future, FutureExt};
+use std::{
+    any::Any,
+    fmt::Debug,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::{
+    sync::{watch, Semaphore},
+    task::JoinHandle,
+    time::{sleep, Instant, Sleep},
+};
+
+/// Extra time given to the children after their shutdown-time, before they are aborted.
+pub(super) const SHUTDOWN_MARGIN: Duration = Duration::from_millis(10);
+
+/// The timer after which halted children are aborted.
+///
+/// A shutdown-time of [`Duration::MAX`] means that the children are never aborted, in which case
+/// no timer is armed at all.
+#[derive(Debug)]
+pub(super) struct AbortTimer(Option<Pin<Box<Sleep>>>);
+
+impl AbortTimer {
+    /// Arm a timer that expires after the shutdown-time, unless it is [`Duration::MAX`].
+    pub(super) fn new(shutdown_time: Duration) -> Self {
+        if shutdown_time == Duration::MAX {
+            Self(None)
+        } else {
+            Self(Some(Box::pin(sleep(shutdown_time))))
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn is_armed(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Whether the timer has expired, which is never the case if it was not armed.
+    pub(super) fn poll_expired(&mut self, cx: &mut Context) -> bool {
+        match &mut self.0 {
+            Some(timer) => timer.as_mut().poll(cx).is_ready(),
+            None => false,
+        }
+    }
+}
+
+/// The default amount of children that may change state in a single poll, before yielding.
+pub const DEFAULT_POLL_BUDGET: usize = 128;
+
+//------------------------------------------------------------------------------------------------
+//  Spec
+//------------------------------------------------------------------------------------------------
+
+/// A [`Specification`] that supervises a group of children, restarting every child individually
+/// when it exits.
+///
+/// Every child is restarted within it's own copy of the [`RestartLimiter`] of the tree. If a child
+/// fails with a [`FatalError`], or exceeds it's limit, the whole tree is shut down:
+/// - If any child has failed, the tree exits with it's [`FatalError`].
+/// - Otherwise the tree exits with it's specification, so that it can be restarted as a whole.
+///
+/// What happens when a child exceeds it's limit can be chosen per child with a [`LimitAction`];
+/// by default this shuts down the tree as described above. Since every child has it's own
+/// limiter, a child that is stopped with [`LimitAction::StopChild`] does not affect the restarts
+/// of it's siblings.
+///
+/// Since the limiters are reset whenever the tree is restarted, and children may be stopped one by
+/// one, a systemic problem can keep the children flapping. A second, tree-wide limit can be set
+/// with [`OneForOneSpec::with_global_intensity`]: it counts the restarts of all children across
+/// restarts of the tree, and always escalates when exceeded.
+///
+/// Every child is boxed into a [`BoxSpec`], so the actors of the children can exit with different
+/// types: their exits are only seen through the [`SupervisionResult`] of their supervisees. The
+/// exit-values of completed children can be collected with an [`ExitCollector`] instead.
+///
+/// A panic while polling the supervisee of a child, for example caused by a bug in a custom
+/// [`Supervisee`], is caught at the child. It fails that child with [`SuperviseePanicked`] instead
+/// of unwinding the rest of the tree.
+///
+/// # Fairness
+/// When several children exit at once, all of them are restarted within the same poll: the tree
+/// keeps on polling it's children until none of them are ready, and the start-future of a
+/// restarted child is polled right away. Children started with
+/// [`OneForOneSpec::with_spawned_starts`] finish starting on their own task instead.
+///
+/// Handling a large burst could keep the supervising task busy for a long time though. After
+/// [`DEFAULT_POLL_BUDGET`] children have changed state in a single poll, the tree yields to the
+/// runtime and continues when it is polled again, so a burst larger than the budget is spread
+/// over multiple polls. This can be changed with [`OneForOneSpec::with_poll_budget`].
+///
+/// # Shutdown
+/// Children are halted in groups of descending shutdown-priority, which can be set with
+/// [`OneForOneSpec::with_shutdown_priority`]. The next group is only halted once all children of
+/// the previous group have exited, or have been aborted after their shutdown-time. By default all
+/// children have priority `0`, and are halted at the same time.
+///
+/// # Child ids
+/// Every child has an id, which is the amount of children that were added to the tree before it.
+/// The id of a child does not change when other children are
+/// [extracted](OneForOneSpec::extract_child), so it is used to refer to the child in the events
+/// and errors of the tree. Without extracted children, the id of a child is it's index.
+///
+/// # Empty trees
+/// A tree without any children has no work to be done, and is treated as completed: Starting it
+/// returns [`StartError::Completed`]. The same goes for a tree of which all children complete
+/// while it is starting. If only some of the children complete while starting, the tree starts
+/// without them. A running tree completes once all of it's children have completed.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let spec = OneForOneSpec::new();
+/// assert!(matches!(spec.start_supervised().await, Err(StartError::Completed)));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OneForOneSpec {
+    children: Vec<OneForOneChild>,
+    next_id: usize,
+    shutdown_priority: Option<u32>,
+    limiter: RestartLimiter,
+    global_intensity: Option<RestartLimiter>,
+    spawned_starts: Option<Arc<Semaphore>>,
+    poll_budget: usize,
+    events: Arc<EventLog>,
+    exits: Option<Box<dyn CollectExits>>,
+    startup_progress: Option<watch::Sender<StartupProgress>>,
+    max_children: Option<usize>,
+}
+
+impl Default for OneForOneSpec {
+    fn default() -> Self {
+        Self {
+            children: Vec::new(),
+            next_id: 0,
+            shutdown_priority: None,
+            limiter: RestartLimiter::default(),
+            global_intensity: None,
+            spawned_starts: None,
+            poll_budget: DEFAULT_POLL_BUDGET,
+            events: Arc::new(EventLog::new(0)),
+            exits: None,
+            startup_progress: None,
+            max_children: None,
+        }
+    }
+}
+
+impl OneForOneSpec {
+    /// Create a new, empty spec with a default [`RestartLimiter`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the given [`RestartLimiter`] for restarting the children. Every child, including the
+    /// ones that were already added, is restarted within it's own copy of the limiter.
+    pub fn with_limiter(mut self, limiter: RestartLimiter) -> Self {
+        for child in &mut self.children {
+            child.limiter = limiter.clone();
+        }
+        self.limiter = limiter;
+        self
+    }
+
+    /// Escalate the whole tree once the children have been restarted more than `limit` times
+    /// within the duration, summed across all children.
+    ///
+    /// This catches correlated failures of children that each stay within their own
+    /// [`RestartLimiter`]: only the restarts allowed by the limiter of a child are counted.
+    /// Unlike those limiters, this limit is not reset when the tree is restarted, and exceeding it
+    /// always shuts down the tree with a [`GlobalIntensityExceeded`] error, regardless of the
+    /// [`LimitAction`] of the child.
+    pub fn with_global_intensity(mut self, limit: usize, within: Duration) -> Self {
+        self.global_intensity = Some(RestartLimiter::new(limit, within));
+        self
+    }
+
+    /// Start every child on it's own task instead of polling all starts from the supervising
+    /// task, with at most `max_concurrent_restarts` starting at the same time.
+    ///
+    /// This lets children with a slow, synchronous start make progress in parallel on a
+    /// multi-threaded runtime, at the cost of spawning a task for every (re)start.
+    ///
+    /// # Panics
+    /// Panics if `max_concurrent_restarts` is `0`.
+    pub fn with_spawned_starts(mut self, max_concurrent_restarts: usize) -> Self {
+        assert!(
+            max_concurrent_restarts > 0,
+            "max_concurrent_restarts must be greater than 0"
+        );
+        self.spawned_starts = Some(Arc::new(Semaphore::new(max_concurrent_restarts)));
+        self
+    }
+
+    /// Set the amount of children that may change state in a single poll, before the tree
+    /// yields to the runtime. The default is [`DEFAULT_POLL_BUDGET`].
+    ///
+    /// # Panics
+    /// Panics if `poll_budget` is `0`.
+    pub fn with_poll_budget(mut self, poll_budget: usize) -> Self {
+        assert!(poll_budget > 0, "poll_budget must be greater than 0");
+        self.poll_budget = poll_budget;
+        self
+    }
+
+    /// Report the [`StartupProgress`] of the tree every time it is started, updating it each
+    /// time one of the children has finished starting.
+    ///
+    /// This only reports while the tree is starting, and stops once it has started or failed.
+    pub fn with_startup_progress(mut self, progress: watch::Sender<StartupProgress>) -> Self {
+        self.startup_progress = Some(progress);
+        self
+    }
+
+    /// Set the shutdown-priority of the child with the given id: when the tree is halted,
+    /// children with a higher priority are halted first. The default priority is `0`.
+    ///
+    /// # Panics
+    /// Panics if the child does not exist.
+    pub fn with_shutdown_priority(mut self, child: usize, priority: u32) -> Self {
+        self.set_shutdown_priority(child, priority);
+        self
+    }
+
+    /// Set the shutdown-priority of a child, see [`OneForOneSpec::with_shutdown_priority`].
+    ///
+    /// # Panics
+    /// Panics if the child does not exist.
+    pub fn set_shutdown_priority(&mut self, child: usize, priority: u32) {
+        let position = self.position(child).expect("Child does not exist");
+        self.children[position].shutdown_priority = priority;
+    }
+
+    /// Collect the exit-values of all children that complete with the [`ExitCollector`], and send
+    /// them to it's [`CollectedExits`] once the tree completes.
+    pub fn with_collected_exits<T: Send + 'static>(mut self, collector: ExitCollector<T>) -> Self {
+        self.exits = Some(Box::new(collector));
+        self
+    }
+
+    /// Limit the amount of children in the tree, including the ones that have completed.
+    ///
+    /// Adding children beyond the limit with [`OneForOneSpec::try_add_spec`] or
+    /// [`OneForOneSpec::try_adopt_child`] fails with [`MaxChildrenReached`]. This prevents a tree
+    /// to which children are added dynamically from growing indefinitely because of a bug.
+    pub fn with_max_children(mut self, max_children: usize) -> Self {
+        self.max_children = Some(max_children);
+        self
+    }
+
+    /// The maximum amount of children, see [`OneForOneSpec::with_max_children`].
+    pub fn max_children(&self) -> Option<usize> {
+        self.max_children
+    }
+
+    /// Add a child to the tree.
+    ///
+    /// # Panics
+    /// Panics if the tree has reached it's [maximum](OneForOneSpec::with_max_children) amount of
+    /// children.
+    pub fn with_spec<S>(mut self, spec: S) -> Self
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.add_spec(spec);
+        self
+    }
+
+    /// Add a child to the tree.
+    ///
+    /// # Panics
+    /// Panics if the tree has reached it's [maximum](OneForOneSpec::with_max_children) amount of
+    /// children.
+    pub fn add_spec<S>(&mut self, spec: S)
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.add_spec_on_limit(spec, LimitAction::default())
+    }
+
+    /// Add a child to the tree, which does `on_limit_exceeded` when it exceeds the
+    /// restart-limit.
+    ///
+    /// # Panics
+    /// Panics if the tree has reached it's [maximum](OneForOneSpec::with_max_children) amount of
+    /// children.
+    pub fn with_spec_on_limit<S>(mut self, spec: S, on_limit_exceeded: LimitAction) -> Self
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.add_spec_on_limit(spec, on_limit_exceeded);
+        self
+    }
+
+    /// Add a child to the tree, which does `on_limit_exceeded` when it exceeds the
+    /// restart-limit.
+    ///
+    /// # Panics
+    /// Panics if the tree has reached it's [maximum](OneForOneSpec::with_max_children) amount of
+    /// children.
+    pub fn add_spec_on_limit<S>(&mut self, spec: S, on_limit_exceeded: LimitAction)
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        if let Err(e) = self.try_add_spec_on_limit(spec, on_limit_exceeded) {
+            panic!("{e}")
+        }
+    }
+
+    /// Add a child to the tree, failing if the tree has reached it's
+    /// [maximum](OneForOneSpec::with_max_children) amount of children.
+    pub fn try_add_spec<S>(&mut self, spec: S) -> Result<(), MaxChildrenReached<S>>
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.try_add_spec_on_limit(spec, LimitAction::default())
+    }
+
+    /// Same as [`OneForOneSpec::try_add_spec`], but the child does `on_limit_exceeded` when it
+    /// exceeds the restart-limit.
+    pub fn try_add_spec_on_limit<S>(
+        &mut self,
+        spec: S,
+        on_limit_exceeded: LimitAction,
+    ) -> Result<(), MaxChildrenReached<S>>
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        if let Some(max) = self.reached_max_children() {
+            return Err(MaxChildrenReached { max, child: spec });
+        }
+        let spec = spec.on_start(|_| ()).into_dyn();
+        self.push_child(OneForOneItem::Spec(spec), on_limit_exceeded, 0);
+        Ok(())
+    }
+
+    /// Same as [`OneForOneSpec::try_add_spec`], for a spec that has already been boxed.
+    fn try_add_boxed(&mut self, spec: BoxSpec) -> Result<(), MaxChildrenReached<BoxSpec>> {
+        if let Some(max) = self.reached_max_children() {
+            return Err(MaxChildrenReached { max, child: spec });
+        }
+        self.push_child(OneForOneItem::Spec(spec), LimitAction::default(), 0);
+        Ok(())
+    }
+
+    /// Add a child with the next id, returning that id.
+    fn push_child(
+        &mut self,
+        item: OneForOneItem,
+        on_limit_exceeded: LimitAction,
+        shutdown_priority: u32,
+    ) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.children.push(OneForOneChild {
+            id,
+            item,
+            on_limit_exceeded,
+            shutdown_priority,
+            limiter: self.limiter.clone(),
+        });
+        id
+    }
+
+    /// The position of the child with the given id. The children are ordered by their ids.
+    fn position(&self, child: usize) -> Option<usize> {
+        self.children
+            .binary_search_by_key(&child, |other| other.id)
+            .ok()
+    }
+
+    fn items(&self) -> impl Iterator<Item = &OneForOneItem> + '_ {
+        self.children.iter().map(|child| &child.item)
+    }
+
+    /// The maximum amount of children, if the tree has reached it.
+    fn reached_max_children(&self) -> Option<usize> {
+        self.max_children.filter(|max| self.children.len() >= *max)
+    }
+
+    /// Remove the running child with the given id from the tree, without stopping it's actor.
+    ///
+    /// The child keeps it's [`Supervisee`] state, and can be handed to another tree with
+    /// [`OneForOneSpec::adopt_child`]. The other children keep their ids. Returns `None` if the
+    /// child does not exist or is not running.
+    pub fn extract_child(&mut self, child: usize) -> Option<RunningChild> {
+        let position = self.position(child)?;
+        if !self.children[position].item.is_started() {
+            return None;
+        }
+        let OneForOneChild {
+            item: OneForOneItem::Supervisee(supervisee),
+            on_limit_exceeded,
+            shutdown_priority,
+            ..
+        } = self.children.remove(position)
+        else {
+            unreachable!()
+        };
+        Some(RunningChild {
+            supervisee,
+            on_limit_exceeded,
+            shutdown_priority,
+        })
+    }
+
+    /// Add a child that was extracted from another tree with [`OneForOneSpec::extract_child`],
+    /// returning it's id in this tree.
+    ///
+    /// The child continues to run, and is supervised and restarted like all other children.
+    ///
+    /// # Panics
+    /// Panics if the tree has reached it's [maximum](OneForOneSpec::with_max_children) amount of
+    /// children.
+    pub fn adopt_child(&mut self, child: RunningChild) -> usize {
+        match self.try_adopt_child(child) {
+            Ok(id) => id,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Same as [`OneForOneSpec::adopt_child`], but fails if the tree has reached it's
+    /// [maximum](OneForOneSpec::with_max_children) amount of children.
+    pub fn try_adopt_child(
+        &mut self,
+        child: RunningChild,
+    ) -> Result<usize, MaxChildrenReached<RunningChild>> {
+        if let Some(max) = self.reached_max_children() {
+            return Err(MaxChildrenReached { max, child });
+        }
+        Ok(self.push_child(
+            OneForOneItem::Supervisee(child.supervisee),
+            child.on_limit_exceeded,
+            child.shutdown_priority,
+        ))
+    }
+
+    /// The amount of children in the tree, including the ones that have completed.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Whether the tree has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// The current [`ChildKind`] of every child, in the order they were added.
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.items().map(OneForOneItem::kind)
+    }
+
+    /// The id of every child, in the same order as [`OneForOneSpec::children`].
+    pub fn child_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.children.iter().map(|child| child.id)
+    }
+
+    /// Subscribe to the events of this tree from now on. These are kept across restarts.
+    pub fn events(&self) -> StreamRx<SupervisionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Get an [`ErrorReporter`] with which the children can report non-fatal errors to the
+    /// events of this tree.
+    pub fn error_reporter(&self) -> ErrorReporter {
+        ErrorReporter::new(self.events.clone())
+    }
+
+    /// Poll all running children, restarting the ones that exit while within the restart-limit.
+    /// This keeps on polling until none of the children are ready anymore.
+    ///
+    /// Returns `true` if the tree should shut down, because a child has failed or the
+    /// restart-limit has been exceeded. Returns pending once the poll-budget has been used up.
+    fn poll_running(&mut self, cx: &mut Context) -> Poll<bool> {
+        let mut budget = self.poll_budget;
+        loop {
+            let mut progressed = false;
+            for OneForOneChild {
+                id: child,
+                item,
+                on_limit_exceeded,
+                limiter,
+                ..
+            } in &mut self.children
+            {
+                let child = *child;
+                // A child that was rejected by `Specification::validate` has failed without
+                // starting.
+                if let OneForOneItem::Irrecoverable(_) = item {
+                    return Poll::Ready(true);
+                }
+                if let Some(reset) = item.take_backoff_reset(child) {
+                    self.events.push(SupervisionEventKind::BackoffReset(reset));
+                }
+                // A restarted child is polled again right away, so that it's start-future can
+                // complete in the same pass.
+                while let Poll::Ready(exit_value) = item.poll_change(cx) {
+                    progressed = true;
+                    if let (Some(exits), Some(exit_value)) = (&self.exits, exit_value) {
+                        exits.collect(exit_value);
+                    }
+                    match item {
+                        OneForOneItem::Spec(_) if limiter.within_limit() => {
+                            // Only restarts that are allowed by the limiter of the child count
+                            // towards the global intensity.
+                            if !self
+                                .global_intensity
+                                .as_mut()
+                                .is_none_or(RestartLimiter::within_limit)
+                            {
+                                let e = GlobalIntensityExceeded { child };
+                                *item = OneForOneItem::Irrecoverable(Box::new(e));
+                                return Poll::Ready(true);
+                            }
+                            item.start(self.spawned_starts.as_ref())
+                        }
+                        OneForOneItem::Spec(_) => match on_limit_exceeded {
+                            LimitAction::KillTree => return Poll::Ready(true),
+                            LimitAction::StopChild => {
+                                item.give_up();
+                                self.events.push(SupervisionEventKind::ChildGaveUp(child));
+                            }
+                            LimitAction::Escalate => {
+                                let e = RestartLimitExceeded { child };
+                                *item = OneForOneItem::Irrecoverable(Box::new(e));
+                                return Poll::Ready(true);
+                            }
+                        },
+                        OneForOneItem::Irrecoverable(_) => return Poll::Ready(true),
+                        _ => (),
+                    }
+                    // The budget is only used up once an exited child has been restarted, since
+                    // a spec is not polled again when the tree continues.
+                    if !use_budget(&mut budget, cx) {
+                        return Poll::Pending;
+                    }
+                }
+            }
+            if !progressed {
+                return Poll::Ready(false);
+            }
+        }
+    }
+
+    /// Send the [`StartupProgress`] if it has changed, given the time every child has been
+    /// pending since.
+    fn report_startup_progress(&self, pending_since: &mut [Option<Instant>]) {
+        let Some(sender) = &self.startup_progress else {
+            return;
+        };
+        let mut progress = StartupProgress {
+            total: self.children.len(),
+            ..Default::default()
+        };
+        for (item, since) in self.items().zip(pending_since.iter_mut()) {
+            match item {
+                OneForOneItem::Supervisee(_) => progress.started += 1,
+                OneForOneItem::Irrecoverable(_) | OneForOneItem::GaveUp(_) => progress.failed += 1,
+                OneForOneItem::Spec(_) | OneForOneItem::StartFut(_) => progress.pending += 1,
+                OneForOneItem::Completed => (),
+            }
+            if matches!(item, OneForOneItem::Spec(_) | OneForOneItem::StartFut(_)) {
+                since.get_or_insert_with(Instant::now);
+            } else {
+                *since = None;
+            }
+        }
+        progress.slowest = pending_since
+            .iter()
+            .enumerate()
+            .filter_map(|(position, since)| Some((position, (*since)?)))
+            .min_by_key(|(_, since)| *since)
+            .map(|(position, _)| self.children[position].id);
+        sender.send_if_modified(|current| {
+            let modified = *current != progress;
+            *current = progress;
+            modified
+        });
+    }
+
+    /// Halt the running children with the highest shutdown-priority, returning the timer after
+    /// which they should be aborted.
+    fn begin_shutdown(&mut self) -> AbortTimer {
+        self.shutdown_priority = None;
+        self.halt_next_group()
+            .unwrap_or_else(|| AbortTimer::new(SHUTDOWN_MARGIN))
+    }
+
+    /// Halt the running children with the highest shutdown-priority below the current one,
+    /// returning the timer after which they should be aborted. Returns `None` if there are none.
+    fn halt_next_group(&mut self) -> Option<AbortTimer> {
+        let current = self.shutdown_priority;
+        let priority = self
+            .children
+            .iter()
+            .filter(|child| {
+                child.item.is_running()
+                    && current.is_none_or(|current| child.shutdown_priority < current)
+            })
+            .map(|child| child.shutdown_priority)
+            .max()?;
+        self.shutdown_priority = Some(priority);
+
+        let mut shutdown_time = Duration::ZERO;
+        for child in self
+            .children
+            .iter_mut()
+            .filter(|child| child.shutdown_priority == priority)
+        {
+            shutdown_time = shutdown_time.max(child.item.shutdown_time().unwrap_or_default());
+            child.item.halt();
+        }
+
+        let timer = AbortTimer::new(shutdown_time.saturating_add(SHUTDOWN_MARGIN));
+        Some(timer)
+    }
+
+    /// Poll all children until they have exited, halting them group by group and aborting a
+    /// group once it's timer has expired.
+    ///
+    /// If `abort_starts` is set, the start-futures of the aborted group are dropped as well, which
+    /// fails those children with [`StartAborted`]. Otherwise they are waited for, and aborted once
+    /// they have started.
+    fn poll_shutdown(
+        &mut self,
+        timer: &mut AbortTimer,
+        abort_starts: bool,
+        cx: &mut Context,
+    ) -> Poll<()> {
+        let mut budget = self.poll_budget;
+        loop {
+            let expired = timer.poll_expired(cx);
+            let current = self.shutdown_priority;
+            let is_halted = |priority: &u32| current.is_some_and(|current| *priority >= current);
+
+            loop {
+                let mut progressed = false;
+                for OneForOneChild {
+                    item,
+                    shutdown_priority: priority,
+                    ..
+                } in &mut self.children
+                {
+                    if let Poll::Ready(exit_value) = item.poll_change(cx) {
+                        progressed = true;
+                        if let (Some(exits), Some(exit_value)) = (&self.exits, exit_value) {
+                            exits.collect(exit_value);
+                        }
+                        if is_halted(priority) {
+                            item.halt();
+                        }
+                        if !use_budget(&mut budget, cx) {
+                            return Poll::Pending;
+                        }
+                    }
+                    if expired && is_halted(priority) {
+                        item.abort();
+                        if abort_starts {
+                            item.abort_start();
+                        }
+                    }
+                }
+                if !progressed {
+                    break;
+                }
+            }
+
+            let group_running = self
+                .children
+                .iter()
+                .any(|child| child.item.is_running() && is_halted(&child.shutdown_priority));
+            if group_running {
+                return Poll::Pending;
+            }
+            match self.halt_next_group() {
+                Some(next_timer) => *timer = next_timer,
+                None => return Poll::Ready(()),
+            }
+        }
+    }
+
+    /// The total shutdown-time of all running children, summed over their shutdown-priorities.
+    fn shutdown_time(&self) -> Duration {
+        let mut priorities = self
+            .children
+            .iter()
+            .filter(|child| child.item.shutdown_time().is_some())
+            .map(|child| child.shutdown_priority)
+            .collect::<Vec<_>>();
+        priorities.sort_unstable();
+        priorities.dedup();
+
+        priorities
+            .into_iter()
+            .map(|priority| {
+                self.children
+                    .iter()
+                    .filter(|child| child.shutdown_priority == priority)
+                    .filter_map(|child| child.item.shutdown_time())
+                    .fold(Duration::ZERO, Duration::max)
+                    .saturating_add(SHUTDOWN_MARGIN)
+            })
+            .fold(Duration::ZERO, Duration::saturating_add)
+            .max(SHUTDOWN_MARGIN)
+    }
+
+    /// Get the exit-value of this tree, once none of the children are running anymore.
+    ///
+    /// Children that have given up are given a new chance when the tree is restarted.
+    fn into_exit(mut self) -> SupervisionResult<Self> {
+        self.shutdown_priority = None;
+        for child in &mut self.children {
+            child.item.retry();
+            child.limiter.reset();
+        }
+
+        let failed = self
+            .items()
+            .position(|item| matches!(item, OneForOneItem::Irrecoverable(_)));
+
+        if let Some(position) = failed {
+            let OneForOneItem::Irrecoverable(e) = self.children.swap_remove(position).item else {
+                unreachable!()
+            };
+            Err(e)
+        } else if self
+            .items()
+            .any(|item| matches!(item, OneForOneItem::Spec(_)))
+        {
+            Ok(Some(self))
+        } else {
+            if let Some(exits) = self.exits.take() {
+                exits.complete();
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Use up one unit of the poll-budget, returning `false` and waking the task if it has run out.
+fn use_budget(budget: &mut usize, cx: &mut Context) -> bool {
+    *budget -= 1;
+    if *budget == 0 {
+        cx.waker().wake_by_ref();
+        false
+    } else {
+        true
+    }
+}
+
+#[async_trait]
+impl Specification for OneForOneSpec {
+    type Ref = ();
+    type Supervisee = OneForOneSupervisee;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        OneForOneStartFut::new(self, None).await
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  StartFut
+//------------------------------------------------------------------------------------------------
+
+/// Waits until all children have started, or shuts them down again if starting fails.
+///
+/// With a quorum, this only waits until that many children have started, and shuts down once it
+/// can no longer be reached.
+pub(super) struct OneForOneStartFut {
+    spec: Option<OneForOneSpec>,
+    quorum: Option<usize>,
+    shutdown_timer: Option<AbortTimer>,
+    pending_since: Vec<Option<Instant>>,
+}
+
+impl Future for OneForOneStartFut {
+    type Output = StartResult<OneForOneSpec>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let spec = this.spec.as_mut().unwrap();
+
+        if this.shutdown_timer.is_none() {
+            let poll = spec.poll_running(cx);
+            spec.report_startup_progress(&mut this.pending_since);
+            if ready!(poll) {
+                this.shutdown_timer = Some(spec.begin_shutdown());
+            } else if let Some(required) = this.quorum {
+                let started = spec.items().filter(|item| item.is_started()).count();
+                let running = spec.items().filter(|item| item.is_running()).count();
+                if started >= required {
+                    let supervisee = OneForOneSupervisee::new(this.spec.take().unwrap());
+                    return Poll::Ready(Ok((supervisee, ())));
+                } else if running >= required {
+                    return Poll::Pending;
+                } else {
+                    this.shutdown_timer = Some(spec.begin_shutdown());
+                }
+            } else if spec.items().any(OneForOneItem::is_starting) {
+                return Poll::Pending;
+            } else if spec.items().any(OneForOneItem::is_running) {
+                let supervisee = OneForOneSupervisee::new(this.spec.take().unwrap());
+                return Poll::Ready(Ok((supervisee, ())));
+            } else {
+                return Poll::Ready(this.failed_start());
+            }
+        }
+
+        // A failed start is not restarted, so children that don't finish starting are dropped.
+        ready!(spec.poll_shutdown(this.shutdown_timer.as_mut().unwrap(), true, cx));
+        Poll::Ready(this.failed_start())
+    }
+}
+
+impl OneForOneStartFut {
+    /// Start all children of the spec.
+    pub(super) fn new(mut spec: OneForOneSpec, quorum: Option<usize>) -> Self {
+        for child in &mut spec.children {
+            child.item.start(spec.spawned_starts.as_ref());
+        }
+        let mut pending_since = vec![None; spec.children.len()];
+        spec.report_startup_progress(&mut pending_since);
+        Self {
+            spec: Some(spec),
+            quorum,
+            shutdown_timer: None,
+            pending_since,
+        }
+    }
+
+    /// The start-error, once none of the children are running anymore.
+    #[allow(clippy::result_large_err)]
+    fn failed_start(&mut self) -> StartResult<OneForOneSpec> {
+        match self.spec.take().unwrap().into_exit() {
+            Ok(Some(spec)) => Err(StartError::StartFailed(spec)),
+            Ok(None) => Err(StartError::Completed),
+            Err(e) => Err(StartError::Fatal(e)),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`OneForOneSpec`].
+#[derive(Debug)]
+pub struct OneForOneSupervisee {
+    spec: Option<OneForOneSpec>,
+    shutdown_timer: Option<AbortTimer>,
+}
+
+impl OneForOneSupervisee {
+    fn new(spec: OneForOneSpec) -> Self {
+        Self {
+            spec: Some(spec),
+            shutdown_timer: None,
+        }
+    }
+
+    fn spec_mut(&mut self) -> &mut OneForOneSpec {
+        self.spec.as_mut().unwrap()
+    }
+
+    /// The current [`ChildKind`] of every child, see [`OneForOneSpec::children`].
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.spec.iter().flat_map(OneForOneSpec::children)
+    }
+
+    /// See [`OneForOneSpec::child_ids`].
+    pub fn child_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.spec.iter().flat_map(OneForOneSpec::child_ids)
+    }
+
+    /// See [`OneForOneSpec::len`].
+    pub fn len(&self) -> usize {
+        self.spec.as_ref().map_or(0, OneForOneSpec::len)
+    }
+
+    /// See [`OneForOneSpec::is_empty`].
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// See [`OneForOneSpec::max_children`].
+    pub fn max_children(&self) -> Option<usize> {
+        self.spec.as_ref()?.max_children()
+    }
+
+    /// See [`OneForOneSpec::events`].
+    pub fn events(&self) -> StreamRx<SupervisionEvent> {
+        self.spec.as_ref().unwrap().events()
+    }
+
+    /// See [`OneForOneSpec::extract_child`]. Returns `None` if the tree is shutting down.
+    pub fn extract_child(&mut self, child: usize) -> Option<RunningChild> {
+        match self.shutdown_timer {
+            Some(_) => None,
+            None => self.spec.as_mut()?.extract_child(child),
+        }
+    }
+
+    /// See [`OneForOneSpec::adopt_child`]. If the tree is shutting down, the child is halted.
+    ///
+    /// The tree must be polled again before it supervises the adopted child.
+    ///
+    /// # Panics
+    /// Panics if the tree has reached it's [maximum](OneForOneSpec::with_max_children) amount of
+    /// children.
+    pub fn adopt_child(&mut self, child: RunningChild) -> usize {
+        match self.try_adopt_child(child) {
+            Ok(id) => id,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// See [`OneForOneSpec::try_adopt_child`]. If the tree is shutting down, the child is halted.
+    pub fn try_adopt_child(
+        &mut self,
+        child: RunningChild,
+    ) -> Result<usize, MaxChildrenReached<RunningChild>> {
+        let shutting_down = self.shutdown_timer.is_some();
+        let spec = self.spec_mut();
+        let id = spec.try_adopt_child(child)?;
+        if shutting_down {
+            spec.children.last_mut().unwrap().item.halt();
+        }
+        Ok(id)
+    }
+
+    /// Add a boxed spec and start it. If the tree is shutting down, it is only started if the
+    /// tree is restarted.
+    ///
+    /// The tree must be polled again before it supervises the started child.
+    pub(super) fn try_add_boxed(
+        &mut self,
+        spec: BoxSpec,
+    ) -> Result<(), MaxChildrenReached<BoxSpec>> {
+        let shutting_down = self.shutdown_timer.is_some();
+        let tree = self.spec_mut();
+        tree.try_add_boxed(spec)?;
+        if !shutting_down {
+            let spawned_starts = tree.spawned_starts.as_ref();
+            tree.children.last_mut().unwrap().item.start(spawned_starts);
+        }
+        Ok(())
+    }
+}
+
+impl Supervisee for OneForOneSupervisee {
+    type Spec = OneForOneSpec;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = &mut *self;
+        let spec = this.spec.as_mut().unwrap();
+
+        if this.shutdown_timer.is_none() {
+            if ready!(spec.poll_running(cx)) {
+                this.shutdown_timer = Some(spec.begin_shutdown());
+            } else if spec.items().any(OneForOneItem::is_running) {
+                return Poll::Pending;
+            } else {
+                return Poll::Ready(this.spec.take().unwrap().into_exit());
+            }
+        }
+
+        ready!(spec.poll_shutdown(this.shutdown_timer.as_mut().unwrap(), false, cx));
+        Poll::Ready(this.spec.take().unwrap().into_exit())
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.spec.as_ref().unwrap().shutdown_time()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        if self.shutdown_timer.is_none() {
+            let timer = self.spec_mut().begin_shutdown();
+            self.shutdown_timer = Some(timer);
+        }
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        let spec = self.spec_mut();
+        spec.shutdown_priority = Some(0);
+        for child in &mut spec.children {
+            child.item.abort();
+        }
+        self.shutdown_timer = Some(AbortTimer::new(Duration::ZERO));
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        Some(self.get_mut())
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        let status = match self.shutdown_timer {
+            Some(_) => ActorStatus::ShuttingDown,
+            None => ActorStatus::Running,
+        };
+        let children = &self.spec.as_ref()?.children;
+        Some(list_items(
+            children.iter().map(|child| (child.id, &child.item)),
+            status,
+        ))
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Item
+//------------------------------------------------------------------------------------------------
+
+/// What a child of a [`OneForOneSpec`] does when it exceeds the restart-limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LimitAction {
+    /// Shut down the tree, which exits so that it can be restarted as a whole.
+    #[default]
+    KillTree,
+    /// Stop only this child, which is marked as [`ChildKind::GaveUp`], and continue supervising
+    /// the rest. A [`SupervisionEventKind::ChildGaveUp`] event is emitted.
+    StopChild,
+    /// Shut down the tree, which exits with a [`RestartLimitExceeded`] error.
+    Escalate,
+}
+
+/// The error a [`OneForOneSpec`] exits with when a child with [`LimitAction::Escalate`] exceeds
+/// the restart-limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't restart child {child} because the restart-limit was exceeded")]
+pub struct RestartLimitExceeded {
+    /// The id of the child, see [`OneForOneSpec`].
+    pub child: usize,
+}
+
+/// The error a [`OneForOneSpec`] exits with when the restarts of all of it's children have
+/// exceeded the limit set with [`OneForOneSpec::with_global_intensity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't restart child {child} because the global restart-intensity was exceeded")]
+pub struct GlobalIntensityExceeded {
+    /// The id of the child that exceeded the limit, see [`OneForOneSpec`].
+    pub child: usize,
+}
+
+/// The error returned when adding a child to a [`OneForOneSpec`] that has reached it's maximum
+/// amount of children, see [`OneForOneSpec::with_max_children`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't add child because the tree has reached it's maximum of {max} children")]
+pub struct MaxChildrenReached<T> {
+    /// The maximum amount of children.
+    pub max: usize,
+    /// The child that could not be added.
+    pub child: T,
+}
+
+/// The error a child of a [`OneForOneSpec`] fails with when the tree fails to start, and the
+/// child has not finished starting within it's shutdown-time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't shut down child because it did not finish starting in time")]
+pub struct StartAborted;
+
+/// The [`FatalError`] of a child in a [`OneForOneSpec`] or [`OneForAllSpec`] of which the
+/// supervisee panicked while being polled.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't supervise child because it's supervisee panicked")]
+pub struct SuperviseePanicked {
+    /// The message of the panic, if it was a string.
+    pub message: Option<String>,
+}
+
+impl SuperviseePanicked {
+    fn new(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<&'static str>() {
+            Ok(msg) => Some(msg.to_string()),
+            Err(payload) => payload.downcast::<String>().ok().map(|msg| *msg),
+        };
+        Self { message }
+    }
+}
+
+/// The progress of starting the children of a [`OneForOneSpec`], see
+/// [`OneForOneSpec::with_startup_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StartupProgress {
+    /// The amount of children in the tree.
+    pub total: usize,
+    /// The amount of children that have started.
+    pub started: usize,
+    /// The amount of children that have failed or given up.
+    pub failed: usize,
+    /// The amount of children that are still starting, or waiting to be restarted.
+    pub pending: usize,
+    /// The id of the pending child that has been pending the longest, see [`OneForOneSpec`].
+    pub slowest: Option<usize>,
+}
+
+/// A running child that has been extracted from a [`OneForOneSpec`], and can be adopted by
+/// another one.
+///
+/// The actor of the child keeps on running while it is not part of a tree, but it is not
+/// supervised: if this is dropped, the child is dropped as well.
+#[derive(Debug)]
+pub struct RunningChild {
+    supervisee: BoxSupervisee,
+    on_limit_exceeded: LimitAction,
+    shutdown_priority: u32,
+}
+
+impl RunningChild {
+    /// What the child does when it exceeds the restart-limit.
+    pub fn on_limit_exceeded(&self) -> LimitAction {
+        self.on_limit_exceeded
+    }
+
+    /// The shutdown-priority of the child, see [`OneForOneSpec::with_shutdown_priority`].
+    pub fn shutdown_priority(&self) -> u32 {
+        self.shutdown_priority
+    }
+}
+
+/// The state of a child in a [`OneForOneSpec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChildKind {
+    /// The child is not running, and may be started.
+    Spec,
+    /// The child is starting.
+    StartFut,
+    /// The child is running.
+    Supervisee,
+    /// The child has completed.
+    Completed,
+    /// The child has failed with a [`FatalError`].
+    Irrecoverable,
+    /// The child has exceeded the restart-limit and is stopped, see [`LimitAction::StopChild`].
+    GaveUp,
+}
+
+/// A child of a [`OneForOneSpec`], together with the way it is supervised.
+#[derive(Debug)]
+struct OneForOneChild {
+    id: usize,
+    item: OneForOneItem,
+    on_limit_exceeded: LimitAction,
+    shutdown_priority: u32,
+    limiter: RestartLimiter,
+}
+
+pub(super) enum OneForOneItem {
+    Spec(BoxSpec),
+    StartFut(BoxFuture<'static, StartResult<BoxSpec>>),
+    Supervisee(BoxSupervisee),
+    Irrecoverable(FatalError),
+    GaveUp(BoxSpec),
+    Completed,
+}
+
+impl Debug for OneForOneItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spec(spec) => f.debug_tuple("Spec").field(spec).finish(),
+            Self::StartFut(_) => f.debug_tuple("StartFut").finish(),
+            Self::Supervisee(supervisee) => f.debug_tuple("Supervisee").field(supervisee).finish(),
+            Self::Irrecoverable(e) => f.debug_tuple("Irrecoverable").field(e).finish(),
+            Self::GaveUp(spec) => f.debug_tuple("GaveUp").field(spec).finish(),
+            Self::Completed => write!(f, "Completed"),
+        }
+    }
+}
+
+impl OneForOneItem {
+    /// Start the item if it is a spec, spawning the start onto a new task if a semaphore is given.
+    ///
+    /// If the spec is rejected by [`Specification::validate`], the item fails right away.
+    pub(super) fn start(&mut self, spawned_starts: Option<&Arc<Semaphore>>) {
+        if let Self::Spec(_) = self {
+            let Self::Spec(spec) = mem::replace(self, Self::Completed) else {
+                unreachable!()
+            };
+            let start_fut = match spec.try_start_supervised() {
+                Ok(start_fut) => start_fut,
+                Err((_spec, e)) => {
+                    *self = Self::Irrecoverable(e);
+                    return;
+                }
+            };
+            *self = Self::StartFut(match spawned_starts {
+                Some(semaphore) => Box::pin(SpawnedStart::new(semaphore.clone(), start_fut)),
+                None => start_fut,
+            });
+        }
+    }
+
+    /// Stop the item if it is a spec, so that it is not started anymore.
+    pub(super) fn give_up(&mut self) {
+        if let Self::Spec(_) = self {
+            let Self::Spec(spec) = mem::replace(self, Self::Completed) else {
+                unreachable!()
+            };
+            *self = Self::GaveUp(spec);
+        }
+    }
+
+    /// Turn the item back into a spec if it has given up.
+    pub(super) fn retry(&mut self) {
+        if let Self::GaveUp(_) = self {
+            let Self::GaveUp(spec) = mem::replace(self, Self::Completed) else {
+                unreachable!()
+            };
+            *self = Self::Spec(spec);
+        }
+    }
+
+    /// Poll the item if it is starting or supervised, returning ready if it's state has changed.
+    /// If the supervisee has completed, this returns it's exit-value.
+    ///
+    /// If the supervisee panics, the item fails with [`SuperviseePanicked`].
+    pub(super) fn poll_change(&mut self, cx: &mut Context) -> Poll<Option<ExitValue>> {
+        let mut exit_value = None;
+        let changed = match self {
+            Self::StartFut(start_fut) => match ready!(start_fut.poll_unpin(cx)) {
+                Ok((supervisee, ())) => Self::Supervisee(supervisee),
+                Err(StartError::StartFailed(spec)) => Self::Spec(spec),
+                Err(StartError::Fatal(e)) => Self::Irrecoverable(e),
+                Err(StartError::Completed) => Self::Completed,
+            },
+            Self::Supervisee(supervisee) => {
+                let poll = panic::catch_unwind(AssertUnwindSafe(|| {
+                    Pin::new(&mut *supervisee).poll_supervise(cx)
+                }));
+                match poll {
+                    Ok(poll) => match ready!(poll) {
+                        Ok(Some(spec)) => Self::Spec(spec),
+                        Ok(None) => {
+                            exit_value = Pin::new(supervisee).take_exit_value();
+                            Self::Completed
+                        }
+                        Err(e) => Self::Irrecoverable(e),
+                    },
+                    Err(payload) => Self::Irrecoverable(Box::new(SuperviseePanicked::new(payload))),
+                }
+            }
+            _ => return Poll::Pending,
+        };
+        *self = changed;
+        Poll::Ready(exit_value)
+    }
+
+    /// Take the [`BackoffReset`] of the item if it is supervised, tagged with the child.
+    pub(super) fn take_backoff_reset(&mut self, child: usize) -> Option<BackoffReset> {
+        let Self::Supervisee(supervisee) = self else {
+            return None;
+        };
+        let reset = Pin::new(supervisee).take_backoff_reset()?;
+        Some(BackoffReset {
+            child: Some(child),
+            ..reset
+        })
+    }
+
+    pub(super) fn halt(&mut self) {
+        if let Self::Supervisee(supervisee) = self {
+            Pin::new(supervisee).halt()
+        }
+    }
+
+    pub(super) fn abort(&mut self) {
+        if let Self::Supervisee(supervisee) = self {
+            Pin::new(supervisee).abort()
+        }
+    }
+
+    /// Drop the start-future of the item if it is starting.
+    ///
+    /// The spec of a dropped start-future is lost, so the item fails with [`StartAborted`].
+    pub(super) fn abort_start(&mut self) {
+        if let Self::StartFut(_) = self {
+            *self = Self::Irrecoverable(Box::new(StartAborted))
+        }
+    }
+
+    pub(super) fn shutdown_time(&self) -> Option<Duration> {
+        match self {
+            Self::Supervisee(supervisee) => Some(Pin::new(supervisee).shutdown_time()),
+            _ => None,
+        }
+    }
+
+    pub(super) fn is_starting(&self) -> bool {
+        matches!(self, Self::StartFut(_))
+    }
+
+    pub(super) fn is_started(&self) -> bool {
+        matches!(self, Self::Supervisee(_))
+    }
+
+    pub(super) fn kind(&self) -> ChildKind {
+        match self {
+            Self::Spec(_) => ChildKind::Spec,
+            Self::StartFut(_) => ChildKind::StartFut,
+            Self::Supervisee(_) => ChildKind::Supervisee,
+            Self::Irrecoverable(_) => ChildKind::Irrecoverable,
+            Self::GaveUp(_) => ChildKind::GaveUp,
+            Self::Completed => ChildKind::Completed,
+        }
+    }
+
+    pub(super) fn is_running(&self) -> bool {
+        matches!(self, Self::StartFut(_) | Self::Supervisee(_))
+    }
+}
+
+/// List the actors of all running children, named by their index or id.
+pub(super) fn list_items<'a>(
+    items: impl IntoIterator<Item = (usize, &'a OneForOneItem)>,
+    status: ActorStatus,
+) -> Vec<ActorInfo> {
+    items
+        .into_iter()
+        .flat_map(|(child, item)| match item {
+            OneForOneItem::Supervisee(supervisee) => {
+                supervisee.list_child(child.to_string(), status)
+            }
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// A start-future that runs on it's own task, and is aborted when dropped.
+struct SpawnedStart(JoinHandle<StartResult<BoxSpec>>);
+
+impl SpawnedStart {
+    fn new(semaphore: Arc<Semaphore>, start_fut: BoxFuture<'static, StartResult<BoxSpec>>) -> Self {
+        Self(tokio::spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            start_fut.await
+        }))
+    }
+}
+
+impl Future for SpawnedStart {
+    type Output = StartResult<BoxSpec>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0
+            .poll_unpin(cx)
+            .map(|res| res.unwrap_or_else(|e| Err(StartError::Fatal(Box::new(e)))))
+    }
+}
+
+impl Drop for SpawnedStart {
+    fn drop(&mut self) {
+        self.0.abort()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::{future::poll_fn, StreamExt};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn spec(restarts: u32) -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |_halter: Halter, restarts: u32| async move { restarts },
+            |exit: Result<u32, ExitError>| async move {
+                match exit {
+                    Ok(0) => Ok(None),
+                    Ok(restarts) => Ok(Some(restarts - 1)),
+                    Err(e) => Err(Box::new(e) as FatalError),
+                }
+            },
+            restarts,
+        )
+    }
+
+    fn halter_spec() -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+    }
+
+    #[tokio::test]
+    async fn empty_tree_is_completed() {
+        let spec = OneForOneSpec::new();
+        assert!(spec.is_empty());
+        let res = spec.start_supervised().await;
+        assert!(matches!(res, Err(StartError::Completed)));
+    }
+
+    #[tokio::test]
+    async fn tree_starts_without_completed_children() {
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_spec(OneForOneSpec::new())
+            .with_spec(halter_spec())
+            .start_supervised()
+            .await
+            .unwrap();
+        assert_eq!(
+            supervisee.children().collect::<Vec<_>>(),
+            vec![ChildKind::Completed, ChildKind::Supervisee]
+        );
+
+        let res = OneForOneSpec::new()
+            .with_spec(OneForOneSpec::new())
+            .with_spec(OneForOneSpec::new())
+            .start_supervised()
+            .await;
+        assert!(matches!(res, Err(StartError::Completed)));
+    }
+
+    #[tokio::test]
+    async fn tree_completes_after_children_complete() {
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_spec(spec(0))
+            .with_spec(spec(2))
+            .start_supervised()
+            .await
+            .unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn poll_budget_yields() {
+        let spec = OneForOneSpec::new()
+            .with_poll_budget(2)
+            .with_spec(halter_spec())
+            .with_spec(halter_spec())
+            .with_spec(halter_spec());
+        let mut start = spec.start_supervised();
+        assert!(futures::poll!(&mut start).is_pending());
+        let (supervisee, ()) = start.await.unwrap();
+        assert!(supervisee.children().all(|kind| kind == ChildKind::Supervisee));
+    }
+
+    #[tokio::test]
+    async fn poll_budget_of_one_restarts_exited_children() {
+        let addresses = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut spec = OneForOneSpec::new().with_poll_budget(1);
+        for _ in 0..2 {
+            let addresses = addresses.clone();
+            spec.add_spec(halter_spec().on_start(move |address| {
+                addresses.lock().unwrap().push(address);
+            }));
+        }
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        addresses.lock().unwrap()[0].halt();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let mut supervisee = Box::pin(supervisee);
+        for _ in 0..10 {
+            let poll = futures::poll!(poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)));
+            assert!(poll.is_pending());
+            tokio::task::yield_now().await;
+        }
+        assert!(supervisee.children().all(|kind| kind == ChildKind::Supervisee));
+        assert_eq!(addresses.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn burst_of_exits_is_handled_in_one_poll() {
+        let addresses = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut spec = OneForOneSpec::new();
+        for _ in 0..3 {
+            let addresses = addresses.clone();
+            spec.add_spec(halter_spec().on_start(move |address| {
+                addresses.lock().unwrap().push(address);
+            }));
+        }
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        for address in addresses.lock().unwrap().drain(..) {
+            address.halt();
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let mut supervisee = Box::pin(supervisee);
+        let poll = futures::poll!(poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)));
+        assert!(poll.is_pending());
+        let actors = supervisee.as_ref().list_actors().unwrap();
+        assert!(actors.iter().all(|actor| actor.restart_count == 1));
+        assert_eq!(addresses.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn spawned_starts() {
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_spawned_starts(1)
+            .with_spec(halter_spec())
+            .with_spec(halter_spec())
+            .start_supervised()
+            .await
+            .unwrap();
+        assert!(supervisee
+            .children()
+            .all(|kind| kind == ChildKind::Supervisee));
+        let mut supervisee = Box::pin(supervisee);
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(exit.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn restart_limit_exits_with_spec() {
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_limiter(RestartLimiter::new(1, Duration::from_secs(10)))
+            .with_spec(spec(5))
+            .start_supervised()
+            .await
+            .unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.children().collect::<Vec<_>>(), vec![ChildKind::Spec]);
+        assert!(spec.children.iter().all(|child| !child.limiter.triggered()));
+    }
+
+    #[tokio::test]
+    async fn failed_start_aborts_pending_starts() {
+        let failing =
+            SharedResourceSpec::new(|| async { Err::<(), _>("unavailable") }, |()| halter_spec())
+                .once();
+        let pending =
+            SharedResourceSpec::new(futures::future::pending::<Result<(), &'static str>>, |()| {
+                halter_spec()
+            });
+        let res = tokio::time::timeout(
+            Duration::from_secs(1),
+            OneForOneSpec::new()
+                .with_spec(pending)
+                .with_spec(failing)
+                .start_supervised(),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(res, Err(StartError::Fatal(_))));
+    }
+
+    #[tokio::test]
+    async fn halted_tree_exits() {
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_spec(halter_spec())
+            .start_supervised()
+            .await
+            .unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(exit.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn child_kinds() {
+        let spec = OneForOneSpec::new()
+            .with_spec(halter_spec())
+            .with_spec(spec(0));
+        assert_eq!(spec.len(), 2);
+        assert_eq!(
+            spec.children().collect::<Vec<_>>(),
+            vec![ChildKind::Spec, ChildKind::Spec]
+        );
+
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert_eq!(
+            exit.unwrap().unwrap().children().collect::<Vec<_>>(),
+            vec![ChildKind::Spec, ChildKind::Completed]
+        );
+    }
+
+    #[tokio::test]
+    async fn heterogeneous_exit_types() {
+        let string_spec = SpawnSpec::new(
+            |_halter: Halter, restarted: bool| async move { format!("restarted: {restarted}") },
+            |exit: Result<String, ExitError>| async move {
+                match exit {
+                    Ok(s) if s == "restarted: false" => Ok(Some(true)),
+                    Ok(_) => Ok(None),
+                    Err(e) => Err(Box::new(e) as FatalError),
+                }
+            },
+            false,
+        );
+        let stream_spec = StreamActorSpec::new(futures::stream::iter([(); 3]), |()| async move {
+            Ok::<_, ()>(())
+        });
+
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_spec(spec(1))
+            .with_spec(string_spec)
+            .with_spec(stream_spec)
+            .start_supervised()
+            .await
+            .unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stop_child_keeps_siblings_running() {
+        let addresses = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = addresses.clone();
+        let spec = OneForOneSpec::new()
+            .with_limiter(RestartLimiter::new(1, Duration::from_secs(10)))
+            .with_spec(halter_spec().on_start(move |address| {
+                recorded.lock().unwrap().push(address);
+            }))
+            .with_spec_on_limit(spec(3), LimitAction::StopChild);
+        let mut events = spec.events();
+        let mut supervisee = Box::pin(spec.start_supervised().await.unwrap().0);
+
+        poll_fn(|cx| {
+            assert!(supervisee.as_mut().poll_supervise(cx).is_pending());
+            match supervisee.children().nth(1) == Some(ChildKind::GaveUp) {
+                true => Poll::Ready(()),
+                false => Poll::Pending,
+            }
+        })
+        .await;
+        assert_eq!(
+            events.try_recv().unwrap().kind,
+            SupervisionEventKind::ChildGaveUp(1)
+        );
+        assert_eq!(supervisee.children().next(), Some(ChildKind::Supervisee));
+
+        // The sibling is still within it's own limit after the other child gave up.
+        addresses.lock().unwrap()[0].halt();
+        poll_fn(|cx| {
+            assert!(supervisee.as_mut().poll_supervise(cx).is_pending());
+            match addresses.lock().unwrap().len() {
+                2 => Poll::Ready(()),
+                _ => Poll::Pending,
+            }
+        })
+        .await;
+        assert_eq!(
+            supervisee.children().collect::<Vec<_>>(),
+            vec![ChildKind::Supervisee, ChildKind::GaveUp]
+        );
+
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert_eq!(
+            exit.unwrap().unwrap().children().collect::<Vec<_>>(),
+            vec![ChildKind::Spec, ChildKind::Spec]
+        );
+    }
+
+    #[tokio::test]
+    async fn escalate_exits_with_error() {
+        let spec = OneForOneSpec::new()
+            .with_limiter(RestartLimiter::new(0, Duration::from_secs(10)))
+            .with_spec(halter_spec())
+            .with_spec_on_limit(spec(3), LimitAction::Escalate);
+        let error = match spec.start_supervised().await {
+            Ok((supervisee, ())) => supervisee.supervise().await.unwrap_err(),
+            Err(StartError::Fatal(e)) => e,
+            Err(_) => panic!("The tree should fail"),
+        };
+        assert_eq!(
+            error.downcast_ref::<RestartLimitExceeded>(),
+            Some(&RestartLimitExceeded { child: 1 })
+        );
+    }
+
+    #[tokio::test]
+    async fn global_intensity_escalates_flapping_children() {
+        // Every child is restarted twice, which is within it's own limit.
+        let flapping = || {
+            (0..4).fold(
+                OneForOneSpec::new().with_limiter(RestartLimiter::new(2, Duration::from_secs(10))),
+                |tree, _| tree.with_spec(spec(2)),
+            )
+        };
+        let (supervisee, ()) = flapping().start_supervised().await.unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+
+        let (supervisee, ()) = flapping()
+            .with_global_intensity(8, Duration::from_secs(10))
+            .start_supervised()
+            .await
+            .unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+
+        let spec = flapping().with_global_intensity(7, Duration::from_secs(10));
+        let error = match spec.start_supervised().await {
+            Ok((supervisee, ())) => supervisee.supervise().await.unwrap_err(),
+            Err(StartError::Fatal(e)) => e,
+            Err(_) => panic!("The tree should fail"),
+        };
+        assert!(error.downcast_ref::<GlobalIntensityExceeded>().is_some());
+    }
+
+    #[tokio::test]
+    async fn global_intensity_ignores_children_that_give_up() {
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_limiter(RestartLimiter::new(1, Duration::from_secs(10)))
+            .with_global_intensity(1, Duration::from_secs(10))
+            .with_spec_on_limit(spec(5), LimitAction::StopChild)
+            .with_spec(spec(0))
+            .start_supervised()
+            .await
+            .unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(
+            spec.children().collect::<Vec<_>>(),
+            vec![ChildKind::Spec, ChildKind::Completed]
+        );
+    }
+
+    #[tokio::test]
+    async fn children_are_halted_by_shutdown_priority() {
+        let halted = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recording_spec = |child: usize, exit_delay: u64| {
+            let halted = halted.clone();
+            SpawnSpec::new(
+                move |halter: Halter, ()| {
+                    let halted = halted.clone();
+                    async move {
+                        halter.await;
+                        sleep(Duration::from_millis(exit_delay)).await;
+                        halted.lock().unwrap().push(child);
+                    }
+                },
+                |_exit| async move { Ok(Some(())) },
+                (),
+            )
+        };
+        let spec = OneForOneSpec::new()
+            .with_spec(recording_spec(0, 0))
+            .with_spec(recording_spec(1, 20))
+            .with_spec(recording_spec(2, 10))
+            .with_spec(recording_spec(3, 20))
+            .with_shutdown_priority(1, 2)
+            .with_shutdown_priority(2, 1)
+            .with_shutdown_priority(3, 2);
+
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(exit.unwrap().is_some());
+
+        let mut halted = halted.lock().unwrap().clone();
+        halted[..2].sort();
+        assert_eq!(halted, vec![1, 3, 2, 0]);
+    }
+
+    #[tokio::test]
+    async fn transfer_child_between_trees() {
+        let starts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = starts.clone();
+        let child = SpawnSpec::new(
+            move |halter: Halter, ()| {
+                counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                halter
+            },
+            |_exit| async move { Ok(Some(())) },
+            (),
+        );
+
+        let (mut source, ()) = OneForOneSpec::new()
+            .with_spec(halter_spec())
+            .with_spec_on_limit(child, LimitAction::StopChild)
+            .with_spec(halter_spec())
+            .start_supervised()
+            .await
+            .unwrap();
+        let (mut target, ()) = OneForOneSpec::new()
+            .with_spec(halter_spec())
+            .start_supervised()
+            .await
+            .unwrap();
+
+        assert!(source.extract_child(3).is_none());
+        let running = source.extract_child(1).unwrap();
+        assert_eq!(running.on_limit_exceeded(), LimitAction::StopChild);
+        assert!(source.extract_child(1).is_none());
+        assert_eq!(source.child_ids().collect::<Vec<_>>(), vec![0, 2]);
+        let names = Pin::new(&source)
+            .list_actors()
+            .unwrap()
+            .into_iter()
+            .map(|actor| actor.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["0", "2"]);
+
+        assert_eq!(target.adopt_child(running), 1);
+        assert_eq!(
+            target.children().collect::<Vec<_>>(),
+            vec![ChildKind::Supervisee; 2]
+        );
+
+        Pin::new(&mut target).halt();
+        let spec = target.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.len(), 2);
+        assert_eq!(starts.load(std::sync::atomic::Ordering::SeqCst), 1);
+        drop(source);
+    }
+
+    #[derive(Debug)]
+    struct PanickingSpec;
+
+    #[async_trait]
+    impl Specification for PanickingSpec {
+        type Ref = ();
+        type Supervisee = PanickingSupervisee;
+
+        async fn start_supervised(self) -> StartResult<Self> {
+            Ok((PanickingSupervisee, ()))
+        }
+    }
+
+    #[derive(Debug)]
+    struct PanickingSupervisee;
+
+    impl Supervisee for PanickingSupervisee {
+        type Spec = PanickingSpec;
+
+        fn poll_supervise(
+            self: Pin<&mut Self>,
+            _cx: &mut Context,
+        ) -> Poll<SupervisionResult<Self::Spec>> {
+            panic!("bug in supervisee")
+        }
+
+        fn shutdown_time(self: Pin<&Self>) -> Duration {
+            Duration::ZERO
+        }
+
+        fn halt(self: Pin<&mut Self>) {}
+
+        fn abort(self: Pin<&mut Self>) {}
+    }
+
+    #[tokio::test]
+    async fn panicking_supervisee_fails_without_unwinding() {
+        let spec = OneForOneSpec::new()
+            .with_spec(halter_spec())
+            .with_spec(PanickingSpec);
+        let Err(StartError::Fatal(error)) = spec.start_supervised().await else {
+            panic!("Tree should have failed")
+        };
+        let panicked = error.downcast_ref::<SuperviseePanicked>().unwrap();
+        assert_eq!(panicked.message.as_deref(), Some("bug in supervisee"));
+    }
+
+    #[derive(Debug)]
+    struct SlowSpec(Duration);
+
+    #[async_trait]
+    impl Specification for SlowSpec {
+        type Ref = ();
+        type Supervisee = PendingSupervisee;
+
+        async fn start_supervised(self) -> StartResult<Self> {
+            sleep(self.0).await;
+            Ok((PendingSupervisee, ()))
+        }
+    }
+
+    #[derive(Debug)]
+    struct PendingSupervisee;
+
+    impl Supervisee for PendingSupervisee {
+        type Spec = SlowSpec;
+
+        fn poll_supervise(
+            self: Pin<&mut Self>,
+            _cx: &mut Context,
+        ) -> Poll<SupervisionResult<Self::Spec>> {
+            Poll::Pending
+        }
+
+        fn shutdown_time(self: Pin<&Self>) -> Duration {
+            Duration::ZERO
+        }
+
+        fn halt(self: Pin<&mut Self>) {}
+
+        fn abort(self: Pin<&mut Self>) {}
+    }
+
+    #[tokio::test]
+    async fn startup_progress_is_reported() {
+        let (tx, mut rx) = watch::channel(StartupProgress::default());
+        let spec = OneForOneSpec::new()
+            .with_spec(SlowSpec(Duration::ZERO))
+            .with_spec(SlowSpec(Duration::from_millis(100)))
+            .with_spec(SlowSpec(Duration::from_millis(30)))
+            .with_startup_progress(tx);
+        let start = tokio::spawn(spec.start_supervised());
+
+        let mut reported = Vec::new();
+        while rx.changed().await.is_ok() {
+            reported.push(*rx.borrow_and_update());
+            if reported.last().unwrap().pending == 0 {
+                break;
+            }
+        }
+        let progress = |started, pending, slowest| StartupProgress {
+            total: 3,
+            started,
+            failed: 0,
+            pending,
+            slowest,
+        };
+        assert!(reported.contains(&progress(2, 1, Some(1))));
+        assert_eq!(reported.last(), Some(&progress(3, 0, None)));
+        let (_supervisee, ()) = start.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn reported_errors_are_events_without_restarting() {
+        let spec = OneForOneSpec::new();
+        let mut events = spec.events();
+        let reporter = spec.error_reporter();
+        let spec = spec.with_spec(SpawnSpec::new(
+            |halter: Halter, reporter: ErrorReporter| async move {
+                reporter.report_error("cache miss");
+                halter.await;
+                reporter
+            },
+            |exit: Result<ErrorReporter, ExitError>| async move { Ok(Some(exit.unwrap())) },
+            reporter,
+        ));
+        let (mut supervisee, ()) = spec.start_supervised().await.unwrap();
+
+        let SupervisionEventKind::ChildError(error) = events.next().await.unwrap().kind else {
+            panic!("Expected a child-error")
+        };
+        assert_eq!(error.error, "cache miss");
+        assert!(error.actor_id.is_some());
+        assert!(
+            poll_fn(|cx| Poll::Ready(Pin::new(&mut supervisee).poll_supervise(cx)))
+                .await
+                .is_pending()
+        );
+        assert_eq!(supervisee.children().next(), Some(ChildKind::Supervisee));
+    }
+
+    #[tokio::test]
+    async fn children_beyond_max_are_rejected() {
+        let mut spec = OneForOneSpec::new().with_max_children(2);
+        assert!(spec.try_add_spec(halter_spec()).is_ok());
+        assert!(spec.try_add_spec(halter_spec()).is_ok());
+        let Err(error) = spec.try_add_spec(halter_spec()) else {
+            panic!("The tree should be full")
+        };
+        assert_eq!(error.max, 2);
+        assert_eq!(spec.len(), 2);
+
+        let (mut source, ()) = OneForOneSpec::new()
+            .with_spec(halter_spec())
+            .start_supervised()
+            .await
+            .unwrap();
+        let (mut target, ()) = spec.start_supervised().await.unwrap();
+        assert_eq!((target.len(), target.max_children()), (2, Some(2)));
+
+        let running = source.extract_child(0).unwrap();
+        let running = target.try_adopt_child(running).unwrap_err().child;
+        assert_eq!(target.len(), 2);
+        assert_eq!(source.try_adopt_child(running).unwrap(), 1);
+        assert_eq!(source.len(), 1);
+    }
+
+    /// A spec that exits once `exit` is set, after which it restarts slower than the
+    /// shutdown-time.
+    #[derive(Debug)]
+    struct SlowRestartSpec {
+        exit: Arc<AtomicBool>,
+        restarted: bool,
+    }
+
+    #[async_trait]
+    impl Specification for SlowRestartSpec {
+        type Ref = ();
+        type Supervisee = SlowRestartSupervisee;
+
+        async fn start_supervised(self) -> StartResult<Self> {
+            if self.restarted {
+                sleep(SHUTDOWN_MARGIN * 5).await;
+            }
+            Ok((SlowRestartSupervisee(Some(self), false), ()))
+        }
+    }
+
+    /// Exits once it is halted, or once `exit` is set if it has not been restarted.
+    #[derive(Debug)]
+    struct SlowRestartSupervisee(Option<SlowRestartSpec>, bool);
+
+    impl Supervisee for SlowRestartSupervisee {
+        type Spec = SlowRestartSpec;
+
+        fn poll_supervise(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context,
+        ) -> Poll<SupervisionResult<Self::Spec>> {
+            let spec = self.0.as_mut().unwrap();
+            if !spec.restarted && spec.exit.load(Ordering::SeqCst) {
+                spec.restarted = true;
+                Poll::Ready(Ok(self.0.take()))
+            } else if self.1 {
+                Poll::Ready(Ok(self.0.take()))
+            } else {
+                Poll::Pending
+            }
+        }
+
+        fn shutdown_time(self: Pin<&Self>) -> Duration {
+            Duration::ZERO
+        }
+
+        fn halt(mut self: Pin<&mut Self>) {
+            self.1 = true;
+        }
+
+        fn abort(mut self: Pin<&mut Self>) {
+            self.1 = true;
+        }
+    }
+
+    #[tokio::test]
+    async fn halt_while_restarting_keeps_spec() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let (supervisee, ()) = OneForOneSpec::new()
+            .with_spec(SlowRestartSpec {
+                exit: exit.clone(),
+                restarted: false,
+            })
+            .start_supervised()
+            .await
+            .unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        exit.store(true, Ordering::SeqCst);
+        let poll = futures::poll!(poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)));
+        assert!(poll.is_pending());
+        assert_eq!(supervisee.children().next(), Some(ChildKind::StartFut));
+
+        // The shutdown-time expires before the child has restarted.
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert_eq!(
+            exit.unwrap().unwrap().children().collect::<Vec<_>>(),
+            vec![ChildKind::Spec]
+        );
+    }
+
+    #[derive(Debug)]
+    struct UnboundedSpec;
+
+    #[async_trait]
+    impl Specification for UnboundedSpec {
+        type Ref = ();
+        type Supervisee = UnboundedSupervisee;
+
+        async fn start_supervised(self) -> StartResult<Self> {
+            Ok((UnboundedSupervisee(false), ()))
+        }
+    }
+
+    /// A supervisee that is never aborted, and exits as soon as it is halted.
+    #[derive(Debug)]
+    struct UnboundedSupervisee(bool);
+
+    impl Supervisee for UnboundedSupervisee {
+        type Spec = UnboundedSpec;
+
+        fn poll_supervise(
+            self: Pin<&mut Self>,
+            _cx: &mut Context,
+        ) -> Poll<SupervisionResult<Self::Spec>> {
+            match self.0 {
+                true => Poll::Ready(Ok(None)),
+                false => Poll::Pending,
+            }
+        }
+
+        fn shutdown_time(self: Pin<&Self>) -> Duration {
+            Duration::MAX
+        }
+
+        fn halt(mut self: Pin<&mut Self>) {
+            self.0 = true;
+        }
+
+        fn abort(self: Pin<&mut Self>) {}
+    }
+
+    #[tokio::test]
+    async fn unbounded_shutdown_time_arms_no_timer() {
+        let (mut supervisee, ()) = OneForOneSpec::new()
+            .with_spec(UnboundedSpec)
+            .start_supervised()
+            .await
+            .unwrap();
+        assert_eq!(Pin::new(&supervisee).shutdown_time(), Duration::MAX);
+
+        Pin::new(&mut supervisee).halt();
+        assert!(!supervisee.shutdown_timer.as_ref().unwrap().is_armed());
+        supervisee.supervise().await.unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] for a child that may be absent, for example one that is only present
+/// depending on the configuration.
+///
+/// When the inner spec is `None`, starting completes immediately with [`StartError::Completed`],
+/// so that the child never runs and is never restarted. Otherwise the inner spec is started and
+/// supervised as usual. This keeps the list of children uniform, while some of them are optional.
+///
+/// An absent child without an inner spec-type can be created with [`OptionalSpec::none`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let debug_endpoint = false;
+/// let spec = OneForOneSpec::new()
+///     .with_spec(OptionalSpec::none())
+///     .with_spec(OptionalSpec::new(debug_endpoint.then(|| {
+///         SpawnSpec::new(
+///             |halter: Halter, ()| halter,
+///             |_exit| async move { Ok(None) },
+///             (),
+///         )
+///     })));
+/// assert!(matches!(spec.start_supervised().await, Err(StartError::Completed)));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OptionalSpec<S = AbsentSpec> {
+    spec: Option<S>,
+}
+
+impl OptionalSpec {
+    /// Create a new spec for a child that is absent.
+    pub fn none() -> Self {
+        Self { spec: None }
+    }
+}
+
+impl<S: Specification> OptionalSpec<S> {
+    /// Create a new spec, which is absent if the inner spec is `None`.
+    pub fn new(spec: Option<S>) -> Self {
+        Self { spec }
+    }
+
+    /// Create a new spec for a child that is present.
+    pub fn some(spec: S) -> Self {
+        Self { spec: Some(spec) }
+    }
+
+    /// Whether the child is present.
+    pub fn is_some(&self) -> bool {
+        self.spec.is_some()
+    }
+
+    /// Convert this back into the inner spec.
+    pub fn into_inner(self) -> Option<S> {
+        self.spec
+    }
+}
+
+impl<S: Specification> From<Option<S>> for OptionalSpec<S> {
+    fn from(spec: Option<S>) -> Self {
+        Self::new(spec)
+    }
+}
+
+#[async_trait]
+impl<S: Specification> Specification for OptionalSpec<S> {
+    type Ref = S::Ref;
+    type Supervisee = OptionalSupervisee<S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        match self.spec {
+            Some(spec) => match spec.start_supervised().await {
+                Ok((supervisee, reference)) => Ok((OptionalSupervisee { supervisee }, reference)),
+                Err(e) => Err(e.map(OptionalSpec::some)),
+            },
+            None => Err(StartError::Completed),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        match &self.spec {
+            Some(spec) => spec.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The [`Supervisee`] of an [`OptionalSpec`] that is present.
+#[pin_project]
+#[derive(Debug)]
+pub struct OptionalSupervisee<S: Specification> {
+    #[pin]
+    supervisee: S::Supervisee,
+}
+
+impl<S: Specification> Supervisee for OptionalSupervisee<S> {
+    type Spec = OptionalSpec<S>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        self.project()
+            .supervisee
+            .poll_supervise(cx)
+            .map(|res| res.map(|spec| spec.map(OptionalSpec::some)))
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+/// The inner spec of an [`OptionalSpec::none`], which can never be created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AbsentSpec {}
+
+#[async_trait]
+impl Specification for AbsentSpec {
+    type Ref = ();
+    type Supervisee = AbsentSpec;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        match self {}
+    }
+}
+
+impl Supervisee for AbsentSpec {
+    type Spec = AbsentSpec;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        _cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        match *self {}
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        match *self {}
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        match *self {}
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        match *self {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn absent_child_completes_while_present_child_runs() {
+        let spec = OneForOneSpec::new()
+            .with_spec(OptionalSpec::none())
+            .with_spec(OptionalSpec::some(SpawnSpec::new(
+                |halter: Halter, ()| halter,
+                |_exit| async move { Ok(Some(())) },
+                (),
+            )));
+        let (mut supervisee, ()) = spec.start_supervised().await.unwrap();
+        assert_eq!(
+            supervisee.children().collect::<Vec<_>>(),
+            vec![ChildKind::Completed, ChildKind::Supervisee]
+        );
+
+        Pin::new(&mut supervisee).halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(
+            spec.children().collect::<Vec<_>>(),
+            vec![ChildKind::Completed, ChildKind::Spec]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::FutureExt;
+use std::{
+    fmt::Debug,
+    io,
+    pin::Pin,
+    process::ExitStatus,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    process::{Child, ChildStderr, ChildStdin, ChildStdout, Command},
+    sync::mpsc,
+    task::JoinHandle,
+};
+
+/// A [`Specification`] that supervises an OS-process instead of an actor.
+///
+/// Every time the spec is started, a new [`Command`] is built and spawned. When the process exits
+/// with a non-zero exit-status (or because of a signal) it is restarted, while a successful exit
+/// completes the spec. Halting the supervisee sends a `SIGTERM` to the process, and aborting it
+/// sends a `SIGKILL`; the [`Supervisee::shutdown_time`] is the grace-period between the two. On
+/// platforms without signals, or if the `SIGTERM` can't be sent, halting kills the process
+/// directly.
+///
+/// The [`ProcessHandles`] give access to the piped stdin, stdout and stderr of the process.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use tokio::process::Command;
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let spec = ProcessSpec::new(|| Command::new("true"));
+/// # #[cfg(unix)]
+/// # {
+/// let (supervisee, _handles) = spec.start_supervised().await.unwrap();
+/// assert!(supervisee.supervise().await.unwrap().is_none());
+/// # }
+/// # }
+/// ```
+pub struct ProcessSpec {
+    command: Arc<dyn Fn() -> Command + Send + Sync>,
+    shutdown_time: Duration,
+}
+
+impl ProcessSpec {
+    /// Create a new spec which spawns the command built by `command` every time it is started.
+    pub fn new(command: impl Fn() -> Command + Send + Sync + 'static) -> Self {
+        Self {
+            command: Arc::new(command),
+            shutdown_time: get_default_shutdown_time(),
+        }
+    }
+
+    /// Set the time the process is given to exit after the `SIGTERM`, before it is killed.
+    pub fn with_shutdown_time(mut self, shutdown_time: Duration) -> Self {
+        self.shutdown_time = shutdown_time;
+        self
+    }
+}
+
+impl Debug for ProcessSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessSpec")
+            .field("shutdown_time", &self.shutdown_time)
+            .finish()
+    }
+}
+
+/// The reference of a [`ProcessSpec`], containing the handles of the spawned process.
+///
+/// The stdio-handles are only present if they were configured as piped in the [`Command`].
+#[derive(Debug)]
+pub struct ProcessHandles {
+    /// The OS-assigned process-id.
+    pub id: Option<u32>,
+    /// The stdin of the process.
+    pub stdin: Option<ChildStdin>,
+    /// The stdout of the process.
+    pub stdout: Option<ChildStdout>,
+    /// The stderr of the process.
+    pub stderr: Option<ChildStderr>,
+}
+
+#[async_trait]
+impl Specification for ProcessSpec {
+    type Ref = ProcessHandles;
+    type Supervisee = ProcessSupervisee;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let mut command = (self.command)();
+        command.kill_on_drop(true);
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => return Err(StartError::Fatal(Box::new(e))),
+        };
+        let handles = ProcessHandles {
+            id: child.id(),
+            stdin: child.stdin.take(),
+            stdout: child.stdout.take(),
+            stderr: child.stderr.take(),
+        };
+        let (signals, receiver) = mpsc::unbounded_channel();
+
+        Ok((
+            ProcessSupervisee {
+                spec: Some(self),
+                signals,
+                exit: tokio::spawn(run_process(child, receiver)),
+            },
+            handles,
+        ))
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`ProcessSpec`].
+#[derive(Debug)]
+pub struct ProcessSupervisee {
+    spec: Option<ProcessSpec>,
+    signals: mpsc::UnboundedSender<Signal>,
+    exit: JoinHandle<io::Result<ExitStatus>>,
+}
+
+#[derive(Debug)]
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+impl Supervisee for ProcessSupervisee {
+    type Spec = ProcessSpec;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let exit = futures::ready!(self.exit.poll_unpin(cx));
+        let spec = self.spec.take().expect("Polled after completion");
+        Poll::Ready(match exit {
+            Ok(Ok(status)) if status.success() => Ok(None),
+            Ok(Ok(_status)) => Ok(Some(spec)),
+            Ok(Err(e)) => Err(Box::new(e)),
+            Err(e) => Err(Box::new(e)),
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.spec
+            .as_ref()
+            .map(|spec| spec.shutdown_time)
+            .unwrap_or_default()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        let _ = self.signals.send(Signal::Terminate);
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        let _ = self.signals.send(Signal::Kill);
+    }
+}
+
+/// Wait for the process to exit, while passing on the signals. The process is killed once the
+/// supervisee has been dropped.
+async fn run_process(
+    mut child: Child,
+    mut signals: mpsc::UnboundedReceiver<Signal>,
+) -> io::Result<ExitStatus> {
+    loop {
+        tokio::select! {
+            status = child.wait() => return status,
+            signal = signals.recv() => match signal {
+                Some(Signal::Terminate) => terminate(&mut child),
+                Some(Signal::Kill) => {
+                    let _ = child.start_kill();
+                }
+                None => {
+                    let _ = child.start_kill();
+                    return child.wait().await;
+                }
+            }
+        }
+    }
+}
+
+/// Send a `SIGTERM` to the process, or kill it if it can't be signalled.
+#[cfg(unix)]
+fn terminate(child: &mut Child) {
+    // The id is only available while the process has not been reaped, so it can't be reused yet.
+    let Some(id) = child.id() else {
+        return;
+    };
+    if unsafe { libc::kill(id as libc::pid_t, libc::SIGTERM) } == -1 {
+        // A process that has already exited is reaped by `run_process`.
+        if io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH) {
+            let _ = child.start_kill();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(child: &mut Child) {
+    let _ = child.start_kill();
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    fn sleep(secs: &'static str) -> ProcessSpec {
+        ProcessSpec::new(move || {
+            let mut command = Command::new("sleep");
+            command.arg(secs);
+            command
+        })
+    }
+
+    #[tokio::test]
+    async fn successful_exit_completes() {
+        let (supervisee, handles) = sleep("0").start_supervised().await.unwrap();
+        assert!(handles.id.is_some());
+        assert!(supervisee.supervise().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn halted_process_is_restarted() {
+        let spec = sleep("10").with_shutdown_time(Duration::from_millis(100));
+        let (mut supervisee, _handles) = spec.start_supervised().await.unwrap();
+        assert_eq!(
+            Pin::new(&supervisee).shutdown_time(),
+            Duration::from_millis(100)
+        );
+
+        Pin::new(&mut supervisee).halt();
+        let exit = tokio::time::timeout(Duration::from_secs(1), supervisee.supervise())
+            .await
+            .unwrap();
+        let spec = exit.unwrap().unwrap();
+
+        let (mut supervisee, _handles) = spec.start_supervised().await.unwrap();
+        Pin::new(&mut supervisee).abort();
+        assert!(supervisee.supervise().await.unwrap().is_some());
+    }
+}
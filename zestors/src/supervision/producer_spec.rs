@@ -0,0 +1,229 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::Stream;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+/// A [`Specification`] of which the reference is a [`ProducerStream`] of the outputs that it's
+/// inner spec produces.
+///
+/// The inner spec is created with the [`mpsc::Sender`] of the outputs, which is usually passed on
+/// as the data of a [`SpawnSpec`]. External code consumes the outputs from the stream, while the
+/// inner spec is supervised and restarted as usual. Since the same sender is passed on to every
+/// restarted actor, the stream continues seamlessly with the outputs of the new instance. The
+/// reference of the inner spec is dropped.
+///
+/// Every start returns a stream of the same outputs, which can be retrieved beforehand with
+/// [`ProducerSpec::stream`]. The stream ends once all senders have been dropped, for example when
+/// the spec has completed.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use futures::StreamExt;
+/// use tokio::sync::mpsc;
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = ProducerSpec::new(8, |tx| {
+///     SpawnSpec::new(
+///         |_halter: Halter, tx: mpsc::Sender<u32>| async move {
+///             tx.send(10).await.unwrap();
+///             tx
+///         },
+///         |exit| async move { Ok(exit.ok()) },
+///         tx,
+///     )
+/// });
+///
+/// let (_supervisee, mut stream) = spec.start_supervised().await.unwrap();
+/// assert_eq!(stream.next().await, Some(10));
+/// # }
+/// ```
+pub struct ProducerSpec<S, Out> {
+    inner_spec: S,
+    stream: ProducerStream<Out>,
+}
+
+impl<S, Out> ProducerSpec<S, Out>
+where
+    S: Specification,
+    Out: Send + 'static,
+{
+    /// Create a new spec with an output-channel of the given capacity, of which the sender is
+    /// passed to `spec_fn` to create the inner spec.
+    pub fn new(capacity: usize, spec_fn: impl FnOnce(mpsc::Sender<Out>) -> S) -> Self {
+        let (tx, rx) = mpsc::channel(capacity);
+        Self {
+            inner_spec: spec_fn(tx),
+            stream: ProducerStream(Arc::new(Mutex::new(rx))),
+        }
+    }
+
+    /// Get the stream of outputs, which is the same one that is returned when the spec is
+    /// started.
+    pub fn stream(&self) -> ProducerStream<Out> {
+        self.stream.clone()
+    }
+}
+
+#[async_trait]
+impl<S, Out> Specification for ProducerSpec<S, Out>
+where
+    S: Specification,
+    Out: Send + 'static,
+{
+    type Ref = ProducerStream<Out>;
+    type Supervisee = ProducerSupervisee<S, Out>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let stream = self.stream;
+        match self.inner_spec.start_supervised().await {
+            Ok((supervisee, _reference)) => Ok((
+                ProducerSupervisee {
+                    supervisee,
+                    stream: stream.clone(),
+                },
+                stream,
+            )),
+            Err(e) => Err(e.map(|inner_spec| Self { inner_spec, stream })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.inner_spec.validate()
+    }
+}
+
+/// The [`Stream`] of outputs of a [`ProducerSpec`], which continues across restarts.
+///
+/// All clones share the same outputs, so that every output is received by only one of them. It
+/// is meant to be consumed by a single consumer.
+#[derive(Debug)]
+pub struct ProducerStream<Out>(Arc<Mutex<mpsc::Receiver<Out>>>);
+
+impl<Out> Clone for ProducerStream<Out> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Out> Stream for ProducerStream<Out> {
+    type Item = Out;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.lock().unwrap().poll_recv(cx)
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`ProducerSpec`].
+#[pin_project]
+pub struct ProducerSupervisee<S: Specification, Out> {
+    #[pin]
+    supervisee: S::Supervisee,
+    stream: ProducerStream<Out>,
+}
+
+impl<S, Out> Supervisee for ProducerSupervisee<S, Out>
+where
+    S: Specification,
+    Out: Send + 'static,
+{
+    type Spec = ProducerSpec<S, Out>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+        this.supervisee.poll_supervise(cx).map(|res| {
+            res.map(|spec| {
+                spec.map(|inner_spec| ProducerSpec {
+                    inner_spec,
+                    stream: this.stream.clone(),
+                })
+            })
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn stream_continues_across_restarts() {
+        let spec = ProducerSpec::new(1, |tx| {
+            SpawnSpec::new(
+                |_halter: Halter, (tx, n): (mpsc::Sender<u32>, u32)| async move {
+                    for n in n..n + 3 {
+                        tx.send(n).await.unwrap();
+                    }
+                    (tx, n + 3)
+                },
+                |exit: Result<_, ExitError>| async move {
+                    match exit.unwrap() {
+                        (tx, n) if n < 6 => Ok(Some((tx, n))),
+                        _ => Ok(None),
+                    }
+                },
+                (tx, 0),
+            )
+        });
+
+        let (supervisee, mut stream) = spec.start_supervised().await.unwrap();
+        let outputs = stream.by_ref().take(3).collect::<Vec<_>>().await;
+        assert_eq!(outputs, vec![0, 1, 2]);
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+
+        let (supervisee, _stream) = spec.start_supervised().await.unwrap();
+        let outputs = stream.by_ref().take(3).collect::<Vec<_>>().await;
+        assert_eq!(outputs, vec![3, 4, 5]);
+        assert!(supervisee.supervise().await.unwrap().is_none());
+        assert_eq!(stream.next().await, None);
+    }
+}
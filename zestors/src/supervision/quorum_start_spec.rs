@@ -0,0 +1,263 @@
+use super::one_for_one::OneForOneStartFut;
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::ready;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] that supervises a [`OneForOneSpec`], which has started once a quorum of
+/// it's children has started.
+///
+/// Starting succeeds as soon as `required` children are running. The other children keep on
+/// (re)starting in the background, and are supervised in the same way as the rest. Starting only
+/// fails once the quorum can no longer be reached, because too many children have completed,
+/// failed or given up. At that point the running children are shut down again.
+///
+/// Children that fail to start are restarted within their own copy of the [`RestartLimiter`] of
+/// the tree. Children that may never start should be added with [`LimitAction::StopChild`],
+/// otherwise they shut down the tree once they exceed the restart-limit.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let replica = || {
+///     SpawnSpec::new(
+///         |halter: Halter, ()| halter,
+///         |_exit| async move { Ok(Some(())) },
+///         (),
+///     )
+/// };
+/// let spec = QuorumStartSpec::new(
+///     2,
+///     OneForOneSpec::new()
+///         .with_spec(replica())
+///         .with_spec(replica())
+///         .with_spec(replica()),
+/// );
+/// let (supervisee, ()) = spec.start_supervised().await.unwrap();
+/// # drop(supervisee);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct QuorumStartSpec {
+    spec: OneForOneSpec,
+    required: usize,
+}
+
+impl QuorumStartSpec {
+    /// Create a new spec, which has started once `required` children of the tree have started.
+    ///
+    /// # Panics
+    /// Panics if `required` is `0`, or larger than the amount of children of the tree.
+    pub fn new(required: usize, spec: OneForOneSpec) -> Self {
+        assert!(required > 0, "required must be greater than 0");
+        assert!(
+            required <= spec.len(),
+            "required must not be greater than the amount of children"
+        );
+        Self { spec, required }
+    }
+
+    /// The amount of children that must start.
+    pub fn required(&self) -> usize {
+        self.required
+    }
+
+    /// The tree that is supervised.
+    pub fn spec(&self) -> &OneForOneSpec {
+        &self.spec
+    }
+
+    /// Convert this back into the tree.
+    pub fn into_spec(self) -> OneForOneSpec {
+        self.spec
+    }
+}
+
+#[async_trait]
+impl Specification for QuorumStartSpec {
+    type Ref = ();
+    type Supervisee = QuorumStartSupervisee;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let required = self.required;
+        match OneForOneStartFut::new(self.spec, Some(required)).await {
+            Ok((supervisee, ())) => Ok((
+                QuorumStartSupervisee {
+                    supervisee,
+                    required,
+                },
+                (),
+            )),
+            Err(e) => Err(e.map(|spec| Self { spec, required })),
+        }
+    }
+}
+
+/// The [`Supervisee`] of a [`QuorumStartSpec`].
+#[derive(Debug)]
+pub struct QuorumStartSupervisee {
+    supervisee: OneForOneSupervisee,
+    required: usize,
+}
+
+impl QuorumStartSupervisee {
+    /// The current [`ChildKind`] of every child, see [`OneForOneSpec::children`].
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.supervisee.children()
+    }
+}
+
+impl Supervisee for QuorumStartSupervisee {
+    type Spec = QuorumStartSpec;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let required = self.required;
+        let exit = ready!(Pin::new(&mut self.supervisee).poll_supervise(cx));
+        Poll::Ready(exit.map(|spec| spec.map(|spec| QuorumStartSpec { spec, required })))
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        Pin::new(&self.supervisee).shutdown_time()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        Pin::new(&mut self.supervisee).halt()
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        Pin::new(&mut self.supervisee).abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        Pin::new(&self.supervisee).list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        Pin::new(&self.supervisee).labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        Pin::new(&mut self.get_mut().supervisee).one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        Pin::new(&mut self.get_mut().supervisee).take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        Pin::new(&mut self.get_mut().supervisee).take_backoff_reset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::future::poll_fn;
+    use std::sync::{Arc, Mutex};
+
+    fn replica() -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+    }
+
+    /// A child that always fails to start.
+    fn failing_replica() -> impl Specification {
+        SharedResourceSpec::new(|| async { Err::<(), _>(()) }, |()| replica())
+    }
+
+    fn cluster(limiter: RestartLimiter) -> OneForOneSpec {
+        OneForOneSpec::new()
+            .with_limiter(limiter)
+            .with_spec(replica())
+            .with_spec(replica())
+            .with_spec(replica())
+            .with_spec_on_limit(failing_replica(), LimitAction::StopChild)
+            .with_spec_on_limit(failing_replica(), LimitAction::StopChild)
+    }
+
+    #[tokio::test]
+    async fn starts_with_quorum() {
+        let addresses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = addresses.clone();
+        let tree = OneForOneSpec::new()
+            .with_spec(replica().on_start(move |address| {
+                recorded.lock().unwrap().push(address);
+            }))
+            .with_spec(replica())
+            .with_spec(replica())
+            .with_spec_on_limit(failing_replica(), LimitAction::StopChild)
+            .with_spec_on_limit(failing_replica(), LimitAction::StopChild);
+        let spec = QuorumStartSpec::new(3, tree);
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        let children = supervisee.children().collect::<Vec<_>>();
+        assert_eq!(children[..3], [ChildKind::Supervisee; 3]);
+
+        let mut supervisee = Box::pin(supervisee);
+        poll_fn(|cx| {
+            assert!(supervisee.as_mut().poll_supervise(cx).is_pending());
+            match supervisee
+                .children()
+                .skip(3)
+                .all(|kind| kind == ChildKind::GaveUp)
+            {
+                true => Poll::Ready(()),
+                false => Poll::Pending,
+            }
+        })
+        .await;
+
+        // A healthy replica that exits after the failing ones gave up is restarted.
+        addresses.lock().unwrap()[0].halt();
+        poll_fn(|cx| {
+            assert!(supervisee.as_mut().poll_supervise(cx).is_pending());
+            match addresses.lock().unwrap().len() {
+                2 => Poll::Ready(()),
+                _ => Poll::Pending,
+            }
+        })
+        .await;
+        assert_eq!(
+            supervisee.children().collect::<Vec<_>>(),
+            [
+                ChildKind::Supervisee,
+                ChildKind::Supervisee,
+                ChildKind::Supervisee,
+                ChildKind::GaveUp,
+                ChildKind::GaveUp
+            ]
+        );
+
+        supervisee.as_mut().halt();
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        let spec = exit.unwrap().unwrap();
+        assert_eq!(spec.required(), 3);
+        assert_eq!(spec.spec().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn fails_when_quorum_is_impossible() {
+        let limiter = RestartLimiter::new(0, Duration::from_secs(10));
+        let spec = QuorumStartSpec::new(4, cluster(limiter));
+        match spec.start_supervised().await {
+            Err(StartError::StartFailed(spec)) => {
+                assert!(spec.spec().children().all(|kind| kind == ChildKind::Spec))
+            }
+            _ => panic!("Starting should fail"),
+        }
+    }
+}
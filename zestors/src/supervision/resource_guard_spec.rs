@@ -0,0 +1,281 @@
+use crate::all::*;
+use async_trait::async_trait;
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+/// A [`Specification`] that samples a resource-metric of it's child, and halts the child once the
+/// metric exceeds a budget.
+///
+/// Every `interval`, the `metric` is called with the reference of the child; this can for example
+/// read a shared atomic that the actor updates with the size of it's cache. When the sample is
+/// larger than the `budget`, the child is halted and exits in the same way as it normally would,
+/// so it is restarted by it's own exit-value. Restarting releases the resources that the child had
+/// built up, and repeated restarts are limited by the [`RestartLimiter`] of the supervisor.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::{sync::{Arc, atomic::{AtomicUsize, Ordering}}, time::Duration};
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let cache_size = Arc::new(AtomicUsize::new(0));
+/// let size = cache_size.clone();
+/// let spec = SpawnSpec::new(
+///     move |mut inbox: Inbox<()>, ()| async move {
+///         size.store(0, Ordering::Relaxed);
+///         while inbox.recv().await.is_ok() {
+///             size.fetch_add(1, Ordering::Relaxed);
+///         }
+///     },
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// );
+/// let spec = ResourceGuardSpec::new(
+///     spec,
+///     move |_address: &Address<Inbox<()>>| cache_size.load(Ordering::Relaxed),
+///     Duration::from_secs(1),
+///     1024 * 1024,
+/// );
+/// let (supervisee, _address) = spec.start_supervised().await.unwrap();
+/// # drop(supervisee);
+/// # }
+/// ```
+pub struct ResourceGuardSpec<S: Specification> {
+    spec: S,
+    guard: Guard<S::Ref>,
+}
+
+struct Guard<R> {
+    metric: Arc<dyn Fn(&R) -> usize + Send + Sync>,
+    interval: Duration,
+    budget: usize,
+}
+
+impl<S: Specification> ResourceGuardSpec<S> {
+    /// Create a new spec that samples the `metric` of the child every `interval`, and halts it
+    /// once a sample exceeds the `budget`.
+    ///
+    /// Panics if the `interval` is zero.
+    pub fn new<F>(spec: S, metric: F, interval: Duration, budget: usize) -> Self
+    where
+        F: Fn(&S::Ref) -> usize + Send + Sync + 'static,
+    {
+        assert!(!interval.is_zero(), "interval must be non-zero");
+        Self {
+            spec,
+            guard: Guard {
+                metric: Arc::new(metric),
+                interval,
+                budget,
+            },
+        }
+    }
+
+    /// The guarded spec.
+    pub fn spec(&self) -> &S {
+        &self.spec
+    }
+
+    /// Convert this back into the guarded spec.
+    pub fn into_spec(self) -> S {
+        self.spec
+    }
+}
+
+impl<R> Guard<R> {
+    fn interval(&self) -> Interval {
+        let mut interval = tokio::time::interval_at(Instant::now() + self.interval, self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval
+    }
+}
+
+impl<R> Clone for Guard<R> {
+    fn clone(&self) -> Self {
+        Self {
+            metric: self.metric.clone(),
+            interval: self.interval,
+            budget: self.budget,
+        }
+    }
+}
+
+impl<S: Specification + Debug> Debug for ResourceGuardSpec<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceGuardSpec")
+            .field("spec", &self.spec)
+            .field("interval", &self.guard.interval)
+            .field("budget", &self.guard.budget)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S> Specification for ResourceGuardSpec<S>
+where
+    S: Specification,
+    S::Ref: Clone + Sync,
+{
+    type Ref = S::Ref;
+    type Supervisee = ResourceGuardSupervisee<S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let guard = self.guard;
+        match self.spec.start_supervised().await {
+            Ok((supervisee, reference)) => Ok((
+                ResourceGuardSupervisee {
+                    supervisee: Box::pin(supervisee),
+                    reference: reference.clone(),
+                    interval: guard.interval(),
+                    guard,
+                    over_budget: false,
+                },
+                reference,
+            )),
+            Err(e) => Err(e.map(|spec| Self { spec, guard })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.spec.validate()
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`ResourceGuardSpec`].
+pub struct ResourceGuardSupervisee<S: Specification> {
+    supervisee: Pin<Box<S::Supervisee>>,
+    reference: S::Ref,
+    guard: Guard<S::Ref>,
+    interval: Interval,
+    over_budget: bool,
+}
+
+impl<S: Specification> ResourceGuardSupervisee<S> {
+    /// Whether the child has been halted because it exceeded it's budget.
+    pub fn is_over_budget(&self) -> bool {
+        self.over_budget
+    }
+}
+
+impl<S: Specification> Unpin for ResourceGuardSupervisee<S> {}
+
+impl<S> Supervisee for ResourceGuardSupervisee<S>
+where
+    S: Specification,
+    S::Ref: Clone + Sync,
+{
+    type Spec = ResourceGuardSpec<S>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = &mut *self;
+
+        while !this.over_budget && this.interval.poll_tick(cx).is_ready() {
+            if (this.guard.metric)(&this.reference) > this.guard.budget {
+                this.over_budget = true;
+                this.supervisee.as_mut().halt();
+            }
+        }
+
+        let exit = futures::ready!(this.supervisee.as_mut().poll_supervise(cx));
+        let guard = this.guard.clone();
+        Poll::Ready(exit.map(|spec| spec.map(|spec| ResourceGuardSpec { spec, guard })))
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.supervisee.as_ref().shutdown_time()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        self.supervisee.as_mut().halt()
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        self.supervisee.as_mut().abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.supervisee.as_ref().list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.supervisee.as_ref().labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.get_mut().supervisee.as_mut().one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.get_mut().supervisee.as_mut().take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.get_mut().supervisee.as_mut().take_backoff_reset()
+    }
+}
+
+impl<S: Specification> Debug for ResourceGuardSupervisee<S>
+where
+    S::Supervisee: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceGuardSupervisee")
+            .field("supervisee", &self.supervisee)
+            .field("over_budget", &self.over_budget)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn spec(
+        usage: Arc<AtomicUsize>,
+    ) -> ResourceGuardSpec<impl Specification<Ref = Address<Halter>>> {
+        let spec = SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        );
+        ResourceGuardSpec::new(
+            spec,
+            move |_address| usage.load(Ordering::SeqCst),
+            Duration::from_millis(5),
+            100,
+        )
+    }
+
+    #[tokio::test]
+    async fn over_budget_child_is_halted() {
+        let usage = Arc::new(AtomicUsize::new(0));
+        let (supervisee, _address) = spec(usage.clone()).start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+
+        let supervise = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx));
+        assert!(tokio::time::timeout(Duration::from_millis(20), supervise)
+            .await
+            .is_err());
+        assert!(!supervisee.is_over_budget());
+
+        usage.store(101, Ordering::SeqCst);
+        let exit = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(supervisee.is_over_budget());
+        assert!(exit.unwrap().is_some());
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Limits how often a supervisee may be restarted within a certain duration.
+///
+/// Once the limit has been exceeded the limiter is triggered, and stays triggered until it is
+/// [reset](RestartLimiter::reset).
+#[derive(Debug, Clone)]
+pub struct RestartLimiter {
+    limit: usize,
+    within: Duration,
+    values: Vec<Instant>,
+    triggered: bool,
+}
+
+impl RestartLimiter {
+    /// Create a new restart-limiter, allowing `limit` restarts within the duration.
+    ///
+    /// A `limit` of `0` allows no restarts at all: the first restart already exceeds the limit.
+    /// To never restart a supervisee, prefer a [`OnceSpec`](crate::supervision::OnceSpec).
+    pub fn new(limit: usize, within: Duration) -> Self {
+        Self {
+            limit,
+            within,
+            values: Vec::new(),
+            triggered: false,
+        }
+    }
+
+    /// The amount of restarts allowed within [`RestartLimiter::within`].
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The duration within which restarts are counted.
+    pub fn within(&self) -> Duration {
+        self.within
+    }
+
+    /// Sets a new limit. The restarts recorded so far count against the new limit.
+    ///
+    /// This does not untrigger the limiter, see [`RestartLimiter::reset`].
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    /// Sets a new duration. The restarts recorded so far are kept if they are within the new
+    /// duration, and forgotten otherwise.
+    ///
+    /// This does not untrigger the limiter, see [`RestartLimiter::reset`].
+    pub fn set_within(&mut self, within: Duration) {
+        self.within = within;
+        self.values.retain(|instant| instant.elapsed() < within);
+    }
+
+    /// Forget all previous restarts and untrigger the limiter.
+    pub fn reset(&mut self) {
+        self.triggered = false;
+        self.values.clear();
+    }
+
+    /// Adds a restart and then checks whether the restart is within the limit.
+    pub fn within_limit(&mut self) -> bool {
+        if !self.triggered {
+            self.values.push(Instant::now());
+            let within = self.within;
+            self.values.retain(|instant| instant.elapsed() < within);
+
+            if self.values.len() > self.limit {
+                self.triggered = true
+            }
+        }
+
+        !self.triggered
+    }
+
+    /// Whether the limit has been exceeded.
+    pub fn triggered(&self) -> bool {
+        self.triggered
+    }
+}
+
+impl Default for RestartLimiter {
+    /// Allows 3 restarts within 5 seconds.
+    fn default() -> Self {
+        Self::new(3, Duration::from_secs(5))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn limit_is_triggered() {
+        let mut limiter = RestartLimiter::new(2, Duration::from_secs(10));
+        assert!(limiter.within_limit());
+        assert!(limiter.within_limit());
+        assert!(!limiter.within_limit());
+        assert!(limiter.triggered());
+        assert!(!limiter.within_limit());
+
+        limiter.reset();
+        assert!(!limiter.triggered());
+        assert!(limiter.within_limit());
+    }
+
+    #[test]
+    fn limit_of_zero_allows_no_restarts() {
+        let mut limiter = RestartLimiter::new(0, Duration::from_secs(10));
+        assert!(!limiter.triggered());
+        assert!(!limiter.within_limit());
+        assert!(limiter.triggered());
+    }
+
+    #[tokio::test]
+    async fn reconfigured_window_forgets_old_restarts() {
+        let mut limiter = RestartLimiter::new(1, Duration::from_secs(10));
+        assert!(limiter.within_limit());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        limiter.set_within(Duration::from_millis(10));
+        limiter.set_within(Duration::from_secs(10));
+        assert!(limiter.within_limit());
+        limiter.set_limit(2);
+        assert!(limiter.within_limit());
+        assert!(!limiter.within_limit());
+    }
+
+    #[tokio::test]
+    async fn old_restarts_are_forgotten() {
+        let mut limiter = RestartLimiter::new(1, Duration::from_millis(10));
+        assert!(limiter.within_limit());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(limiter.within_limit());
+        assert!(!limiter.within_limit());
+    }
+}
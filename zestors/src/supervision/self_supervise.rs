@@ -0,0 +1,103 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+
+/// Receive messages from the inbox and handle them, resetting the state of the actor whenever the
+/// handler fails. This inlines a supervision loop into the actor's own task, for leaf actors that
+/// want to recover from errors without an external supervisor.
+///
+/// - Every message is passed to `handle` together with the current state, which returns the new
+///   state.
+/// - When `handle` returns an error, the state is reset with `init`. This counts as a restart for
+///   the `limiter`; once it is exceeded, the error is returned.
+/// - When the inbox is halted or closed and empty, the current state is returned.
+///
+/// Panics are not caught, and still exit the actor.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, protocol, supervision::*};
+///
+/// #[protocol]
+/// enum NumberProtocol {
+///     Number(u32),
+/// }
+///
+/// # async fn main() {
+/// let (child, address) = spawn(|inbox: Inbox<NumberProtocol>| {
+///     self_supervise(
+///         inbox,
+///         RestartLimiter::default(),
+///         || 0,
+///         |total: u32, NumberProtocol::Number(n)| async move {
+///             match n {
+///                 0 => Err("Received zero"),
+///                 n => Ok(total + n),
+///             }
+///         },
+///     )
+/// });
+/// address.send(10u32).await.unwrap();
+/// address.send(0u32).await.unwrap();
+/// address.send(5u32).await.unwrap();
+/// address.close();
+/// assert_eq!(child.await.unwrap(), Ok(5));
+/// # }
+/// ```
+pub async fn self_supervise<P, S, E, I, H, Fut>(
+    mut inbox: Inbox<P>,
+    mut limiter: RestartLimiter,
+    mut init: I,
+    mut handle: H,
+) -> Result<S, E>
+where
+    P: Protocol,
+    I: FnMut() -> S,
+    H: FnMut(S, P) -> Fut,
+    Fut: Future<Output = Result<S, E>>,
+{
+    let mut state = init();
+    loop {
+        let Ok(msg) = inbox.recv().await else {
+            return Ok(state);
+        };
+        state = match handle(state, msg).await {
+            Ok(state) => state,
+            Err(e) => {
+                if !limiter.within_limit() {
+                    return Err(e);
+                }
+                init()
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn exits_once_limiter_is_exceeded() {
+        let (child, address) = spawn(|inbox: Inbox<U32Protocol>| {
+            self_supervise(
+                inbox,
+                RestartLimiter::new(1, Duration::from_secs(10)),
+                || 0,
+                |total: u32, U32Protocol::U32(n)| async move {
+                    match n {
+                        0 => Err(total),
+                        n => Ok(total + n),
+                    }
+                },
+            )
+        });
+        for n in [1u32, 0, 2, 3, 0] {
+            address.send(n).await.unwrap();
+        }
+        assert_eq!(child.await.unwrap(), Err(5));
+    }
+}
@@ -0,0 +1,486 @@
+use super::one_for_one::{list_items, AbortTimer, OneForOneItem, SHUTDOWN_MARGIN};
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{future::poll_fn, ready};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+//------------------------------------------------------------------------------------------------
+//  Spec
+//------------------------------------------------------------------------------------------------
+
+/// A [`Specification`] that supervises a chain of children, where every child depends on the
+/// ones added before it.
+///
+/// The children are started one by one in the order they were added, and every child is only
+/// started once the previous one is running. When a child exits, fails or can not be started, the
+/// whole sequence is shut down and exits, so that it can be restarted as a whole.
+///
+/// # Shutdown order
+/// The sequence shuts down in the reverse order of starting, so that dependents stop before
+/// their dependencies: the last child is halted first, and the next child is only halted after
+/// it has exited. Every child is given it's own shutdown-time before it is aborted, so the
+/// shutdown-time of the sequence is the sum of that of all children.
+///
+/// # Exit-value
+/// When all children have completed, the sequence has completed with the exit-value of the last
+/// child, see [`Supervisee::take_exit_value`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::prelude::*;
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let spec = SequenceSpec::new();
+/// assert!(matches!(spec.start_supervised().await, Err(StartError::Completed)));
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct SequenceSpec {
+    items: Vec<OneForOneItem>,
+    exit_value: Option<ExitValue>,
+}
+
+impl SequenceSpec {
+    /// Create a new, empty sequence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a child to the end of the sequence.
+    pub fn with_spec<S>(mut self, spec: S) -> Self
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.add_spec(spec);
+        self
+    }
+
+    /// Add a child to the end of the sequence.
+    pub fn add_spec<S>(&mut self, spec: S)
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.items
+            .push(OneForOneItem::Spec(spec.on_start(|_| ()).into_dyn()))
+    }
+
+    /// The amount of children in the sequence, including the ones that have completed.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the sequence has no children.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The current [`ChildKind`] of every child, in the order they were added.
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.items.iter().map(OneForOneItem::kind)
+    }
+
+    /// Poll the child at `index`, keeping it's exit-value if it is the last child.
+    fn poll_child(&mut self, index: usize, cx: &mut Context) -> Poll<()> {
+        let exit_value = ready!(self.items[index].poll_change(cx));
+        if index + 1 == self.items.len() && exit_value.is_some() {
+            self.exit_value = exit_value;
+        }
+        Poll::Ready(())
+    }
+
+    /// Poll all running children, returning `true` if any of them has exited or failed.
+    fn poll_running(&mut self, cx: &mut Context) -> bool {
+        for index in 0..self.items.len() {
+            while self.poll_child(index, cx).is_ready() {
+                if matches!(
+                    self.items[index],
+                    OneForOneItem::Spec(_) | OneForOneItem::Irrecoverable(_)
+                ) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Shut down the running children one by one, starting with the last one.
+    fn poll_shutdown(&mut self, shutdown: &mut ReverseShutdown, cx: &mut Context) -> Poll<()> {
+        loop {
+            if let Some((index, timer)) = &mut shutdown.current {
+                let index = *index;
+                if timer.poll_expired(cx) {
+                    self.items[index].abort();
+                }
+                while self.items[index].is_running() {
+                    ready!(self.poll_child(index, cx));
+                    self.items[index].halt();
+                }
+                shutdown.current = None;
+            }
+
+            let Some(index) = (0..shutdown.next)
+                .rev()
+                .find(|i| self.items[*i].is_running())
+            else {
+                return Poll::Ready(());
+            };
+            let item = &mut self.items[index];
+            let shutdown_time = item.shutdown_time().unwrap_or_default();
+            item.halt();
+            shutdown.next = index;
+            shutdown.current = Some((
+                index,
+                AbortTimer::new(shutdown_time.saturating_add(SHUTDOWN_MARGIN)),
+            ));
+        }
+    }
+
+    fn shutdown_time(&self) -> Duration {
+        self.items
+            .iter()
+            .filter_map(OneForOneItem::shutdown_time)
+            .fold(Duration::ZERO, |total, time| {
+                total.saturating_add(time).saturating_add(SHUTDOWN_MARGIN)
+            })
+    }
+
+    /// Get the exit-value of this sequence, once none of the children are running anymore.
+    fn into_exit(mut self) -> SupervisionResult<Self> {
+        let failed = self
+            .items
+            .iter()
+            .position(|item| matches!(item, OneForOneItem::Irrecoverable(_)));
+
+        if let Some(index) = failed {
+            let OneForOneItem::Irrecoverable(e) = self.items.swap_remove(index) else {
+                unreachable!()
+            };
+            Err(e)
+        } else if self
+            .items
+            .iter()
+            .any(|item| matches!(item, OneForOneItem::Spec(_)))
+        {
+            Ok(Some(self))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn into_start_error(self) -> StartError<Self> {
+        match self.into_exit() {
+            Ok(Some(spec)) => StartError::StartFailed(spec),
+            Ok(None) => StartError::Completed,
+            Err(e) => StartError::Fatal(e),
+        }
+    }
+}
+
+#[async_trait]
+impl Specification for SequenceSpec {
+    type Ref = ();
+    type Supervisee = SequenceSupervisee;
+
+    async fn start_supervised(mut self) -> StartResult<Self> {
+        for index in 0..self.items.len() {
+            self.items[index].start(None);
+            poll_fn(|cx| {
+                let item = &mut self.items[index];
+                while item.is_starting() {
+                    ready!(item.poll_change(cx));
+                }
+                Poll::Ready(())
+            })
+            .await;
+
+            if matches!(
+                self.items[index],
+                OneForOneItem::Spec(_) | OneForOneItem::Irrecoverable(_)
+            ) {
+                let mut shutdown = ReverseShutdown::new(self.items.len());
+                poll_fn(|cx| self.poll_shutdown(&mut shutdown, cx)).await;
+                return Err(self.into_start_error());
+            }
+        }
+
+        if self.items.iter().any(OneForOneItem::is_running) {
+            Ok((
+                SequenceSupervisee {
+                    spec: Some(self),
+                    shutdown: None,
+                    exit_value: None,
+                },
+                (),
+            ))
+        } else {
+            Err(self.into_start_error())
+        }
+    }
+}
+
+/// The progress of shutting down a sequence in reverse order.
+#[derive(Debug)]
+struct ReverseShutdown {
+    /// Only children before this index remain to be shut down.
+    next: usize,
+    /// The child that is currently shutting down, with the timer after which it is aborted.
+    current: Option<(usize, AbortTimer)>,
+}
+
+impl ReverseShutdown {
+    fn new(len: usize) -> Self {
+        Self {
+            next: len,
+            current: None,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`SequenceSpec`].
+#[derive(Debug)]
+pub struct SequenceSupervisee {
+    spec: Option<SequenceSpec>,
+    shutdown: Option<ReverseShutdown>,
+    exit_value: Option<ExitValue>,
+}
+
+impl SequenceSupervisee {
+    /// The current [`ChildKind`] of every child, see [`SequenceSpec::children`].
+    pub fn children(&self) -> impl Iterator<Item = ChildKind> + '_ {
+        self.spec.iter().flat_map(SequenceSpec::children)
+    }
+}
+
+impl Supervisee for SequenceSupervisee {
+    type Spec = SequenceSpec;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = &mut *self;
+        let spec = this.spec.as_mut().unwrap();
+
+        if this.shutdown.is_none() {
+            if spec.poll_running(cx) {
+                this.shutdown = Some(ReverseShutdown::new(spec.items.len()));
+            } else if spec.items.iter().any(OneForOneItem::is_running) {
+                return Poll::Pending;
+            }
+        }
+
+        if let Some(shutdown) = &mut this.shutdown {
+            ready!(spec.poll_shutdown(shutdown, cx));
+        }
+        let mut spec = this.spec.take().unwrap();
+        this.exit_value = spec.exit_value.take();
+        Poll::Ready(spec.into_exit())
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.spec.as_ref().unwrap().shutdown_time()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        if self.shutdown.is_none() {
+            let len = self.spec.as_ref().unwrap().items.len();
+            self.shutdown = Some(ReverseShutdown::new(len));
+        }
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        for item in &mut self.spec.as_mut().unwrap().items {
+            item.abort();
+        }
+        self.halt();
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        let status = match self.shutdown {
+            Some(_) => ActorStatus::ShuttingDown,
+            None => ActorStatus::Running,
+        };
+        Some(list_items(
+            self.spec.as_ref()?.items.iter().enumerate(),
+            status,
+        ))
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.get_mut().exit_value.take()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        let spec = self.get_mut().spec.as_mut()?;
+        spec.items
+            .iter_mut()
+            .enumerate()
+            .find_map(|(child, item)| item.take_backoff_reset(child))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    /// A child that logs when it starts and when it exits.
+    fn logging_spec(name: &'static str, log: &Log) -> impl Specification {
+        let log = log.clone();
+        SpawnSpec::new(
+            move |halter: Halter, ()| {
+                let log = log.clone();
+                async move {
+                    log.lock().unwrap().push(format!("start {name}"));
+                    halter.await;
+                    log.lock().unwrap().push(format!("exit {name}"));
+                }
+            },
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+    }
+
+    #[tokio::test]
+    async fn shuts_down_in_reverse_order() {
+        let log = Log::default();
+        let spec = SequenceSpec::new()
+            .with_spec(logging_spec("a", &log))
+            .with_spec(logging_spec("b", &log))
+            .with_spec(logging_spec("c", &log));
+        let (mut supervisee, ()) = spec.start_supervised().await.unwrap();
+
+        // Let the children start.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Pin::new(&mut supervisee).halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(
+            spec.children().collect::<Vec<_>>(),
+            vec![ChildKind::Spec; 3]
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["start a", "start b", "start c", "exit c", "exit b", "exit a"]
+        );
+    }
+
+    #[tokio::test]
+    async fn exiting_child_shuts_down_sequence() {
+        let log = Log::default();
+        let spec =
+            SequenceSpec::new()
+                .with_spec(logging_spec("a", &log))
+                .with_spec(SpawnSpec::new(
+                    |_halter: Halter, ()| async move {
+                        tokio::time::sleep(Duration::from_millis(10)).await
+                    },
+                    |_exit| async move { Ok(Some(())) },
+                    (),
+                ))
+                .with_spec(logging_spec("c", &log));
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.len(), 3);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["start a", "start c", "exit c", "exit a"]
+        );
+    }
+
+    #[tokio::test]
+    async fn forwards_exit_value_of_last_child() {
+        let value = |x: u32| {
+            SpawnSpec::new(
+                |_halter: Halter, x: u32| async move { x },
+                |_exit: Result<u32, ExitError>| async move { Ok(None) },
+                x,
+            )
+            .with_exit_value()
+        };
+        let spec = SequenceSpec::new().with_spec(value(1)).with_spec(value(2));
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+
+        let exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(exit.unwrap().is_none());
+        let exit_value = supervisee.as_mut().take_exit_value().unwrap();
+        assert_eq!(*exit_value.downcast::<u32>().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn forwards_backoff_reset() {
+        let spec = SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+        .with_min_healthy_uptime(Duration::from_millis(20))
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        address.halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+
+        let spec = SequenceSpec::new()
+            .with_spec(logging_spec("a", &Log::default()))
+            .with_spec(spec);
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        assert!(supervisee.as_mut().take_backoff_reset().is_none());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let reset = supervisee.as_mut().take_backoff_reset().unwrap();
+        assert_eq!(reset.child, Some(1));
+        assert_eq!(reset.unhealthy_restarts, 1);
+
+        supervisee.as_mut().halt();
+        poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn unbounded_shutdown_time_arms_no_timer() {
+        let spec = SequenceSpec::new().with_spec(
+            SpawnSpec::new(
+                |_halter: Halter, ()| futures::future::pending::<()>(),
+                |_exit| async move { Ok(None) },
+                (),
+            )
+            .with_shutdown_time(Duration::MAX),
+        );
+        let (supervisee, ()) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+
+        supervisee.as_mut().halt();
+        let poll = futures::poll!(poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)));
+        assert!(poll.is_pending());
+        let (_, timer) = supervisee
+            .shutdown
+            .as_ref()
+            .unwrap()
+            .current
+            .as_ref()
+            .unwrap();
+        assert!(!timer.is_armed());
+
+        supervisee.as_mut().abort();
+        poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,370 @@
+use super::one_for_one::AbortTimer;
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{future::BoxFuture, Future, FutureExt};
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{interval_at, Instant, Interval};
+
+/// A [`Specification`] that creates a shared resource `R`, such as a connection-pool, before
+/// starting it's child `S` with a clone of it.
+///
+/// The child is built by calling the `build` function with the resource. When the child exits
+/// and would like to be restarted, it is restarted with the same resource. The resource is only
+/// recreated (and the child rebuilt) if:
+/// - The health-check set with [`with_health_check`](Self::with_health_check) fails. The child is
+///   then halted, and aborted if it does not exit within it's shutdown-time.
+/// - The child fails to start.
+///
+/// If the resource can not be created, starting fails with [`StartError::StartFailed`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::sync::Arc;
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = SharedResourceSpec::new(
+///     || async { Ok::<_, ()>(Arc::new("connection-pool")) },
+///     |pool: Arc<&'static str>| {
+///         OneForOneSpec::new().with_spec(SpawnSpec::new(
+///             move |halter: Halter, ()| async move {
+///                 let _pool = pool;
+///                 halter.await
+///             },
+///             |_exit| async move { Ok(Some(())) },
+///             (),
+///         ))
+///     },
+/// );
+/// let (supervisee, ()) = spec.start_supervised().await.unwrap();
+/// # drop(supervisee);
+/// # }
+/// ```
+pub struct SharedResourceSpec<R, S> {
+    fns: ResourceFns<R, S>,
+    resource: Option<R>,
+    spec: Option<S>,
+}
+
+#[allow(clippy::type_complexity)]
+struct ResourceFns<R, S> {
+    create: Box<dyn FnMut() -> BoxFuture<'static, Option<R>> + Send>,
+    build: Box<dyn FnMut(R) -> S + Send>,
+    health_check: Option<(
+        Box<dyn FnMut(R) -> BoxFuture<'static, bool> + Send>,
+        Duration,
+    )>,
+}
+
+impl<R, S> SharedResourceSpec<R, S>
+where
+    R: Clone + Send + 'static,
+    S: Specification,
+{
+    /// Create a new spec that creates the resource with `create`, and builds the child from it
+    /// with `build`.
+    pub fn new<CFun, CFut, E, BFun>(mut create: CFun, build: BFun) -> Self
+    where
+        CFun: FnMut() -> CFut + Send + 'static,
+        CFut: Future<Output = Result<R, E>> + Send + 'static,
+        E: 'static,
+        BFun: FnMut(R) -> S + Send + 'static,
+    {
+        Self {
+            fns: ResourceFns {
+                create: Box::new(move || create().map(Result::ok).boxed()),
+                build: Box::new(build),
+                health_check: None,
+            },
+            resource: None,
+            spec: None,
+        }
+    }
+
+    /// Check the health of the resource every `interval`. If the check returns `false`, the
+    /// child is shut down and the spec exits so that it can be restarted with a new resource.
+    ///
+    /// Panics if the interval is zero.
+    pub fn with_health_check<HFun, HFut>(mut self, mut check: HFun, interval: Duration) -> Self
+    where
+        HFun: FnMut(R) -> HFut + Send + 'static,
+        HFut: Future<Output = bool> + Send + 'static,
+    {
+        assert!(!interval.is_zero());
+        self.fns.health_check = Some((Box::new(move |resource| check(resource).boxed()), interval));
+        self
+    }
+
+    /// The resource, if it has been created and is still healthy.
+    pub fn resource(&self) -> Option<&R> {
+        self.resource.as_ref()
+    }
+}
+
+#[async_trait]
+impl<R, S> Specification for SharedResourceSpec<R, S>
+where
+    R: Clone + Send + 'static,
+    S: Specification,
+{
+    type Ref = S::Ref;
+    type Supervisee = SharedResourceSupervisee<R, S>;
+
+    async fn start_supervised(mut self) -> StartResult<Self> {
+        let resource = match self.resource.take() {
+            Some(resource) => resource,
+            None => match (self.fns.create)().await {
+                Some(resource) => resource,
+                None => return Err(StartError::StartFailed(self)),
+            },
+        };
+        let spec = match self.spec.take() {
+            Some(spec) => spec,
+            None => (self.fns.build)(resource.clone()),
+        };
+
+        match spec.start_supervised().await {
+            Ok((supervisee, reference)) => {
+                let interval = self
+                    .fns
+                    .health_check
+                    .as_ref()
+                    .map(|(_, interval)| interval_at(Instant::now() + *interval, *interval));
+                Ok((
+                    SharedResourceSupervisee {
+                        supervisee,
+                        resource,
+                        fns: Some(self.fns),
+                        interval,
+                        check: None,
+                        unhealthy: false,
+                        abort_timer: None,
+                    },
+                    reference,
+                ))
+            }
+            Err(e) => Err(e.map(|_| self)),
+        }
+    }
+}
+
+/// The [`Supervisee`] of a [`SharedResourceSpec`].
+#[pin_project]
+pub struct SharedResourceSupervisee<R, S: Specification> {
+    #[pin]
+    supervisee: S::Supervisee,
+    resource: R,
+    fns: Option<ResourceFns<R, S>>,
+    interval: Option<Interval>,
+    check: Option<BoxFuture<'static, bool>>,
+    unhealthy: bool,
+    abort_timer: Option<AbortTimer>,
+}
+
+impl<R, S: Specification> SharedResourceSupervisee<R, S> {
+    /// The shared resource.
+    pub fn resource(&self) -> &R {
+        &self.resource
+    }
+
+    /// Whether the health-check of the resource has failed.
+    pub fn is_unhealthy(&self) -> bool {
+        self.unhealthy
+    }
+}
+
+impl<R, S> Supervisee for SharedResourceSupervisee<R, S>
+where
+    R: Clone + Send + 'static,
+    S: Specification,
+{
+    type Spec = SharedResourceSpec<R, S>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let mut this = self.project();
+
+        if let Poll::Ready(exit) = this.supervisee.as_mut().poll_supervise(cx) {
+            let fns = this.fns.take().unwrap();
+            let healthy = !*this.unhealthy;
+            return Poll::Ready(exit.map(|spec| {
+                spec.map(|spec| SharedResourceSpec {
+                    fns,
+                    resource: healthy.then(|| this.resource.clone()),
+                    spec: healthy.then_some(spec),
+                })
+            }));
+        }
+
+        if *this.unhealthy {
+            if let Some(timer) = this.abort_timer {
+                if timer.poll_expired(cx) {
+                    *this.abort_timer = None;
+                    this.supervisee.abort();
+                }
+            }
+            return Poll::Pending;
+        }
+
+        let (Some(interval), Some(fns)) = (this.interval.as_mut(), this.fns.as_mut()) else {
+            return Poll::Pending;
+        };
+        loop {
+            if let Some(check) = this.check {
+                match check.poll_unpin(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(true) => *this.check = None,
+                    Poll::Ready(false) => {
+                        *this.check = None;
+                        *this.unhealthy = true;
+                        let shutdown_time = this.supervisee.as_ref().shutdown_time();
+                        this.supervisee.halt();
+                        let mut timer = AbortTimer::new(shutdown_time);
+                        let _ = timer.poll_expired(cx);
+                        *this.abort_timer = Some(timer);
+                        return Poll::Pending;
+                    }
+                }
+            }
+            match interval.poll_tick(cx) {
+                Poll::Ready(_) => {
+                    let (check, _) = fns.health_check.as_mut().unwrap();
+                    *this.check = Some(check(this.resource.clone()));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Clone)]
+    struct Resource {
+        id: usize,
+        healthy: Arc<AtomicBool>,
+    }
+
+    fn spec(
+        created: Arc<AtomicUsize>,
+    ) -> SharedResourceSpec<Resource, impl Specification<Ref = (Address<Halter>, usize)>> {
+        SharedResourceSpec::new(
+            move || {
+                let id = created.fetch_add(1, Ordering::AcqRel);
+                async move {
+                    Ok::<_, ()>(Resource {
+                        id,
+                        healthy: Arc::new(AtomicBool::new(true)),
+                    })
+                }
+            },
+            |resource: Resource| {
+                SpawnSpec::new(
+                    |halter: Halter, ()| halter,
+                    |_exit| async move { Ok(Some(())) },
+                    (),
+                )
+                .on_start(move |address| (address, resource.id))
+            },
+        )
+        .with_health_check(
+            |resource: Resource| async move { resource.healthy.load(Ordering::Acquire) },
+            Duration::from_millis(10),
+        )
+    }
+
+    #[tokio::test]
+    async fn child_restarts_with_same_resource() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let (supervisee, (address, id)) = spec(created.clone()).start_supervised().await.unwrap();
+        assert_eq!(id, 0);
+
+        address.halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(spec.resource().unwrap().id, 0);
+        let (_supervisee, (_address, id)) = spec.start_supervised().await.unwrap();
+        assert_eq!(id, 0);
+        assert_eq!(created.load(Ordering::Acquire), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_health_check_recreates_resource() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let (supervisee, (address, _)) = spec(created.clone()).start_supervised().await.unwrap();
+        supervisee
+            .resource()
+            .healthy
+            .store(false, Ordering::Release);
+
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert!(address.has_exited());
+        assert!(spec.resource().is_none());
+        let (_supervisee, (_address, id)) = spec.start_supervised().await.unwrap();
+        assert_eq!(id, 1);
+        assert_eq!(created.load(Ordering::Acquire), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_creation_fails_start() {
+        let spec = SharedResourceSpec::new(
+            || async { Err::<(), _>("unavailable") },
+            |()| {
+                SpawnSpec::new(
+                    |halter: Halter, ()| halter,
+                    |_exit| async move { Ok(Some(())) },
+                    (),
+                )
+            },
+        );
+        assert!(matches!(
+            spec.start_supervised().await,
+            Err(StartError::StartFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,705 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{
+    future::{self, BoxFuture},
+    ready, Future, FutureExt,
+};
+use pin_project::pin_project;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+#[cfg(feature = "message-replay")]
+use std::{collections::VecDeque, sync::Mutex};
+use tokio::{runtime::Handle, time::Sleep};
+
+/// A [`Specification`] that spawns a single actor when it is started.
+///
+/// - `spawn_fn`: Called with the inbox and data to spawn the actor.
+/// - `exit_fn`: Called with the exit-value of the actor, and decides whether the actor should be
+///   restarted with new data, is completed, or has failed.
+///
+/// The `exit_fn` is called for every exit, including when the task of the actor is aborted by
+/// something other than the supervisee: for example by shutting down the runtime it was spawned
+/// on. Such an exit is an [`ExitError::Abort`], which the `exit_fn` can restart or escalate like
+/// any other failure. To handle it differently from an abort by the supervisee itself, see
+/// [`SpawnSpec::on_external_abort`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{actor_reference::ExitError, prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = SpawnSpec::new(
+///     |inbox: Inbox<()>, restarts: u32| async move { restarts },
+///     |exit: Result<u32, ExitError>| async move {
+///         match exit {
+///             Ok(restarts) => Ok(Some(restarts + 1)),
+///             Err(e) => Err(Box::new(e) as FatalError),
+///         }
+///     },
+///     0,
+/// )
+/// .with_shutdown_time(std::time::Duration::from_millis(100));
+///
+/// let (supervisee, _address) = spec.start_supervised().await.unwrap();
+/// let _spec = supervisee.supervise().await.unwrap().unwrap();
+/// # }
+/// ```
+pub struct SpawnSpec<I: InboxType, D, E, SFun, EFun, S = TokioSpawner> {
+    spawn_fn: SFun,
+    exit_fn: EFun,
+    data: D,
+    config: I::Config,
+    shutdown_time: Duration,
+    drain_timeout: Option<Duration>,
+    spawner: S,
+    name: Option<Arc<str>>,
+    replay: Option<Arc<dyn Replay<I>>>,
+    keep_exit: Option<fn(&E) -> ExitValue>,
+    external_abort_fn: Option<fn() -> SupervisionResult<D>>,
+    phantom: PhantomData<fn() -> E>,
+}
+
+impl<I, D, E, SFun, SFut, EFun, EFut> SpawnSpec<I, D, E, SFun, EFun>
+where
+    I: InboxType,
+    I::Config: Clone,
+    D: Send + 'static,
+    E: Send + 'static,
+    SFun: FnOnce(I, D) -> SFut + Clone + Send + 'static,
+    SFut: Future<Output = E> + Send + 'static,
+    EFun: FnOnce(Result<E, ExitError>) -> EFut + Clone + Send + 'static,
+    EFut: Future<Output = SupervisionResult<D>> + Send + 'static,
+{
+    /// Create a new spec with a default [`InboxType::Config`] and shutdown-time, which spawns the
+    /// actor with the [`TokioSpawner`].
+    pub fn new(spawn_fn: SFun, exit_fn: EFun, data: D) -> Self {
+        Self {
+            spawn_fn,
+            exit_fn,
+            data,
+            config: Default::default(),
+            shutdown_time: get_default_shutdown_time(),
+            drain_timeout: None,
+            spawner: TokioSpawner,
+            name: None,
+            replay: None,
+            keep_exit: None,
+            external_abort_fn: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, D, E, SFun, SFut, EFun, EFut, S> SpawnSpec<I, D, E, SFun, EFun, S>
+where
+    I: InboxType,
+    I::Config: Clone,
+    D: Send + 'static,
+    E: Send + 'static,
+    SFun: FnOnce(I, D) -> SFut + Clone + Send + 'static,
+    SFut: Future<Output = E> + Send + 'static,
+    EFun: FnOnce(Result<E, ExitError>) -> EFut + Clone + Send + 'static,
+    EFut: Future<Output = SupervisionResult<D>> + Send + 'static,
+    S: Spawner,
+{
+    /// Set the [`InboxType::Config`] the actor is spawned with.
+    pub fn with_config(mut self, config: I::Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the time the actor is given to exit after being halted, before it is aborted.
+    pub fn with_shutdown_time(mut self, shutdown_time: Duration) -> Self {
+        self.shutdown_time = shutdown_time;
+        self
+    }
+
+    /// Drain the inbox when the actor is halted, instead of halting it directly.
+    ///
+    /// Halting closes the inbox, so that the actor can keep on processing (and replying to) the
+    /// messages that are already queued while new messages are refused. Once the inbox is closed
+    /// and empty, the actor is expected to exit on it's own. If it has not exited after the
+    /// `drain_timeout`, it is halted as usual and given the shutdown-time to exit.
+    ///
+    /// Inboxes that can't be closed, such as a [`Halter`], are halted directly.
+    pub fn with_drain_on_halt(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = Some(drain_timeout);
+        self
+    }
+
+    /// Spawn the actor with the given [`Spawner`] instead of onto the ambient runtime.
+    ///
+    /// This is kept when the actor is restarted.
+    pub fn with_spawner<S2: Spawner>(self, spawner: S2) -> SpawnSpec<I, D, E, SFun, EFun, S2> {
+        SpawnSpec {
+            spawn_fn: self.spawn_fn,
+            exit_fn: self.exit_fn,
+            data: self.data,
+            config: self.config,
+            shutdown_time: self.shutdown_time,
+            drain_timeout: self.drain_timeout,
+            spawner,
+            name: self.name,
+            replay: self.replay,
+            keep_exit: self.keep_exit,
+            external_abort_fn: self.external_abort_fn,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Spawn the actor onto the runtime of the given [`Handle`] instead of the ambient runtime.
+    ///
+    /// This is kept when the actor is restarted.
+    pub fn with_runtime(self, runtime: Handle) -> SpawnSpec<I, D, E, SFun, EFun, Handle> {
+        self.with_spawner(runtime)
+    }
+
+    /// Set the name of the actor, which is available from within it through the
+    /// [`ActorContext`].
+    ///
+    /// This is kept when the actor is restarted.
+    pub fn with_name(mut self, name: impl Into<Arc<str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Keep a clone of the exit-value of the actor, so that it is collected by the
+    /// [`ExitCollector`] of the tree once the actor completes.
+    ///
+    /// This is kept when the actor is restarted.
+    pub fn with_exit_value(mut self) -> Self
+    where
+        E: Clone,
+    {
+        self.keep_exit = Some(|exit: &E| Box::new(exit.clone()) as ExitValue);
+        self
+    }
+
+    /// Handle an abort of the actor by something other than the supervisee with `external_abort_fn`
+    /// instead of the `exit_fn`, for example when the runtime it was spawned on is shut down. Just
+    /// like the `exit_fn`, it decides whether the actor is restarted, completed or has failed.
+    ///
+    /// Aborts by the supervisee itself, for example when it's shutdown-time has passed, are still
+    /// given to the `exit_fn`. This is kept when the actor is restarted.
+    pub fn on_external_abort(mut self, external_abort_fn: fn() -> SupervisionResult<D>) -> Self {
+        self.external_abort_fn = Some(external_abort_fn);
+        self
+    }
+
+    /// Get a reference to the data the actor will be spawned with.
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+
+    /// Get a mutable reference to the data the actor will be spawned with.
+    pub fn data_mut(&mut self) -> &mut D {
+        &mut self.data
+    }
+}
+
+#[cfg(feature = "message-replay")]
+impl<P, D, E, SFun, EFun, S> SpawnSpec<Inbox<P>, D, E, SFun, EFun, S>
+where
+    P: Protocol + Clone + Send,
+{
+    /// Keep the last `n` messages received by the actor, and send them to it again after it has
+    /// been restarted. This is opt-in with the `message-replay` feature, and kept when the actor is
+    /// restarted.
+    ///
+    /// An actor that crashed on a poison message can use this to reconstruct it's state, or to
+    /// reproduce the crash. The replayed messages are sent before any new messages, as long as
+    /// they fit into the inbox.
+    ///
+    /// # Hazards
+    /// Every replayed message is handled twice: side-effects of handling it, such as writes to a
+    /// database, are therefore duplicated. A poison message is also replayed, so that the actor
+    /// crashes again until the restart-limit is reached. Messages that carry a reply, such as
+    /// requests, can't be cloned and are therefore not part of a protocol that can be replayed.
+    pub fn replay_on_restart(mut self, n: usize) -> Self {
+        self.replay = Some(Arc::new(ReplayBuffer::<P>::new(n)));
+        self
+    }
+}
+
+/// Keeps the messages received by the actor of a [`SpawnSpec`], see
+/// [`SpawnSpec::replay_on_restart`].
+trait Replay<I: ActorType>: Send + Sync {
+    /// Keep the messages that are received from the inbox of a newly spawned actor.
+    fn record(self: Arc<Self>, inbox: &I);
+
+    /// Send the messages that have been kept to the restarted actor.
+    fn replay(&self, address: &Address<I>);
+}
+
+#[cfg(feature = "message-replay")]
+struct ReplayBuffer<P> {
+    capacity: usize,
+    messages: Mutex<VecDeque<P>>,
+}
+
+#[cfg(feature = "message-replay")]
+impl<P> ReplayBuffer<P> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            messages: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+}
+
+#[cfg(feature = "message-replay")]
+impl<P: Protocol + Clone + Send> Replay<Inbox<P>> for ReplayBuffer<P> {
+    fn record(self: Arc<Self>, inbox: &Inbox<P>) {
+        <Inbox<P> as ActorRef>::channel_ref(inbox).set_recorder(Box::new(move |msg: &P| {
+            let mut messages = self.messages.lock().unwrap();
+            if messages.len() == self.capacity {
+                messages.pop_front();
+            }
+            if self.capacity > 0 {
+                messages.push_back(msg.clone());
+            }
+        }));
+    }
+
+    fn replay(&self, address: &Address<Inbox<P>>) {
+        let messages = std::mem::take(&mut *self.messages.lock().unwrap());
+        let channel = <Address<Inbox<P>> as ActorRef>::channel_ref(address);
+        for msg in messages {
+            let _ = channel.send_protocol_now(msg);
+        }
+    }
+}
+
+#[async_trait]
+impl<I, D, E, SFun, SFut, EFun, EFut, S> Specification for SpawnSpec<I, D, E, SFun, EFun, S>
+where
+    I: InboxType,
+    I::Config: Clone,
+    D: Send + 'static,
+    E: Send + 'static,
+    SFun: FnOnce(I, D) -> SFut + Clone + Send + 'static,
+    SFut: Future<Output = E> + Send + 'static,
+    EFun: FnOnce(Result<E, ExitError>) -> EFut + Clone + Send + 'static,
+    EFut: Future<Output = SupervisionResult<D>> + Send + 'static,
+    S: Spawner,
+{
+    type Ref = Address<I>;
+    type Supervisee = SpawnSupervisee<I, D, E, SFun, EFun, S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let link = Link::Attached(self.shutdown_time);
+        let spawn_fn = self.spawn_fn.clone();
+        let data = self.data;
+        let name = self.name.clone();
+        let replay = self.replay.clone();
+        let function = move |inbox: I| {
+            if let Some(replay) = replay {
+                replay.record(&inbox);
+            }
+            let context = ActorContext {
+                id: inbox.actor_id(),
+                name,
+            };
+            context.scope(spawn_fn(inbox, data))
+        };
+
+        let (child, address) =
+            spawn_with_spawner(&self.spawner, link, self.config.clone(), function);
+        if let Some(replay) = &self.replay {
+            replay.replay(&address);
+        }
+
+        Ok((
+            SpawnSupervisee {
+                spawn_fn: self.spawn_fn,
+                exit_fn: self.exit_fn,
+                config: self.config,
+                shutdown_time: self.shutdown_time,
+                drain_timeout: self.drain_timeout,
+                spawner: self.spawner,
+                name: self.name,
+                replay: self.replay,
+                keep_exit: self.keep_exit,
+                external_abort_fn: self.external_abort_fn,
+                exit_value: None,
+                child,
+                aborted: false,
+                drain_timer: None,
+                exit_fut: None,
+            },
+            address,
+        ))
+    }
+}
+
+/// The [`Supervisee`] of a [`SpawnSpec`].
+#[pin_project]
+pub struct SpawnSupervisee<I, D, E, SFun, EFun, S = TokioSpawner>
+where
+    I: InboxType,
+    E: Send + 'static,
+{
+    spawn_fn: SFun,
+    exit_fn: EFun,
+    config: I::Config,
+    shutdown_time: Duration,
+    drain_timeout: Option<Duration>,
+    spawner: S,
+    name: Option<Arc<str>>,
+    replay: Option<Arc<dyn Replay<I>>>,
+    keep_exit: Option<fn(&E) -> ExitValue>,
+    external_abort_fn: Option<fn() -> SupervisionResult<D>>,
+    exit_value: Option<ExitValue>,
+    child: Child<E, I>,
+    aborted: bool,
+    drain_timer: Option<Pin<Box<Sleep>>>,
+    exit_fut: Option<BoxFuture<'static, SupervisionResult<D>>>,
+}
+
+impl<I, D, E, SFun, SFut, EFun, EFut, S> Supervisee for SpawnSupervisee<I, D, E, SFun, EFun, S>
+where
+    I: InboxType,
+    I::Config: Clone,
+    D: Send + 'static,
+    E: Send + 'static,
+    SFun: FnOnce(I, D) -> SFut + Clone + Send + 'static,
+    SFut: Future<Output = E> + Send + 'static,
+    EFun: FnOnce(Result<E, ExitError>) -> EFut + Clone + Send + 'static,
+    EFut: Future<Output = SupervisionResult<D>> + Send + 'static,
+    S: Spawner,
+{
+    type Spec = SpawnSpec<I, D, E, SFun, EFun, S>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+
+        if let Some(drain_timer) = this.drain_timer {
+            if drain_timer.poll_unpin(cx).is_ready() {
+                *this.drain_timer = None;
+                this.child.halt();
+            }
+        }
+
+        loop {
+            match this.exit_fut {
+                Some(exit_fut) => {
+                    break exit_fut.poll_unpin(cx).map_ok(|data| {
+                        data.map(|data| SpawnSpec {
+                            spawn_fn: this.spawn_fn.clone(),
+                            exit_fn: this.exit_fn.clone(),
+                            data,
+                            config: this.config.clone(),
+                            shutdown_time: *this.shutdown_time,
+                            drain_timeout: *this.drain_timeout,
+                            spawner: this.spawner.clone(),
+                            name: this.name.clone(),
+                            replay: this.replay.clone(),
+                            keep_exit: *this.keep_exit,
+                            external_abort_fn: *this.external_abort_fn,
+                            phantom: PhantomData,
+                        })
+                    });
+                }
+                None => {
+                    let exit = ready!(this.child.poll_unpin(cx));
+                    if let (Err(ExitError::Abort), false, Some(external_abort_fn)) =
+                        (&exit, *this.aborted, *this.external_abort_fn)
+                    {
+                        *this.exit_fut = Some(Box::pin(future::ready(external_abort_fn())));
+                        continue;
+                    }
+                    if let (Some(keep_exit), Ok(exit)) = (*this.keep_exit, &exit) {
+                        *this.exit_value = Some(keep_exit(exit));
+                    }
+                    *this.exit_fut = Some(Box::pin((this.exit_fn.clone())(exit)));
+                }
+            }
+        }
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.shutdown_time
+            .saturating_add(self.drain_timeout.unwrap_or_default())
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        let this = self.project();
+        match this.drain_timeout {
+            Some(drain_timeout) if this.drain_timer.is_none() && this.child.close() => {
+                *this.drain_timer = Some(Box::pin(tokio::time::sleep(*drain_timeout)));
+            }
+            _ => {
+                *this.drain_timer = None;
+                this.child.halt();
+            }
+        }
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        let this = self.project();
+        *this.aborted = true;
+        this.child.abort();
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().exit_value.take()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::_test::U32Protocol;
+
+    fn spec() -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |halter: Halter, restarts: u32| async move {
+                halter.await;
+                restarts
+            },
+            |exit: Result<u32, ExitError>| async move {
+                match exit {
+                    Ok(restarts) if restarts < 2 => Ok(Some(restarts + 1)),
+                    Ok(_) => Ok(None),
+                    Err(e) => Err(Box::new(e) as FatalError),
+                }
+            },
+            0,
+        )
+        .on_external_abort(|| Ok(Some(0)))
+    }
+
+    #[tokio::test]
+    async fn restart_until_completed() {
+        let mut spec = spec();
+        for _ in 0..2 {
+            let (supervisee, address) = spec.start_supervised().await.unwrap();
+            address.halt();
+            spec = supervisee.supervise().await.unwrap().unwrap();
+        }
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        address.halt();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn abort_is_fatal() {
+        let (supervisee, _address) = spec().start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        supervisee.as_mut().abort();
+        let exit = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        let Err(e) = exit else {
+            panic!("Expected a fatal error")
+        };
+        assert!(matches!(e.downcast_ref(), Some(ExitError::Abort)));
+    }
+
+    #[tokio::test]
+    async fn spawned_onto_given_runtime() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("other-runtime")
+            .build()
+            .unwrap();
+
+        let spec = SpawnSpec::new(
+            |_inbox: Halter, _: ()| async move {
+                std::thread::current().name().map(|name| name.to_string())
+            },
+            |exit: Result<Option<String>, ExitError>| async move {
+                assert_eq!(exit.unwrap().as_deref(), Some("other-runtime"));
+                Ok(Some(()))
+            },
+            (),
+        )
+        .with_runtime(runtime.handle().clone());
+
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        supervisee.supervise().await.unwrap().unwrap();
+
+        runtime.shutdown_background();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn spawner_is_kept_across_restarts() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let thread = std::thread::current().id();
+                let spec = SpawnSpec::new(
+                    |_inbox: Halter, _: ()| async move { std::thread::current().id() },
+                    move |exit: Result<std::thread::ThreadId, ExitError>| async move {
+                        assert_eq!(exit.unwrap(), thread);
+                        Ok(Some(()))
+                    },
+                    (),
+                )
+                .with_spawner(LocalSpawner);
+
+                let (supervisee, _address) = spec.start_supervised().await.unwrap();
+                let spec = supervisee.supervise().await.unwrap().unwrap();
+                let (supervisee, _address) = spec.start_supervised().await.unwrap();
+                supervisee.supervise().await.unwrap().unwrap();
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn externally_aborted_actor_is_restarted() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .build()
+            .unwrap();
+
+        let spec = SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit: Result<(), ExitError>| async move { Ok(None) },
+            (),
+        )
+        .on_external_abort(|| Ok(Some(())))
+        .with_runtime(runtime.handle().clone())
+        .on_restart(|spec, _restarts| spec.with_runtime(Handle::current()));
+        let (child, handle) = SupervisorBuilder::new(spec).spawn();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        // Aborts the task of the actor, without going through the supervisee.
+        runtime.shutdown_background();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let actors = handle.list_actors().await;
+        assert_eq!(actors.len(), 1);
+        assert_eq!(actors[0].restart_count, 1);
+        assert_eq!(actors[0].status, ActorStatus::Running);
+        handle.halt();
+        child.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn named_actor_context_is_kept_across_restarts() {
+        let spec = SpawnSpec::new(
+            |_inbox: Halter, _: ()| async move { ActorContext::current().unwrap() },
+            |exit: Result<ActorContext, ExitError>| async move {
+                assert_eq!(exit.unwrap().name.as_deref(), Some("worker"));
+                Ok(Some(()))
+            },
+            (),
+        )
+        .with_name("worker");
+
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        supervisee.supervise().await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn drain_on_halt_replies_to_queued_requests() {
+        let spec = SpawnSpec::new(
+            |mut inbox: Inbox<U32Protocol>, ()| async move {
+                while let Ok(U32Protocol::U32(_)) = inbox.recv().await {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            },
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+        .with_drain_on_halt(Duration::from_secs(1))
+        .with_shutdown_time(Duration::MAX);
+
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        assert_eq!(supervisee.as_ref().shutdown_time(), Duration::MAX);
+        for n in 0..10 {
+            address.send(n).await.unwrap();
+        }
+        supervisee.as_mut().halt();
+        assert!(address.send(10u32).await.is_err());
+
+        futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap();
+        assert_eq!(address.msg_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn drain_timeout_halts_actor() {
+        let spec = SpawnSpec::new(
+            |mut inbox: Inbox<U32Protocol>, ()| async move {
+                loop {
+                    match inbox.recv().await {
+                        Ok(_) => tokio::time::sleep(Duration::from_millis(50)).await,
+                        Err(e) => break e,
+                    }
+                }
+            },
+            |exit: Result<RecvError, ExitError>| async move {
+                assert_eq!(exit.unwrap(), RecvError::Halted);
+                Ok(Some(()))
+            },
+            (),
+        )
+        .with_drain_on_halt(Duration::from_millis(10));
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+        for n in 0..5 {
+            address.send(n).await.unwrap();
+        }
+        supervisee.as_mut().halt();
+        futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx))
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "message-replay")]
+    #[protocol]
+    #[derive(Clone, Debug)]
+    enum ReplayProtocol {
+        U32(u32),
+    }
+
+    #[cfg(feature = "message-replay")]
+    #[tokio::test]
+    async fn last_messages_are_replayed_after_restart() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let spec = SpawnSpec::new(
+            |mut inbox: Inbox<ReplayProtocol>, tx: tokio::sync::mpsc::UnboundedSender<u32>| {
+                async move {
+                    while let Ok(ReplayProtocol::U32(n)) = inbox.recv().await {
+                        tx.send(n).unwrap();
+                    }
+                    tx
+                }
+            },
+            |exit: Result<_, ExitError>| async move { Ok(Some(exit.unwrap())) },
+            tx,
+        )
+        .replay_on_restart(2);
+
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        for n in 1..=3 {
+            address.send(n).await.unwrap();
+            assert_eq!(rx.recv().await, Some(n));
+        }
+        address.halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        address.send(4u32).await.unwrap();
+        for n in [2, 3, 4] {
+            assert_eq!(rx.recv().await, Some(n));
+        }
+        address.halt();
+        supervisee.supervise().await.unwrap().unwrap();
+    }
+}
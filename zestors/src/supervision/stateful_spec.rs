@@ -0,0 +1,199 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::ready;
+use pin_project::pin_project;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A handle that a supervised actor can use to save a checkpoint of it's state.
+///
+/// The latest checkpoint is passed on when the [`StatefulSpec`] is restarted.
+#[derive(Debug)]
+pub struct Checkpoint<State>(Arc<Mutex<State>>);
+
+impl<State> Checkpoint<State> {
+    fn new(state: State) -> Self {
+        Self(Arc::new(Mutex::new(state)))
+    }
+
+    /// Save a new checkpoint, replacing the previous one.
+    pub fn save(&self, state: State) {
+        *self.0.lock().unwrap() = state;
+    }
+
+    /// Get a clone of the latest checkpoint.
+    pub fn latest(&self) -> State
+    where
+        State: Clone,
+    {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl<State> Clone for Checkpoint<State> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// A [`Specification`] that restarts it's child from the last saved [`Checkpoint`] instead of
+/// cold-starting it.
+///
+/// Whenever the spec is started, the `build` function is called with the latest checkpoint and
+/// a [`Checkpoint`] handle, and returns the specification `S` that is started. The child can
+/// then save it's state with [`Checkpoint::save`].
+///
+/// # Staleness
+/// Only the state that was saved is restored: any changes made after the last checkpoint but
+/// before the child exited are lost. How stale the restored state is depends on how often the
+/// child saves a checkpoint.
+pub struct StatefulSpec<S, State, F> {
+    build: F,
+    checkpoint: Checkpoint<State>,
+    phantom: PhantomData<fn() -> S>,
+}
+
+impl<S, State, F> StatefulSpec<S, State, F>
+where
+    S: Specification,
+    State: Clone + Send + 'static,
+    F: FnMut(State, Checkpoint<State>) -> S + Send + 'static,
+{
+    /// Create a new spec, starting from the `initial` state.
+    pub fn new(initial: State, build: F) -> Self {
+        Self {
+            build,
+            checkpoint: Checkpoint::new(initial),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Get the [`Checkpoint`] of this spec.
+    pub fn checkpoint(&self) -> &Checkpoint<State> {
+        &self.checkpoint
+    }
+}
+
+#[async_trait]
+impl<S, State, F> Specification for StatefulSpec<S, State, F>
+where
+    S: Specification,
+    State: Clone + Send + 'static,
+    F: FnMut(State, Checkpoint<State>) -> S + Send + 'static,
+{
+    type Ref = S::Ref;
+    type Supervisee = StatefulSupervisee<S, State, F>;
+
+    async fn start_supervised(mut self) -> StartResult<Self> {
+        let spec = (self.build)(self.checkpoint.latest(), self.checkpoint.clone());
+        match spec.start_supervised().await {
+            Ok((supervisee, reference)) => Ok((
+                StatefulSupervisee {
+                    supervisee,
+                    build: Some(self.build),
+                    checkpoint: self.checkpoint,
+                },
+                reference,
+            )),
+            Err(e) => Err(e.map(|_| self)),
+        }
+    }
+}
+
+/// The [`Supervisee`] of a [`StatefulSpec`].
+#[pin_project]
+pub struct StatefulSupervisee<S: Specification, State, F> {
+    #[pin]
+    supervisee: S::Supervisee,
+    build: Option<F>,
+    checkpoint: Checkpoint<State>,
+}
+
+impl<S, State, F> Supervisee for StatefulSupervisee<S, State, F>
+where
+    S: Specification,
+    State: Clone + Send + 'static,
+    F: FnMut(State, Checkpoint<State>) -> S + Send + 'static,
+{
+    type Spec = StatefulSpec<S, State, F>;
+
+    fn poll_supervise(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = self.project();
+        // The returned spec is discarded, and rebuilt from the latest checkpoint instead.
+        Poll::Ready(ready!(this.supervisee.poll_supervise(cx)).map(|spec| {
+            spec.map(|_| StatefulSpec {
+                build: this.build.take().unwrap(),
+                checkpoint: this.checkpoint.clone(),
+                phantom: PhantomData,
+            })
+        }))
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.project_ref().supervisee.shutdown_time()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.project().supervisee.halt()
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        self.project().supervisee.abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.project_ref().supervisee.list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.project_ref().supervisee.labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.project().supervisee.one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.project().supervisee.take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.project().supervisee.take_backoff_reset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn restarts_from_latest_checkpoint() {
+        let mut spec = StatefulSpec::new(0, |state: u32, checkpoint: Checkpoint<u32>| {
+            SpawnSpec::new(
+                move |_inbox: Halter, state: u32| async move {
+                    checkpoint.save(state + 1);
+                    checkpoint.save(state + 2);
+                    state
+                },
+                |exit: Result<u32, ExitError>| async move {
+                    exit.map(Some).map_err(|e| Box::new(e) as FatalError)
+                },
+                state,
+            )
+        });
+
+        for expected in [2, 4, 6] {
+            let (supervisee, _address) = spec.start_supervised().await.unwrap();
+            spec = supervisee.supervise().await.unwrap().unwrap();
+            assert_eq!(spec.checkpoint().latest(), expected);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{ready, Future, FutureExt, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] that spawns an actor which drives a [`Stream`], calling the handler for
+/// every item.
+///
+/// - When the stream is exhausted, the actor is completed.
+/// - When the handler returns an error, the actor exits and can be restarted. The restarted actor
+///   continues with the same stream, and the error is available with
+///   [`StreamActorSpec::last_error`].
+/// - When the actor is halted, it stops pulling from the stream and exits so that it can be
+///   restarted.
+///
+/// If the actor is aborted or panics the stream is lost, and it exits with a [`FatalError`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::supervision::*;
+///
+/// # async fn main() {
+/// let spec = StreamActorSpec::new(futures::stream::iter(0..3), |n: u32| async move {
+///     println!("{n}");
+///     Ok::<_, ()>(())
+/// });
+///
+/// let (supervisee, _address) = spec.start_supervised().await.unwrap();
+/// assert!(supervisee.supervise().await.unwrap().is_none());
+/// # }
+/// ```
+pub struct StreamActorSpec<St, F, E> {
+    stream: St,
+    handler: F,
+    shutdown_time: Duration,
+    last_error: Option<E>,
+}
+
+impl<St, F, Fut, E> StreamActorSpec<St, F, E>
+where
+    St: Stream + Unpin + Send + 'static,
+    St::Item: Send,
+    F: FnMut(St::Item) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), E>> + Send,
+    E: Send + 'static,
+{
+    /// Create a new spec with a default shutdown-time.
+    pub fn new(stream: St, handler: F) -> Self {
+        Self {
+            stream,
+            handler,
+            shutdown_time: get_default_shutdown_time(),
+            last_error: None,
+        }
+    }
+
+    /// Set the time the actor is given to exit after being halted, before it is aborted.
+    pub fn with_shutdown_time(mut self, shutdown_time: Duration) -> Self {
+        self.shutdown_time = shutdown_time;
+        self
+    }
+
+    /// The error returned by the handler, if that is why the actor last exited.
+    pub fn last_error(&self) -> Option<&E> {
+        self.last_error.as_ref()
+    }
+}
+
+#[async_trait]
+impl<St, F, Fut, E> Specification for StreamActorSpec<St, F, E>
+where
+    St: Stream + Unpin + Send + 'static,
+    St::Item: Send,
+    F: FnMut(St::Item) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), E>> + Send,
+    E: Send + 'static,
+{
+    type Ref = Address<Halter>;
+    type Supervisee = StreamActorSupervisee<St, F, E>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let (stream, handler) = (self.stream, self.handler);
+        let (child, address) = spawn_with(
+            Link::Attached(self.shutdown_time),
+            (),
+            move |halter: Halter| drive_stream(halter, stream, handler),
+        );
+
+        Ok((
+            StreamActorSupervisee {
+                child,
+                shutdown_time: self.shutdown_time,
+            },
+            address,
+        ))
+    }
+}
+
+/// The [`Supervisee`] of a [`StreamActorSpec`].
+pub struct StreamActorSupervisee<St, F, E>
+where
+    St: Send + 'static,
+    F: Send + 'static,
+    E: Send + 'static,
+{
+    child: Child<StreamExit<St, F, E>, Halter>,
+    shutdown_time: Duration,
+}
+
+impl<St, F, Fut, E> Supervisee for StreamActorSupervisee<St, F, E>
+where
+    St: Stream + Unpin + Send + 'static,
+    St::Item: Send,
+    F: FnMut(St::Item) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), E>> + Send,
+    E: Send + 'static,
+{
+    type Spec = StreamActorSpec<St, F, E>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let shutdown_time = self.shutdown_time;
+        let spec = |stream, handler, last_error| StreamActorSpec {
+            stream,
+            handler,
+            shutdown_time,
+            last_error,
+        };
+
+        Poll::Ready(match ready!(self.child.poll_unpin(cx)) {
+            Ok(StreamExit::Exhausted) => Ok(None),
+            Ok(StreamExit::Halted(stream, handler)) => Ok(Some(spec(stream, handler, None))),
+            Ok(StreamExit::Failed(stream, handler, e)) => Ok(Some(spec(stream, handler, Some(e)))),
+            Err(e) => Err(Box::new(e)),
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.shutdown_time
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.child.halt();
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        self.child.abort();
+    }
+}
+
+/// The exit-value of the actor of a [`StreamActorSpec`].
+enum StreamExit<St, F, E> {
+    Exhausted,
+    Halted(St, F),
+    Failed(St, F, E),
+}
+
+async fn drive_stream<St, F, Fut, E>(
+    mut halter: Halter,
+    mut stream: St,
+    mut handler: F,
+) -> StreamExit<St, F, E>
+where
+    St: Stream + Unpin,
+    F: FnMut(St::Item) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    loop {
+        let item = tokio::select! {
+            biased;
+            _ = &mut halter => return StreamExit::Halted(stream, handler),
+            item = stream.next() => item,
+        };
+        match item {
+            Some(item) => {
+                if let Err(e) = handler(item).await {
+                    return StreamExit::Failed(stream, handler, e);
+                }
+            }
+            None => return StreamExit::Exhausted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn failure_restarts_with_same_stream() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let handled2 = handled.clone();
+        let mut spec = StreamActorSpec::new(futures::stream::iter(0..5), move |n: u32| {
+            handled2.lock().unwrap().push(n);
+            async move {
+                match n {
+                    1 | 3 => Err(n),
+                    _ => Ok(()),
+                }
+            }
+        });
+
+        for error in [1, 3] {
+            let (supervisee, _address) = spec.start_supervised().await.unwrap();
+            spec = supervisee.supervise().await.unwrap().unwrap();
+            assert_eq!(spec.last_error(), Some(&error));
+        }
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+        assert_eq!(*handled.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn halt_stops_pulling() {
+        let spec = StreamActorSpec::new(futures::stream::pending::<()>(), |()| async move {
+            Ok::<_, ()>(())
+        });
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        address.halt();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert!(spec.last_error().is_none());
+    }
+}
@@ -0,0 +1,691 @@
+use super::{one_for_one::AbortTimer, EventLog};
+use crate::all::*;
+use futures::{future::BoxFuture, ready, Future, FutureExt, StreamExt};
+use std::{
+    any::type_name,
+    mem,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+//------------------------------------------------------------------------------------------------
+//  SupervisorBuilder
+//------------------------------------------------------------------------------------------------
+
+/// Builds a supervisor, an actor that starts a [`Specification`] and restarts it whenever it exits.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = SpawnSpec::new(
+///     |mut inbox: Inbox<()>, _: ()| async move { let _ = inbox.recv().await; },
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// );
+///
+/// let (child, handle) = SupervisorBuilder::new(spec).with_event_buffer(10).spawn();
+/// handle.halt();
+/// assert!(child.await.unwrap().unwrap().is_some());
+/// # }
+/// ```
+pub struct SupervisorBuilder<S> {
+    spec: S,
+    limiter: RestartLimiter,
+    event_buffer: usize,
+    capture_panics: bool,
+}
+
+impl<S: Specification> SupervisorBuilder<S> {
+    /// Create a new builder with the default [`RestartLimiter`], retaining the last 64 events.
+    pub fn new(spec: S) -> Self {
+        Self {
+            spec,
+            limiter: RestartLimiter::default(),
+            event_buffer: 64,
+            capture_panics: false,
+        }
+    }
+
+    /// Set the [`RestartLimiter`] used to limit restarts of the specification.
+    pub fn with_limiter(mut self, limiter: RestartLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Set the amount of events retained for [`SupervisorHandle::recent_events`].
+    pub fn with_event_buffer(mut self, size: usize) -> Self {
+        self.event_buffer = size;
+        self
+    }
+
+    /// Whether to capture the panics of the supervised actors, together with their backtraces.
+    ///
+    /// When enabled, the [`ExitError::Panic`] of a panicking actor contains a [`PanicReport`],
+    /// which can be retrieved with [`ExitError::panic_report`].
+    ///
+    /// # Capturing
+    /// Panics are captured by a process-wide panic hook, which is installed the first time this
+    /// is enabled and still calls the hook that was installed before it. A panic is correlated to
+    /// it's actor through a thread-local flag, which is set while polling an actor that captures
+    /// panics: the hook only records the backtrace if the flag is set, and the actor takes the
+    /// backtrace from the same thread when the panic unwinds out of it's poll.
+    ///
+    /// Actors capture panics if they are spawned while another capturing actor is polled, so
+    /// this applies to the supervisor and all actors spawned from it, including the children of
+    /// those actors. Tasks spawned directly with [`tokio::spawn`] do not capture panics.
+    pub fn capture_panics(mut self, capture: bool) -> Self {
+        self.capture_panics = capture;
+        self
+    }
+
+    /// Spawn the supervisor.
+    ///
+    /// The child exits when the specification exits without being restarted:
+    /// - `Ok(Some(spec))`: The supervisor was halted, or the restart-limit was reached.
+    /// - `Ok(None)`: The specification has completed.
+    /// - `Err(FatalError)`: The specification has failed.
+    pub fn spawn(self) -> (Child<SupervisionResult<S>>, SupervisorHandle) {
+        let events = Arc::new(EventLog::new(self.event_buffer));
+        let process_events = events.clone();
+        let spawn_supervisor = move || {
+            spawn(move |inbox: Inbox<SupervisorProtocol>| SupervisorProcess {
+                inbox,
+                events: process_events,
+                limiter: self.limiter,
+                state: SupervisorState::NotStarted(self.spec),
+                restarted: false,
+                restarts: 0,
+                started: Instant::now(),
+                to_shutdown: false,
+                shutdown_timer: None,
+                aborted: false,
+            })
+        };
+        let (child, address) = if self.capture_panics {
+            install_panic_hook();
+            with_captured_panics(spawn_supervisor)
+        } else {
+            spawn_supervisor()
+        };
+        (child.into_dyn(), SupervisorHandle { address, events })
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  SupervisorHandle
+//------------------------------------------------------------------------------------------------
+
+/// A handle to a supervisor spawned with [`SupervisorBuilder::spawn`].
+#[derive(Debug, Clone)]
+pub struct SupervisorHandle {
+    address: Address<Inbox<SupervisorProtocol>>,
+    events: Arc<EventLog>,
+}
+
+impl SupervisorHandle {
+    /// Get the last `n` events of the supervisor, oldest first.
+    ///
+    /// The amount of events retained is set with [`SupervisorBuilder::with_event_buffer`].
+    pub fn recent_events(&self, n: usize) -> Vec<SupervisionEvent> {
+        self.events.recent(n)
+    }
+
+    /// Subscribe to all events of the supervisor from now on.
+    pub fn events(&self) -> StreamRx<SupervisionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Get a snapshot of all running actors in the tree.
+    ///
+    /// This recurses into groups such as the [`OneForOneSpec`], listing the actors of their
+    /// children instead of the groups themselves. Returns an empty list once the supervisor has
+    /// exited.
+    pub async fn list_actors(&self) -> Vec<ActorInfo> {
+        self.address.request(ListActors).await.unwrap_or_default()
+    }
+
+    /// Change the [`RestartLimiter`] of the supervisor while it is running, allowing `limit`
+    /// restarts `within` the duration from now on.
+    ///
+    /// The restarts recorded so far are kept, as described by [`RestartLimiter::set_within`].
+    pub async fn set_restart_limit(
+        &self,
+        limit: usize,
+        within: Duration,
+    ) -> Result<(), SupervisorExited> {
+        self.address
+            .request(SetRestartLimit { limit, within })
+            .await
+            .map_err(|_| SupervisorExited)
+    }
+
+    /// Add a child to the [`OneForOneSpec`] of this supervisor, which then starts it.
+    ///
+    /// The tree can be wrapped in other specifications, such as a [`LabeledSpec`]; a tree that is
+    /// a child of the supervised specification can't be reached. This fails if the supervisor is
+    /// not running a tree, or if the tree has reached it's
+    /// [maximum](OneForOneSpec::with_max_children) amount of children.
+    pub async fn add_child<S>(&self, spec: S) -> Result<(), AddChildError>
+    where
+        S: Specification,
+        S::Supervisee: 'static,
+    {
+        self.address
+            .request(AddChild(spec.on_start(|_| ()).into_dyn()))
+            .await
+            .unwrap_or(Err(AddChildError::Exited))
+    }
+
+    /// Get the amount of children of the [`OneForOneSpec`] of this supervisor, together with it's
+    /// maximum. Returns `None` if the supervisor is not running a tree, see
+    /// [`SupervisorHandle::add_child`].
+    pub async fn child_count(&self) -> Result<Option<ChildCount>, SupervisorExited> {
+        self.address
+            .request(GetChildCount)
+            .await
+            .map_err(|_| SupervisorExited)
+    }
+
+    /// Halt the supervisor, shutting down the supervisee.
+    pub fn halt(&self) {
+        self.address.halt()
+    }
+
+    /// Whether the supervisor has exited.
+    pub fn has_exited(&self) -> bool {
+        self.address.has_exited()
+    }
+}
+
+/// Error returned by the [`SupervisorHandle`] when the supervisor has exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+#[error("Couldn't reach the supervisor because it has exited")]
+pub struct SupervisorExited;
+
+/// Error returned by [`SupervisorHandle::add_child`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum AddChildError {
+    /// The supervisor has exited.
+    #[error("Couldn't add child because the supervisor has exited")]
+    Exited,
+    /// The supervisor is not running a [`OneForOneSpec`] to add the child to.
+    #[error("Couldn't add child because the supervisor is not running a OneForOneSpec")]
+    NoTree,
+    /// The tree has reached it's maximum amount of children, see
+    /// [`OneForOneSpec::with_max_children`].
+    #[error("Couldn't add child because the tree has reached it's maximum of {max} children")]
+    Full { max: usize },
+}
+
+/// The amount of children of the tree of a supervisor, see [`SupervisorHandle::child_count`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChildCount {
+    /// The amount of children, including the ones that have completed.
+    pub count: usize,
+    /// The maximum amount of children, see [`OneForOneSpec::with_max_children`].
+    pub max: Option<usize>,
+}
+
+//------------------------------------------------------------------------------------------------
+//  SupervisorProcess
+//------------------------------------------------------------------------------------------------
+
+#[protocol]
+#[derive(Debug)]
+enum SupervisorProtocol {
+    ListActors(ListActors),
+    SetRestartLimit(SetRestartLimit),
+    AddChild(AddChild),
+    GetChildCount(GetChildCount),
+}
+
+#[derive(Message, Debug)]
+#[request(Vec<ActorInfo>)]
+struct ListActors;
+
+#[derive(Message, Debug)]
+#[request(())]
+struct SetRestartLimit {
+    limit: usize,
+    within: Duration,
+}
+
+#[derive(Message, Debug)]
+#[request(Result<(), AddChildError>)]
+struct AddChild(BoxSpec);
+
+#[derive(Message, Debug)]
+#[request(Option<ChildCount>)]
+struct GetChildCount;
+
+struct SupervisorProcess<S: Specification> {
+    inbox: Inbox<SupervisorProtocol>,
+    events: Arc<EventLog>,
+    limiter: RestartLimiter,
+    state: SupervisorState<S>,
+    restarted: bool,
+    restarts: usize,
+    started: Instant,
+    to_shutdown: bool,
+    shutdown_timer: Option<AbortTimer>,
+    aborted: bool,
+}
+
+enum SupervisorState<S: Specification> {
+    NotStarted(S),
+    Starting(BoxFuture<'static, StartResult<S>>),
+    Supervising(Pin<Box<S::Supervisee>>),
+    Exited,
+}
+
+impl<S: Specification> SupervisorProcess<S> {
+    /// Decide whether the spec is restarted, or whether the supervisor exits.
+    fn restart(&mut self, spec: S) -> Option<SupervisionResult<S>> {
+        if self.to_shutdown {
+            self.events.push(SupervisionEventKind::Shutdown);
+        } else if self.limiter.within_limit() {
+            self.restarted = true;
+            self.restarts += 1;
+            self.state = SupervisorState::NotStarted(spec);
+            return None;
+        } else {
+            self.events.push(SupervisionEventKind::RestartLimitReached);
+        }
+        self.state = SupervisorState::Exited;
+        Some(Ok(Some(spec)))
+    }
+
+    fn list_actors(&self) -> Vec<ActorInfo> {
+        let SupervisorState::Supervising(supervisee) = &self.state else {
+            return Vec::new();
+        };
+        let info = ActorInfo {
+            name: "root".to_string(),
+            kind: type_name::<S>(),
+            uptime: self.started.elapsed(),
+            restart_count: self.restarts,
+            status: match self.to_shutdown {
+                true => ActorStatus::ShuttingDown,
+                false => ActorStatus::Running,
+            },
+            labels: supervisee.as_ref().labels(),
+        };
+        ActorInfo::list_child(supervisee.as_ref(), info)
+    }
+
+    /// The tree of the supervisee, if it is running one.
+    fn tree(&mut self) -> Option<&mut OneForOneSupervisee> {
+        match &mut self.state {
+            SupervisorState::Supervising(supervisee) => supervisee.as_mut().one_for_one_mut(),
+            _ => None,
+        }
+    }
+
+    fn add_child(&mut self, spec: BoxSpec) -> Result<(), AddChildError> {
+        let tree = self.tree().ok_or(AddChildError::NoTree)?;
+        tree.try_add_boxed(spec)
+            .map_err(|MaxChildrenReached { max, .. }| AddChildError::Full { max })
+    }
+
+    fn child_count(&mut self) -> Option<ChildCount> {
+        let tree = self.tree()?;
+        Some(ChildCount {
+            count: tree.len(),
+            max: tree.max_children(),
+        })
+    }
+
+    fn exit(
+        &mut self,
+        kind: SupervisionEventKind,
+        result: SupervisionResult<S>,
+    ) -> SupervisionResult<S> {
+        self.events.push(kind);
+        self.state = SupervisorState::Exited;
+        result
+    }
+}
+
+impl<S: Specification> Unpin for SupervisorProcess<S> {}
+
+impl<S: Specification> Future for SupervisorProcess<S> {
+    type Output = SupervisionResult<S>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        if this.inbox.halted() {
+            this.to_shutdown = true;
+        }
+        while let Poll::Ready(msg) = this.inbox.poll_next_unpin(cx) {
+            match msg {
+                Some(Ok(SupervisorProtocol::ListActors((ListActors, tx)))) => {
+                    let _ = tx.send(this.list_actors());
+                }
+                Some(Ok(SupervisorProtocol::SetRestartLimit((msg, tx)))) => {
+                    this.limiter.set_limit(msg.limit);
+                    this.limiter.set_within(msg.within);
+                    let _ = tx.send(());
+                }
+                Some(Ok(SupervisorProtocol::AddChild((AddChild(spec), tx)))) => {
+                    let _ = tx.send(this.add_child(spec));
+                }
+                Some(Ok(SupervisorProtocol::GetChildCount((GetChildCount, tx)))) => {
+                    let _ = tx.send(this.child_count());
+                }
+                Some(Err(Halted)) => this.to_shutdown = true,
+                None => {
+                    this.to_shutdown = true;
+                    break;
+                }
+            }
+        }
+
+        loop {
+            match &mut this.state {
+                SupervisorState::NotStarted(_) => {
+                    let SupervisorState::NotStarted(spec) =
+                        mem::replace(&mut this.state, SupervisorState::Exited)
+                    else {
+                        unreachable!()
+                    };
+                    match spec.try_start_supervised() {
+                        Ok(start_fut) => this.state = SupervisorState::Starting(start_fut),
+                        Err((_spec, e)) => {
+                            let kind = SupervisionEventKind::Failed(e.to_string());
+                            break Poll::Ready(this.exit(kind, Err(e)));
+                        }
+                    }
+                }
+
+                SupervisorState::Starting(start_fut) => match ready!(start_fut.poll_unpin(cx)) {
+                    Ok((supervisee, _reference)) => {
+                        this.events.push(match this.restarted {
+                            true => SupervisionEventKind::Restarted,
+                            false => SupervisionEventKind::Started,
+                        });
+                        this.started = Instant::now();
+                        this.state = SupervisorState::Supervising(Box::pin(supervisee));
+                    }
+                    Err(StartError::StartFailed(spec)) => {
+                        this.events.push(SupervisionEventKind::StartFailed);
+                        if let Some(exit) = this.restart(spec) {
+                            break Poll::Ready(exit);
+                        }
+                    }
+                    Err(StartError::Completed) => {
+                        break Poll::Ready(this.exit(SupervisionEventKind::Completed, Ok(None)));
+                    }
+                    Err(StartError::Fatal(e)) => {
+                        let kind = SupervisionEventKind::Failed(e.to_string());
+                        break Poll::Ready(this.exit(kind, Err(e)));
+                    }
+                },
+
+                SupervisorState::Supervising(supervisee) => {
+                    if this.to_shutdown && this.shutdown_timer.is_none() {
+                        supervisee.as_mut().halt();
+                        this.events.push(SupervisionEventKind::ShuttingDown);
+                        let shutdown_time = supervisee.as_ref().shutdown_time();
+                        this.shutdown_timer = Some(AbortTimer::new(shutdown_time));
+                    }
+
+                    if let Some(reset) = supervisee.as_mut().take_backoff_reset() {
+                        this.events.push(SupervisionEventKind::BackoffReset(reset));
+                    }
+                    match supervisee.as_mut().poll_supervise(cx) {
+                        Poll::Ready(Ok(Some(spec))) => {
+                            if !this.to_shutdown {
+                                this.events.push(SupervisionEventKind::Exited);
+                            }
+                            if let Some(exit) = this.restart(spec) {
+                                break Poll::Ready(exit);
+                            }
+                        }
+                        Poll::Ready(Ok(None)) => {
+                            let kind = match this.to_shutdown {
+                                true => SupervisionEventKind::Shutdown,
+                                false => SupervisionEventKind::Completed,
+                            };
+                            break Poll::Ready(this.exit(kind, Ok(None)));
+                        }
+                        Poll::Ready(Err(e)) => {
+                            let kind = SupervisionEventKind::Failed(e.to_string());
+                            break Poll::Ready(this.exit(kind, Err(e)));
+                        }
+                        Poll::Pending => {
+                            if let Some(timer) = &mut this.shutdown_timer {
+                                if !this.aborted && timer.poll_expired(cx) {
+                                    supervisee.as_mut().abort();
+                                    this.aborted = true;
+                                    this.events.push(SupervisionEventKind::Aborted);
+                                    continue;
+                                }
+                            }
+                            break Poll::Pending;
+                        }
+                    }
+                }
+
+                SupervisorState::Exited => panic!("Supervisor polled after exiting"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn spec() -> impl Specification<Ref = Address<Inbox<()>>> {
+        SpawnSpec::new(
+            |mut inbox: Inbox<()>, exit_early: bool| async move {
+                if !exit_early {
+                    let _ = inbox.recv().await;
+                }
+            },
+            |_exit| async move { Ok(Some(true)) },
+            false,
+        )
+    }
+
+    fn kinds(events: Vec<SupervisionEvent>) -> Vec<SupervisionEventKind> {
+        events.into_iter().map(|event| event.kind).collect()
+    }
+
+    #[tokio::test]
+    async fn recent_events_after_shutdown() {
+        let (child, handle) = SupervisorBuilder::new(spec()).spawn();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        handle.halt();
+        assert!(child.await.unwrap().unwrap().is_some());
+
+        assert_eq!(
+            kinds(handle.recent_events(10)),
+            vec![
+                SupervisionEventKind::Started,
+                SupervisionEventKind::ShuttingDown,
+                SupervisionEventKind::Shutdown
+            ]
+        );
+        assert_eq!(
+            kinds(handle.recent_events(1)),
+            vec![SupervisionEventKind::Shutdown]
+        );
+    }
+
+    #[tokio::test]
+    async fn restart_limit_reached() {
+        let spec = SpawnSpec::new(
+            |_inbox: Inbox<()>, _: ()| async move {},
+            |_exit| async move { Ok(Some(())) },
+            (),
+        );
+        let (child, handle) = SupervisorBuilder::new(spec)
+            .with_limiter(RestartLimiter::new(2, Duration::from_secs(10)))
+            .with_event_buffer(4)
+            .spawn();
+        assert!(child.await.unwrap().unwrap().is_some());
+
+        assert_eq!(
+            kinds(handle.recent_events(10)),
+            vec![
+                SupervisionEventKind::Exited,
+                SupervisionEventKind::Restarted,
+                SupervisionEventKind::Exited,
+                SupervisionEventKind::RestartLimitReached
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn restart_limit_is_reconfigured() {
+        let spec = SpawnSpec::new(
+            |_inbox: Inbox<()>, _: ()| tokio::time::sleep(Duration::from_millis(10)),
+            |_exit| async move { Ok(Some(())) },
+            (),
+        );
+        let (child, handle) = SupervisorBuilder::new(spec)
+            .with_limiter(RestartLimiter::new(1, Duration::from_secs(10)))
+            .spawn();
+        handle
+            .set_restart_limit(3, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert!(child.await.unwrap().unwrap().is_some());
+
+        let restarts = kinds(handle.recent_events(64))
+            .into_iter()
+            .filter(|kind| *kind == SupervisionEventKind::Restarted)
+            .count();
+        assert_eq!(restarts, 3);
+        assert_eq!(
+            handle.set_restart_limit(1, Duration::from_secs(10)).await,
+            Err(SupervisorExited)
+        );
+    }
+
+    #[tokio::test]
+    async fn live_events() {
+        let (child, handle) = SupervisorBuilder::new(spec()).spawn();
+        let mut events = handle.events();
+        handle.halt();
+        child.await.unwrap().unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+        assert_eq!(
+            kinds(received).last(),
+            Some(&SupervisionEventKind::Shutdown)
+        );
+    }
+
+    #[tokio::test]
+    async fn list_actors_of_nested_tree() {
+        let exits_once = SpawnSpec::new(
+            |halter: Halter, exit: bool| async move {
+                if !exit {
+                    halter.await;
+                }
+            },
+            |_exit| async move { Ok(Some(false)) },
+            true,
+        );
+        let nested = OneForOneSpec::new().with_spec(exits_once);
+        let tree = OneForOneSpec::new().with_spec(spec()).with_spec(nested);
+        let (child, handle) = SupervisorBuilder::new(tree).spawn();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let actors = handle.list_actors().await;
+        let names = actors
+            .iter()
+            .map(|actor| actor.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["root/0", "root/1/0"]);
+        assert_eq!(actors[0].restart_count, 0);
+        assert_eq!(actors[1].restart_count, 1);
+        assert!(actors[1].kind.contains("SpawnSpec"));
+        assert!(actors
+            .iter()
+            .all(|actor| actor.status == ActorStatus::Running));
+
+        handle.halt();
+        child.await.unwrap().unwrap();
+        assert!(handle.list_actors().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn add_child_until_tree_is_full() {
+        let tree = OneForOneSpec::new()
+            .with_max_children(2)
+            .with_spec(spec())
+            .with_labels([("tree", "root")]);
+        let (child, handle) = SupervisorBuilder::new(tree).spawn();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(
+            handle.child_count().await,
+            Ok(Some(ChildCount {
+                count: 1,
+                max: Some(2)
+            }))
+        );
+
+        handle.add_child(spec()).await.unwrap();
+        assert_eq!(
+            handle.add_child(spec()).await,
+            Err(AddChildError::Full { max: 2 })
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(handle.list_actors().await.len(), 2);
+        assert_eq!(
+            handle.child_count().await.unwrap().map(|count| count.count),
+            Some(2)
+        );
+
+        handle.halt();
+        child.await.unwrap().unwrap();
+        assert_eq!(handle.add_child(spec()).await, Err(AddChildError::Exited));
+        assert_eq!(handle.child_count().await, Err(SupervisorExited));
+    }
+
+    #[tokio::test]
+    async fn add_child_without_tree() {
+        let (child, handle) = SupervisorBuilder::new(spec()).spawn();
+        assert_eq!(handle.add_child(spec()).await, Err(AddChildError::NoTree));
+        assert_eq!(handle.child_count().await, Ok(None));
+        handle.halt();
+        child.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn captured_panics_have_reports() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = std::sync::Arc::new(std::sync::Mutex::new(Some(tx)));
+        let spec = SpawnSpec::new(
+            |_halter: Halter, ()| async move { panic!("crashed") },
+            move |exit: Result<(), ExitError>| {
+                let report = exit.unwrap_err().panic_report().map(|report| {
+                    (
+                        report.message().map(String::from),
+                        report.location().is_some(),
+                    )
+                });
+                let _ = tx.lock().unwrap().take().unwrap().send(report);
+                async move { Ok(None) }
+            },
+            (),
+        );
+        let (child, _handle) = SupervisorBuilder::new(spec).capture_panics(true).spawn();
+        assert!(child.await.unwrap().unwrap().is_none());
+        assert_eq!(rx.await.unwrap(), Some((Some("crashed".to_string()), true)));
+    }
+}
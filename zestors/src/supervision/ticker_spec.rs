@@ -0,0 +1,228 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{ready, Future, FutureExt};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::MissedTickBehavior;
+
+/// A [`Specification`] that spawns an actor which calls an async closure every `period`.
+///
+/// The closure is called for the first time after one period. Halting the actor is only noticed
+/// between ticks, so that a tick that has started always runs to completion. After being halted,
+/// the actor exits and the spec is completed.
+///
+/// If the closure returns an error or panics, the actor exits and is restarted; this is limited
+/// by the [`RestartLimiter`] of the supervisor. How missed ticks are handled can be set with
+/// [`TickerSpec::with_missed_tick_behavior`], which defaults to [`MissedTickBehavior::Burst`].
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let spec = TickerSpec::new(Duration::from_millis(10), || async move {
+///     println!("tick");
+///     Ok::<_, std::io::Error>(())
+/// });
+/// let (supervisee, address) = spec.start_supervised().await.unwrap();
+/// address.halt();
+/// assert!(supervisee.supervise().await.unwrap().is_none());
+/// # }
+/// ```
+pub struct TickerSpec<F> {
+    tick: F,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+    shutdown_time: Duration,
+}
+
+impl<F> TickerSpec<F> {
+    /// Create a new spec that calls `tick` every `period`.
+    ///
+    /// Panics if the `period` is zero.
+    pub fn new(period: Duration, tick: F) -> Self {
+        assert!(!period.is_zero(), "period must be non-zero");
+        Self {
+            tick,
+            period,
+            missed_tick_behavior: MissedTickBehavior::Burst,
+            shutdown_time: get_default_shutdown_time(),
+        }
+    }
+
+    /// Set how ticks are handled that were missed because a tick took too long.
+    pub fn with_missed_tick_behavior(mut self, missed_tick_behavior: MissedTickBehavior) -> Self {
+        self.missed_tick_behavior = missed_tick_behavior;
+        self
+    }
+
+    /// Set the time the actor is given to exit after being halted, before it is aborted.
+    pub fn with_shutdown_time(mut self, shutdown_time: Duration) -> Self {
+        self.shutdown_time = shutdown_time;
+        self
+    }
+}
+
+impl<F> Debug for TickerSpec<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TickerSpec")
+            .field("period", &self.period)
+            .field("missed_tick_behavior", &self.missed_tick_behavior)
+            .field("shutdown_time", &self.shutdown_time)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<F, Fut, E> Specification for TickerSpec<F>
+where
+    F: FnMut() -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<(), E>> + Send + 'static,
+    E: Send + 'static,
+{
+    type Ref = Address<Halter>;
+    type Supervisee = TickerSupervisee<F, E>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let mut tick = self.tick.clone();
+        let (period, missed_tick_behavior) = (self.period, self.missed_tick_behavior);
+        let link = Link::Attached(self.shutdown_time);
+
+        let (child, address) = spawn_with(link, (), move |mut halter: Halter| async move {
+            let start = tokio::time::Instant::now() + period;
+            let mut interval = tokio::time::interval_at(start, period);
+            interval.set_missed_tick_behavior(missed_tick_behavior);
+            loop {
+                tokio::select! {
+                    _ = &mut halter => return Ok(()),
+                    _ = interval.tick() => tick().await?,
+                }
+            }
+        });
+
+        Ok((
+            TickerSupervisee {
+                spec: Some(self),
+                child,
+            },
+            address,
+        ))
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`TickerSpec`].
+pub struct TickerSupervisee<F, E: Send + 'static> {
+    spec: Option<TickerSpec<F>>,
+    child: Child<Result<(), E>, Halter>,
+}
+
+impl<F, E: Send + 'static> Unpin for TickerSupervisee<F, E> {}
+
+impl<F, Fut, E> Supervisee for TickerSupervisee<F, E>
+where
+    F: FnMut() -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<(), E>> + Send + 'static,
+    E: Send + 'static,
+{
+    type Spec = TickerSpec<F>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let exit = ready!(self.child.poll_unpin(cx));
+        let spec = self.spec.take().expect("Polled after completion");
+        Poll::Ready(match exit {
+            Ok(Ok(())) => Ok(None),
+            Ok(Err(_)) | Err(_) => Ok(Some(spec)),
+        })
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.spec
+            .as_ref()
+            .map(|spec| spec.shutdown_time)
+            .unwrap_or_default()
+    }
+
+    fn halt(self: Pin<&mut Self>) {
+        self.child.halt();
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        self.child.abort();
+    }
+}
+
+impl<F, E: Send + 'static> Debug for TickerSupervisee<F, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TickerSupervisee")
+            .field("spec", &self.spec)
+            .field("actor_id", &self.child.actor_id())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    fn counting_spec(
+        ticks: Arc<AtomicU32>,
+        fail_at: u32,
+    ) -> impl Specification<Ref = Address<Halter>> {
+        TickerSpec::new(Duration::from_millis(5), move || {
+            let ticks = ticks.clone();
+            async move {
+                match ticks.fetch_add(1, Ordering::SeqCst) + 1 {
+                    n if n == fail_at => Err(()),
+                    _ => Ok(()),
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn ticks_until_halted() {
+        let ticks = Arc::new(AtomicU32::new(0));
+        let (supervisee, address) = counting_spec(ticks.clone(), 0)
+            .start_supervised()
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        address.halt();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+        assert!(ticks.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[tokio::test]
+    async fn failed_tick_restarts() {
+        let ticks = Arc::new(AtomicU32::new(0));
+        let (supervisee, _address) = counting_spec(ticks.clone(), 2)
+            .start_supervised()
+            .await
+            .unwrap();
+        let spec = supervisee.supervise().await.unwrap().unwrap();
+        assert_eq!(ticks.load(Ordering::SeqCst), 2);
+
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        address.halt();
+        assert!(supervisee.supervise().await.unwrap().is_none());
+        assert!(ticks.load(Ordering::SeqCst) > 2);
+    }
+}
@@ -0,0 +1,243 @@
+#[allow(unused)]
+use crate::all::*;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::{
+    any::Any,
+    error::Error,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// An unrecoverable error that is passed on to the supervisor.
+pub type FatalError = Box<dyn Error + Send>;
+
+/// The type-erased exit-value of a completed [`Supervisee`], see [`Supervisee::take_exit_value`].
+pub type ExitValue = Box<dyn Any + Send>;
+
+/// Specifies how a supervisee is started and supervised as a [`Supervisee`].
+#[async_trait]
+pub trait Specification: Send + Sized + 'static {
+    /// The reference passed on when the supervisee is started.
+    type Ref: Send + 'static;
+
+    /// The [`Supervisee`] returned after starting succesfully.
+    type Supervisee: Supervisee<Spec = Self>;
+
+    /// Start the supervisee.
+    async fn start_supervised(self) -> StartResult<Self>;
+
+    /// Check the configuration of the spec before it is started, without doing any work.
+    ///
+    /// Specs can implement this to reject a configuration that can never start, which is then
+    /// treated as irrecoverable without starting the spec at all. The default accepts every spec.
+    fn validate(&self) -> Result<(), FatalError> {
+        Ok(())
+    }
+
+    /// Start the supervisee, unless [`Specification::validate`] rejects it. In that case the spec
+    /// is returned together with the error.
+    ///
+    /// This is used by all trees and the [`Supervisor`] to start their children.
+    #[allow(clippy::type_complexity)]
+    fn try_start_supervised(
+        self,
+    ) -> Result<BoxFuture<'static, StartResult<Self>>, (Self, FatalError)> {
+        match self.validate() {
+            Ok(()) => Ok(self.start_supervised()),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+/// Returned when starting a [`Specification`].
+pub type StartResult<S> =
+    Result<(<S as Specification>::Supervisee, <S as Specification>::Ref), StartError<S>>;
+
+/// An error returned when starting a [`Specification`] has failed.
+#[derive(Error)]
+pub enum StartError<S> {
+    /// Starting the supervisee has failed, but it may be retried.
+    #[error("Couldn't start the supervisee, but it may be retried")]
+    StartFailed(S),
+    /// Starting the supervisee has failed, with no way to retry.
+    #[error("Couldn't start the supervisee because of a fatal error: {0}")]
+    Fatal(FatalError),
+    /// The supervisee is completed and does not need to be restarted.
+    #[error("Couldn't start the supervisee because it has completed")]
+    Completed,
+}
+
+impl<S> StartError<S> {
+    /// Map the specification of [`StartError::StartFailed`], keeping the other variants as they
+    /// are. This is used by specs that wrap another spec, to return the error of the inner spec.
+    pub fn map<T>(self, f: impl FnOnce(S) -> T) -> StartError<T> {
+        match self {
+            Self::StartFailed(spec) => StartError::StartFailed(f(spec)),
+            Self::Fatal(e) => StartError::Fatal(e),
+            Self::Completed => StartError::Completed,
+        }
+    }
+}
+
+impl<S> From<FatalError> for StartError<S> {
+    fn from(e: FatalError) -> Self {
+        Self::Fatal(e)
+    }
+}
+
+impl<S> std::fmt::Debug for StartError<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StartFailed(_) => f.debug_tuple("StartFailed").finish(),
+            Self::Fatal(e) => f.debug_tuple("Fatal").field(e).finish(),
+            Self::Completed => write!(f, "Completed"),
+        }
+    }
+}
+
+/// Specifies how a [`Specification`] is supervised.
+///
+/// # Forwarding
+/// A supervisee that wraps another one must forward [`Supervisee::labels`],
+/// [`Supervisee::one_for_one_mut`], [`Supervisee::take_exit_value`] and
+/// [`Supervisee::take_backoff_reset`] to it, unless it deliberately hides them. A supervisee
+/// that supervises several children forwards the backoff-resets of all of them, tagged with the
+/// child, and the exit-value of the child that decides when it completes.
+pub trait Supervisee: Send + Sized {
+    /// The [`Specification`] this supervisee was started from.
+    type Spec: Specification<Supervisee = Self>;
+
+    /// Supervise the supervisee until it exits.
+    fn poll_supervise(self: Pin<&mut Self>, cx: &mut Context)
+        -> Poll<SupervisionResult<Self::Spec>>;
+
+    /// The upper limit of how long the supervisee needs to exit after being halted.
+    fn shutdown_time(self: Pin<&Self>) -> Duration;
+
+    /// Halt the supervisee.
+    fn halt(self: Pin<&mut Self>);
+
+    /// Abort the supervisee.
+    fn abort(self: Pin<&mut Self>);
+
+    /// List the actors supervised by this supervisee, if it supervises a group of children.
+    ///
+    /// This returns `None` by default, in which case the supervisee is listed as a single actor by
+    /// its parent. The [`ActorInfo::name`] of each actor is relative to this supervisee.
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        None
+    }
+
+    /// The labels of this supervisee, set with [`SpecificationExt::with_labels`].
+    ///
+    /// These are inherited by all actors it supervises.
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// The [`OneForOneSupervisee`] this supervisee is or wraps, to which children can be added
+    /// with [`SupervisorHandle::add_child`].
+    ///
+    /// This returns `None` by default.
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        None
+    }
+
+    /// Take the exit-value of this supervisee, after it has completed with `Ok(None)`.
+    ///
+    /// This is how an [`ExitCollector`] collects the exit-values of the children of a tree. It
+    /// returns `None` by default, and a [`SpawnSpec`] only keeps its exit-value when created with
+    /// [`SpawnSpec::with_exit_value`].
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        None
+    }
+
+    /// Take the [`BackoffReset`] of this supervisee, once it has recovered from backing off.
+    ///
+    /// This is checked every time a tree or [`SupervisorBuilder::spawn`] polls the supervisee,
+    /// which reports it as a [`SupervisionEventKind::BackoffReset`]. It returns `None` by default,
+    /// and a [`MinUptimeSupervisee`] returns it once after it has been running for the
+    /// `min_healthy_uptime`.
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        None
+    }
+}
+
+/// Returned when a [`Supervisee`] exits.
+///
+/// - `Ok(Some(spec))`: The supervisee would like to be restarted.
+/// - `Ok(None)`: The supervisee has completed.
+/// - `Err(FatalError)`: The supervisee has failed and can not be restarted.
+pub type SupervisionResult<S> = Result<Option<S>, FatalError>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A spec with an invalid configuration, which must never be started.
+    struct InvalidSpec;
+
+    #[async_trait]
+    impl Specification for InvalidSpec {
+        type Ref = ();
+        type Supervisee = InvalidSupervisee;
+
+        async fn start_supervised(self) -> StartResult<Self> {
+            // Never reached, but the tests would fail if it were.
+            Err(StartError::Completed)
+        }
+
+        fn validate(&self) -> Result<(), FatalError> {
+            Err(Box::new(std::io::Error::other("invalid configuration")))
+        }
+    }
+
+    struct InvalidSupervisee;
+
+    impl Supervisee for InvalidSupervisee {
+        type Spec = InvalidSpec;
+
+        fn poll_supervise(
+            self: Pin<&mut Self>,
+            _cx: &mut Context,
+        ) -> Poll<SupervisionResult<Self::Spec>> {
+            unreachable!()
+        }
+
+        fn shutdown_time(self: Pin<&Self>) -> Duration {
+            unreachable!()
+        }
+
+        fn halt(self: Pin<&mut Self>) {
+            unreachable!()
+        }
+
+        fn abort(self: Pin<&mut Self>) {
+            unreachable!()
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_spec_fails_tree_without_starting() {
+        assert!(InvalidSpec.try_start_supervised().is_err());
+
+        let sibling = SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        );
+        let tree = OneForOneSpec::new()
+            .with_spec(sibling)
+            .with_spec(InvalidSpec.once());
+        match tree.start_supervised().await {
+            Err(StartError::Fatal(e)) => assert_eq!(e.to_string(), "invalid configuration"),
+            _ => panic!("The tree should fail"),
+        }
+
+        let (child, _handle) = SupervisorBuilder::new(InvalidSpec).spawn();
+        assert!(child.await.unwrap().is_err());
+    }
+}
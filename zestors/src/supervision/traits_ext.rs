@@ -0,0 +1,91 @@
+#[allow(unused)]
+use crate::all::*;
+use futures::Future;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Extension methods for every [`Specification`].
+pub trait SpecificationExt: Specification {
+    /// Map the reference of this spec every time it is started.
+    fn on_start<F, T>(self, on_start: F) -> OnStartSpec<Self, F, T>
+    where
+        F: FnMut(Self::Ref) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        OnStartSpec::new(self, on_start)
+    }
+
+    /// Transform this spec before every restart, see [`OnRestartSpec`].
+    fn on_restart<F>(self, on_restart: F) -> OnRestartSpec<Self, F>
+    where
+        F: FnMut(Self, usize) -> Self + Send + 'static,
+    {
+        OnRestartSpec::new(self, on_restart)
+    }
+
+    /// Never restart this spec, see [`OnceSpec`].
+    fn once(self) -> OnceSpec<Self> {
+        OnceSpec::new(self)
+    }
+
+    /// Back off restarts until this spec stays up for the `min_healthy_uptime`, see
+    /// [`MinUptimeSpec`].
+    fn with_min_healthy_uptime(self, min_healthy_uptime: Duration) -> MinUptimeSpec<Self> {
+        MinUptimeSpec::new(self, min_healthy_uptime)
+    }
+
+    /// Attach key-value labels to this spec, see [`LabeledSpec`].
+    fn with_labels<K, V>(self, labels: impl IntoIterator<Item = (K, V)>) -> LabeledSpec<Self>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        LabeledSpec::new(self, labels)
+    }
+
+    /// Record metrics about the supervision of this spec into the sink, see [`MeteredSpec`].
+    fn with_metrics(self, name: impl Into<String>, sink: impl MetricsSink) -> MeteredSpec<Self> {
+        MeteredSpec::new(self, name).with_sink(sink)
+    }
+
+    /// Box and type-erase this spec.
+    fn into_dyn(self) -> BoxSpec<Self::Ref>
+    where
+        Self::Supervisee: 'static,
+    {
+        BoxSpec::new(self)
+    }
+}
+impl<S: Specification> SpecificationExt for S {}
+
+/// Extension methods for every [`Supervisee`].
+pub trait SuperviseeExt: Supervisee {
+    /// Returns a future that resolves when the supervisee exits.
+    fn supervise(self) -> SupervisionFuture<Self::Spec> {
+        SupervisionFuture(self)
+    }
+}
+impl<S: Supervisee> SuperviseeExt for S {}
+
+/// A future that supervises a [`Supervisee`] until it exits.
+#[pin_project]
+pub struct SupervisionFuture<S: Specification>(#[pin] S::Supervisee);
+
+impl<S: Specification> SupervisionFuture<S> {
+    /// Stop supervising and return the supervisee.
+    pub fn cancel(self) -> S::Supervisee {
+        self.0
+    }
+}
+
+impl<S: Specification> Future for SupervisionFuture<S> {
+    type Output = SupervisionResult<S>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().0.poll_supervise(cx)
+    }
+}
@@ -0,0 +1,354 @@
+use crate::all::*;
+use async_trait::async_trait;
+use futures::{future::BoxFuture, Future, FutureExt};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A [`Specification`] that supervises a primary child together with a watchdog, which pings the
+/// primary and halts it when it has become unresponsive.
+///
+/// The watchdog is spawned as a sibling of the primary every time it is started, and is given a
+/// clone of it's reference. Every `interval`, the watchdog calls `ping` with the reference; if
+/// this does not resolve to `true` within the `timeout`, the primary is halted and exits in the
+/// same way as it normally would, so it is restarted by it's own exit-value.
+///
+/// The two share their fate: when the primary exits, the watchdog is stopped and the spec exits
+/// with the exit of the primary. If the watchdog itself exits, for example because it panicked,
+/// it is restarted without affecting the primary.
+///
+/// # Usage
+/// ```
+/// # tokio_test::block_on(main());
+/// use std::time::Duration;
+/// use zestors::{prelude::*, supervision::*};
+///
+/// # async fn main() {
+/// let primary = SpawnSpec::new(
+///     |mut inbox: Inbox<()>, ()| async move { while inbox.recv().await.is_ok() {} },
+///     |_exit| async move { Ok(Some(())) },
+///     (),
+/// );
+/// let spec = WatchedSpec::new(
+///     primary,
+///     |address: Address<Inbox<()>>| async move { address.send(()).await.is_ok() },
+///     Duration::from_secs(1),
+///     Duration::from_millis(100),
+/// );
+/// let (supervisee, _address) = spec.start_supervised().await.unwrap();
+/// # drop(supervisee);
+/// # }
+/// ```
+pub struct WatchedSpec<S: Specification> {
+    spec: S,
+    watchdog: Watchdog<S::Ref>,
+}
+
+struct Watchdog<R> {
+    ping: Arc<dyn Fn(R) -> BoxFuture<'static, bool> + Send + Sync>,
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl<S> WatchedSpec<S>
+where
+    S: Specification,
+    S::Ref: Clone + Sync,
+{
+    /// Create a new spec that pings the primary `spec` every `interval`, and halts it if a ping
+    /// does not succeed within the `timeout`.
+    pub fn new<PFun, PFut>(spec: S, ping: PFun, interval: Duration, timeout: Duration) -> Self
+    where
+        PFun: Fn(S::Ref) -> PFut + Send + Sync + 'static,
+        PFut: Future<Output = bool> + Send + 'static,
+    {
+        Self {
+            spec,
+            watchdog: Watchdog {
+                ping: Arc::new(move |reference| ping(reference).boxed()),
+                interval,
+                timeout,
+            },
+        }
+    }
+
+    /// The primary spec.
+    pub fn spec(&self) -> &S {
+        &self.spec
+    }
+
+    /// Convert this back into the primary spec.
+    pub fn into_spec(self) -> S {
+        self.spec
+    }
+}
+
+impl<R: Clone + Send + Sync + 'static> Watchdog<R> {
+    /// Spawn a watchdog, which exits with `true` once the primary has become unresponsive.
+    fn spawn(&self, reference: &R) -> Child<bool, Halter> {
+        let ping = self.ping.clone();
+        let reference = reference.clone();
+        let (interval, timeout) = (self.interval, self.timeout);
+        let (child, _) = spawn(move |halter: Halter| async move {
+            let mut halter = halter;
+            loop {
+                tokio::select! {
+                    _ = &mut halter => return false,
+                    _ = tokio::time::sleep(interval) => (),
+                }
+                match tokio::time::timeout(timeout, ping(reference.clone())).await {
+                    Ok(true) => (),
+                    Ok(false) | Err(_) => return true,
+                }
+            }
+        });
+        child
+    }
+}
+
+impl<R> Clone for Watchdog<R> {
+    fn clone(&self) -> Self {
+        Self {
+            ping: self.ping.clone(),
+            interval: self.interval,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<S: Specification + Debug> Debug for WatchedSpec<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchedSpec")
+            .field("spec", &self.spec)
+            .field("interval", &self.watchdog.interval)
+            .field("timeout", &self.watchdog.timeout)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S> Specification for WatchedSpec<S>
+where
+    S: Specification,
+    S::Ref: Clone + Sync,
+{
+    type Ref = S::Ref;
+    type Supervisee = WatchedSupervisee<S>;
+
+    async fn start_supervised(self) -> StartResult<Self> {
+        let watchdog = self.watchdog;
+        match self.spec.start_supervised().await {
+            Ok((supervisee, reference)) => Ok((
+                WatchedSupervisee {
+                    watchdog_child: Some(watchdog.spawn(&reference)),
+                    primary: Box::pin(supervisee),
+                    reference: reference.clone(),
+                    watchdog,
+                    unresponsive: false,
+                },
+                reference,
+            )),
+            Err(e) => Err(e.map(|spec| Self { spec, watchdog })),
+        }
+    }
+
+    fn validate(&self) -> Result<(), FatalError> {
+        self.spec.validate()
+    }
+}
+
+//------------------------------------------------------------------------------------------------
+//  Supervisee
+//------------------------------------------------------------------------------------------------
+
+/// The [`Supervisee`] of a [`WatchedSpec`].
+pub struct WatchedSupervisee<S: Specification> {
+    primary: Pin<Box<S::Supervisee>>,
+    reference: S::Ref,
+    watchdog: Watchdog<S::Ref>,
+    watchdog_child: Option<Child<bool, Halter>>,
+    unresponsive: bool,
+}
+
+impl<S: Specification> WatchedSupervisee<S> {
+    /// Whether the primary has been halted because the watchdog found it unresponsive.
+    pub fn is_unresponsive(&self) -> bool {
+        self.unresponsive
+    }
+
+    fn stop_watchdog(&mut self) {
+        if let Some(mut child) = self.watchdog_child.take() {
+            child.abort();
+        }
+    }
+}
+
+impl<S: Specification> Unpin for WatchedSupervisee<S> {}
+
+impl<S> Supervisee for WatchedSupervisee<S>
+where
+    S: Specification,
+    S::Ref: Clone + Sync,
+{
+    type Spec = WatchedSpec<S>;
+
+    fn poll_supervise(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<SupervisionResult<Self::Spec>> {
+        let this = &mut *self;
+
+        while let Some(child) = &mut this.watchdog_child {
+            let Poll::Ready(exit) = child.poll_unpin(cx) else {
+                break;
+            };
+            if let Ok(true) = exit {
+                this.unresponsive = true;
+                this.watchdog_child = None;
+                this.primary.as_mut().halt();
+            } else {
+                this.watchdog_child = Some(this.watchdog.spawn(&this.reference));
+            }
+        }
+
+        let exit = futures::ready!(this.primary.as_mut().poll_supervise(cx));
+        this.stop_watchdog();
+        let watchdog = this.watchdog.clone();
+        Poll::Ready(exit.map(|spec| spec.map(|spec| WatchedSpec { spec, watchdog })))
+    }
+
+    fn shutdown_time(self: Pin<&Self>) -> Duration {
+        self.primary.as_ref().shutdown_time()
+    }
+
+    fn halt(mut self: Pin<&mut Self>) {
+        self.stop_watchdog();
+        self.primary.as_mut().halt()
+    }
+
+    fn abort(mut self: Pin<&mut Self>) {
+        self.stop_watchdog();
+        self.primary.as_mut().abort()
+    }
+
+    fn list_actors(self: Pin<&Self>) -> Option<Vec<ActorInfo>> {
+        self.primary.as_ref().list_actors()
+    }
+
+    fn labels(self: Pin<&Self>) -> Vec<(String, String)> {
+        self.primary.as_ref().labels()
+    }
+
+    fn one_for_one_mut(self: Pin<&mut Self>) -> Option<&mut OneForOneSupervisee> {
+        self.get_mut().primary.as_mut().one_for_one_mut()
+    }
+
+    fn take_exit_value(self: Pin<&mut Self>) -> Option<ExitValue> {
+        self.get_mut().primary.as_mut().take_exit_value()
+    }
+
+    fn take_backoff_reset(self: Pin<&mut Self>) -> Option<BackoffReset> {
+        self.get_mut().primary.as_mut().take_backoff_reset()
+    }
+}
+
+impl<S: Specification> Debug for WatchedSupervisee<S>
+where
+    S::Supervisee: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchedSupervisee")
+            .field("primary", &self.primary)
+            .field("watching", &self.watchdog_child.is_some())
+            .field("unresponsive", &self.unresponsive)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    fn primary() -> impl Specification<Ref = Address<Halter>> {
+        SpawnSpec::new(
+            |halter: Halter, ()| halter,
+            |_exit| async move { Ok(Some(())) },
+            (),
+        )
+    }
+
+    #[tokio::test]
+    async fn unresponsive_primary_is_halted() {
+        let responsive = Arc::new(AtomicBool::new(true));
+        let ping = {
+            let responsive = responsive.clone();
+            move |_address: Address<Halter>| {
+                let responsive = responsive.load(Ordering::SeqCst);
+                async move {
+                    if !responsive {
+                        futures::future::pending::<()>().await;
+                    }
+                    true
+                }
+            }
+        };
+        let spec = WatchedSpec::new(
+            primary(),
+            ping,
+            Duration::from_millis(5),
+            Duration::from_millis(5),
+        );
+        let (supervisee, _address) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!supervisee.is_unresponsive());
+        responsive.store(false, Ordering::SeqCst);
+
+        let exit = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(supervisee.is_unresponsive());
+        assert!(exit.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn panicking_watchdog_is_restarted() {
+        let pings = Arc::new(AtomicUsize::new(0));
+        let ping = {
+            let pings = pings.clone();
+            move |_address: Address<Halter>| {
+                let first = pings.fetch_add(1, Ordering::SeqCst) == 0;
+                async move {
+                    if first {
+                        panic!("watchdog crashed");
+                    }
+                    true
+                }
+            }
+        };
+        let spec = WatchedSpec::new(
+            primary(),
+            ping,
+            Duration::from_millis(5),
+            Duration::from_millis(100),
+        );
+        let (supervisee, address) = spec.start_supervised().await.unwrap();
+        let mut supervisee = Box::pin(supervisee);
+
+        let supervise = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx));
+        assert!(tokio::time::timeout(Duration::from_millis(30), supervise)
+            .await
+            .is_err());
+        assert!(pings.load(Ordering::SeqCst) > 1);
+        assert!(!supervisee.is_unresponsive());
+        assert!(!address.has_exited());
+
+        supervisee.as_mut().halt();
+        let exit = futures::future::poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)).await;
+        assert!(exit.unwrap().is_some());
+    }
+}
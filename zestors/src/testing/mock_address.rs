@@ -0,0 +1,243 @@
+use crate::all::*;
+use futures::Future;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+type ReplyFn<M> = Box<dyn FnMut(<M as Message>::Payload) -> <M as Message>::Payload + Send>;
+
+/// A test-double for an [`Address`] that records all messages sent to it, instead of delivering them
+/// to an actor.
+///
+/// Sent messages are converted into the protocol `P` and can be inspected with
+/// [`take_sent`](Self::take_sent). With [`with_reply`](Self::with_reply) a canned reply can be
+/// returned for a request. Requests without a reply are recorded together with their [`Tx`], which
+/// can be used to reply manually.
+///
+/// All clones of a mock share the same recorded messages and replies. This module is only available
+/// for tests or with the `testing` feature.
+pub struct MockAddress<P> {
+    inner: Arc<Mutex<MockInner<P>>>,
+}
+
+struct MockInner<P> {
+    sent: Vec<P>,
+    replies: HashMap<TypeId, Box<dyn Any + Send>>,
+    closed: bool,
+}
+
+impl<P: Protocol> MockAddress<P> {
+    /// Create a new mock without any replies.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(MockInner {
+                sent: Vec::new(),
+                replies: HashMap::new(),
+                closed: false,
+            })),
+        }
+    }
+
+    /// Reply to every request `M` with the result of `reply`, replacing any previous reply for `M`.
+    ///
+    /// The request is still recorded, but the [`Tx`] it is recorded with has been closed.
+    pub fn with_reply<M, R, F>(self, mut reply: F) -> Self
+    where
+        M: Message<Payload = (M, Tx<R>), Returned = Rx<R>> + Send + 'static,
+        R: Send + 'static,
+        F: FnMut(&M) -> R + Send + 'static,
+    {
+        let reply: ReplyFn<M> = Box::new(move |(msg, tx): (M, Tx<R>)| {
+            let _ = tx.send(reply(&msg));
+            (msg, new_request().0)
+        });
+        self.lock()
+            .replies
+            .insert(TypeId::of::<M>(), Box::new(reply));
+        self
+    }
+
+    /// Close the mock, after which all messages are returned as if the actor had exited.
+    pub fn close(&self) {
+        self.lock().closed = true;
+    }
+
+    /// Whether the mock has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// The amount of messages sent to the mock that have not been taken.
+    pub fn sent_count(&self) -> usize {
+        self.lock().sent.len()
+    }
+
+    /// Take all messages sent to the mock, in the order they were sent.
+    pub fn take_sent(&self) -> Vec<P> {
+        std::mem::take(&mut self.lock().sent)
+    }
+
+    /// Record a message, see [`ActorRefExt::try_send`].
+    ///
+    /// This only fails if the mock has been closed.
+    pub fn try_send<M>(&self, msg: M) -> Result<M::Returned, TrySendError<M>>
+    where
+        M: Message + 'static,
+        P: FromPayload<M>,
+    {
+        self.record(msg).map_err(TrySendError::Closed)
+    }
+
+    /// Record a message, see [`ActorRefExt::force_send`].
+    pub fn force_send<M>(&self, msg: M) -> Result<M::Returned, TrySendError<M>>
+    where
+        M: Message + 'static,
+        P: FromPayload<M>,
+    {
+        self.try_send(msg)
+    }
+
+    /// Record a message, see [`ActorRefExt::send_blocking`].
+    pub fn send_blocking<M>(&self, msg: M) -> Result<M::Returned, SendError<M>>
+    where
+        M: Message + 'static,
+        P: FromPayload<M>,
+    {
+        self.record(msg).map_err(SendError)
+    }
+
+    /// Record a message, see [`ActorRefExt::send`].
+    pub async fn send<M>(&self, msg: M) -> Result<M::Returned, SendError<M>>
+    where
+        M: Message + 'static,
+        P: FromPayload<M>,
+    {
+        self.record(msg).map_err(SendError)
+    }
+
+    /// Record a message and wait for the reply, see [`ActorRefExt::request`].
+    pub async fn request<M, F, E, R>(&self, msg: M) -> Result<R, RequestError<M, E>>
+    where
+        M: Message<Returned = F> + 'static,
+        F: Future<Output = Result<R, E>>,
+        P: FromPayload<M>,
+    {
+        match self.record(msg) {
+            Ok(rx) => rx.await.map_err(RequestError::NoReply),
+            Err(msg) => Err(RequestError::Closed(msg)),
+        }
+    }
+
+    fn record<M>(&self, msg: M) -> Result<M::Returned, M>
+    where
+        M: Message + 'static,
+        P: FromPayload<M>,
+    {
+        let (payload, returned) = msg.create();
+        let mut inner = self.lock();
+        if inner.closed {
+            return Err(M::cancel(payload, returned));
+        }
+        let payload = match inner.replies.get_mut(&TypeId::of::<M>()) {
+            Some(reply) => reply.downcast_mut::<ReplyFn<M>>().unwrap()(payload),
+            None => payload,
+        };
+        inner.sent.push(P::from_payload(payload));
+        Ok(returned)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockInner<P>> {
+        self.inner.lock().unwrap()
+    }
+}
+
+impl<P: Protocol> Default for MockAddress<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> Clone for MockAddress<P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<P: Protocol + Debug> Debug for MockAddress<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock().unwrap();
+        f.debug_struct("MockAddress")
+            .field("sent", &inner.sent)
+            .field("closed", &inner.closed)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Message, Debug, PartialEq)]
+    struct Log(&'static str);
+
+    #[derive(Message, Debug)]
+    #[request(u32)]
+    struct Double(u32);
+
+    #[protocol]
+    #[derive(Debug)]
+    enum Dependency {
+        Log(Log),
+        Double(Double),
+    }
+
+    async fn component(address: &MockAddress<Dependency>, x: u32) -> u32 {
+        address.send(Log("doubling")).await.unwrap();
+        address.request(Double(x)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_messages_and_replies() {
+        let mock = MockAddress::<Dependency>::new().with_reply(|Double(x): &Double| x * 2);
+        assert_eq!(component(&mock.clone(), 10).await, 20);
+
+        let sent = mock.take_sent();
+        assert!(matches!(
+            &sent[..],
+            [
+                Dependency::Log(Log("doubling")),
+                Dependency::Double((Double(10), _))
+            ]
+        ));
+        assert_eq!(mock.sent_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn manual_replies() {
+        let mock = MockAddress::<Dependency>::new();
+        let rx = mock.try_send(Double(3)).unwrap();
+        match mock.take_sent().pop() {
+            Some(Dependency::Double((Double(x), tx))) => tx.send(x + 1).unwrap(),
+            _ => panic!("Expected a request"),
+        }
+        assert_eq!(rx.await, Ok(4));
+    }
+
+    #[tokio::test]
+    async fn closed_mock_returns_messages() {
+        let mock = MockAddress::<Dependency>::new();
+        mock.close();
+        assert!(mock.is_closed());
+        assert_eq!(mock.try_send(Log("a")), Err(TrySendError::Closed(Log("a"))));
+        assert!(matches!(
+            mock.request(Double(1)).await,
+            Err(RequestError::Closed(Double(1)))
+        ));
+        assert_eq!(mock.sent_count(), 0);
+    }
+}
@@ -0,0 +1,14 @@
+//! # Overview
+//! Utilities for unit-testing code that interacts with actors, without spawning them. This module
+//! is only available for tests or with the `testing` feature.
+//!
+//! A [`MockAddress<P>`] can be used in place of an [`Address`](crate::actor_reference::Address). It
+//! records all messages sent to it as the protocol `P`, and can be configured to reply to requests.
+//!
+//! With [`assert_stable`], a test can wait until a supervision-tree has reached a steady state,
+//! instead of sleeping and hoping that all children have been started.
+
+mod mock_address;
+mod stable;
+pub use mock_address::*;
+pub use stable::*;
@@ -0,0 +1,149 @@
+use crate::all::*;
+use futures::future::poll_fn;
+use std::{fmt::Write, pin::Pin, time::Duration};
+use tokio::time::{sleep, sleep_until, Instant};
+
+/// The interval at which the actors of the supervisee are listed.
+const CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Drive the supervisee until it's tree has reached a steady state, panicking if it has not done
+/// so `within` the given duration.
+///
+/// The tree is steady once all actors listed by [`Supervisee::list_actors`] are
+/// [running](ActorStatus::Running), and none of them have been restarted for a single check
+/// interval of 10 ms. If the supervisee is a [`OneForOneSpec`], all of it's children must also be
+/// running or completed: a child that is not started, or has given up, is not steady. A supervisee
+/// without any listed actors is steady while it has not exited. When the supervisee exits, or the
+/// timeout fires, this panics with a description of all actors and children in the tree.
+///
+/// Paused tokio time advances automatically, so with [`tokio::time::pause`] this does not slow
+/// down the test. (Unless a child restarts without any delay, which keeps the runtime busy.)
+pub async fn assert_stable<S: Supervisee>(mut supervisee: Pin<&mut S>, within: Duration) {
+    let deadline = Instant::now() + within;
+    let mut previous = None;
+
+    loop {
+        let snapshot = snapshot(supervisee.as_mut());
+        if snapshot.is_some() && snapshot == previous {
+            return;
+        }
+        previous = snapshot;
+
+        tokio::select! {
+            _exit = poll_fn(|cx| supervisee.as_mut().poll_supervise(cx)) => {
+                panic!("Supervisee exited before reaching a steady state")
+            }
+            () = sleep(CHECK_INTERVAL) => (),
+            () = sleep_until(deadline) => {
+                panic!(
+                    "Supervisee did not reach a steady state within {within:?}:\n{}",
+                    describe(supervisee.as_mut())
+                )
+            }
+        }
+    }
+}
+
+/// The name and restart-count of every actor, together with the state of every child of the tree.
+type Snapshot = (Vec<(String, usize)>, Vec<ChildKind>);
+
+/// Takes a [`Snapshot`], or returns `None` if any actor or child is not running.
+fn snapshot<S: Supervisee>(mut supervisee: Pin<&mut S>) -> Option<Snapshot> {
+    let children = children(supervisee.as_mut());
+    if !children
+        .iter()
+        .all(|child| matches!(child, ChildKind::Supervisee | ChildKind::Completed))
+    {
+        return None;
+    }
+    let actors = supervisee
+        .as_ref()
+        .list_actors()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|actor| match actor.status {
+            ActorStatus::Running => Some((actor.name, actor.restart_count)),
+            ActorStatus::ShuttingDown => None,
+        })
+        .collect::<Option<_>>()?;
+    Some((actors, children))
+}
+
+/// The state of every child, if the supervisee is a [`OneForOneSpec`].
+fn children<S: Supervisee>(supervisee: Pin<&mut S>) -> Vec<ChildKind> {
+    match supervisee.one_for_one_mut() {
+        Some(tree) => tree.children().collect(),
+        None => Vec::new(),
+    }
+}
+
+fn describe<S: Supervisee>(supervisee: Pin<&mut S>) -> String {
+    let mut description = String::new();
+    for actor in supervisee.as_ref().list_actors().unwrap_or_default() {
+        let _ = writeln!(
+            description,
+            "- {} ({}): {:?}, restarted {} times, up for {:?}",
+            actor.name, actor.kind, actor.status, actor.restart_count, actor.uptime
+        );
+    }
+    for (child, kind) in children(supervisee).into_iter().enumerate() {
+        let _ = writeln!(description, "- child {child}: {kind:?}");
+    }
+    description
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec(exits: bool) -> impl Specification {
+        SpawnSpec::new(
+            |halter: Halter, exits: bool| async move {
+                if !exits {
+                    halter.await;
+                }
+            },
+            move |_exit| async move {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                Ok(Some(exits))
+            },
+            exits,
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn restarting_tree_is_not_stable() {
+        let tree = OneForOneSpec::new().with_spec(spec(false));
+        let mut supervisee = Box::pin(tree.start_supervised().await.unwrap().0);
+        assert_stable(supervisee.as_mut(), Duration::from_secs(1)).await;
+
+        let tree = OneForOneSpec::new()
+            .with_limiter(RestartLimiter::new(usize::MAX, Duration::from_secs(1)))
+            .with_spec(spec(false))
+            .with_spec(spec(true));
+        let mut supervisee = Box::pin(tree.start_supervised().await.unwrap().0);
+        let handle = tokio::spawn(async move {
+            assert_stable(supervisee.as_mut(), Duration::from_secs(1)).await;
+        });
+        let panic = handle.await.unwrap_err().into_panic();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains("- 0 ("));
+        assert!(message.contains("- 1 ("));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tree_with_child_that_gave_up_is_not_stable() {
+        let tree = OneForOneSpec::new()
+            .with_limiter(RestartLimiter::new(0, Duration::from_secs(1)))
+            .with_spec(spec(false))
+            .with_spec_on_limit(spec(true), LimitAction::StopChild);
+        let mut supervisee = Box::pin(tree.start_supervised().await.unwrap().0);
+        let handle = tokio::spawn(async move {
+            assert_stable(supervisee.as_mut(), Duration::from_secs(1)).await;
+        });
+        let panic = handle.await.unwrap_err().into_panic();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains("- child 0: Supervisee"));
+        assert!(message.contains("- child 1: GaveUp"));
+    }
+}